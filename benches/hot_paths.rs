@@ -0,0 +1,192 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rusqlite::params;
+use std::hint::black_box;
+use weavex::client::{SearchResponse, SearchResult};
+use weavex::formatter::{highlighter, Json, Renderer, Terminal};
+use weavex::kb::{self, KnowledgeBase};
+use weavex::ollama_local::OllamaLocal;
+use weavex::store::Store;
+use weavex::{dedup, markdown_preview, text};
+
+const DIMENSIONS: usize = 64;
+const SNIPPET: &str = "Tokio is an asynchronous runtime for the Rust programming language. \
+    It provides the building blocks needed for writing network applications. ";
+
+fn report_markdown() -> String {
+    let section = "## Findings\n\n\
+        Tokio's scheduler uses **work stealing** across worker threads [1]. \
+        See the [tutorial](https://tokio.rs/tokio/tutorial) for details.\n\n\
+        | Runtime | Threads | Notes |\n|---|---|---|\n\
+        | tokio | n | work stealing |\n| smol | 1+ | minimal |\n\n\
+        ```rust\n#[tokio::main]\nasync fn main() {\n    let handle = tokio::spawn(async { 42 });\n    println!(\"{}\", handle.await.unwrap());\n}\n```\n\n\
+        ```python\nimport asyncio\nasync def main():\n    await asyncio.sleep(1)\n```\n\n\
+        - [x] benchmarked\n- [ ] profiled\n\n";
+    section.repeat(20)
+}
+
+fn search_response(count: usize) -> SearchResponse {
+    SearchResponse {
+        results: (0..count)
+            .map(|idx| SearchResult {
+                title: format!("Result {} — async runtimes compared", idx),
+                url: format!("https://www.example{}.com/posts/{}/#section", idx % 50, idx),
+                content: SNIPPET.repeat(4),
+                explain: vec![format!("ollama rank #{}", idx + 1)],
+                score: None,
+                credibility: None,
+                published: None,
+            })
+            .collect(),
+    }
+}
+
+fn page_text() -> String {
+    "Ownership is Rust's most unique feature. It enables memory safety without a garbage \
+     collector! Does it have a cost? Not at runtime. 所有权规则很重要。\u{1b}[31mred\u{1b}[0m\r\n"
+        .repeat(1_000)
+}
+
+fn embedding(seed: usize) -> Vec<f32> {
+    (0..DIMENSIONS)
+        .map(|dim| (((seed * 31 + dim * 17) % 97) as f32 / 97.0) - 0.5)
+        .collect()
+}
+
+fn knowledge_base(dir: &tempfile::TempDir, chunks: usize) -> KnowledgeBase {
+    let store = Store::open_at(&dir.path().join("bench.db")).unwrap();
+    let conn = store.conn();
+    conn.execute_batch("BEGIN").unwrap();
+    for doc in 0..chunks / 100 {
+        conn.execute(
+            "INSERT INTO kb_documents (source, title, added_at) VALUES (?1, ?1, 0)",
+            params![format!("https://docs.example.com/{}", doc)],
+        )
+        .unwrap();
+        let document_id = conn.last_insert_rowid();
+        for idx in 0..100 {
+            let seed = doc * 100 + idx;
+            conn.execute(
+                "INSERT INTO kb_chunks (document_id, chunk_index, content, embedding) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    document_id,
+                    idx,
+                    format!("chunk {} about async runtimes, executors, and io_uring {}", seed, seed % 13),
+                    kb::encode_embedding(&embedding(seed)),
+                ],
+            )
+            .unwrap();
+        }
+    }
+    conn.execute_batch("COMMIT").unwrap();
+
+    let kb = KnowledgeBase::new(
+        store,
+        OllamaLocal::new(None).unwrap(),
+        kb::DEFAULT_EMBED_MODEL.to_string(),
+    );
+    kb.compact().unwrap();
+    kb
+}
+
+fn bench_markdown(c: &mut Criterion) {
+    let markdown = report_markdown();
+    let mut group = c.benchmark_group("markdown");
+    group.throughput(Throughput::Bytes(markdown.len() as u64));
+    group.bench_function("to_html_highlighted", |b| {
+        b.iter(|| markdown_preview::markdown_to_html(black_box(&markdown)))
+    });
+    group.finish();
+}
+
+fn bench_formatter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("formatter");
+    for count in [10, 500] {
+        let response = search_response(count);
+        group.bench_with_input(BenchmarkId::new("plain", count), &response, |b, r| {
+            b.iter(|| {
+                Terminal {
+                    color: false,
+                    limits: text::Limits::DEFAULT,
+                    highlight: None,
+                    scores: false,
+                }
+                .search_results(black_box(r))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("color", count), &response, |b, r| {
+            b.iter(|| {
+                Terminal {
+                    color: true,
+                    limits: text::Limits::DEFAULT,
+                    highlight: highlighter("async runtimes"),
+                    scores: false,
+                }
+                .search_results(black_box(r))
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("json", count), &response, |b, r| {
+            b.iter(|| Json.search_results(black_box(r)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_text(c: &mut Criterion) {
+    let page = page_text();
+    let mut group = c.benchmark_group("text");
+    group.throughput(Throughput::Bytes(page.len() as u64));
+    group.bench_function("truncate_utf8", |b| {
+        b.iter(|| text::truncate_utf8(black_box(&page), 8_000))
+    });
+    group.bench_function("truncate_at_sentence", |b| {
+        b.iter(|| text::truncate_at_sentence(black_box(&page), 8_000))
+    });
+    group.bench_function("sanitize", |b| b.iter(|| text::sanitize(black_box(&page))));
+    group.finish();
+}
+
+fn bench_urls(c: &mut Criterion) {
+    let urls: Vec<String> = search_response(1_000)
+        .results
+        .into_iter()
+        .map(|r| r.url)
+        .collect();
+    c.bench_function("dedup_key/1000_urls", |b| {
+        b.iter(|| {
+            urls.iter()
+                .map(|url| dedup::key(black_box(url)))
+                .collect::<Vec<_>>()
+        })
+    });
+    let results = search_response(100).results;
+    c.bench_function("dedup_results/100", |b| {
+        b.iter(|| dedup::dedup(black_box(results.clone()), false))
+    });
+}
+
+fn bench_kb(c: &mut Criterion) {
+    let query = embedding(4_242);
+    let mut group = c.benchmark_group("kb_hybrid_search");
+    group.sample_size(20);
+    for chunks in [1_000, kb::INDEX_THRESHOLD + 1_000] {
+        let dir = tempfile::tempdir().unwrap();
+        let kb = knowledge_base(&dir, chunks);
+        group.bench_with_input(BenchmarkId::from_parameter(chunks), &kb, |b, kb| {
+            b.iter(|| {
+                kb.hybrid_search(black_box("io_uring executors"), black_box(&query), 5)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_markdown,
+    bench_formatter,
+    bench_text,
+    bench_urls,
+    bench_kb
+);
+criterion_main!(benches);
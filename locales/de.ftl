@@ -0,0 +1,113 @@
+help-examples =
+    BEISPIELE:
+        # Einfache Suche (öffnet standardmäßig im Browser)
+        weavex "what is rust programming"
+
+    # Browser-Vorschau deaktivieren, im Terminal anzeigen
+        weavex --no-preview "what is rust programming"
+
+    # Ergebnisse begrenzen
+        weavex --max-results 5 "best practices for async rust"
+
+    # Ergebnisse mehrerer Anbieter mischen
+        weavex --providers ollama,brave --blend "obscure query"
+
+    # JSON-Ausgabe
+        weavex --json "machine learning trends 2025"
+
+    # Eine URL abrufen
+        weavex fetch https://example.com
+
+    # KI-Agent (öffnet das Ergebnis standardmäßig im Browser)
+        weavex agent "what are the latest rust async runtime benchmarks"
+
+    # Agent nur mit Terminal-Ausgabe
+        weavex agent --no-preview "query"
+
+    # Anderes Modell verwenden
+        weavex agent --model qwen3:14b "research topic"
+
+    # Denkschritte und Begründung anzeigen
+        weavex agent --show-thinking "query"
+
+    # Reasoning-Modus deaktivieren
+        weavex agent --disable-reasoning "query"
+
+    # Aktuelle Paper zu einem Thema finden
+        weavex arxiv "speculative decoding" --sort submitted
+
+    # Ergebnisse in einer tastaturgesteuerten TUI durchsuchen
+        weavex browse "rust error handling"
+
+    # Ein Suchergebnis bewerten, um künftige Rankings anzupassen
+        weavex feedback 1a2b3c4d good
+
+    # Frühere Recherchen durchsuchen und eine Antwort erneut öffnen
+        weavex history tokio
+        weavex history show 42 --preview
+
+    # Eine Seite in der Wissensdatenbank des Agenten speichern
+        weavex kb add https://tokio.rs/tokio/tutorial
+
+    # Projektdokumentation in einer eigenen Sammlung halten und den Agenten darauf beschränken
+        weavex kb add ./docs/design.md --collection projx
+        weavex agent "summarize the design" --kb-collection projx
+
+    # Die Wissensdatenbank aus den Seiten erweitern, die der Agent liest
+        weavex agent "how does io_uring work" --learn=linux
+
+    # Ein Thema beobachten und nur neue oder geänderte Ergebnisse melden
+        weavex watch "openssl CVE" --interval 6h
+
+    # Einen täglichen Agent-Bericht planen und den Daemon starten
+        weavex jobs add cves "new openssl CVEs" --schedule "0 8 * * *" --agent
+        weavex daemon
+
+    # Sehen, warum jedes Ergebnis an seiner Stelle steht
+        weavex --providers ollama,brave --blend --explain "rust tracing"
+
+    # Die Antwort nach Abschluss der Recherche in einen Slack-Kanal posten
+        weavex agent "rust async runtimes" --notify-webhook https://hooks.slack.com/services/...
+
+    # Eine Quelle als Lesezeichen speichern und ihre Domain oben anheften
+        weavex bookmark add https://docs.rs --tags rust,docs
+        weavex --bookmarks pin "tokio select macro"
+
+    # Den Agenten lokale Notizen lesen lassen, für diesen Lauf oder dauerhaft
+        weavex agent --grant read_file --allow-files ~/notes "summarize my notes"
+        weavex permissions grant read_file
+
+    # Aufbewahrungsrichtlinien aus der Konfigurationsdatei anwenden
+        weavex clean --enforce-policy
+
+    # Eine frühere Sitzung dort fortsetzen, wo sie aufgehört hat
+        weavex sessions tui
+
+    # Eigener API-Schlüssel
+        weavex --api-key YOUR_KEY "query here"
+
+researching = Recherchiere: { $query }
+initializing-agent = Starte Agent mit Modell: { $model }
+opened-result = Ergebnis im Browser geöffnet
+opened-results = Ergebnisse im Browser geöffnet
+opened-answer = Ergebnis im Browser geöffnet
+final-answer = Endgültige Antwort:
+no-results = Keine Ergebnisse gefunden.
+found-results = { $count } Ergebnisse gefunden:
+found-topics = { $count } Ergebnisse in { $topics } Themen gefunden:
+search-results-title = Suchergebnisse
+next-page-prompt = n und Enter für die nächste Seite, Enter zum Beenden:
+no-more-results = Keine weiteren Ergebnisse.
+
+error-load-config = Konfigurationsdatei konnte nicht geladen werden
+error-open-store = Lokaler Speicher konnte nicht geöffnet werden
+error-open-browser = Browser konnte nicht geöffnet werden
+error-fetch = URL konnte nicht abgerufen werden
+error-search = Suchanfrage fehlgeschlagen
+error-agent = Ausführung des Agenten fehlgeschlagen
+error-ollama-client = Ollama-Client konnte nicht erstellt werden
+error-local-ollama-client = Lokaler Ollama-Client konnte nicht erstellt werden
+ollama-unreachable = Ollama ist unter { $url } nicht erreichbar
+ollama-fix-serve = Läuft Ollama? Starte es mit: ollama serve
+ollama-fix-url = Läuft es auf einem anderen Host oder Port? Gib ihn mit --ollama-url an
+ollama-fix-cloud = Keine lokale GPU? Nutze ein -cloud-Modell oder --backend openai/anthropic
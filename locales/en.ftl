@@ -0,0 +1,113 @@
+help-examples =
+    EXAMPLES:
+        # Basic search (opens in browser by default)
+        weavex "what is rust programming"
+
+    # Disable browser preview, show in terminal
+        weavex --no-preview "what is rust programming"
+
+    # Limit results
+        weavex --max-results 5 "best practices for async rust"
+
+    # Blend results from several providers
+        weavex --providers ollama,brave --blend "obscure query"
+
+    # JSON output
+        weavex --json "machine learning trends 2025"
+
+    # Fetch a URL
+        weavex fetch https://example.com
+
+    # AI agent (opens result in browser by default)
+        weavex agent "what are the latest rust async runtime benchmarks"
+
+    # Agent with terminal output only
+        weavex agent --no-preview "query"
+
+    # Use different model
+        weavex agent --model qwen3:14b "research topic"
+
+    # Show thinking steps and reasoning process
+        weavex agent --show-thinking "query"
+
+    # Disable reasoning mode
+        weavex agent --disable-reasoning "query"
+
+    # Find recent papers on a topic
+        weavex arxiv "speculative decoding" --sort submitted
+
+    # Browse results with a keyboard-driven TUI
+        weavex browse "rust error handling"
+
+    # Rate a search result to tune future ranking
+        weavex feedback 1a2b3c4d good
+
+    # Search past research and re-open an answer
+        weavex history tokio
+        weavex history show 42 --preview
+
+    # Save a page to the knowledge base the agent can consult
+        weavex kb add https://tokio.rs/tokio/tutorial
+
+    # Keep project docs in their own collection and scope the agent to it
+        weavex kb add ./docs/design.md --collection projx
+        weavex agent "summarize the design" --kb-collection projx
+
+    # Grow the knowledge base from the pages the agent reads
+        weavex agent "how does io_uring work" --learn=linux
+
+    # Monitor a topic and report only new or changed results
+        weavex watch "openssl CVE" --interval 6h
+
+    # Schedule a daily agent report and run the daemon
+        weavex jobs add cves "new openssl CVEs" --schedule "0 8 * * *" --agent
+        weavex daemon
+
+    # See why each result was ranked where it is
+        weavex --providers ollama,brave --blend --explain "rust tracing"
+
+    # Post the answer to a Slack channel when research finishes
+        weavex agent "rust async runtimes" --notify-webhook https://hooks.slack.com/services/...
+
+    # Bookmark a source and pin its domain to the top of results
+        weavex bookmark add https://docs.rs --tags rust,docs
+        weavex --bookmarks pin "tokio select macro"
+
+    # Let the agent read local notes, for this run or for good
+        weavex agent --grant read_file --allow-files ~/notes "summarize my notes"
+        weavex permissions grant read_file
+
+    # Apply retention policies from the config file
+        weavex clean --enforce-policy
+
+    # Pick up where a past session left off
+        weavex sessions tui
+
+    # Custom API key
+        weavex --api-key YOUR_KEY "query here"
+
+researching = Researching: { $query }
+initializing-agent = Initializing agent with model: { $model }
+opened-result = Opened result in browser
+opened-results = Opened results in browser
+opened-answer = Opened result in browser
+final-answer = Final Answer:
+no-results = No results found.
+found-results = Found { $count } results:
+found-topics = Found { $count } results in { $topics } topics:
+search-results-title = Search Results
+next-page-prompt = Press n and Enter for the next page, or Enter to quit:
+no-more-results = No more results.
+
+error-load-config = Failed to load config file
+error-open-store = Failed to open local store
+error-open-browser = Failed to open browser
+error-fetch = Failed to fetch URL
+error-search = Search request failed
+error-agent = Agent execution failed
+error-ollama-client = Failed to create Ollama client
+error-local-ollama-client = Failed to create local Ollama client
+ollama-unreachable = Can't reach Ollama at { $url }
+ollama-fix-serve = Is Ollama running? Start it with: ollama serve
+ollama-fix-url = Running on another host or port? Point weavex at it with --ollama-url
+ollama-fix-cloud = No local GPU? Use a -cloud model or --backend openai/anthropic
@@ -0,0 +1,113 @@
+help-examples =
+    EJEMPLOS:
+        # Búsqueda básica (se abre en el navegador por defecto)
+        weavex "what is rust programming"
+
+    # Sin vista previa en el navegador, mostrar en la terminal
+        weavex --no-preview "what is rust programming"
+
+    # Limitar resultados
+        weavex --max-results 5 "best practices for async rust"
+
+    # Combinar resultados de varios proveedores
+        weavex --providers ollama,brave --blend "obscure query"
+
+    # Salida JSON
+        weavex --json "machine learning trends 2025"
+
+    # Obtener una URL
+        weavex fetch https://example.com
+
+    # Agente de IA (abre el resultado en el navegador por defecto)
+        weavex agent "what are the latest rust async runtime benchmarks"
+
+    # Agente con salida solo en la terminal
+        weavex agent --no-preview "query"
+
+    # Usar otro modelo
+        weavex agent --model qwen3:14b "research topic"
+
+    # Mostrar los pasos de pensamiento y el razonamiento
+        weavex agent --show-thinking "query"
+
+    # Desactivar el modo de razonamiento
+        weavex agent --disable-reasoning "query"
+
+    # Buscar artículos recientes sobre un tema
+        weavex arxiv "speculative decoding" --sort submitted
+
+    # Explorar resultados con una TUI manejada por teclado
+        weavex browse "rust error handling"
+
+    # Valorar un resultado para ajustar la clasificación futura
+        weavex feedback 1a2b3c4d good
+
+    # Buscar investigaciones anteriores y reabrir una respuesta
+        weavex history tokio
+        weavex history show 42 --preview
+
+    # Guardar una página en la base de conocimiento que consulta el agente
+        weavex kb add https://tokio.rs/tokio/tutorial
+
+    # Guardar documentación de un proyecto en su propia colección y limitar el agente a ella
+        weavex kb add ./docs/design.md --collection projx
+        weavex agent "summarize the design" --kb-collection projx
+
+    # Ampliar la base de conocimiento con las páginas que lee el agente
+        weavex agent "how does io_uring work" --learn=linux
+
+    # Vigilar un tema e informar solo de resultados nuevos o cambiados
+        weavex watch "openssl CVE" --interval 6h
+
+    # Programar un informe diario del agente y ejecutar el daemon
+        weavex jobs add cves "new openssl CVEs" --schedule "0 8 * * *" --agent
+        weavex daemon
+
+    # Ver por qué cada resultado quedó en su posición
+        weavex --providers ollama,brave --blend --explain "rust tracing"
+
+    # Publicar la respuesta en un canal de Slack al terminar la investigación
+        weavex agent "rust async runtimes" --notify-webhook https://hooks.slack.com/services/...
+
+    # Guardar una fuente como marcador y fijar su dominio arriba de los resultados
+        weavex bookmark add https://docs.rs --tags rust,docs
+        weavex --bookmarks pin "tokio select macro"
+
+    # Permitir que el agente lea notas locales, para esta ejecución o de forma permanente
+        weavex agent --grant read_file --allow-files ~/notes "summarize my notes"
+        weavex permissions grant read_file
+
+    # Aplicar las políticas de retención del archivo de configuración
+        weavex clean --enforce-policy
+
+    # Retomar una sesión anterior donde se dejó
+        weavex sessions tui
+
+    # Clave de API personalizada
+        weavex --api-key YOUR_KEY "query here"
+
+researching = Investigando: { $query }
+initializing-agent = Iniciando el agente con el modelo: { $model }
+opened-result = Resultado abierto en el navegador
+opened-results = Resultados abiertos en el navegador
+opened-answer = Resultado abierto en el navegador
+final-answer = Respuesta final:
+no-results = No se encontraron resultados.
+found-results = Se encontraron { $count } resultados:
+found-topics = Se encontraron { $count } resultados en { $topics } temas:
+search-results-title = Resultados de búsqueda
+next-page-prompt = Pulsa n y Enter para la siguiente página, o Enter para salir:
+no-more-results = No hay más resultados.
+
+error-load-config = No se pudo cargar el archivo de configuración
+error-open-store = No se pudo abrir el almacén local
+error-open-browser = No se pudo abrir el navegador
+error-fetch = No se pudo obtener la URL
+error-search = La búsqueda falló
+error-agent = La ejecución del agente falló
+error-ollama-client = No se pudo crear el cliente de Ollama
+error-local-ollama-client = No se pudo crear el cliente local de Ollama
+ollama-unreachable = No se puede conectar con Ollama en { $url }
+ollama-fix-serve = ¿Está Ollama en ejecución? Inícialo con: ollama serve
+ollama-fix-url = ¿Se ejecuta en otro host o puerto? Indícalo a weavex con --ollama-url
+ollama-fix-cloud = ¿Sin GPU local? Usa un modelo -cloud o --backend openai/anthropic
@@ -0,0 +1,113 @@
+help-examples =
+    使用例:
+        # 基本的な検索（デフォルトでブラウザに表示）
+        weavex "what is rust programming"
+
+    # ブラウザプレビューを無効にしてターミナルに表示
+        weavex --no-preview "what is rust programming"
+
+    # 結果の件数を制限
+        weavex --max-results 5 "best practices for async rust"
+
+    # 複数のプロバイダーの結果を統合
+        weavex --providers ollama,brave --blend "obscure query"
+
+    # JSON 出力
+        weavex --json "machine learning trends 2025"
+
+    # URL を取得
+        weavex fetch https://example.com
+
+    # AI エージェント（デフォルトで結果をブラウザに表示）
+        weavex agent "what are the latest rust async runtime benchmarks"
+
+    # ターミナル出力のみのエージェント
+        weavex agent --no-preview "query"
+
+    # 別のモデルを使用
+        weavex agent --model qwen3:14b "research topic"
+
+    # 思考ステップと推論過程を表示
+        weavex agent --show-thinking "query"
+
+    # 推論モードを無効化
+        weavex agent --disable-reasoning "query"
+
+    # トピックに関する最新の論文を検索
+        weavex arxiv "speculative decoding" --sort submitted
+
+    # キーボード操作の TUI で結果を閲覧
+        weavex browse "rust error handling"
+
+    # 検索結果を評価して今後のランキングを調整
+        weavex feedback 1a2b3c4d good
+
+    # 過去の調査を検索して回答を再表示
+        weavex history tokio
+        weavex history show 42 --preview
+
+    # エージェントが参照するナレッジベースにページを保存
+        weavex kb add https://tokio.rs/tokio/tutorial
+
+    # プロジェクトのドキュメントを専用コレクションに保存し、エージェントをそこに限定
+        weavex kb add ./docs/design.md --collection projx
+        weavex agent "summarize the design" --kb-collection projx
+
+    # エージェントが読んだページでナレッジベースを拡充
+        weavex agent "how does io_uring work" --learn=linux
+
+    # トピックを監視し、新規または変更された結果のみを報告
+        weavex watch "openssl CVE" --interval 6h
+
+    # エージェントの日次レポートをスケジュールしてデーモンを実行
+        weavex jobs add cves "new openssl CVEs" --schedule "0 8 * * *" --agent
+        weavex daemon
+
+    # 各結果がその順位になった理由を表示
+        weavex --providers ollama,brave --blend --explain "rust tracing"
+
+    # 調査完了時に回答を Slack チャンネルに投稿
+        weavex agent "rust async runtimes" --notify-webhook https://hooks.slack.com/services/...
+
+    # ソースをブックマークし、そのドメインを結果の上位に固定
+        weavex bookmark add https://docs.rs --tags rust,docs
+        weavex --bookmarks pin "tokio select macro"
+
+    # エージェントにローカルのメモを読ませる（今回のみ、または常に）
+        weavex agent --grant read_file --allow-files ~/notes "summarize my notes"
+        weavex permissions grant read_file
+
+    # 設定ファイルの保持ポリシーを適用
+        weavex clean --enforce-policy
+
+    # 過去のセッションを途中から再開
+        weavex sessions tui
+
+    # API キーを指定
+        weavex --api-key YOUR_KEY "query here"
+
+researching = 調査中: { $query }
+initializing-agent = モデル { $model } でエージェントを起動しています
+opened-result = 結果をブラウザで開きました
+opened-results = 検索結果をブラウザで開きました
+opened-answer = 結果をブラウザで開きました
+final-answer = 最終回答:
+no-results = 結果が見つかりませんでした。
+found-results = { $count } 件の結果が見つかりました:
+found-topics = { $count } 件の結果が { $topics } 個のトピックに見つかりました:
+search-results-title = 検索結果
+next-page-prompt = 次のページは n と Enter、終了は Enter:
+no-more-results = これ以上の結果はありません。
+
+error-load-config = 設定ファイルを読み込めませんでした
+error-open-store = ローカルストアを開けませんでした
+error-open-browser = ブラウザを開けませんでした
+error-fetch = URL を取得できませんでした
+error-search = 検索リクエストに失敗しました
+error-agent = エージェントの実行に失敗しました
+error-ollama-client = Ollama クライアントを作成できませんでした
+error-local-ollama-client = ローカル Ollama クライアントを作成できませんでした
+ollama-unreachable = { $url } の Ollama に接続できません
+ollama-fix-serve = Ollama は起動していますか？ 次のコマンドで起動します: ollama serve
+ollama-fix-url = 別のホストやポートで動いていますか？ --ollama-url で指定してください
+ollama-fix-cloud = ローカル GPU がない場合は -cloud モデルか --backend openai/anthropic を使ってください
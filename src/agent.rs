@@ -1,49 +1,332 @@
-use crate::client::OllamaClient;
-use crate::error::Result;
+use crate::arxiv::{self, ArxivClient, SortBy};
+use crate::audit;
+use crate::calc;
+use crate::chunking::ChunkOptions;
+use crate::citations;
+use crate::client::{FetchResponse, OllamaClient};
+use crate::credibility;
+use crate::discussions::{self, DiscussionClient, Source};
+use crate::error::{OllamaError, Result};
+use crate::events::{self, Event};
+use crate::exit_code;
+use crate::feed::{self, FeedClient};
+use crate::feedback::domain_of;
+use crate::files::FileSandbox;
+use crate::github::{GithubClient, SearchKind};
+use crate::glyphs::Glyph;
+use crate::kb::{self, KnowledgeBase};
 use crate::loading::LoadingAnimation;
-use crate::ollama_local::{create_web_fetch_tool, create_web_search_tool, OllamaLocal, ToolCall};
+use crate::offline;
+use crate::ollama_local::{
+    create_arxiv_search_tool, create_calculate_tool, create_current_datetime_tool,
+    create_describe_image_tool, create_discussion_search_tool, create_discussion_thread_tool,
+    create_fetch_feed_tool, create_geocode_tool, create_github_search_tool, create_kb_search_tool,
+    create_list_notes_tool, create_read_file_tool, create_save_note_tool, create_weather_tool,
+    create_web_fetch_tool, create_web_search_tool, create_wikipedia_tool, ChatBackend, ToolCall,
+};
+use crate::permissions::Permissions;
+use crate::provider::SearchProvider;
+use crate::scratchpad::{self, Scratchpad};
+use crate::store::{self, Store};
+use crate::text::{sanitize, truncate_at_sentence, truncate_utf8, Limits};
+use crate::translate::{self, AnswerLanguage};
+use crate::usage;
+use crate::vision::{self, VisionClient};
+use crate::weather::{self, Units, WeatherClient};
+use crate::wikipedia::{self, WikipediaClient};
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use clap::ValueEnum;
 use serde_json::json;
+use std::io;
+use std::sync::Mutex;
 use tracing::{info, warn};
 
+const DRY_RUN_LOCAL_TOOLS: &[&str] = &["calculate", "current_datetime"];
+const METERED_TOOLS: &[&str] = &["web_search", "web_fetch"];
+
+pub struct AgentOptions {
+    pub model: String,
+    pub max_iterations: usize,
+    pub show_thinking: bool,
+    pub enable_reasoning: bool,
+}
+
 pub struct Agent {
-    local_ollama: OllamaLocal,
+    chat: Box<dyn ChatBackend>,
     web_client: OllamaClient,
+    search: Box<dyn SearchProvider>,
+    kb: Option<KnowledgeBase>,
+    learn: Option<KnowledgeBase>,
+    wikipedia: Option<WikipediaClient>,
+    weather: Option<WeatherClient>,
+    arxiv: Option<ArxivClient>,
+    github: Option<GithubClient>,
+    discussions: Option<DiscussionClient>,
+    feeds: Option<FeedClient>,
+    files: Option<FileSandbox>,
+    vision: Option<(VisionClient, String)>,
+    permissions: Permissions,
+    scratchpad: Scratchpad,
     model: String,
     max_iterations: usize,
     show_thinking: bool,
     enable_reasoning: bool,
+    spinner: bool,
+    reports_exit_code: bool,
+    dry_run: bool,
+    max_domains: Option<usize>,
+    domains: Mutex<Vec<String>>,
+    limits: Limits,
+    cache: Option<Mutex<Store>>,
+    offline: bool,
+    language: Option<AnswerLanguage>,
+    translate_sources: bool,
+    prefer_credible: bool,
 }
 
 impl Agent {
     pub fn new(
-        local_ollama: OllamaLocal,
+        chat: Box<dyn ChatBackend>,
         web_client: OllamaClient,
-        model: String,
-        show_thinking: bool,
-        enable_reasoning: bool,
-        max_iterations: usize,
+        search: Box<dyn SearchProvider>,
+        options: AgentOptions,
     ) -> Self {
         Self {
-            local_ollama,
+            chat,
             web_client,
-            model,
-            max_iterations,
-            show_thinking,
-            enable_reasoning,
+            search,
+            kb: None,
+            learn: None,
+            wikipedia: None,
+            weather: None,
+            arxiv: None,
+            github: None,
+            discussions: None,
+            feeds: None,
+            files: None,
+            vision: None,
+            permissions: Permissions::default(),
+            scratchpad: Scratchpad::new(),
+            model: options.model,
+            max_iterations: options.max_iterations,
+            show_thinking: options.show_thinking,
+            enable_reasoning: options.enable_reasoning,
+            spinner: true,
+            reports_exit_code: true,
+            dry_run: false,
+            max_domains: None,
+            domains: Mutex::new(Vec::new()),
+            limits: Limits::default(),
+            cache: None,
+            offline: false,
+            language: None,
+            translate_sources: false,
+            prefer_credible: false,
         }
     }
 
+    pub fn with_knowledge_base(mut self, kb: KnowledgeBase) -> Self {
+        self.kb = Some(kb);
+        self
+    }
+
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_cache(mut self, store: Store) -> Self {
+        self.cache = Some(Mutex::new(store));
+        self
+    }
+
+    pub fn with_learning(mut self, kb: KnowledgeBase) -> Self {
+        self.learn = Some(kb);
+        self
+    }
+
+    pub fn with_wikipedia(mut self, wikipedia: WikipediaClient) -> Self {
+        self.wikipedia = Some(wikipedia);
+        self
+    }
+
+    pub fn with_weather(mut self, weather: WeatherClient) -> Self {
+        self.weather = Some(weather);
+        self
+    }
+
+    pub fn with_arxiv(mut self, arxiv: ArxivClient) -> Self {
+        self.arxiv = Some(arxiv);
+        self
+    }
+
+    pub fn with_github(mut self, github: GithubClient) -> Self {
+        self.github = Some(github);
+        self
+    }
+
+    pub fn with_discussions(mut self, discussions: DiscussionClient) -> Self {
+        self.discussions = Some(discussions);
+        self
+    }
+
+    pub fn with_feeds(mut self, feeds: FeedClient) -> Self {
+        self.feeds = Some(feeds);
+        self
+    }
+
+    pub fn with_files(mut self, files: FileSandbox) -> Self {
+        self.files = Some(files);
+        self
+    }
+
+    pub fn with_vision(mut self, vision: VisionClient, model: String) -> Self {
+        self.vision = Some((vision, model));
+        self
+    }
+
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn with_scratchpad(mut self, scratchpad: Scratchpad) -> Self {
+        self.scratchpad = scratchpad;
+        self
+    }
+
+    pub fn with_spinner(mut self, spinner: bool) -> Self {
+        self.spinner = spinner;
+        self
+    }
+
+    pub fn with_exit_code(mut self, reports_exit_code: bool) -> Self {
+        self.reports_exit_code = reports_exit_code;
+        self
+    }
+
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn with_language(
+        mut self,
+        language: Option<AnswerLanguage>,
+        translate_sources: bool,
+    ) -> Self {
+        self.language = language;
+        self.translate_sources = translate_sources;
+        self
+    }
+
+    pub fn with_prefer_credible(mut self, prefer_credible: bool) -> Self {
+        self.prefer_credible = prefer_credible;
+        self
+    }
+
+    pub fn with_max_domains(mut self, max_domains: Option<usize>) -> Self {
+        self.max_domains = max_domains;
+        self
+    }
+
     pub async fn run(&self, user_query: &str) -> Result<String> {
-        let tools = vec![create_web_search_tool(), create_web_fetch_tool()];
+        audit::start_run(user_query, &self.model);
+        events::emit(Event::RunStart {
+            query: user_query,
+            model: &self.model,
+        });
+        self.visited_domains().clear();
+        let result = self.run_loop(user_query).await;
+        let status = match &result {
+            Ok(_) => "completed",
+            Err(e) => {
+                events::emit(Event::Error {
+                    message: &e.to_string(),
+                });
+                "failed"
+            }
+        };
+        audit::end_run(status);
+        result
+    }
+
+    async fn run_loop(&self, user_query: &str) -> Result<String> {
+        let mut tools = vec![
+            create_web_search_tool(),
+            create_web_fetch_tool(self.max_domains),
+            create_current_datetime_tool(),
+        ];
+        if self.wikipedia.is_some() {
+            tools.push(create_wikipedia_tool());
+        }
+        if self.weather.is_some() {
+            tools.push(create_weather_tool());
+            tools.push(create_geocode_tool());
+        }
+        if self.vision.is_some() {
+            tools.push(create_describe_image_tool());
+        }
+        if self.arxiv.is_some() {
+            tools.push(create_arxiv_search_tool());
+        }
+        if self.github.is_some() {
+            tools.push(create_github_search_tool());
+        }
+        if self.discussions.is_some() {
+            tools.push(create_discussion_search_tool());
+            tools.push(create_discussion_thread_tool());
+        }
+        if self.feeds.is_some() {
+            tools.push(create_fetch_feed_tool());
+        }
+        if let Some(files) = &self.files {
+            tools.push(create_read_file_tool(files.roots()));
+        }
+        tools.push(create_calculate_tool());
+        tools.push(create_save_note_tool());
+        tools.push(create_list_notes_tool());
+        if self.kb.is_some() {
+            tools.push(create_kb_search_tool());
+        }
 
         let mut messages = vec![json!({
             "role": "user",
             "content": user_query
         })];
+        if let Some(language) = &self.language {
+            messages.insert(
+                0,
+                json!({"role": "system", "content": language.instruction()}),
+            );
+        }
+        if self.prefer_credible {
+            messages.insert(
+                0,
+                json!({"role": "system", "content": credibility::PREFER_INSTRUCTION}),
+            );
+        }
+        if citations::enabled() {
+            messages.insert(
+                0,
+                json!({"role": "system", "content": citations::FOOTNOTE_INSTRUCTION}),
+            );
+        }
+        if self.offline {
+            messages.insert(
+                0,
+                json!({"role": "system", "content": offline::AGENT_NOTICE}),
+            );
+        }
 
         info!("Starting agent loop with query: {}", user_query);
 
-        let loading = if !self.show_thinking {
+        let loading = if self.spinner && !self.show_thinking && !events::enabled() {
             Some(LoadingAnimation::start())
         } else {
             None
@@ -51,9 +334,17 @@ impl Agent {
 
         for iteration in 0..self.max_iterations {
             info!("Agent iteration {}/{}", iteration + 1, self.max_iterations);
+            audit::iteration(iteration + 1);
+            events::emit(Event::IterationStart {
+                iteration: iteration + 1,
+                max_iterations: self.max_iterations,
+            });
+            if let Some(ref loader) = loading {
+                loader.iteration(iteration + 1, self.max_iterations);
+            }
 
             let response = self
-                .local_ollama
+                .chat
                 .chat(
                     &self.model,
                     messages.clone(),
@@ -62,23 +353,22 @@ impl Agent {
                 )
                 .await?;
 
-            if let Some(ref loader) = loading {
-                loader.pause();
-            }
-
             if let Some(thinking) = &response.message.thinking {
+                if !thinking.is_empty() {
+                    events::emit(Event::Thinking { text: thinking });
+                }
                 if !thinking.is_empty() && self.show_thinking {
-                    info!("Model thinking: {}", &thinking[..thinking.len().min(100)]);
-                    println!("\n🧠 Reasoning:");
+                    info!("Model thinking: {}", truncate_utf8(thinking, 100));
+                    println!("\n{}Reasoning:", Glyph::Thinking);
                     println!("   {}", thinking.replace("\n", "\n   "));
                 }
             }
 
             let content = &response.message.content;
             if !content.is_empty() {
-                info!("Model response: {}", &content[..content.len().min(100)]);
+                info!("Model response: {}", truncate_utf8(content, 100));
                 if self.show_thinking {
-                    println!("\n💬 Response:");
+                    println!("\n{}Response:", Glyph::Response);
                     println!("   {}", content.replace("\n", "\n   "));
                 }
             }
@@ -89,7 +379,11 @@ impl Agent {
                 "tool_calls": response.message.tool_calls
             }));
 
-            if let Some(tool_calls) = response.message.tool_calls {
+            if let Some(tool_calls) = response
+                .message
+                .tool_calls
+                .filter(|calls| !calls.is_empty())
+            {
                 info!("Model requested {} tool call(s)", tool_calls.len());
 
                 for tool_call in tool_calls {
@@ -98,20 +392,146 @@ impl Agent {
                             "web_search" => {
                                 let query =
                                     tool_call.function.arguments["query"].as_str().unwrap_or("");
-                                println!("   🔎 Searching: {}...", query);
+                                println!("   {}Searching: {}...", Glyph::Search, query);
                             }
                             "web_fetch" => {
                                 let url =
                                     tool_call.function.arguments["url"].as_str().unwrap_or("");
-                                println!("   🌐 Fetching: {}...", url);
+                                println!("   {}Fetching: {}...", Glyph::Web, url);
+                            }
+                            "kb_search" => {
+                                let query =
+                                    tool_call.function.arguments["query"].as_str().unwrap_or("");
+                                println!(
+                                    "   {}Consulting knowledge base: {}...",
+                                    Glyph::Knowledge,
+                                    query
+                                );
+                            }
+                            "wikipedia_lookup" => {
+                                let title =
+                                    tool_call.function.arguments["title"].as_str().unwrap_or("");
+                                println!(
+                                    "   {}Looking up Wikipedia: {}...",
+                                    Glyph::Wikipedia,
+                                    title
+                                );
+                            }
+                            "get_weather" => {
+                                let location = tool_call.function.arguments["location"]
+                                    .as_str()
+                                    .unwrap_or("coordinates");
+                                println!(
+                                    "   {}Checking the weather: {}...",
+                                    Glyph::Weather,
+                                    location
+                                );
+                            }
+                            "geocode" => {
+                                let query =
+                                    tool_call.function.arguments["query"].as_str().unwrap_or("");
+                                println!("   {}Locating: {}...", Glyph::Location, query);
+                            }
+                            "describe_image" => {
+                                let url =
+                                    tool_call.function.arguments["url"].as_str().unwrap_or("");
+                                println!("   {}Looking at image: {}...", Glyph::Image, url);
+                            }
+                            "arxiv_search" => {
+                                let query =
+                                    tool_call.function.arguments["query"].as_str().unwrap_or("");
+                                println!("   {}Searching arXiv: {}...", Glyph::Arxiv, query);
+                            }
+                            "github_search" => {
+                                let query =
+                                    tool_call.function.arguments["query"].as_str().unwrap_or("");
+                                println!("   {}Searching GitHub: {}...", Glyph::GitHub, query);
+                            }
+                            "discussion_search" => {
+                                let query =
+                                    tool_call.function.arguments["query"].as_str().unwrap_or("");
+                                println!(
+                                    "   {}Searching discussions: {}...",
+                                    Glyph::Discussion,
+                                    query
+                                );
+                            }
+                            "discussion_thread" => {
+                                let url =
+                                    tool_call.function.arguments["url"].as_str().unwrap_or("");
+                                println!("   {}Reading thread: {}...", Glyph::Discussion, url);
+                            }
+                            "fetch_feed" => {
+                                let url =
+                                    tool_call.function.arguments["url"].as_str().unwrap_or("");
+                                println!("   {}Reading feed: {}...", Glyph::Feed, url);
+                            }
+                            "read_file" => {
+                                let path =
+                                    tool_call.function.arguments["path"].as_str().unwrap_or("");
+                                println!("   {}Reading file: {}...", Glyph::File, path);
+                            }
+                            "current_datetime" => {
+                                println!(
+                                    "   {}Checking the current date and time...",
+                                    Glyph::Clock
+                                );
+                            }
+                            "calculate" => {
+                                let expression = tool_call.function.arguments["expression"]
+                                    .as_str()
+                                    .unwrap_or("");
+                                println!("   {}Calculating: {}", Glyph::Calculator, expression);
+                            }
+                            "save_note" => {
+                                let topic =
+                                    tool_call.function.arguments["topic"].as_str().unwrap_or("");
+                                println!("   {}Noting under '{}'", Glyph::Note, topic);
+                            }
+                            "list_notes" => {
+                                println!("   {}Reviewing notes...", Glyph::Notes);
                             }
                             _ => {}
                         }
                     }
-                    let result = self.execute_tool(&tool_call).await?;
+                    audit::tool(&tool_call.function.name, &tool_call.function.arguments);
+                    events::emit(Event::ToolCall {
+                        name: &tool_call.function.name,
+                        arguments: &tool_call.function.arguments,
+                    });
+                    let result = match self.execute_tool(&tool_call).await {
+                        Err(e @ OllamaError::BudgetExceeded { .. }) if usage::degrades() => {
+                            if let Some(cached) =
+                                self.cached_result(&tool_call, "the web budget is used up")
+                            {
+                                info!("{}; serving {} from cache", e, tool_call.function.name);
+                                cached
+                            } else {
+                                warn!("{}; continuing without web tools", e);
+                                audit::policy("deny", &tool_call.function.name, "", &e.to_string());
+                                tools.retain(|tool| {
+                                    !METERED_TOOLS.contains(&tool.function.name.as_str())
+                                });
+                                format!(
+                                    "Budget: {}. Web tools are unavailable for the rest of this \
+                                     run, so do not call them again. Answer from the knowledge \
+                                     base, your notes, and what you have already found, and tell \
+                                     the user the answer may be incomplete.",
+                                    e
+                                )
+                            }
+                        }
+                        result => result?,
+                    };
+                    if let Some(ref loader) = loading {
+                        loader.tool(&tool_call.function.name);
+                    }
 
-                    let truncated_result = if result.len() > 8000 {
-                        format!("{}... [truncated]", truncate_utf8(&result, 8000))
+                    let truncated_result = if result.len() > self.limits.tool_result {
+                        format!(
+                            "{}... [truncated]",
+                            truncate_utf8(&result, self.limits.tool_result)
+                        )
                     } else {
                         result.clone()
                     };
@@ -121,6 +541,12 @@ impl Agent {
                         tool_call.function.name,
                         result.len()
                     );
+                    events::emit(Event::ToolResult {
+                        name: &tool_call.function.name,
+                        chars: result.len(),
+                        truncated: result.len() > self.limits.tool_result,
+                        summary: events::summary(&result),
+                    });
 
                     messages.push(json!({
                         "role": "tool",
@@ -130,20 +556,25 @@ impl Agent {
                 }
             } else {
                 info!("Agent completed without tool calls");
+                usage::agent_run(iteration + 1);
                 if let Some(loader) = loading {
                     loader.stop();
                 }
-                return Ok(response.message.content);
-            }
-
-            if let Some(ref loader) = loading {
-                loader.resume();
+                let answer = response.message.content;
+                events::emit(Event::AnswerChunk { text: &answer });
+                events::emit(Event::Final {
+                    status: "completed",
+                    iterations: iteration + 1,
+                    answer: &answer,
+                });
+                return Ok(answer);
             }
         }
 
         if let Some(loader) = loading {
             loader.stop();
         }
+        usage::agent_run(self.max_iterations);
 
         let last_action = messages
             .iter()
@@ -157,6 +588,21 @@ impl Agent {
                             call["function"]["name"].as_str().map(|name| match name {
                                 "web_search" => "searching the web".to_string(),
                                 "web_fetch" => "fetching a webpage".to_string(),
+                                "kb_search" => "consulting the knowledge base".to_string(),
+                                "wikipedia_lookup" => "reading Wikipedia".to_string(),
+                                "get_weather" => "checking the weather".to_string(),
+                                "geocode" => "locating a place".to_string(),
+                                "describe_image" => "looking at an image".to_string(),
+                                "arxiv_search" => "searching arXiv".to_string(),
+                                "github_search" => "searching GitHub".to_string(),
+                                "discussion_search" => "searching discussions".to_string(),
+                                "discussion_thread" => "reading a discussion thread".to_string(),
+                                "fetch_feed" => "reading a feed".to_string(),
+                                "calculate" => "calculating".to_string(),
+                                "read_file" => "reading a local file".to_string(),
+                                "current_datetime" => "checking the date".to_string(),
+                                "save_note" => "saving a note".to_string(),
+                                "list_notes" => "reviewing notes".to_string(),
                                 _ => format!("using {}", name),
                             })
                         })
@@ -171,13 +617,185 @@ impl Agent {
             "Agent reached max iterations ({}) while {}",
             self.max_iterations, last_action
         );
-        Ok(format!(
+        if self.reports_exit_code {
+            exit_code::report(exit_code::MAX_ITERATIONS);
+        }
+        let mut message = format!(
             "Reached maximum iterations ({}) while {}. Try a more specific query or use --max-iterations to increase the limit.",
             self.max_iterations, last_action
-        ))
+        );
+        if !self.scratchpad.is_empty() {
+            message.push_str("\n\nNotes gathered so far:\n\n");
+            message.push_str(&scratchpad::format_notes(&self.scratchpad.notes(None)));
+        }
+        events::emit(Event::AnswerChunk { text: &message });
+        events::emit(Event::Final {
+            status: "max_iterations",
+            iterations: self.max_iterations,
+            answer: &message,
+        });
+        Ok(message)
+    }
+
+    fn cached_result(&self, tool_call: &ToolCall, reason: &str) -> Option<String> {
+        let store = self
+            .cache
+            .as_ref()?
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let cached = self.lookup_cache(&store, tool_call, reason);
+        usage::cache_lookup(cached.is_some());
+        cached
+    }
+
+    fn lookup_cache(&self, store: &Store, tool_call: &ToolCall, reason: &str) -> Option<String> {
+        let arguments = &tool_call.function.arguments;
+        match tool_call.function.name.as_str() {
+            "web_search" => {
+                let max_results = arguments["max_results"].as_u64().map(|v| v as usize);
+                let results = offline::search(store, arguments["query"].as_str()?, max_results)
+                    .ok()
+                    .filter(|results| !results.is_empty())?;
+                let mut result = format!("Cached results from earlier runs ({}):\n\n", reason);
+                for (idx, cached) in results.iter().enumerate() {
+                    result.push_str(&format!(
+                        "Result {}:\nTitle: {}\nURL: {}\n",
+                        idx + 1,
+                        sanitize(&cached.title),
+                        sanitize(&cached.url)
+                    ));
+                    if !cached.content.is_empty() {
+                        result.push_str(&format!("Content: {}\n", sanitize(&cached.content)));
+                    }
+                    result.push('\n');
+                }
+                Some(result)
+            }
+            "web_fetch" => {
+                let url = arguments["url"].as_str()?;
+                let (page, fetched) = offline::page(store, url).ok().flatten()?;
+                Some(format!(
+                    "Cached copy from {} ({})\nTitle: {}\nContent:\n{}",
+                    fetched,
+                    reason,
+                    sanitize(&page.title),
+                    sanitize(truncate_at_sentence(&page.content, self.limits.page()))
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    fn visited_domains(&self) -> std::sync::MutexGuard<'_, Vec<String>> {
+        self.domains.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn check_domain(&self, url: &str, justification: Option<&str>) -> Option<String> {
+        let max_domains = self.max_domains?;
+        let domain = domain_of(url);
+        let mut domains = self.visited_domains();
+        if domain.is_empty() || domains.contains(&domain) {
+            return None;
+        }
+        if domains.len() < max_domains {
+            domains.push(domain);
+            return None;
+        }
+
+        match justification.map(str::trim).filter(|j| !j.is_empty()) {
+            Some(justification) => {
+                info!(
+                    "Allowing domain {} beyond the limit: {}",
+                    domain, justification
+                );
+                audit::policy("allow", "web_fetch", url, justification);
+                if self.show_thinking {
+                    println!(
+                        "   {}New domain {} justified: {}",
+                        Glyph::Judge,
+                        domain,
+                        justification
+                    );
+                }
+                domains.push(domain);
+                None
+            }
+            None => {
+                warn!("Denied fetch from {}: domain limit reached", domain);
+                audit::policy("deny", "web_fetch", url, "domain limit reached");
+                Some(format!(
+                    "Policy: this run is limited to {} domains ({}) and {} would be a new one. \
+                     Fetch pages from those domains, or call web_fetch again with a \
+                     'justification' explaining why {} is essential to the question. \
+                     Otherwise, write your final answer with what you have.",
+                    max_domains,
+                    domains.join(", "),
+                    domain,
+                    domain
+                ))
+            }
+        }
     }
 
     async fn execute_tool(&self, tool_call: &ToolCall) -> Result<String> {
+        if let Err(denial) = self.permissions.check(&tool_call.function.name) {
+            warn!(
+                "Denied tool {}: permission not granted",
+                tool_call.function.name
+            );
+            audit::policy(
+                "deny",
+                &tool_call.function.name,
+                &tool_call.function.arguments.to_string(),
+                "permission not granted",
+            );
+            if self.show_thinking {
+                println!(
+                    "   {}{} is not permitted in this run",
+                    Glyph::Denied,
+                    tool_call.function.name
+                );
+            }
+            return Ok(denial);
+        }
+
+        if self.dry_run && !DRY_RUN_LOCAL_TOOLS.contains(&tool_call.function.name.as_str()) {
+            println!(
+                "   {}[dry-run] {} {}",
+                Glyph::DryRun,
+                tool_call.function.name,
+                tool_call.function.arguments
+            );
+            audit::policy(
+                "dry-run",
+                &tool_call.function.name,
+                &tool_call.function.arguments.to_string(),
+                "not executed",
+            );
+            return Ok(format!(
+                "[dry-run: {} not executed]",
+                tool_call.function.name
+            ));
+        }
+
+        if self.offline && METERED_TOOLS.contains(&tool_call.function.name.as_str()) {
+            if let Some(cached) = self.cached_result(tool_call, "offline mode") {
+                return Ok(cached);
+            }
+            audit::policy(
+                "offline",
+                &tool_call.function.name,
+                &tool_call.function.arguments.to_string(),
+                "no cached copy",
+            );
+            return Ok(format!(
+                "Offline: no cached copy is available for this {} call and live web access is \
+                 unavailable, so do not retry it. Answer from the knowledge base, your notes, \
+                 and what you have already found.",
+                tool_call.function.name
+            ));
+        }
+
         match tool_call.function.name.as_str() {
             "web_search" => {
                 let query = tool_call.function.arguments["query"]
@@ -206,17 +824,23 @@ impl Agent {
                     query, max_results
                 );
 
-                let response = self.web_client.search(query).await?;
+                let response = self.search.search(query, max_results).await?;
 
                 let mut result = String::new();
                 for (idx, search_result) in response.results.iter().enumerate() {
-                    let truncated_content = truncate_utf8(&search_result.content, 500);
+                    let truncated_content =
+                        truncate_at_sentence(&search_result.content, self.limits.agent_snippet());
+                    let credibility = search_result
+                        .credibility
+                        .map(|tier| format!("Credibility: {}\n", tier))
+                        .unwrap_or_default();
                     result.push_str(&format!(
-                        "Result {}:\nTitle: {}\nURL: {}\nContent: {}\n\n",
+                        "Result {}:\nTitle: {}\nURL: {}\n{}Content: {}\n\n",
                         idx + 1,
-                        search_result.title,
-                        search_result.url,
-                        truncated_content
+                        sanitize(&search_result.title),
+                        sanitize(&search_result.url),
+                        credibility,
+                        sanitize(truncated_content)
                     ));
                 }
 
@@ -237,54 +861,825 @@ impl Agent {
                     ));
                 }
 
+                let justification = tool_call.function.arguments["justification"].as_str();
+                if let Some(denial) = self.check_domain(url, justification) {
+                    return Ok(denial);
+                }
+
                 info!("Executing web_fetch: url='{}'", url);
 
                 let response = self.web_client.fetch(url).await?;
+                citations::record(url, &response);
+                if let Some(cache) = &self.cache {
+                    let store = cache.lock().unwrap_or_else(|e| e.into_inner());
+                    if let Err(e) = offline::record(&store, url, &response) {
+                        warn!("Failed to cache fetched page: {}", e);
+                    }
+                }
+                self.learn_from(url, &response).await;
 
-                let truncated_content = truncate_utf8(&response.content, 2000);
+                let truncated_content = truncate_at_sentence(&response.content, self.limits.page());
+                let (label, content) = self.translated(truncated_content).await;
                 Ok(format!(
-                    "Title: {}\n\nContent:\n{}\n\nLinks found: {}",
-                    response.title,
-                    truncated_content,
+                    "Title: {}\n{}\n{}:\n{}\n\nLinks found: {}",
+                    sanitize(&response.title),
+                    sanitize(&response.attribution()),
+                    label,
+                    sanitize(&content),
                     response.links.len()
                 ))
             }
+            "kb_search" => {
+                let query = tool_call.function.arguments["query"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'query' field in kb_search".to_string(),
+                        )
+                    })?;
+
+                let Some(kb) = &self.kb else {
+                    return Ok("Error: No knowledge base is available".to_string());
+                };
+
+                info!("Executing kb_search: query='{}'", query);
+
+                let passages = kb.search(query, 5).await?;
+                Ok(sanitize(&kb::format_passages(&passages, false)))
+            }
+            "wikipedia_lookup" => {
+                let title = tool_call.function.arguments["title"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'title' field in wikipedia_lookup".to_string(),
+                        )
+                    })?;
+                let section = tool_call.function.arguments["section"].as_str();
+                let language = tool_call.function.arguments["language"]
+                    .as_str()
+                    .filter(|lang| {
+                        (2..=12).contains(&lang.len())
+                            && lang.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+                    })
+                    .unwrap_or("en");
+
+                let Some(client) = &self.wikipedia else {
+                    return Ok("Error: Wikipedia lookup is not available".to_string());
+                };
+
+                info!(
+                    "Executing wikipedia_lookup: title='{}', language={}",
+                    title, language
+                );
+
+                match client.lookup(title, language).await {
+                    Ok(Some(article)) => {
+                        Ok(sanitize(&wikipedia::format_article(&article, section)))
+                    }
+                    Ok(None) => Ok(format!(
+                        "No Wikipedia article found for '{}'. Try web_search instead.",
+                        title
+                    )),
+                    Err(e) => Ok(format!("Error: Wikipedia lookup failed: {}", e)),
+                }
+            }
+            "get_weather" => {
+                let arguments = &tool_call.function.arguments;
+                let days = arguments["days"]
+                    .as_u64()
+                    .map_or(3, |d| d.clamp(1, weather::MAX_FORECAST_DAYS as u64) as u8);
+                let units = arguments["units"]
+                    .as_str()
+                    .map(Units::parse)
+                    .unwrap_or_default();
+
+                let Some(client) = &self.weather else {
+                    return Ok("Error: Weather lookup is not available".to_string());
+                };
+
+                let (location, lat, lon) = match (
+                    arguments["latitude"].as_f64(),
+                    arguments["longitude"].as_f64(),
+                    arguments["location"].as_str(),
+                ) {
+                    (Some(lat), Some(lon), location) => {
+                        (location.unwrap_or("Coordinates").to_string(), lat, lon)
+                    }
+                    (_, _, Some(location)) => match client.geocode(location, 1).await {
+                        Ok(places) => match places.into_iter().next() {
+                            Some(place) => (place.name, place.lat, place.lon),
+                            None => {
+                                return Ok(format!(
+                                    "No place found for '{}'. Try a more specific name.",
+                                    location
+                                ))
+                            }
+                        },
+                        Err(e) => return Ok(format!("Error: Geocoding failed: {}", e)),
+                    },
+                    _ => {
+                        return Err(crate::error::OllamaError::InvalidResponse(
+                            "get_weather needs a location or latitude and longitude".to_string(),
+                        ))
+                    }
+                };
+
+                info!(
+                    "Executing get_weather: location='{}', days={}",
+                    location, days
+                );
+
+                match client.forecast(lat, lon, days, units).await {
+                    Ok(forecast) => Ok(sanitize(&weather::format_forecast(&location, &forecast))),
+                    Err(e) => Ok(format!("Error: Weather lookup failed: {}", e)),
+                }
+            }
+            "geocode" => {
+                let query = tool_call.function.arguments["query"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'query' field in geocode".to_string(),
+                        )
+                    })?;
+                let limit = tool_call.function.arguments["limit"]
+                    .as_u64()
+                    .map_or(3, |l| l.clamp(1, weather::MAX_PLACES as u64) as usize);
+
+                let Some(client) = &self.weather else {
+                    return Ok("Error: Geocoding is not available".to_string());
+                };
+
+                info!("Executing geocode: query='{}'", query);
+
+                match client.geocode(query, limit).await {
+                    Ok(places) => Ok(sanitize(&weather::format_places(query, &places))),
+                    Err(e) => Ok(format!("Error: Geocoding failed: {}", e)),
+                }
+            }
+            "describe_image" => {
+                let url = tool_call.function.arguments["url"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'url' field in describe_image".to_string(),
+                        )
+                    })?;
+                let question = tool_call.function.arguments["question"].as_str();
+
+                let Some((client, model)) = &self.vision else {
+                    return Ok("Error: Image description is not available".to_string());
+                };
+
+                info!("Executing describe_image: url='{}', model={}", url, model);
+
+                let image = match client.download(url).await {
+                    Ok(image) => image,
+                    Err(e) => return Ok(format!("Error: Could not load image: {}", e)),
+                };
+                let response = self
+                    .chat
+                    .chat(model, vec![vision::message(&image, question)], None, false)
+                    .await?;
+                Ok(vision::format_description(
+                    url,
+                    model,
+                    &image,
+                    &sanitize(&response.message.content),
+                ))
+            }
+            "arxiv_search" => {
+                let query = tool_call.function.arguments["query"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'query' field in arxiv_search".to_string(),
+                        )
+                    })?;
+                let max_results = tool_call
+                    .function
+                    .arguments
+                    .get("max_results")
+                    .and_then(|v| v.as_u64())
+                    .map_or(5, |v| v as usize);
+                let sort = tool_call.function.arguments["sort"]
+                    .as_str()
+                    .and_then(|sort| SortBy::from_str(sort, true).ok())
+                    .unwrap_or_default();
+
+                let Some(client) = &self.arxiv else {
+                    return Ok("Error: arXiv search is not available".to_string());
+                };
+
+                info!(
+                    "Executing arxiv_search: query='{}', max_results={}, sort={:?}",
+                    query, max_results, sort
+                );
+
+                match client.search(query, max_results, sort).await {
+                    Ok(papers) => Ok(sanitize(&arxiv::format_papers(&papers))),
+                    Err(e) => Ok(format!("Error: arXiv search failed: {}", e)),
+                }
+            }
+            "github_search" => {
+                let query = tool_call.function.arguments["query"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'query' field in github_search".to_string(),
+                        )
+                    })?;
+                let kind = tool_call.function.arguments["kind"]
+                    .as_str()
+                    .and_then(SearchKind::parse)
+                    .unwrap_or_default();
+                let max_results = tool_call
+                    .function
+                    .arguments
+                    .get("max_results")
+                    .and_then(|v| v.as_u64())
+                    .map_or(5, |v| v as usize);
+
+                let Some(client) = &self.github else {
+                    return Ok("Error: GitHub search is not available".to_string());
+                };
+
+                info!(
+                    "Executing github_search: query='{}', kind={:?}, max_results={}",
+                    query, kind, max_results
+                );
+
+                match client.search(kind, query, max_results).await {
+                    Ok(output) => Ok(sanitize(&output)),
+                    Err(e) => Ok(format!("Error: GitHub search failed: {}", e)),
+                }
+            }
+            "discussion_search" => {
+                let query = tool_call.function.arguments["query"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'query' field in discussion_search".to_string(),
+                        )
+                    })?;
+                let source = tool_call.function.arguments["source"]
+                    .as_str()
+                    .and_then(Source::parse)
+                    .unwrap_or_default();
+                let subreddit = tool_call.function.arguments["subreddit"]
+                    .as_str()
+                    .filter(|s| !s.trim().is_empty());
+                let max_results = tool_call
+                    .function
+                    .arguments
+                    .get("max_results")
+                    .and_then(|v| v.as_u64())
+                    .map_or(5, |v| v as usize);
+
+                let Some(client) = &self.discussions else {
+                    return Ok("Error: Discussion search is not available".to_string());
+                };
+
+                info!(
+                    "Executing discussion_search: query='{}', source={:?}, subreddit={:?}",
+                    query, source, subreddit
+                );
+
+                match client.search(query, source, subreddit, max_results).await {
+                    Ok(found) => Ok(sanitize(&discussions::format_discussions(&found))),
+                    Err(e) => Ok(format!("Error: Discussion search failed: {}", e)),
+                }
+            }
+            "discussion_thread" => {
+                let url = tool_call.function.arguments["url"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'url' field in discussion_thread".to_string(),
+                        )
+                    })?;
+
+                let Some(client) = &self.discussions else {
+                    return Ok("Error: Discussion threads are not available".to_string());
+                };
+
+                info!("Executing discussion_thread: url='{}'", url);
+
+                match client.thread(url).await {
+                    Ok(thread) => Ok(sanitize(&discussions::format_thread(&thread))),
+                    Err(e) => Ok(format!("Error: Could not read thread: {}", e)),
+                }
+            }
+            "fetch_feed" => {
+                let url = tool_call.function.arguments["url"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'url' field in fetch_feed".to_string(),
+                        )
+                    })?;
+                let max_results = tool_call
+                    .function
+                    .arguments
+                    .get("max_results")
+                    .and_then(|v| v.as_u64())
+                    .map_or(10, |v| v as usize);
+
+                let Some(client) = &self.feeds else {
+                    return Ok("Error: Feed reading is not available".to_string());
+                };
+
+                info!("Executing fetch_feed: url='{}'", url);
+
+                match client.fetch(url, max_results).await {
+                    Ok(found) => Ok(sanitize(&feed::format_feed(&found))),
+                    Err(e) => Ok(format!("Error: Could not read feed: {}", e)),
+                }
+            }
+            "read_file" => {
+                let path = tool_call.function.arguments["path"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'path' field in read_file".to_string(),
+                        )
+                    })?;
+                let start_line = tool_call
+                    .function
+                    .arguments
+                    .get("start_line")
+                    .and_then(|v| v.as_u64())
+                    .map_or(1, |v| v as usize);
+
+                let Some(files) = &self.files else {
+                    return Ok("Error: File access is not enabled".to_string());
+                };
+
+                info!(
+                    "Executing read_file: path='{}', start_line={}",
+                    path, start_line
+                );
+
+                match files.read(path, start_line) {
+                    Ok(content) => Ok(sanitize(&content)),
+                    Err(OllamaError::IoError(e)) if e.kind() == io::ErrorKind::PermissionDenied => {
+                        audit::policy("deny", "read_file", path, &e.to_string());
+                        Ok(format!("Error: {}", e))
+                    }
+                    Err(e) => Ok(format!("Error: {}", e)),
+                }
+            }
+            "current_datetime" => {
+                info!("Executing current_datetime");
+                Ok(format_datetime(&Local::now()))
+            }
+            "calculate" => {
+                let expression = tool_call.function.arguments["expression"]
+                    .as_str()
+                    .ok_or_else(|| {
+                        crate::error::OllamaError::InvalidResponse(
+                            "Missing 'expression' field in calculate".to_string(),
+                        )
+                    })?;
+
+                info!("Executing calculate: expression='{}'", expression);
+
+                Ok(calc::calculate(expression).unwrap_or_else(|e| format!("Error: {}", e)))
+            }
+            "save_note" => {
+                let args = &tool_call.function.arguments;
+                let content = args["content"].as_str().ok_or_else(|| {
+                    crate::error::OllamaError::InvalidResponse(
+                        "Missing 'content' field in save_note".to_string(),
+                    )
+                })?;
+                let topic = args["topic"].as_str().unwrap_or("");
+                let source = args.get("source").and_then(|v| v.as_str());
+
+                info!("Executing save_note: topic='{}'", topic);
+
+                match self.scratchpad.save(topic, content, source) {
+                    Ok(count) => Ok(format!("Saved as note #{}.", count)),
+                    Err(e) => Ok(format!("Error: Could not save note: {}", e)),
+                }
+            }
+            "list_notes" => {
+                let topic = tool_call
+                    .function
+                    .arguments
+                    .get("topic")
+                    .and_then(|v| v.as_str())
+                    .filter(|topic| !topic.trim().is_empty());
+
+                info!("Executing list_notes: topic={:?}", topic);
+
+                Ok(sanitize(&scratchpad::format_notes(
+                    &self.scratchpad.notes(topic),
+                )))
+            }
             _ => {
                 warn!("Unknown tool: {}", tool_call.function.name);
                 Ok(format!("Error: Unknown tool '{}'", tool_call.function.name))
             }
         }
     }
-}
 
-fn truncate_utf8(s: &str, max_bytes: usize) -> &str {
-    if s.len() <= max_bytes {
-        return s;
+    async fn translated(&self, content: &str) -> (String, String) {
+        let untouched = ("Content".to_string(), content.to_string());
+        let Some(language) = self.language.as_ref().filter(|_| self.translate_sources) else {
+            return untouched;
+        };
+        if language.is_english() || !translate::looks_english(content) {
+            return untouched;
+        }
+
+        info!("Translating fetched page into {}", language.name());
+        match translate::translate(self.chat.as_ref(), &self.model, language, content).await {
+            Ok(translation) if !translation.trim().is_empty() => (
+                format!(
+                    "Content (translated from English into {} by {}; check quotes against the original)",
+                    language.name(),
+                    self.model
+                ),
+                translation,
+            ),
+            Ok(_) => untouched,
+            Err(e) => {
+                warn!("Failed to translate fetched page: {}", e);
+                untouched
+            }
+        }
     }
 
-    let mut end = max_bytes;
-    while end > 0 && !s.is_char_boundary(end) {
-        end -= 1;
+    async fn learn_from(&self, url: &str, response: &FetchResponse) {
+        let Some(kb) = &self.learn else {
+            return;
+        };
+        if response.no_archive {
+            info!("Not learning from {}: the page opts out of archiving", url);
+            audit::policy("skip", "learn", url, "page opts out of archiving");
+            return;
+        }
+
+        let source = response.canonical_url.as_deref().unwrap_or(url);
+        let title = if response.title.is_empty() {
+            source
+        } else {
+            &response.title
+        };
+        let metadata = kb::Metadata {
+            url: Some(source.to_string()),
+            fetched_at: Some(store::now()),
+        };
+        if let Err(e) = kb
+            .add(
+                source,
+                title,
+                &response.content,
+                &metadata,
+                &ChunkOptions::default(),
+            )
+            .await
+        {
+            warn!("Failed to learn from {}: {}", source, e);
+        }
     }
-    &s[..end]
+}
+
+fn format_datetime<Tz: TimeZone>(now: &DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    format!(
+        "Date: {}\nTime: {}\nTimezone: UTC{}\nISO 8601: {}\nUTC: {}\nUnix timestamp: {}",
+        now.format("%A, %B %-d, %Y"),
+        now.format("%H:%M:%S"),
+        now.format("%:z"),
+        now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        now.with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+        now.timestamp()
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::{SearchResponse, SearchResult};
+    use crate::config::Config;
+    use crate::ollama_local::{ChatResponse, Tool};
+    use crate::permissions::Capability;
+    use async_trait::async_trait;
+    use serde_json::Value;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use tokio::runtime::Runtime;
+
+    #[derive(Clone, Default)]
+    struct ScriptedModel {
+        replies: Arc<Mutex<VecDeque<Value>>>,
+        requests: Arc<Mutex<Vec<Vec<Value>>>>,
+        repeat_last: bool,
+    }
+
+    impl ScriptedModel {
+        fn new(replies: Vec<Value>) -> Self {
+            Self {
+                replies: Arc::new(Mutex::new(replies.into())),
+                ..Self::default()
+            }
+        }
+
+        fn repeating(reply: Value) -> Self {
+            Self {
+                repeat_last: true,
+                ..Self::new(vec![reply])
+            }
+        }
+
+        fn requests(&self) -> Vec<Vec<Value>> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl ChatBackend for ScriptedModel {
+        async fn chat(
+            &self,
+            _model: &str,
+            messages: Vec<Value>,
+            _tools: Option<Vec<Tool>>,
+            _think: bool,
+        ) -> Result<ChatResponse> {
+            self.requests.lock().unwrap().push(messages);
+            let mut replies = self.replies.lock().unwrap();
+            let reply = match (replies.len(), self.repeat_last) {
+                (1, true) => replies.front().cloned(),
+                _ => replies.pop_front(),
+            }
+            .ok_or_else(|| {
+                crate::error::OllamaError::InvalidResponse("script exhausted".to_string())
+            })?;
+            Ok(serde_json::from_value(reply)?)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct FakeSearch {
+        queries: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl SearchProvider for FakeSearch {
+        fn name(&self) -> &str {
+            "fake"
+        }
+
+        async fn search(&self, query: &str, _max_results: Option<usize>) -> Result<SearchResponse> {
+            self.queries.lock().unwrap().push(query.to_string());
+            Ok(SearchResponse {
+                results: vec![SearchResult {
+                    title: "Tokio tutorial".to_string(),
+                    url: "https://tokio.rs/tokio/tutorial".to_string(),
+                    content: "Tokio is an asynchronous runtime for Rust.".to_string(),
+                    ..Default::default()
+                }],
+            })
+        }
+    }
+
+    fn reply(content: &str) -> Value {
+        json!({"message": {"role": "assistant", "content": content}, "done": true})
+    }
+
+    fn tool_call(name: &str, arguments: Value) -> Value {
+        json!({
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{"function": {"name": name, "arguments": arguments}}]
+            },
+            "done": true
+        })
+    }
+
+    fn agent(model: &ScriptedModel, search: &FakeSearch, max_iterations: usize) -> Agent {
+        let config = Config::new("test_key".to_string());
+        Agent::new(
+            Box::new(model.clone()),
+            OllamaClient::new(config).unwrap(),
+            Box::new(search.clone()),
+            AgentOptions {
+                model: "scripted".to_string(),
+                max_iterations,
+                show_thinking: true,
+                enable_reasoning: false,
+            },
+        )
+    }
+
+    #[test]
+    fn test_runs_tool_calls_and_returns_final_answer() {
+        let model = ScriptedModel::new(vec![
+            tool_call("web_search", json!({"query": "tokio runtime"})),
+            tool_call("not_a_tool", json!({})),
+            reply("Tokio is an async runtime [1]."),
+        ]);
+        let search = FakeSearch::default();
+
+        let answer = Runtime::new()
+            .unwrap()
+            .block_on(agent(&model, &search, 5).run("what is tokio"))
+            .unwrap();
+
+        assert_eq!(answer, "Tokio is an async runtime [1].");
+        assert_eq!(*search.queries.lock().unwrap(), vec!["tokio runtime"]);
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[0],
+            vec![json!({"role": "user", "content": "what is tokio"})]
+        );
+        let search_result = requests[1][2]["content"].as_str().unwrap();
+        assert!(search_result.contains("URL: https://tokio.rs/tokio/tutorial"));
+        assert_eq!(
+            requests[2][4]["content"],
+            "Error: Unknown tool 'not_a_tool'"
+        );
+    }
+
+    #[test]
+    fn test_handles_malformed_model_output() {
+        let model = ScriptedModel::new(vec![
+            json!({"message": {"role": "assistant", "content": "Done.", "tool_calls": []}, "done": true}),
+        ]);
+        let answer = Runtime::new()
+            .unwrap()
+            .block_on(agent(&model, &FakeSearch::default(), 5).run("q"))
+            .unwrap();
+        assert_eq!(answer, "Done.");
+
+        let model = ScriptedModel::new(vec![tool_call("web_search", json!({"q": "typo"}))]);
+        let error = Runtime::new()
+            .unwrap()
+            .block_on(agent(&model, &FakeSearch::default(), 5).run("q"))
+            .unwrap_err();
+        assert!(error.to_string().contains("Missing 'query' field"));
+
+        let model = ScriptedModel::new(vec![json!({"message": {"content": "no role"}})]);
+        assert!(Runtime::new()
+            .unwrap()
+            .block_on(agent(&model, &FakeSearch::default(), 5).run("q"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_stops_at_max_iterations() {
+        let model = ScriptedModel::repeating(tool_call("web_search", json!({"query": "loop"})));
+        let search = FakeSearch::default();
+
+        let answer = Runtime::new()
+            .unwrap()
+            .block_on(agent(&model, &search, 3).run("never finishes"))
+            .unwrap();
+
+        assert!(answer.starts_with("Reached maximum iterations (3) while processing tool response"));
+        assert_eq!(model.requests().len(), 3);
+        assert_eq!(search.queries.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_dry_run_stubs_tools_that_spend_quota() {
+        let model = ScriptedModel::new(vec![
+            tool_call("web_search", json!({"query": "tokio runtime"})),
+            tool_call("calculate", json!({"expression": "2 + 2"})),
+            reply("Planned."),
+        ]);
+        let search = FakeSearch::default();
+        let dry = agent(&model, &search, 5).with_dry_run(true);
+
+        let answer = Runtime::new().unwrap().block_on(dry.run("q")).unwrap();
+        assert_eq!(answer, "Planned.");
+        assert!(search.queries.lock().unwrap().is_empty());
+
+        let requests = model.requests();
+        assert_eq!(
+            requests[1].last().unwrap()["content"],
+            "[dry-run: web_search not executed]"
+        );
+        assert!(requests[2].last().unwrap()["content"]
+            .as_str()
+            .unwrap()
+            .contains('4'));
+    }
+
+    #[test]
+    fn test_domain_limit_requires_justification() {
+        let model = ScriptedModel::default();
+        let limited = agent(&model, &FakeSearch::default(), 5).with_max_domains(Some(2));
+
+        assert!(limited
+            .check_domain("https://tokio.rs/tokio", None)
+            .is_none());
+        assert!(limited
+            .check_domain("https://www.docs.rs/tokio", None)
+            .is_none());
+        assert!(limited.check_domain("https://docs.rs/smol", None).is_none());
+
+        let denial = limited
+            .check_domain("https://github.com/tokio-rs", Some("  "))
+            .unwrap();
+        assert!(denial.starts_with("Policy: this run is limited to 2 domains (tokio.rs, docs.rs)"));
+        assert!(limited
+            .check_domain(
+                "https://github.com/tokio-rs",
+                Some("Release notes live there")
+            )
+            .is_none());
+        assert!(limited
+            .check_domain("https://github.com/smol-rs", None)
+            .is_none());
+
+        let unlimited = agent(&model, &FakeSearch::default(), 5);
+        assert!(unlimited.check_domain("https://a.dev", None).is_none());
+    }
 
     #[test]
-    fn test_truncate_utf8_agent() {
-        let text = "Hello 🌍 World!";
-        let result = truncate_utf8(text, 10);
-        assert!(result.len() <= 10);
-        assert!(result.is_char_boundary(result.len()));
+    fn test_current_datetime_tool_reports_local_time() {
+        let model = ScriptedModel::new(vec![
+            tool_call("current_datetime", json!({})),
+            reply("It is 2024."),
+        ]);
+        Runtime::new()
+            .unwrap()
+            .block_on(agent(&model, &FakeSearch::default(), 5).run("what year is it"))
+            .unwrap();
+        let requests = model.requests();
+        let tool_result = requests[1].last().unwrap()["content"].as_str().unwrap();
+        assert!(tool_result.contains("Timezone: UTC"));
+
+        let now = chrono::FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 3, 9, 14, 5, 0)
+            .unwrap();
+        assert_eq!(
+            format_datetime(&now),
+            "Date: Saturday, March 9, 2024\nTime: 14:05:00\nTimezone: UTC+02:00\n\
+             ISO 8601: 2024-03-09T14:05:00+02:00\nUTC: 2024-03-09T12:05:00Z\nUnix timestamp: 1709985900"
+        );
     }
 
     #[test]
-    fn test_truncate_utf8_large_result() {
-        let text = "a".repeat(10000);
-        let result = truncate_utf8(&text, 8000);
-        assert_eq!(result.len(), 8000);
+    fn test_read_file_requires_permission() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.md"), "tokio notes").unwrap();
+        let sandbox = || FileSandbox::new(&[dir.path().to_path_buf()]).unwrap();
+        let script = || {
+            ScriptedModel::new(vec![
+                tool_call("read_file", json!({"path": "notes.md"})),
+                reply("Done."),
+            ])
+        };
+
+        let model = script();
+        let denied = agent(&model, &FakeSearch::default(), 5).with_files(sandbox());
+        Runtime::new().unwrap().block_on(denied.run("q")).unwrap();
+        let requests = model.requests();
+        let tool_result = requests[1].last().unwrap()["content"].as_str().unwrap();
+        assert!(tool_result.starts_with("Policy: the read_file tool needs"));
+
+        let model = script();
+        let granted = agent(&model, &FakeSearch::default(), 5)
+            .with_files(sandbox())
+            .with_permissions(Permissions::new([Capability::ReadFile]));
+        Runtime::new().unwrap().block_on(granted.run("q")).unwrap();
+        let requests = model.requests();
+        let tool_result = requests[1].last().unwrap()["content"].as_str().unwrap();
+        assert!(tool_result.ends_with("    1  tokio notes\n"));
+    }
+
+    #[test]
+    fn test_scratchpad_notes_survive_max_iterations() {
+        let model = ScriptedModel::new(vec![
+            tool_call(
+                "save_note",
+                json!({"topic": "runtimes", "content": "tokio is work-stealing", "source": "https://tokio.rs"}),
+            ),
+            tool_call("list_notes", json!({})),
+        ]);
+
+        let answer = Runtime::new()
+            .unwrap()
+            .block_on(agent(&model, &FakeSearch::default(), 2).run("compare runtimes"))
+            .unwrap();
+
+        let requests = model.requests();
+        assert_eq!(requests[1].last().unwrap()["content"], "Saved as note #1.");
+        assert!(answer.starts_with("Reached maximum iterations (2) while processing tool response"));
+        assert!(answer.ends_with(
+            "Notes gathered so far:\n\n1 note:\n\n## runtimes\n- tokio is work-stealing (https://tokio.rs)\n"
+        ));
     }
 }
@@ -0,0 +1,275 @@
+use crate::cli::Cli;
+use crate::error::{OllamaError, Result};
+use crate::settings::Alias;
+use clap::CommandFactory;
+use std::ffi::OsString;
+
+const AGENT: &str = "agent";
+
+enum Part {
+    Flag(Vec<OsString>, bool),
+    Value(OsString),
+}
+
+pub fn expand(args: Vec<OsString>, aliases: &[Alias]) -> Result<Vec<OsString>> {
+    if aliases.is_empty() || args.is_empty() {
+        return Ok(args);
+    }
+    let mut command = Cli::command();
+    command.build();
+    let agent = command
+        .find_subcommand(AGENT)
+        .cloned()
+        .unwrap_or_else(|| clap::Command::new(AGENT));
+
+    let Some(position) = first_value(&command, &args) else {
+        return Ok(args);
+    };
+    let Some(name) = args[position].to_str() else {
+        return Ok(args);
+    };
+    if command.find_subcommand(name).is_some() {
+        return Ok(args);
+    }
+    let Some(alias) = aliases.iter().find(|alias| alias.name == name) else {
+        return Ok(args);
+    };
+
+    let flags: Vec<OsString> = alias.flags.iter().map(OsString::from).collect();
+    let mut top = Vec::new();
+    let mut sub = Vec::new();
+    let mut values = Vec::new();
+    for part in scan(&command, &agent, &flags).into_iter().chain(scan(
+        &command,
+        &agent,
+        &args[position + 1..],
+    )) {
+        match part {
+            Part::Flag(flag, for_agent) if alias.agent && for_agent => sub.extend(flag),
+            Part::Flag(flag, _) => top.extend(flag),
+            Part::Value(value) => values.push(value.to_string_lossy().into_owned()),
+        }
+    }
+
+    let mut expanded = args[..position].to_vec();
+    expanded.extend(top);
+    if alias.agent {
+        expanded.push(AGENT.into());
+    }
+    expanded.extend(sub);
+    expanded.push("--".into());
+    expanded.push(render(alias, &values)?.into());
+    Ok(expanded)
+}
+
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.starts_with('-') || name.contains(char::is_whitespace) {
+        return Err(OllamaError::ConfigError(format!(
+            "'{}' is not a valid alias name",
+            name
+        )));
+    }
+    if Cli::command().find_subcommand(name).is_some() {
+        return Err(OllamaError::ConfigError(format!(
+            "'{}' is a weavex command and cannot be used as an alias",
+            name
+        )));
+    }
+    Ok(())
+}
+
+pub fn render(alias: &Alias, values: &[String]) -> Result<String> {
+    let mut query = String::with_capacity(alias.query.len());
+    let mut used = vec![false; values.len()];
+    let mut rest = alias.query.as_str();
+    while let Some(start) = rest.find('{') {
+        query.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let placeholder = after
+            .find('}')
+            .map(|end| (&after[..end], &after[end + 1..]));
+        match placeholder {
+            Some(("*", remainder)) => {
+                query.push_str(&values.join(" "));
+                used.fill(true);
+                rest = remainder;
+            }
+            Some((number, remainder))
+                if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                let index = number.parse::<usize>().unwrap_or_default();
+                let value = index
+                    .checked_sub(1)
+                    .and_then(|i| values.get(i).map(|value| (i, value)))
+                    .ok_or_else(|| {
+                        OllamaError::ConfigError(format!(
+                            "Alias '{}' needs at least {} argument(s): {}",
+                            alias.name, index, alias.query
+                        ))
+                    })?;
+                query.push_str(value.1);
+                used[value.0] = true;
+                rest = remainder;
+            }
+            _ => {
+                query.push('{');
+                rest = after;
+            }
+        }
+    }
+    query.push_str(rest);
+
+    for (value, _) in values.iter().zip(used).filter(|(_, used)| !used) {
+        query.push(' ');
+        query.push_str(value);
+    }
+    Ok(query)
+}
+
+fn first_value(command: &clap::Command, args: &[OsString]) -> Option<usize> {
+    let mut index = 1;
+    while let Some(token) = args.get(index).and_then(|arg| arg.to_str()) {
+        if token == "--" {
+            return None;
+        }
+        if !token.starts_with('-') || token.len() == 1 {
+            return Some(index);
+        }
+        index += if find(command, token).is_some_and(takes_separate_value) {
+            2
+        } else {
+            1
+        };
+    }
+    None
+}
+
+fn scan(command: &clap::Command, agent: &clap::Command, args: &[OsString]) -> Vec<Part> {
+    let mut parts = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(token) = arg
+            .to_str()
+            .filter(|token| token.starts_with('-') && token.len() > 1)
+        else {
+            parts.push(Part::Value(arg.clone()));
+            continue;
+        };
+        if token == "--" {
+            parts.extend(args.by_ref().cloned().map(Part::Value));
+            break;
+        }
+        let for_agent = find(agent, token).is_some();
+        let mut flag = vec![arg.clone()];
+        let known = find(agent, token).or_else(|| find(command, token));
+        if known.is_some_and(takes_separate_value) {
+            flag.extend(args.next().cloned());
+        }
+        parts.push(Part::Flag(flag, for_agent));
+    }
+    parts
+}
+
+fn find<'a>(command: &'a clap::Command, token: &str) -> Option<(&'a clap::Arg, bool)> {
+    if let Some(long) = token.strip_prefix("--") {
+        let (long, inline) = match long.split_once('=') {
+            Some((long, _)) => (long, true),
+            None => (long, false),
+        };
+        let arg = command.get_arguments().find(|arg| {
+            arg.get_long() == Some(long)
+                || arg
+                    .get_all_aliases()
+                    .is_some_and(|aliases| aliases.contains(&long))
+        })?;
+        return Some((arg, inline));
+    }
+    let mut shorts = token[1..].chars();
+    let short = shorts.next()?;
+    let arg = command
+        .get_arguments()
+        .find(|arg| arg.get_short() == Some(short))?;
+    Some((arg, shorts.next().is_some()))
+}
+
+fn takes_separate_value((arg, inline): (&clap::Arg, bool)) -> bool {
+    !inline && arg.get_action().takes_values() && !arg.is_require_equals_set()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(agent: bool, flags: &[&str]) -> Alias {
+        Alias {
+            name: "cve-rust".to_string(),
+            query: "recent security advisories for {1}".to_string(),
+            agent,
+            flags: flags.iter().map(|flag| flag.to_string()).collect(),
+        }
+    }
+
+    fn expand_strs(args: &[&str], aliases: &[Alias]) -> Vec<String> {
+        let args = args.iter().map(OsString::from).collect();
+        expand(args, aliases)
+            .unwrap()
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_expands_alias_and_places_flags() {
+        let aliases = [alias(
+            true,
+            &["--max-results", "5", "--max-iterations", "8"],
+        )];
+        assert_eq!(
+            expand_strs(
+                &[
+                    "weavex",
+                    "--no-preview",
+                    "cve-rust",
+                    "tokio",
+                    "--model",
+                    "qwen3"
+                ],
+                &aliases
+            ),
+            [
+                "weavex",
+                "--no-preview",
+                "--max-results",
+                "5",
+                "agent",
+                "--max-iterations",
+                "8",
+                "--model",
+                "qwen3",
+                "--",
+                "recent security advisories for tokio",
+            ]
+        );
+
+        let plain = ["weavex", "agent", "cve-rust"];
+        assert_eq!(expand_strs(&plain, &aliases), plain);
+        let search = ["weavex", "--no-preview", "rust news"];
+        assert_eq!(expand_strs(&search, &aliases), search);
+    }
+
+    #[test]
+    fn test_renders_placeholders_and_appends_extra_values() {
+        let mut cve = alias(false, &[]);
+        let values = ["tokio".to_string(), "2026".to_string()];
+        assert_eq!(
+            render(&cve, &values).unwrap(),
+            "recent security advisories for tokio 2026"
+        );
+        assert!(render(&cve, &[]).is_err());
+
+        cve.query = "{*} in {json}".to_string();
+        assert_eq!(render(&cve, &values).unwrap(), "tokio 2026 in {json}");
+        assert!(validate_name("agent").is_err());
+        assert!(validate_name("cve-rust").is_ok());
+    }
+}
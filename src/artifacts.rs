@@ -0,0 +1,278 @@
+use crate::error::{OllamaError, Result};
+use crate::store::{self, Store};
+use rusqlite::{params, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use tracing::debug;
+
+const COMPRESSION_LEVEL: i32 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Owner {
+    History,
+    Kb,
+}
+
+impl Owner {
+    fn as_str(self) -> &'static str {
+        match self {
+            Owner::History => "history",
+            Owner::Kb => "kb",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    pub artifacts: usize,
+    pub references: usize,
+    pub raw_bytes: u64,
+    pub stored_bytes: u64,
+}
+
+pub fn hash(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+pub fn put(
+    store: &Store,
+    owner: Owner,
+    owner_id: i64,
+    data: &[u8],
+    media_type: &str,
+) -> Result<String> {
+    let hash = hash(data);
+    let path = path_for(store, &hash)?;
+
+    let stored_size = if path.exists() {
+        debug!("Artifact {} already stored", hash);
+        std::fs::metadata(&path)?.len()
+    } else {
+        let compressed = zstd::encode_all(data, COMPRESSION_LEVEL)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, &compressed)?;
+        std::fs::rename(&partial, &path)?;
+        debug!(
+            "Stored artifact {} ({} -> {} bytes)",
+            hash,
+            data.len(),
+            compressed.len()
+        );
+        compressed.len() as u64
+    };
+
+    store.conn().execute(
+        "INSERT INTO artifacts (hash, media_type, size, stored_size, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5) ON CONFLICT(hash) DO NOTHING",
+        params![
+            hash,
+            media_type,
+            data.len() as i64,
+            stored_size as i64,
+            store::now()
+        ],
+    )?;
+    store.conn().execute(
+        "INSERT INTO artifact_refs (hash, owner, owner_id) VALUES (?1, ?2, ?3)
+         ON CONFLICT DO NOTHING",
+        params![hash, owner.as_str(), owner_id],
+    )?;
+
+    Ok(hash)
+}
+
+pub fn load(store: &Store, hash: &str) -> Result<Vec<u8>> {
+    let compressed = std::fs::read(path_for(store, hash)?)?;
+    Ok(zstd::decode_all(compressed.as_slice())?)
+}
+
+pub fn hash_for(store: &Store, owner: Owner, owner_id: i64) -> Result<Option<String>> {
+    let hash = store
+        .conn()
+        .query_row(
+            "SELECT hash FROM artifact_refs WHERE owner = ?1 AND owner_id = ?2",
+            params![owner.as_str(), owner_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(hash)
+}
+
+pub fn load_for(store: &Store, owner: Owner, owner_id: i64) -> Result<Option<String>> {
+    match hash_for(store, owner, owner_id)? {
+        Some(hash) => {
+            let data = load(store, &hash)?;
+            Ok(Some(String::from_utf8_lossy(&data).into_owned()))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn release(store: &Store, owner: Owner, owner_id: i64) -> Result<()> {
+    store.conn().execute(
+        "DELETE FROM artifact_refs WHERE owner = ?1 AND owner_id = ?2",
+        params![owner.as_str(), owner_id],
+    )?;
+    collect_garbage(store)?;
+    Ok(())
+}
+
+pub fn collect_garbage(store: &Store) -> Result<usize> {
+    store.conn().execute_batch(
+        "DELETE FROM artifact_refs WHERE owner = 'history'
+             AND owner_id NOT IN (SELECT id FROM history);
+         DELETE FROM artifact_refs WHERE owner = 'kb'
+             AND owner_id NOT IN (SELECT id FROM kb_documents);",
+    )?;
+
+    let mut stmt = store
+        .conn()
+        .prepare("SELECT hash FROM artifacts WHERE hash NOT IN (SELECT hash FROM artifact_refs)")?;
+    let unreferenced = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for hash in &unreferenced {
+        match std::fs::remove_file(path_for(store, hash)?) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        store
+            .conn()
+            .execute("DELETE FROM artifacts WHERE hash = ?1", params![hash])?;
+    }
+
+    if !unreferenced.is_empty() {
+        debug!("Removed {} unreferenced artifact(s)", unreferenced.len());
+    }
+    Ok(unreferenced.len())
+}
+
+pub fn stats(store: &Store) -> Result<Stats> {
+    let (artifacts, raw_bytes, stored_bytes) = store.conn().query_row(
+        "SELECT COUNT(*), COALESCE(SUM(size), 0), COALESCE(SUM(stored_size), 0) FROM artifacts",
+        [],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        },
+    )?;
+    let references: i64 =
+        store
+            .conn()
+            .query_row("SELECT COUNT(*) FROM artifact_refs", [], |row| row.get(0))?;
+
+    Ok(Stats {
+        artifacts: artifacts as usize,
+        references: references as usize,
+        raw_bytes: raw_bytes as u64,
+        stored_bytes: stored_bytes as u64,
+    })
+}
+
+fn path_for(store: &Store, hash: &str) -> Result<PathBuf> {
+    if hash.len() < 3 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(OllamaError::InvalidResponse(format!(
+            "Invalid artifact hash '{}'",
+            hash
+        )));
+    }
+    Ok(store
+        .artifacts_dir()?
+        .join(&hash[..2])
+        .join(format!("{}.zst", &hash[2..])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history;
+
+    #[test]
+    fn test_identical_content_is_stored_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(&dir.path().join("weavex.db")).unwrap();
+        let page = "# Tokio\n\nAn asynchronous runtime.\n".repeat(50);
+
+        let first = history::record(
+            &store,
+            history::Kind::Fetch,
+            "https://tokio.rs",
+            "Tokio",
+            "",
+        )
+        .unwrap();
+        let second = history::record(
+            &store,
+            history::Kind::Fetch,
+            "https://tokio.rs",
+            "Tokio",
+            "",
+        )
+        .unwrap();
+        let hash_a = put(
+            &store,
+            Owner::History,
+            first,
+            page.as_bytes(),
+            "text/markdown",
+        )
+        .unwrap();
+        let hash_b = put(
+            &store,
+            Owner::History,
+            second,
+            page.as_bytes(),
+            "text/markdown",
+        )
+        .unwrap();
+        assert_eq!(hash_a, hash_b);
+
+        let stats = stats(&store).unwrap();
+        assert_eq!(stats.artifacts, 1);
+        assert_eq!(stats.references, 2);
+        assert!(stats.stored_bytes < stats.raw_bytes);
+        assert_eq!(
+            load_for(&store, Owner::History, second).unwrap().unwrap(),
+            page
+        );
+
+        release(&store, Owner::History, first).unwrap();
+        assert_eq!(stats_count(&store), 1);
+        release(&store, Owner::History, second).unwrap();
+        assert_eq!(stats_count(&store), 0);
+        assert!(!path_for(&store, &hash_a).unwrap().exists());
+    }
+
+    #[test]
+    fn test_garbage_collection_follows_deleted_owners() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(&dir.path().join("weavex.db")).unwrap();
+        let id = history::record(&store, history::Kind::Fetch, "u", "t", "").unwrap();
+        put(&store, Owner::History, id, b"content", "text/plain").unwrap();
+
+        store
+            .conn()
+            .execute("DELETE FROM history WHERE id = ?1", params![id])
+            .unwrap();
+        assert_eq!(collect_garbage(&store).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rejects_malformed_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(&dir.path().join("weavex.db")).unwrap();
+        assert!(load(&store, "../../etc/passwd").is_err());
+    }
+
+    fn stats_count(store: &Store) -> usize {
+        stats(store).unwrap().artifacts
+    }
+}
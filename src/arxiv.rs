@@ -0,0 +1,297 @@
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use chrono::DateTime;
+use clap::ValueEnum;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+const API_URL: &str = "https://export.arxiv.org/api/query";
+const TIMEOUT: Duration = Duration::from_secs(30);
+const USER_AGENT: &str = concat!(
+    "weavex/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/guitaripod/weavex)"
+);
+const FIELD_PREFIXES: &[&str] = &[
+    "ti:", "au:", "abs:", "co:", "jr:", "cat:", "rn:", "id:", "all:",
+];
+pub const MAX_RESULTS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum SortBy {
+    #[default]
+    Relevance,
+    Submitted,
+    Updated,
+}
+
+impl SortBy {
+    fn as_param(self) -> &'static str {
+        match self {
+            SortBy::Relevance => "relevance",
+            SortBy::Submitted => "submittedDate",
+            SortBy::Updated => "lastUpdatedDate",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Feed {
+    #[serde(default, rename = "entry")]
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Entry {
+    id: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    published: String,
+    #[serde(default, rename = "author")]
+    authors: Vec<Author>,
+    #[serde(default, rename = "link")]
+    links: Vec<Link>,
+    #[serde(default, rename = "category")]
+    categories: Vec<Category>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(default, rename = "@title")]
+    title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Category {
+    #[serde(rename = "@term")]
+    term: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Paper {
+    pub id: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub published: String,
+    pub categories: Vec<String>,
+    pub summary: String,
+    pub url: String,
+    pub pdf_url: String,
+}
+
+pub struct ArxivClient {
+    client: Client,
+}
+
+impl ArxivClient {
+    pub fn new() -> Result<Self> {
+        let client = network::client_builder()
+            .timeout(TIMEOUT)
+            .user_agent(network::user_agent(USER_AGENT))
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self { client })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn search(&self, query: &str, limit: usize, sort: SortBy) -> Result<Vec<Paper>> {
+        let search_query = search_query(query);
+        let limit = limit.clamp(1, MAX_RESULTS).to_string();
+        debug!("Querying arXiv: {}", search_query);
+
+        let response = self
+            .client
+            .get(API_URL)
+            .query(&[
+                ("search_query", search_query.as_str()),
+                ("start", "0"),
+                ("max_results", limit.as_str()),
+                ("sortBy", sort.as_param()),
+                ("sortOrder", "descending"),
+            ])
+            .send_audited()
+            .await?;
+        let status = response.status();
+        let papers = parse_feed(&response.text().await?);
+
+        if !status.is_success() {
+            return Err(papers.err().unwrap_or(OllamaError::ApiError {
+                status: status.as_u16(),
+                message: "arXiv request failed".to_string(),
+            }));
+        }
+        papers
+    }
+}
+
+pub fn search_query(query: &str) -> String {
+    let query = query.trim();
+    let has_fields = query
+        .split_whitespace()
+        .any(|term| FIELD_PREFIXES.iter().any(|prefix| term.starts_with(prefix)));
+    if has_fields {
+        return query.to_string();
+    }
+
+    query
+        .split_whitespace()
+        .map(|term| format!("all:{}", term))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+pub fn parse_feed(body: &str) -> Result<Vec<Paper>> {
+    let feed: Feed = quick_xml::de::from_str(body)
+        .map_err(|e| OllamaError::InvalidResponse(format!("Invalid arXiv response: {}", e)))?;
+
+    let mut papers = Vec::new();
+    for entry in feed.entries {
+        if !entry.id.contains("arxiv.org/abs/") {
+            let message = collapse_whitespace(&entry.summary);
+            return Err(OllamaError::ApiError {
+                status: 400,
+                message: format!("arXiv rejected the query: {}", message),
+            });
+        }
+        papers.push(paper(entry));
+    }
+    Ok(papers)
+}
+
+fn paper(entry: Entry) -> Paper {
+    let id = entry
+        .id
+        .rsplit("/abs/")
+        .next()
+        .unwrap_or(&entry.id)
+        .to_string();
+    let pdf_url = entry
+        .links
+        .iter()
+        .find(|link| link.title.as_deref() == Some("pdf"))
+        .map(|link| link.href.replacen("http://", "https://", 1))
+        .unwrap_or_else(|| format!("https://arxiv.org/pdf/{}", id));
+
+    Paper {
+        url: format!("https://arxiv.org/abs/{}", id),
+        pdf_url,
+        title: collapse_whitespace(&entry.title),
+        authors: entry.authors.into_iter().map(|a| a.name).collect(),
+        published: DateTime::parse_from_rfc3339(&entry.published)
+            .map(|at| at.format("%Y-%m-%d").to_string())
+            .unwrap_or(entry.published),
+        categories: entry.categories.into_iter().map(|c| c.term).collect(),
+        summary: collapse_whitespace(&entry.summary),
+        id,
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn format_papers(papers: &[Paper]) -> String {
+    if papers.is_empty() {
+        return "No arXiv papers found.".to_string();
+    }
+
+    let mut output = String::new();
+    for (idx, paper) in papers.iter().enumerate() {
+        output.push_str(&format!("{}. {}\n", idx + 1, paper.title));
+        output.push_str(&format!("   Authors: {}\n", authors(&paper.authors)));
+        output.push_str(&format!(
+            "   Published: {} · {}\n",
+            paper.published,
+            paper.categories.join(", ")
+        ));
+        output.push_str(&format!("   URL: {}\n", paper.url));
+        output.push_str(&format!("   PDF: {}\n", paper.pdf_url));
+        output.push_str(&format!("   Abstract: {}\n\n", paper.summary));
+    }
+    output
+}
+
+fn authors(authors: &[String]) -> String {
+    match authors.len() {
+        0..=5 => authors.join(", "),
+        n => format!("{} et al. ({} authors)", authors[..3].join(", "), n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="html">ArXiv Query: search_query=all:raft</title>
+  <opensearch:totalResults xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">1</opensearch:totalResults>
+  <entry>
+    <id>http://arxiv.org/abs/2101.00001v2</id>
+    <updated>2021-02-01T00:00:00Z</updated>
+    <published>2021-01-01T10:30:00Z</published>
+    <title>Consensus in
+      Raft</title>
+    <summary>  We study   consensus.
+    </summary>
+    <author><name>Diego Ongaro</name></author>
+    <author><name>John Ousterhout</name></author>
+    <link href="http://arxiv.org/abs/2101.00001v2" rel="alternate" type="text/html"/>
+    <link title="pdf" href="http://arxiv.org/pdf/2101.00001v2" rel="related" type="application/pdf"/>
+    <arxiv:primary_category xmlns:arxiv="http://arxiv.org/schemas/atom" term="cs.DC" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.DC" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.OS" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_feed() {
+        let papers = parse_feed(FEED).unwrap();
+        assert_eq!(
+            papers,
+            vec![Paper {
+                id: "2101.00001v2".to_string(),
+                title: "Consensus in Raft".to_string(),
+                authors: vec!["Diego Ongaro".to_string(), "John Ousterhout".to_string()],
+                published: "2021-01-01".to_string(),
+                categories: vec!["cs.DC".to_string(), "cs.OS".to_string()],
+                summary: "We study consensus.".to_string(),
+                url: "https://arxiv.org/abs/2101.00001v2".to_string(),
+                pdf_url: "https://arxiv.org/pdf/2101.00001v2".to_string(),
+            }]
+        );
+
+        let empty = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>none</title></feed>"#;
+        assert!(parse_feed(empty).unwrap().is_empty());
+
+        let error = r#"<feed xmlns="http://www.w3.org/2005/Atom"><entry>
+            <id>http://arxiv.org/api/errors#incorrect_id_format</id>
+            <title>Error</title><summary>incorrect id format</summary></entry></feed>"#;
+        assert!(parse_feed(error).is_err());
+    }
+
+    #[test]
+    fn test_search_query() {
+        assert_eq!(
+            search_query("  diffusion models  "),
+            "all:diffusion AND all:models"
+        );
+        assert_eq!(
+            search_query("au:hinton AND ti:capsule"),
+            "au:hinton AND ti:capsule"
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use crate::client::SearchResult;
+use crate::error::Result;
+use crate::feedback::domain_of;
+use crate::store::{self, Store};
+use clap::ValueEnum;
+use rusqlite::params;
+use std::collections::HashSet;
+
+const BOOST_POSITIONS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum BookmarkMode {
+    Off,
+    #[default]
+    Boost,
+    Pin,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub url: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub created_at: i64,
+}
+
+pub fn add(store: &Store, url: &str, title: &str, tags: &[String]) -> Result<()> {
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    store.conn().execute(
+        "INSERT INTO bookmarks (url, domain, title, tags, created_at) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(url) DO UPDATE SET title = excluded.title, tags = excluded.tags",
+        params![url, domain_of(url), title, tags.join(","), store::now()],
+    )?;
+    Ok(())
+}
+
+pub fn remove(store: &Store, url: &str) -> Result<bool> {
+    let removed = store
+        .conn()
+        .execute("DELETE FROM bookmarks WHERE url = ?1", params![url])?;
+    Ok(removed > 0)
+}
+
+pub fn list(store: &Store, tag: Option<&str>) -> Result<Vec<Bookmark>> {
+    let mut stmt = store
+        .conn()
+        .prepare("SELECT url, title, tags, created_at FROM bookmarks ORDER BY created_at DESC")?;
+    let rows = stmt.query_map([], |row| {
+        let tags: String = row.get(2)?;
+        Ok(Bookmark {
+            url: row.get(0)?,
+            title: row.get(1)?,
+            tags: tags
+                .split(',')
+                .filter(|t| !t.is_empty())
+                .map(String::from)
+                .collect(),
+            created_at: row.get(3)?,
+        })
+    })?;
+
+    let bookmarks = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(match tag.map(str::to_lowercase) {
+        Some(tag) => bookmarks
+            .into_iter()
+            .filter(|b| b.tags.contains(&tag))
+            .collect(),
+        None => bookmarks,
+    })
+}
+
+pub fn domains(store: &Store) -> Result<HashSet<String>> {
+    let mut stmt = store
+        .conn()
+        .prepare("SELECT DISTINCT domain FROM bookmarks")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    Ok(rows.collect::<rusqlite::Result<HashSet<_>>>()?)
+}
+
+pub fn apply_bookmarks(
+    results: &mut Vec<SearchResult>,
+    domains: &HashSet<String>,
+    mode: BookmarkMode,
+) {
+    if domains.is_empty() || mode == BookmarkMode::Off {
+        return;
+    }
+
+    let mut ranked: Vec<((bool, usize), SearchResult)> = results
+        .drain(..)
+        .enumerate()
+        .map(|(rank, result)| {
+            let adjusted = match (domains.contains(&domain_of(&result.url)), mode) {
+                (false, BookmarkMode::Pin) => (true, rank),
+                (false, _) => (false, rank),
+                (true, BookmarkMode::Pin) => (false, rank),
+                (true, _) => (false, rank.saturating_sub(BOOST_POSITIONS)),
+            };
+            (adjusted, result)
+        })
+        .collect();
+
+    ranked.sort_by_key(|(adjusted, _)| *adjusted);
+    results.extend(ranked.into_iter().map(|(_, result)| result));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(urls: &[&str]) -> Vec<SearchResult> {
+        urls.iter()
+            .map(|url| SearchResult {
+                title: url.to_string(),
+                url: url.to_string(),
+                content: String::new(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn urls(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.url.as_str()).collect()
+    }
+
+    #[test]
+    fn test_add_list_and_remove() {
+        let store = Store::open_in_memory().unwrap();
+        add(
+            &store,
+            "https://docs.rs/tokio",
+            "Tokio docs",
+            &["Rust".to_string(), " async ".to_string()],
+        )
+        .unwrap();
+        add(&store, "https://go.dev/doc", "Go docs", &[]).unwrap();
+
+        let tagged = list(&store, Some("rust")).unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].tags, vec!["rust", "async"]);
+        assert_eq!(list(&store, None).unwrap().len(), 2);
+        assert!(domains(&store).unwrap().contains("docs.rs"));
+
+        assert!(remove(&store, "https://go.dev/doc").unwrap());
+        assert!(!remove(&store, "https://go.dev/doc").unwrap());
+    }
+
+    #[test]
+    fn test_pin_moves_bookmarked_domains_to_top() {
+        let mut results = results(&[
+            "https://a.com/1",
+            "https://b.com/1",
+            "https://c.com/1",
+            "https://b.com/2",
+        ]);
+        let domains = HashSet::from(["b.com".to_string()]);
+
+        apply_bookmarks(&mut results, &domains, BookmarkMode::Pin);
+        assert_eq!(
+            urls(&results),
+            vec![
+                "https://b.com/1",
+                "https://b.com/2",
+                "https://a.com/1",
+                "https://c.com/1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_boost_and_off_modes() {
+        let original: Vec<String> = (0..8).map(|i| format!("https://s{}.com/", i)).collect();
+        let original: Vec<&str> = original.iter().map(String::as_str).collect();
+        let domains = HashSet::from(["s7.com".to_string()]);
+
+        let mut boosted = results(&original);
+        apply_bookmarks(&mut boosted, &domains, BookmarkMode::Boost);
+        assert_eq!(boosted[3].url, "https://s7.com/");
+
+        let mut untouched = results(&original);
+        apply_bookmarks(&mut untouched, &domains, BookmarkMode::Off);
+        assert_eq!(urls(&untouched), original);
+    }
+}
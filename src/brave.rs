@@ -0,0 +1,127 @@
+use crate::client::{SearchResponse, SearchResult};
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use crate::provider::SearchProvider;
+use crate::usage;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+const BRAVE_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+const BRAVE_MAX_COUNT: usize = 20;
+const BRAVE_MAX_PAGE: usize = 9;
+
+#[derive(Debug, Deserialize)]
+struct BraveResponse {
+    #[serde(default)]
+    web: Option<BraveWebResults>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveWebResults {
+    #[serde(default)]
+    results: Vec<BraveResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BraveResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    page_age: Option<String>,
+}
+
+pub struct BraveClient {
+    client: Client,
+    api_key: String,
+}
+
+impl BraveClient {
+    pub fn new(api_key: String, timeout: Duration) -> Result<Self> {
+        let client = network::client_builder()
+            .timeout(timeout)
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self { client, api_key })
+    }
+}
+
+#[async_trait]
+impl SearchProvider for BraveClient {
+    fn name(&self) -> &str {
+        "brave"
+    }
+
+    async fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse> {
+        self.search_page(query, max_results, 0).await
+    }
+
+    #[instrument(skip(self))]
+    async fn search_page(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+        offset: usize,
+    ) -> Result<SearchResponse> {
+        debug!("Sending search request to Brave");
+
+        let count = max_results.unwrap_or(10).clamp(1, BRAVE_MAX_COUNT);
+        let page = (offset / count).min(BRAVE_MAX_PAGE);
+        let (count_param, page_param) = (count.to_string(), page.to_string());
+
+        usage::check(usage::Kind::WebCalls)?;
+        usage::add(usage::Kind::WebCalls, 1);
+        let response = self
+            .client
+            .get(BRAVE_SEARCH_URL)
+            .header("X-Subscription-Token", &self.api_key)
+            .header("Accept", "application/json")
+            .query(&[
+                ("q", query),
+                ("count", count_param.as_str()),
+                ("offset", page_param.as_str()),
+            ])
+            .send_audited()
+            .await?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(OllamaError::ApiError {
+                status: status.as_u16(),
+                message: error_text,
+            });
+        }
+
+        let brave_response = response.json::<BraveResponse>().await.map_err(|e| {
+            OllamaError::InvalidResponse(format!("Failed to parse Brave response: {}", e))
+        })?;
+
+        let skip = offset.saturating_sub(page * count);
+        let results = brave_response
+            .web
+            .map(|web| web.results)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|result| SearchResult {
+                title: result.title,
+                url: result.url,
+                content: result.description,
+                published: result.page_age,
+                ..Default::default()
+            })
+            .skip(skip)
+            .collect();
+
+        Ok(SearchResponse { results })
+    }
+}
@@ -0,0 +1,221 @@
+use crate::audit;
+use crate::error::{OllamaError, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+pub const DEFAULT_THRESHOLD: u32 = 5;
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+static BREAKERS: OnceLock<Mutex<HashMap<String, Arc<CircuitBreaker>>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Closed,
+    Open { retry_in: Duration },
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    failures: u32,
+    opened_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    upstream: String,
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+pub fn shared(upstream: &str) -> Arc<CircuitBreaker> {
+    let breakers = BREAKERS.get_or_init(Default::default);
+    let mut breakers = breakers.lock().unwrap_or_else(|e| e.into_inner());
+    breakers
+        .entry(upstream.to_string())
+        .or_insert_with(|| Arc::new(CircuitBreaker::new(upstream)))
+        .clone()
+}
+
+impl CircuitBreaker {
+    pub fn new(upstream: &str) -> Self {
+        Self {
+            upstream: upstream.to_string(),
+            threshold: DEFAULT_THRESHOLD,
+            cooldown: DEFAULT_COOLDOWN,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = threshold.max(1);
+        self
+    }
+
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn status(&self) -> Status {
+        let state = self.lock();
+        match state.opened_at {
+            None => Status::Closed,
+            Some(opened_at) => match self.cooldown.checked_sub(opened_at.elapsed()) {
+                Some(retry_in) if !retry_in.is_zero() => Status::Open { retry_in },
+                _ => Status::HalfOpen,
+            },
+        }
+    }
+
+    pub async fn call<T, F>(&self, request: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        self.acquire()?;
+        let result = request.await;
+        match &result {
+            Err(e) if is_upstream_failure(e) => self.record_failure(),
+            _ => self.record_success(),
+        }
+        result
+    }
+
+    fn acquire(&self) -> Result<()> {
+        let mut state = self.lock();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+
+        let elapsed = opened_at.elapsed();
+        if elapsed < self.cooldown {
+            audit::policy(
+                "deny",
+                "request",
+                &self.upstream,
+                "circuit breaker open after repeated failures",
+            );
+            return Err(OllamaError::UpstreamDegraded {
+                upstream: self.upstream.clone(),
+                retry_in: (self.cooldown - elapsed).as_secs().max(1),
+            });
+        }
+
+        info!("Probing {} after circuit breaker cooldown", self.upstream);
+        state.opened_at = Some(Instant::now());
+        Ok(())
+    }
+
+    fn record_success(&self) {
+        let mut state = self.lock();
+        if state.opened_at.is_some() {
+            info!("{} recovered; circuit breaker closed", self.upstream);
+        }
+        *state = State::default();
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.lock();
+        state.failures += 1;
+        if state.failures >= self.threshold {
+            if state.opened_at.is_none() {
+                warn!(
+                    "{} failed {} times in a row; failing fast for {}s",
+                    self.upstream,
+                    state.failures,
+                    self.cooldown.as_secs()
+                );
+            }
+            state.opened_at = Some(Instant::now());
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn reset(&self) {
+        *self.lock() = State::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn is_upstream_failure(error: &OllamaError) -> bool {
+    match error {
+        OllamaError::RequestFailed(e) => !e.is_builder() && !e.is_decode(),
+        OllamaError::ApiError { status, .. } => *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_error() -> Result<()> {
+        Err(OllamaError::ApiError {
+            status: 503,
+            message: "unavailable".to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new("https://ollama.com/api")
+            .with_threshold(3)
+            .with_cooldown(Duration::from_secs(60));
+
+        for _ in 0..2 {
+            assert!(breaker.call(async { server_error() }).await.is_err());
+        }
+        assert!(breaker.call(async { Ok(()) }).await.is_ok());
+        for _ in 0..2 {
+            assert!(breaker.call(async { server_error() }).await.is_err());
+        }
+        assert_eq!(breaker.status(), Status::Closed);
+
+        let _ = breaker.call(async { server_error() }).await;
+        assert!(matches!(breaker.status(), Status::Open { .. }));
+        let err = breaker.call(async { Ok(()) }).await.unwrap_err();
+        assert!(err.to_string().contains("upstream degraded"));
+
+        let not_found = breaker
+            .call(async {
+                Err::<(), _>(OllamaError::ApiError {
+                    status: 404,
+                    message: "missing".to_string(),
+                })
+            })
+            .await;
+        assert!(matches!(
+            not_found,
+            Err(OllamaError::UpstreamDegraded { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_half_open_probe_closes_or_reopens() {
+        let breaker = CircuitBreaker::new("http://localhost:11434")
+            .with_threshold(1)
+            .with_cooldown(Duration::ZERO);
+
+        let _ = breaker.call(async { server_error() }).await;
+        assert_eq!(breaker.status(), Status::HalfOpen);
+        let _ = breaker.call(async { server_error() }).await;
+        assert_eq!(breaker.status(), Status::HalfOpen);
+
+        assert!(breaker.call(async { Ok(()) }).await.is_ok());
+        assert_eq!(breaker.status(), Status::Closed);
+
+        let client_error = OllamaError::ApiError {
+            status: 400,
+            message: "bad request".to_string(),
+        };
+        assert!(!is_upstream_failure(&client_error));
+        assert!(Arc::ptr_eq(&shared("http://a"), &shared("http://a")));
+    }
+}
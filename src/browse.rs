@@ -0,0 +1,311 @@
+use crate::chunking::ChunkOptions;
+use crate::client::{OllamaClient, SearchResult};
+use crate::feedback::domain_of;
+use crate::glyphs::Glyph;
+use crate::kb::{KnowledgeBase, Metadata};
+use crate::store;
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+const MAX_CONTEXT_CHARS: usize = 8000;
+const HELP: &str =
+    "j/k move · enter preview · J/K scroll · o open · y copy URL · s save to KB · a ask agent · q quit";
+
+pub enum Outcome {
+    Quit,
+    SendToAgent(String),
+}
+
+struct App {
+    query: String,
+    results: Vec<SearchResult>,
+    list_state: ListState,
+    previews: HashMap<usize, String>,
+    scroll: u16,
+    status: String,
+}
+
+impl App {
+    fn new(query: &str, results: Vec<SearchResult>) -> Self {
+        Self {
+            query: query.to_string(),
+            results,
+            list_state: ListState::default().with_selected(Some(0)),
+            previews: HashMap::new(),
+            scroll: 0,
+            status: HELP.to_string(),
+        }
+    }
+
+    fn selected(&self) -> usize {
+        self.list_state.selected().unwrap_or(0)
+    }
+
+    fn selected_result(&self) -> &SearchResult {
+        &self.results[self.selected()]
+    }
+
+    fn select_next(&mut self) {
+        let next = (self.selected() + 1) % self.results.len();
+        self.list_state.select(Some(next));
+        self.scroll = 0;
+    }
+
+    fn select_previous(&mut self) {
+        let len = self.results.len();
+        let previous = (self.selected() + len - 1) % len;
+        self.list_state.select(Some(previous));
+        self.scroll = 0;
+    }
+
+    async fn load_preview(&mut self, client: &OllamaClient) {
+        let idx = self.selected();
+        if self.previews.contains_key(&idx) {
+            return;
+        }
+
+        match client.fetch(&self.results[idx].url).await {
+            Ok(response) => {
+                self.previews.insert(idx, response.content);
+                self.status = HELP.to_string();
+            }
+            Err(e) => self.status = format!("Fetch failed: {}", e),
+        }
+    }
+
+    async fn save_to_kb(&mut self, kb: &KnowledgeBase) {
+        let idx = self.selected();
+        let result = &self.results[idx];
+        let content = self.previews.get(&idx).unwrap_or(&result.content);
+
+        let metadata = Metadata {
+            url: Some(result.url.clone()),
+            fetched_at: Some(store::now()),
+        };
+        self.status = match kb
+            .add(
+                &result.url,
+                &result.title,
+                content,
+                &metadata,
+                &ChunkOptions::default(),
+            )
+            .await
+        {
+            Ok(added) => format!("Saved {} chunk(s) to the knowledge base", added.chunks),
+            Err(e) => format!("Failed to save to knowledge base: {}", e),
+        };
+    }
+
+    fn agent_context(&self) -> String {
+        let result = self.selected_result();
+        let content = self
+            .previews
+            .get(&self.selected())
+            .unwrap_or(&result.content);
+        let content: String = content.chars().take(MAX_CONTEXT_CHARS).collect();
+
+        format!(
+            "Use the following source as context:\nTitle: {}\nURL: {}\n\n{}",
+            result.title, result.url, content
+        )
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(frame.area());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(rows[0]);
+
+        let items: Vec<ListItem> = self
+            .results
+            .iter()
+            .enumerate()
+            .map(|(idx, result)| {
+                ListItem::new(vec![
+                    Line::from(format!("{}. {}", idx + 1, result.title)),
+                    Line::from(Span::styled(
+                        format!("   {}", domain_of(&result.url)),
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                ])
+            })
+            .collect();
+
+        let pointer = Glyph::Pointer.to_string();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                " {}{} ",
+                Glyph::Weave,
+                self.query
+            )))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(&pointer);
+        frame.render_stateful_widget(list, columns[0], &mut self.list_state);
+
+        let result = self.selected_result();
+        let body = match self.previews.get(&self.selected()) {
+            Some(content) => content.clone(),
+            None => format!("{}\n\n(press enter to load the full page)", result.content),
+        };
+        let preview = Paragraph::new(format!("{}\n\n{}", result.url, body))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" {} ", result.title)),
+            )
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(preview, columns[1]);
+
+        let status =
+            Paragraph::new(self.status.as_str()).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(status, rows[1]);
+    }
+}
+
+pub async fn run(
+    query: &str,
+    results: Vec<SearchResult>,
+    client: &OllamaClient,
+    kb: Option<&KnowledgeBase>,
+) -> Result<Outcome> {
+    if results.is_empty() {
+        println!("No results found.");
+        return Ok(Outcome::Quit);
+    }
+
+    let mut app = App::new(query, results);
+    let mut terminal = ratatui::init();
+    let outcome = event_loop(&mut terminal, &mut app, client, kb).await;
+    ratatui::restore();
+    outcome
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    client: &OllamaClient,
+    kb: Option<&KnowledgeBase>,
+) -> Result<Outcome> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Outcome::Quit),
+            KeyCode::Char('j') | KeyCode::Down => app.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => app.select_previous(),
+            KeyCode::Char('J') | KeyCode::PageDown => app.scroll = app.scroll.saturating_add(10),
+            KeyCode::Char('K') | KeyCode::PageUp => app.scroll = app.scroll.saturating_sub(10),
+            KeyCode::Enter | KeyCode::Char('p') => {
+                app.status = "Fetching page...".to_string();
+                terminal.draw(|frame| app.draw(frame))?;
+                app.load_preview(client).await;
+            }
+            KeyCode::Char('o') => {
+                let url = app.selected_result().url.clone();
+                app.status = match webbrowser::open(&url) {
+                    Ok(()) => format!("Opened {}", url),
+                    Err(e) => format!("Failed to open browser: {}", e),
+                };
+            }
+            KeyCode::Char('y') => {
+                let url = app.selected_result().url.clone();
+                app.status = match copy_to_clipboard(&url) {
+                    Ok(()) => format!("Copied {}", url),
+                    Err(e) => format!("Failed to copy: {}", e),
+                };
+            }
+            KeyCode::Char('s') => match kb {
+                Some(kb) => {
+                    app.status = "Fetching and embedding page...".to_string();
+                    terminal.draw(|frame| app.draw(frame))?;
+                    app.load_preview(client).await;
+                    app.save_to_kb(kb).await;
+                }
+                None => app.status = "Knowledge base is unavailable".to_string(),
+            },
+            KeyCode::Char('a') => {
+                app.status = "Fetching page for the agent...".to_string();
+                terminal.draw(|frame| app.draw(frame))?;
+                app.load_preview(client).await;
+                return Ok(Outcome::SendToAgent(app.agent_context()));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "{}", osc52(text))?;
+    stdout.flush()
+}
+
+fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", STANDARD.encode(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(count: usize) -> Vec<SearchResult> {
+        (0..count)
+            .map(|i| SearchResult {
+                title: format!("Result {}", i),
+                url: format!("https://example.com/{}", i),
+                content: format!("snippet {}", i),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_selection_wraps_around() {
+        let mut app = App::new("query", results(3));
+        app.select_previous();
+        assert_eq!(app.selected(), 2);
+        app.select_next();
+        assert_eq!(app.selected(), 0);
+    }
+
+    #[test]
+    fn test_agent_context_prefers_fetched_content() {
+        let mut app = App::new("query", results(2));
+        assert!(app.agent_context().contains("snippet 0"));
+
+        app.previews.insert(0, "full page".to_string());
+        let context = app.agent_context();
+        assert!(context.contains("full page"));
+        assert!(context.contains("https://example.com/0"));
+    }
+
+    #[test]
+    fn test_osc52_encodes_text() {
+        assert_eq!(osc52("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
@@ -0,0 +1,717 @@
+use std::f64::consts::{E, PI, TAU};
+
+const CONVERSION_WORDS: &[&str] = &[" to ", " in ", " as "];
+const MAX_DEPTH: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Length,
+    Mass,
+    Time,
+    Data,
+    Volume,
+    Speed,
+    Area,
+    Temperature,
+}
+
+struct Unit {
+    names: &'static [&'static str],
+    dimension: Dimension,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(names: &'static [&'static str], dimension: Dimension, factor: f64) -> Unit {
+    Unit {
+        names,
+        dimension,
+        factor,
+        offset: 0.0,
+    }
+}
+
+const UNITS: &[Unit] = &[
+    unit(
+        &["m", "meter", "meters", "metre", "metres"],
+        Dimension::Length,
+        1.0,
+    ),
+    unit(
+        &["km", "kilometer", "kilometers", "kilometre", "kilometres"],
+        Dimension::Length,
+        1e3,
+    ),
+    unit(
+        &["cm", "centimeter", "centimeters"],
+        Dimension::Length,
+        1e-2,
+    ),
+    unit(
+        &["mm", "millimeter", "millimeters"],
+        Dimension::Length,
+        1e-3,
+    ),
+    unit(
+        &["um", "µm", "micrometer", "micrometers", "micron", "microns"],
+        Dimension::Length,
+        1e-6,
+    ),
+    unit(&["nm", "nanometer", "nanometers"], Dimension::Length, 1e-9),
+    unit(&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    unit(&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    unit(&["ft", "foot", "feet"], Dimension::Length, 0.3048),
+    unit(&["in", "inch", "inches"], Dimension::Length, 0.0254),
+    unit(
+        &["nmi", "nautical mile", "nautical miles"],
+        Dimension::Length,
+        1852.0,
+    ),
+    unit(&["kg", "kilogram", "kilograms"], Dimension::Mass, 1.0),
+    unit(&["g", "gram", "grams"], Dimension::Mass, 1e-3),
+    unit(&["mg", "milligram", "milligrams"], Dimension::Mass, 1e-6),
+    unit(
+        &["t", "tonne", "tonnes", "metric ton", "metric tons"],
+        Dimension::Mass,
+        1e3,
+    ),
+    unit(
+        &["lb", "lbs", "pound", "pounds"],
+        Dimension::Mass,
+        0.453_592_37,
+    ),
+    unit(
+        &["oz", "ounce", "ounces"],
+        Dimension::Mass,
+        0.028_349_523_125,
+    ),
+    unit(&["st", "stone", "stones"], Dimension::Mass, 6.350_293_18),
+    unit(
+        &["s", "sec", "secs", "second", "seconds"],
+        Dimension::Time,
+        1.0,
+    ),
+    unit(
+        &["ms", "millisecond", "milliseconds"],
+        Dimension::Time,
+        1e-3,
+    ),
+    unit(
+        &["us", "µs", "microsecond", "microseconds"],
+        Dimension::Time,
+        1e-6,
+    ),
+    unit(&["ns", "nanosecond", "nanoseconds"], Dimension::Time, 1e-9),
+    unit(&["min", "mins", "minute", "minutes"], Dimension::Time, 60.0),
+    unit(
+        &["h", "hr", "hrs", "hour", "hours"],
+        Dimension::Time,
+        3600.0,
+    ),
+    unit(&["d", "day", "days"], Dimension::Time, 86_400.0),
+    unit(&["wk", "week", "weeks"], Dimension::Time, 604_800.0),
+    unit(&["yr", "year", "years"], Dimension::Time, 31_557_600.0),
+    unit(&["b", "bit", "bits"], Dimension::Data, 0.125),
+    unit(&["B", "byte", "bytes"], Dimension::Data, 1.0),
+    unit(&["KB", "kB", "kilobyte", "kilobytes"], Dimension::Data, 1e3),
+    unit(&["MB", "megabyte", "megabytes"], Dimension::Data, 1e6),
+    unit(&["GB", "gigabyte", "gigabytes"], Dimension::Data, 1e9),
+    unit(&["TB", "terabyte", "terabytes"], Dimension::Data, 1e12),
+    unit(&["PB", "petabyte", "petabytes"], Dimension::Data, 1e15),
+    unit(&["KiB", "kibibyte", "kibibytes"], Dimension::Data, 1024.0),
+    unit(
+        &["MiB", "mebibyte", "mebibytes"],
+        Dimension::Data,
+        1_048_576.0,
+    ),
+    unit(
+        &["GiB", "gibibyte", "gibibytes"],
+        Dimension::Data,
+        1_073_741_824.0,
+    ),
+    unit(
+        &["TiB", "tebibyte", "tebibytes"],
+        Dimension::Data,
+        1_099_511_627_776.0,
+    ),
+    unit(
+        &["Kb", "kbit", "kilobit", "kilobits"],
+        Dimension::Data,
+        125.0,
+    ),
+    unit(
+        &["Mb", "Mbit", "megabit", "megabits"],
+        Dimension::Data,
+        125e3,
+    ),
+    unit(
+        &["Gb", "Gbit", "gigabit", "gigabits"],
+        Dimension::Data,
+        125e6,
+    ),
+    unit(
+        &["l", "L", "liter", "liters", "litre", "litres"],
+        Dimension::Volume,
+        1.0,
+    ),
+    unit(
+        &[
+            "ml",
+            "mL",
+            "milliliter",
+            "milliliters",
+            "millilitre",
+            "millilitres",
+        ],
+        Dimension::Volume,
+        1e-3,
+    ),
+    unit(
+        &["m3", "m³", "cubic meter", "cubic meters"],
+        Dimension::Volume,
+        1e3,
+    ),
+    unit(
+        &["gal", "gallon", "gallons"],
+        Dimension::Volume,
+        3.785_411_784,
+    ),
+    unit(&["qt", "quart", "quarts"], Dimension::Volume, 0.946_352_946),
+    unit(&["pt", "pint", "pints"], Dimension::Volume, 0.473_176_473),
+    unit(&["cup", "cups"], Dimension::Volume, 0.236_588_236_5),
+    unit(
+        &["floz", "fl oz", "fluid ounce", "fluid ounces"],
+        Dimension::Volume,
+        0.029_573_529_562_5,
+    ),
+    unit(&["m/s", "mps"], Dimension::Speed, 1.0),
+    unit(&["km/h", "kmh", "kph"], Dimension::Speed, 1.0 / 3.6),
+    unit(&["mph", "mi/h"], Dimension::Speed, 0.447_04),
+    unit(
+        &["kn", "kt", "knot", "knots"],
+        Dimension::Speed,
+        1852.0 / 3600.0,
+    ),
+    unit(&["ft/s", "fps"], Dimension::Speed, 0.3048),
+    unit(
+        &["m2", "m²", "sqm", "square meter", "square meters"],
+        Dimension::Area,
+        1.0,
+    ),
+    unit(
+        &["km2", "km²", "square kilometer", "square kilometers"],
+        Dimension::Area,
+        1e6,
+    ),
+    unit(
+        &["ft2", "ft²", "sqft", "square foot", "square feet"],
+        Dimension::Area,
+        0.092_903_04,
+    ),
+    unit(
+        &["mi2", "mi²", "square mile", "square miles"],
+        Dimension::Area,
+        2_589_988.110_336,
+    ),
+    unit(&["ha", "hectare", "hectares"], Dimension::Area, 1e4),
+    unit(&["acre", "acres"], Dimension::Area, 4_046.856_422_4),
+    unit(&["K", "kelvin"], Dimension::Temperature, 1.0),
+    Unit {
+        names: &["C", "°C", "celsius"],
+        dimension: Dimension::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        names: &["F", "°F", "fahrenheit"],
+        dimension: Dimension::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 273.15 - 32.0 * 5.0 / 9.0,
+    },
+];
+
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    let name = name.trim();
+    UNITS
+        .iter()
+        .find(|unit| unit.names.contains(&name))
+        .or_else(|| {
+            UNITS
+                .iter()
+                .find(|unit| unit.names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+        })
+}
+
+pub fn calculate(input: &str) -> Result<String, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Expression is empty".to_string());
+    }
+
+    if let Some((amount, from, to)) = split_conversion(input) {
+        let value = if amount.is_empty() {
+            1.0
+        } else {
+            evaluate(amount)?
+        };
+        if from.dimension != to.dimension {
+            return Err(format!(
+                "Cannot convert {} to {}: {:?} and {:?} are different quantities",
+                from.names[0], to.names[0], from.dimension, to.dimension
+            ));
+        }
+        let converted = ((value * from.factor + from.offset) - to.offset) / to.factor;
+        return Ok(format!(
+            "{} {} = {} {}",
+            format_number(value),
+            from.names[0],
+            format_number(converted),
+            to.names[0]
+        ));
+    }
+
+    Ok(format!("{} = {}", input, format_number(evaluate(input)?)))
+}
+
+fn split_conversion(input: &str) -> Option<(&str, &'static Unit, &'static Unit)> {
+    let lower = input.to_ascii_lowercase();
+    let (at, word) = CONVERSION_WORDS
+        .iter()
+        .filter_map(|word| lower.rfind(word).map(|at| (at, word.len())))
+        .max_by_key(|(at, _)| *at)?;
+    let to = find_unit(&input[at + word..])?;
+
+    let lhs = input[..at].trim_end();
+    let mut unit_start = lhs
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphabetic() || matches!(c, '/' | '°' | '²' | '³' | ' '))
+        .last()
+        .map_or(lhs.len(), |(idx, _)| idx);
+    let from = loop {
+        let candidate = lhs[unit_start..].trim();
+        if candidate.is_empty() {
+            return None;
+        }
+        if let Some(unit) = find_unit(candidate) {
+            break unit;
+        }
+        unit_start += lhs[unit_start..].chars().next()?.len_utf8();
+    };
+
+    Some((lhs[..unit_start].trim(), from, to))
+}
+
+pub fn evaluate(expression: &str) -> Result<f64, String> {
+    let mut parser = Parser {
+        tokens: tokenize(expression)?,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.expression()?;
+    if let Some(token) = parser.peek() {
+        return Err(format!("Unexpected {}", token));
+    }
+    if !value.is_finite() {
+        return Err("Result is not a finite number".to_string());
+    }
+    Ok(value)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "number {}", n),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Op(op) => write!(f, "'{}'", op),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek() {
+                    let exponent_sign = matches!(c, '+' | '-') && number.ends_with(['e', 'E']);
+                    if c.is_ascii_digit() || c == '.' || c == '_' || exponent_sign {
+                        number.push(c);
+                    } else if matches!(c, 'e' | 'E') && !number.contains(['e', 'E']) {
+                        let mut ahead = chars.clone();
+                        ahead.next();
+                        if !ahead
+                            .peek()
+                            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '+' | '-'))
+                        {
+                            break;
+                        }
+                        number.push(c);
+                    } else {
+                        break;
+                    }
+                    chars.next();
+                }
+                let value = number
+                    .replace('_', "")
+                    .parse()
+                    .map_err(|_| format!("Invalid number '{}'", number))?;
+                tokens.push(Token::Number(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(name.to_lowercase()));
+            }
+            '*' => {
+                chars.next();
+                if chars.next_if_eq(&'*').is_some() {
+                    tokens.push(Token::Op('^'));
+                } else {
+                    tokens.push(Token::Op('*'));
+                }
+            }
+            '×' | '·' => {
+                chars.next();
+                tokens.push(Token::Op('*'));
+            }
+            '÷' => {
+                chars.next();
+                tokens.push(Token::Op('/'));
+            }
+            '−' => {
+                chars.next();
+                tokens.push(Token::Op('-'));
+            }
+            '+' | '-' | '/' | '^' | '(' | ')' | ',' | '%' | '!' => {
+                chars.next();
+                tokens.push(Token::Op(c));
+            }
+            c => return Err(format!("Unexpected character '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(name)) if name == word) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn starts_operand(&self) -> bool {
+        match self.peek() {
+            Some(Token::Number(_)) | Some(Token::Op('(')) => true,
+            Some(Token::Ident(name)) => !matches!(name.as_str(), "mod" | "of"),
+            _ => false,
+        }
+    }
+
+    fn expression(&mut self) -> Result<f64, String> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') {
+                value += self.term()?;
+            } else if self.eat('-') {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') || self.eat_word("of") {
+                value *= self.unary()?;
+            } else if self.eat('/') {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err("Division by zero".to_string());
+                }
+                value /= divisor;
+            } else if self.eat_word("mod") || self.modulo_percent() {
+                let divisor = self.unary()?;
+                if divisor == 0.0 {
+                    return Err("Division by zero".to_string());
+                }
+                value %= divisor;
+            } else if self.starts_operand() {
+                value *= self.power()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn modulo_percent(&mut self) -> bool {
+        if self.peek() != Some(&Token::Op('%')) {
+            return false;
+        }
+        self.pos += 1;
+        if self.starts_operand() {
+            return true;
+        }
+        self.pos -= 1;
+        false
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err("Expression is nested too deeply".to_string());
+        }
+        self.depth += 1;
+        let value = self.signed();
+        self.depth -= 1;
+        value
+    }
+
+    fn signed(&mut self) -> Result<f64, String> {
+        if self.eat('-') {
+            return Ok(-self.unary()?);
+        }
+        if self.eat('+') {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.postfix()?;
+        if self.eat('^') {
+            return Ok(base.powf(self.unary()?));
+        }
+        Ok(base)
+    }
+
+    fn postfix(&mut self) -> Result<f64, String> {
+        let mut value = self.primary()?;
+        loop {
+            if self.peek() == Some(&Token::Op('%')) {
+                self.pos += 1;
+                if self.starts_operand() {
+                    self.pos -= 1;
+                    return Ok(value);
+                }
+                value /= 100.0;
+            } else if self.eat('!') {
+                value = factorial(value)?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::Op('(')) => {
+                let value = self.expression()?;
+                if !self.eat(')') {
+                    return Err("Missing closing parenthesis".to_string());
+                }
+                Ok(value)
+            }
+            Some(Token::Ident(name)) => {
+                if self.eat('(') {
+                    let mut args = vec![self.expression()?];
+                    while self.eat(',') {
+                        args.push(self.expression()?);
+                    }
+                    if !self.eat(')') {
+                        return Err(format!("Missing closing parenthesis after {}(", name));
+                    }
+                    return call(&name, &args);
+                }
+                match name.as_str() {
+                    "pi" | "π" => Ok(PI),
+                    "e" => Ok(E),
+                    "tau" | "τ" => Ok(TAU),
+                    _ => Err(format!("Unknown name '{}'", name)),
+                }
+            }
+            Some(token) => Err(format!("Unexpected {}", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+fn call(name: &str, args: &[f64]) -> Result<f64, String> {
+    let one = |f: fn(f64) -> f64| match args {
+        [x] => Ok(f(*x)),
+        _ => Err(format!("{}() takes one argument", name)),
+    };
+    match name {
+        "sqrt" => one(f64::sqrt),
+        "cbrt" => one(f64::cbrt),
+        "abs" => one(f64::abs),
+        "ln" => one(f64::ln),
+        "log2" => one(f64::log2),
+        "log10" => one(f64::log10),
+        "log" => match args {
+            [x] => Ok(x.log10()),
+            [x, base] => Ok(x.log(*base)),
+            _ => Err("log() takes a value and an optional base".to_string()),
+        },
+        "exp" => one(f64::exp),
+        "sin" => one(f64::sin),
+        "cos" => one(f64::cos),
+        "tan" => one(f64::tan),
+        "asin" => one(f64::asin),
+        "acos" => one(f64::acos),
+        "atan" => one(f64::atan),
+        "floor" => one(f64::floor),
+        "ceil" => one(f64::ceil),
+        "round" => match args {
+            [x] => Ok(x.round()),
+            [x, digits] => {
+                let scale = 10f64.powi(*digits as i32);
+                Ok((x * scale).round() / scale)
+            }
+            _ => Err("round() takes a value and optional digits".to_string()),
+        },
+        "pow" => match args {
+            [x, y] => Ok(x.powf(*y)),
+            _ => Err("pow() takes two arguments".to_string()),
+        },
+        "min" => Ok(args.iter().copied().fold(f64::INFINITY, f64::min)),
+        "max" => Ok(args.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+        _ => Err(format!("Unknown function '{}'", name)),
+    }
+}
+
+fn factorial(value: f64) -> Result<f64, String> {
+    if value < 0.0 || value.fract() != 0.0 || value > 170.0 {
+        return Err(format!(
+            "Factorial needs a whole number from 0 to 170, got {}",
+            value
+        ));
+    }
+    Ok((1..=value as u64).map(|n| n as f64).product())
+}
+
+pub fn format_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let magnitude = value.abs();
+    if !(1e-6..1e15).contains(&magnitude) {
+        let formatted = format!("{:.9e}", value);
+        let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        return format!("{}e{}", mantissa, exponent);
+    }
+    if value.fract() == 0.0 {
+        return format!("{}", value as i64);
+    }
+
+    let decimals = (9 - magnitude.log10().floor() as i32).clamp(0, 15) as usize;
+    let formatted = format!("{:.*}", decimals, value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluates_arithmetic() {
+        assert_eq!(evaluate("2 + 3 * 4").unwrap(), 14.0);
+        assert_eq!(evaluate("(2 + 3) * 4").unwrap(), 20.0);
+        assert_eq!(evaluate("-2^2").unwrap(), -4.0);
+        assert_eq!(evaluate("2^3^2").unwrap(), 512.0);
+        assert_eq!(evaluate("15% of 200").unwrap(), 30.0);
+        assert_eq!(evaluate("200 * 15%").unwrap(), 30.0);
+        assert_eq!(evaluate("10 % 3").unwrap(), 1.0);
+        assert_eq!(evaluate("2pi").unwrap(), 2.0 * PI);
+        assert_eq!(evaluate("max(1, 7, 3) + 5!").unwrap(), 127.0);
+        assert_eq!(evaluate("round(2/3, 2)").unwrap(), 0.67);
+        assert_eq!(evaluate("1.5e3 + 1_000").unwrap(), 2500.0);
+        assert_eq!(calculate("0.1 + 0.2").unwrap(), "0.1 + 0.2 = 0.3");
+        assert_eq!(format_number(1234567.891), "1234567.891");
+        assert_eq!(format_number(6.02214076e23), "6.02214076e23");
+    }
+
+    #[test]
+    fn test_converts_units() {
+        assert_eq!(calculate("5 km to mi").unwrap(), "5 km = 3.106855961 mi");
+        assert_eq!(calculate("100 F to C").unwrap(), "100 F = 37.77777778 C");
+        assert_eq!(calculate("-40 °C in °F").unwrap(), "-40 C = -40 F");
+        assert_eq!(
+            calculate("1.5 GB to MiB").unwrap(),
+            "1.5 GB = 1430.511475 MiB"
+        );
+        assert_eq!(
+            calculate("(2 + 1) * 60 mph to km/h").unwrap(),
+            "180 mph = 289.68192 km/h"
+        );
+        assert_eq!(calculate("week in hours").unwrap(), "1 wk = 168 h");
+    }
+
+    #[test]
+    fn test_reports_errors() {
+        assert!(evaluate("1 / 0").is_err());
+        assert!(evaluate("2 +").is_err());
+        assert!(evaluate("(1 + 2").is_err());
+        assert!(evaluate("foo(3)").is_err());
+        assert!(evaluate("sqrt(-1)").is_err());
+        assert!(calculate("5 kg to m").is_err());
+        assert!(calculate("").is_err());
+
+        let nested = |depth: usize| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(evaluate(&nested(200)), Ok(1.0));
+        assert!(evaluate(&nested(100_000)).is_err());
+        assert!(evaluate(&"-".repeat(100_000)).is_err());
+        assert!(evaluate(&format!("2{}", "^2".repeat(100_000))).is_err());
+    }
+}
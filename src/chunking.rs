@@ -0,0 +1,298 @@
+use crate::kb::cosine_similarity;
+use clap::ValueEnum;
+
+pub const DEFAULT_CHUNK_TOKENS: usize = 300;
+pub const DEFAULT_OVERLAP_TOKENS: usize = 50;
+
+const CHARS_PER_TOKEN: usize = 4;
+const MIN_SEMANTIC_DROP: f32 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Strategy {
+    #[default]
+    Paragraph,
+    Heading,
+    Tokens,
+    Semantic,
+}
+
+impl Strategy {
+    fn as_str(self) -> &'static str {
+        match self {
+            Strategy::Paragraph => "paragraph",
+            Strategy::Heading => "heading",
+            Strategy::Tokens => "tokens",
+            Strategy::Semantic => "semantic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkOptions {
+    pub strategy: Strategy,
+    pub size: usize,
+    pub overlap: usize,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            strategy: Strategy::Paragraph,
+            size: DEFAULT_CHUNK_TOKENS,
+            overlap: DEFAULT_OVERLAP_TOKENS,
+        }
+    }
+}
+
+impl ChunkOptions {
+    pub fn max_chars(&self) -> usize {
+        self.size.max(1) * CHARS_PER_TOKEN
+    }
+
+    pub fn describe(&self) -> String {
+        match self.strategy {
+            Strategy::Tokens => format!("tokens:{}:{}", self.size, self.overlap),
+            strategy => format!("{}:{}", strategy.as_str(), self.size),
+        }
+    }
+}
+
+pub fn chunk(text: &str, options: &ChunkOptions) -> Vec<String> {
+    match options.strategy {
+        Strategy::Paragraph | Strategy::Semantic => chunk_text(text, options.max_chars()),
+        Strategy::Heading => by_heading(text, options.max_chars()),
+        Strategy::Tokens => by_tokens(text, options.size, options.overlap),
+    }
+}
+
+pub fn paragraphs(text: &str, max_chars: usize) -> Vec<&str> {
+    text.split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .flat_map(|paragraph| split_long(paragraph, max_chars))
+        .collect()
+}
+
+pub fn semantic_groups(
+    paragraphs: &[&str],
+    embeddings: &[Vec<f32>],
+    max_chars: usize,
+) -> Vec<String> {
+    let similarities: Vec<f32> = embeddings
+        .windows(2)
+        .map(|pair| cosine_similarity(&pair[0], &pair[1]))
+        .collect();
+    let threshold = breakpoint(&similarities);
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for (idx, paragraph) in paragraphs.iter().enumerate() {
+        let topic_shift = idx > 0 && similarities.get(idx - 1).is_some_and(|&s| s < threshold);
+        let too_long = current.chars().count() + paragraph.chars().count() + 2 > max_chars;
+        if !current.is_empty() && (topic_shift || too_long) {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+fn breakpoint(similarities: &[f32]) -> f32 {
+    if similarities.is_empty() {
+        return f32::MIN;
+    }
+    let count = similarities.len() as f32;
+    let mean = similarities.iter().sum::<f32>() / count;
+    let variance = similarities.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / count;
+    mean - variance.sqrt().max(MIN_SEMANTIC_DROP)
+}
+
+fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for piece in paragraphs(text, max_chars) {
+        if !current.is_empty() && current.chars().count() + piece.chars().count() + 2 > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(piece);
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn by_heading(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+
+    for section in sections(text) {
+        let heading = section.lines().next().filter(|line| is_heading(line));
+        for (idx, piece) in chunk_text(&section, max_chars).into_iter().enumerate() {
+            match heading {
+                Some(heading) if idx > 0 => chunks.push(format!("{}\n\n{}", heading.trim(), piece)),
+                _ => chunks.push(piece),
+            }
+        }
+    }
+    chunks
+}
+
+fn sections(text: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current = String::new();
+    let mut in_fence = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence && is_heading(line) && !current.trim().is_empty() {
+            sections.push(std::mem::take(&mut current));
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+
+    if !current.trim().is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+fn is_heading(line: &str) -> bool {
+    let line = line.trim_start();
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+fn by_tokens(text: &str, size: usize, overlap: usize) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let size = size.max(1);
+    let step = size - overlap.min(size - 1);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < words.len() {
+        let end = (start + size).min(words.len());
+        chunks.push(words[start..end].join(" "));
+        if end == words.len() {
+            break;
+        }
+        start += step;
+    }
+    chunks
+}
+
+fn split_long(paragraph: &str, max_chars: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = paragraph;
+
+    while rest.chars().count() > max_chars {
+        let limit = rest
+            .char_indices()
+            .nth(max_chars)
+            .map(|(idx, _)| idx)
+            .unwrap_or(rest.len());
+        let cut = rest[..limit]
+            .rfind(char::is_whitespace)
+            .filter(|&idx| idx > 0)
+            .unwrap_or(limit);
+        pieces.push(rest[..cut].trim_end());
+        rest = rest[cut..].trim_start();
+    }
+
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_groups_paragraphs() {
+        let text = "First paragraph.\n\nSecond paragraph.\n\n\n\nThird.";
+        let chunks = chunk_text(text, 40);
+        assert_eq!(
+            chunks,
+            vec!["First paragraph.\n\nSecond paragraph.", "Third."]
+        );
+    }
+
+    #[test]
+    fn test_chunk_text_splits_long_paragraphs_on_whitespace() {
+        let text = "word ".repeat(100);
+        let chunks = chunk_text(&text, 50);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 50));
+        assert!(chunks
+            .iter()
+            .all(|c| !c.starts_with(' ') && !c.ends_with(' ')));
+    }
+
+    #[test]
+    fn test_chunk_text_handles_multibyte_characters() {
+        let text = "🦀".repeat(30);
+        let chunks = chunk_text(&text, 10);
+        assert_eq!(chunks.len(), 3);
+    }
+
+    #[test]
+    fn test_by_heading_ignores_comments_in_code_fences() {
+        let text = "# Install\n\nRun this:\n\n```bash\n# not a heading\ncargo install weavex\n```\n\n## Usage\n\nweavex agent";
+        let chunks = by_heading(text, 1000);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].contains("# not a heading"));
+        assert!(chunks[1].starts_with("## Usage"));
+    }
+
+    #[test]
+    fn test_by_tokens_overlaps_windows() {
+        let text = (1..=10)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(
+            by_tokens(&text, 4, 1),
+            vec!["1 2 3 4", "4 5 6 7", "7 8 9 10"]
+        );
+        assert_eq!(by_tokens("a b", 4, 10), vec!["a b"]);
+    }
+
+    #[test]
+    fn test_semantic_groups_split_on_topic_shift() {
+        let paragraphs = [
+            "Rust ownership.",
+            "Borrowing.",
+            "Sourdough starter.",
+            "Baking times.",
+        ];
+        let embeddings = vec![
+            vec![1.0, 0.0],
+            vec![1.0, 0.05],
+            vec![0.0, 1.0],
+            vec![0.05, 1.0],
+        ];
+        assert_eq!(
+            semantic_groups(&paragraphs, &embeddings, 1000),
+            vec![
+                "Rust ownership.\n\nBorrowing.",
+                "Sourdough starter.\n\nBaking times."
+            ]
+        );
+    }
+}
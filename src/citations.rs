@@ -0,0 +1,237 @@
+use crate::client::FetchResponse;
+use crate::feedback::domain_of;
+use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
+use serde_json::{json, Value};
+use std::sync::{Mutex, OnceLock};
+
+static SOURCES: OnceLock<Mutex<Vec<Source>>> = OnceLock::new();
+
+pub const FOOTNOTE_INSTRUCTION: &str = "Cite your sources with markdown footnotes: put a \
+    marker such as [^1] right after each claim it supports, and end the answer with one \
+    definition per source, like [^1]: Page title, https://example.com/page";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CitationFormat {
+    Bibtex,
+    CslJson,
+}
+
+impl CitationFormat {
+    pub fn default_file(self) -> &'static str {
+        match self {
+            CitationFormat::Bibtex => "citations.bib",
+            CitationFormat::CslJson => "citations.json",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    pub url: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub published: Option<NaiveDate>,
+    pub accessed: NaiveDate,
+}
+
+impl Source {
+    pub fn new(url: &str, page: &FetchResponse, accessed: NaiveDate) -> Self {
+        let url = page
+            .canonical_url
+            .clone()
+            .unwrap_or_else(|| url.to_string());
+        Self {
+            title: match page.title.trim() {
+                "" => url.clone(),
+                title => title.to_string(),
+            },
+            authors: page
+                .author
+                .iter()
+                .flat_map(|authors| authors.split(", "))
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+            published: page
+                .published
+                .as_deref()
+                .and_then(|date| date.get(..10))
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()),
+            accessed,
+            url,
+        }
+    }
+}
+
+pub fn enable() {
+    let _ = SOURCES.set(Mutex::new(Vec::new()));
+}
+
+pub fn enabled() -> bool {
+    SOURCES.get().is_some()
+}
+
+pub fn record(url: &str, page: &FetchResponse) {
+    let Some(sources) = SOURCES.get() else {
+        return;
+    };
+    let source = Source::new(url, page, chrono::Local::now().date_naive());
+    let mut sources = sources.lock().unwrap_or_else(|e| e.into_inner());
+    if !sources.iter().any(|seen| seen.url == source.url) {
+        sources.push(source);
+    }
+}
+
+pub fn take() -> Vec<Source> {
+    SOURCES
+        .get()
+        .map(|sources| std::mem::take(&mut *sources.lock().unwrap_or_else(|e| e.into_inner())))
+        .unwrap_or_default()
+}
+
+pub fn render(format: CitationFormat, sources: &[Source]) -> String {
+    match format {
+        CitationFormat::Bibtex => bibtex(sources),
+        CitationFormat::CslJson => csl_json(sources),
+    }
+}
+
+pub fn bibtex(sources: &[Source]) -> String {
+    let mut keys: Vec<String> = Vec::new();
+    let mut bibliography = String::new();
+    for source in sources {
+        let key = unique_key(&mut keys, source);
+        bibliography.push_str(&format!("@online{{{},\n", key));
+        bibliography.push_str(&format!("  title = {{{}}},\n", escape(&source.title)));
+        if !source.authors.is_empty() {
+            let authors: Vec<String> = source
+                .authors
+                .iter()
+                .map(|name| format!("{{{}}}", escape(name)))
+                .collect();
+            bibliography.push_str(&format!("  author = {{{}}},\n", authors.join(" and ")));
+        }
+        if let Some(published) = source.published {
+            bibliography.push_str(&format!("  date = {{{}}},\n", published));
+            bibliography.push_str(&format!("  year = {{{}}},\n", published.year()));
+        }
+        bibliography.push_str(&format!("  url = {{{}}},\n", source.url));
+        bibliography.push_str(&format!("  urldate = {{{}}},\n", source.accessed));
+        bibliography.push_str("}\n\n");
+    }
+    bibliography
+}
+
+pub fn csl_json(sources: &[Source]) -> String {
+    let mut keys: Vec<String> = Vec::new();
+    let items: Vec<Value> = sources
+        .iter()
+        .map(|source| {
+            let mut item = json!({
+                "id": unique_key(&mut keys, source),
+                "type": "webpage",
+                "title": source.title,
+                "URL": source.url,
+                "container-title": domain_of(&source.url),
+                "accessed": date_parts(source.accessed),
+            });
+            if !source.authors.is_empty() {
+                item["author"] = source
+                    .authors
+                    .iter()
+                    .map(|name| json!({"literal": name}))
+                    .collect();
+            }
+            if let Some(published) = source.published {
+                item["issued"] = date_parts(published);
+            }
+            item
+        })
+        .collect();
+    serde_json::to_string_pretty(&items).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn date_parts(date: NaiveDate) -> Value {
+    json!({"date-parts": [[date.year(), date.month(), date.day()]]})
+}
+
+fn unique_key(keys: &mut Vec<String>, source: &Source) -> String {
+    let domain = domain_of(&source.url);
+    let name: String = domain
+        .split('.')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+    let base = match source.published {
+        Some(published) => format!("{}{}", name, published.year()),
+        None => format!("{}nd", name),
+    };
+    let key = (0..)
+        .map(|n| match n {
+            0 => base.clone(),
+            _ => format!("{}-{}", base, n + 1),
+        })
+        .find(|key| !keys.contains(key))
+        .unwrap_or(base);
+    keys.push(key.clone());
+    key
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '{' | '}' | '\\' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources() -> Vec<Source> {
+        let accessed = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let page = FetchResponse {
+            title: "Async in depth & more".to_string(),
+            author: Some("Carl Lerche, Alice Ryhl".to_string()),
+            published: Some("2024-03-01T09:00:00Z".to_string()),
+            ..Default::default()
+        };
+        vec![
+            Source::new("https://tokio.rs/tokio/tutorial", &page, accessed),
+            Source::new("https://tokio.rs/blog", &FetchResponse::default(), accessed),
+        ]
+    }
+
+    #[test]
+    fn test_bibtex_entries_escape_titles_and_list_authors() {
+        let bibtex = bibtex(&sources());
+        assert!(bibtex.starts_with("@online{tokio2024,\n  title = {Async in depth \\& more},"));
+        assert!(bibtex.contains("  author = {{Carl Lerche} and {Alice Ryhl}},"));
+        assert!(bibtex.contains("  date = {2024-03-01},"));
+        assert!(bibtex.contains("@online{tokiond,\n  title = {https://tokio.rs/blog},"));
+        assert_eq!(bibtex.matches("urldate = {2026-10-15}").count(), 2);
+    }
+
+    #[test]
+    fn test_csl_json_items() {
+        let items: Vec<Value> = serde_json::from_str(&csl_json(&sources())).unwrap();
+        assert_eq!(items[0]["id"], "tokio2024");
+        assert_eq!(items[0]["type"], "webpage");
+        assert_eq!(items[0]["author"][1]["literal"], "Alice Ryhl");
+        assert_eq!(items[0]["issued"]["date-parts"], json!([[2024, 3, 1]]));
+        assert_eq!(items[1]["accessed"]["date-parts"], json!([[2026, 10, 15]]));
+        assert!(items[1].get("author").is_none());
+    }
+}
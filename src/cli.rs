@@ -1,4 +1,21 @@
-use clap::{Parser, Subcommand};
+use crate::arxiv::SortBy;
+use crate::bookmarks::BookmarkMode;
+use crate::chunking::Strategy;
+use crate::citations::CitationFormat;
+use crate::cloud::Backend;
+use crate::exit_code;
+use crate::feedback::Rating;
+use crate::glyphs::Spinner;
+use crate::i18n::{self, Language};
+use crate::permissions::Capability;
+use crate::provider::ProviderKind;
+use crate::summarizer::Summarizer;
+use crate::translate::AnswerLanguage;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+pub const STDIN_QUERY: &str = "-";
 
 #[derive(Parser, Debug)]
 #[command(
@@ -6,30 +23,7 @@ use clap::{Parser, Subcommand};
     version,
     about = "Weave together web search and AI reasoning for autonomous research",
     long_about = "Weave combines Ollama's web search API with local LLMs for intelligent, autonomous research.\n\n\
-                  Requires an API key from https://ollama.com - set via OLLAMA_API_KEY environment variable or --api-key flag.",
-    after_help = "EXAMPLES:\n    \
-                  # Basic search (opens in browser by default)\n    \
-                  weavex \"what is rust programming\"\n    \n\
-                  # Disable browser preview, show in terminal\n    \
-                  weavex --no-preview \"what is rust programming\"\n    \n\
-                  # Limit results\n    \
-                  weavex --max-results 5 \"best practices for async rust\"\n    \n\
-                  # JSON output\n    \
-                  weavex --json \"machine learning trends 2025\"\n    \n\
-                  # Fetch a URL\n    \
-                  weavex fetch https://example.com\n    \n\
-                  # AI agent (opens result in browser by default)\n    \
-                  weavex agent \"what are the latest rust async runtime benchmarks\"\n    \n\
-                  # Agent with terminal output only\n    \
-                  weavex agent --no-preview \"query\"\n    \n\
-                  # Use different model\n    \
-                  weavex agent --model qwen3:14b \"research topic\"\n    \n\
-                  # Show thinking steps and reasoning process\n    \
-                  weavex agent --show-thinking \"query\"\n    \n\
-                  # Disable reasoning mode\n    \
-                  weavex agent --disable-reasoning \"query\"\n    \n\
-                  # Custom API key\n    \
-                  weavex --api-key YOUR_KEY \"query here\""
+                  Requires an API key from https://ollama.com - set via OLLAMA_API_KEY environment variable or --api-key flag."
 )]
 pub struct Cli {
     #[command(subcommand)]
@@ -37,7 +31,7 @@ pub struct Cli {
 
     #[arg(
         value_name = "QUERY",
-        help = "Search query (when not using a subcommand)"
+        help = "Search query (when not using a subcommand); read from stdin when piped or -"
     )]
     pub query: Option<String>,
 
@@ -57,23 +51,513 @@ pub struct Cli {
     )]
     pub max_results: Option<usize>,
 
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..),
+        conflicts_with = "offset",
+        help = "Show page N of the search results (pages are --max-results long, default 10)"
+    )]
+    pub page: Option<u64>,
+
+    #[arg(long, value_name = "N", help = "Skip the first N search results")]
+    pub offset: Option<usize>,
+
+    #[arg(
+        long,
+        help = "After each page of terminal results (--no-preview), offer to load the next one"
+    )]
+    pub paginate: bool,
+
+    #[arg(
+        long,
+        value_name = "LIST",
+        value_enum,
+        value_delimiter = ',',
+        default_value = "ollama",
+        help = "Comma-separated search providers (ollama, brave, searxng, mock). \n\
+                Brave reads BRAVE_API_KEY and SearXNG reads SEARXNG_URL. \n\
+                Mock serves canned results and pages from WEAVEX_FIXTURES. \n\
+                Without --blend, later providers are used as fallbacks."
+    )]
+    pub providers: Vec<ProviderKind>,
+
+    #[arg(
+        long,
+        help = "Query all providers concurrently and interleave deduplicated results \n\
+                using reciprocal rank fusion"
+    )]
+    pub blend: bool,
+
+    #[arg(
+        long = "bookmarks",
+        value_name = "MODE",
+        value_enum,
+        default_value = "boost",
+        help = "How bookmarked domains affect ranking: off, boost, or pin to the top"
+    )]
+    pub bookmark_mode: BookmarkMode,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Show a desktop notification when an agent run finishes or fails"
+    )]
+    pub notify: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Read the final agent answer aloud, sentence by sentence, with a local TTS command \n\
+                (set [speech] command in the config file, e.g. piper; defaults to espeak-ng or say)"
+    )]
+    pub speak: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Private mode: keep no history or cached results, scrub the query from logs \n\
+                and the audit log, and send a generic User-Agent"
+    )]
+    pub private: bool,
+
+    #[arg(
+        long = "notify-webhook",
+        global = true,
+        value_name = "URL",
+        help = "POST the final agent answer and sources to a webhook when a run completes \n\
+                (repeatable; Slack and Discord URLs get formatted messages, others get JSON)"
+    )]
+    pub notify_webhooks: Vec<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Limit the agent's knowledge base tool to one collection"
+    )]
+    pub kb_collection: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "COLLECTION",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "learned",
+        help = "Add every page the agent fetches to a knowledge base collection \n\
+                [default: learned] (pages that opt out of archiving are skipped)"
+    )]
+    pub learn: Option<String>,
+
+    #[arg(
+        long = "allow-files",
+        global = true,
+        value_name = "DIR",
+        help = "Let the agent read files under DIR with its read_file tool (repeatable)"
+    )]
+    pub allow_files: Vec<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "CAPABILITY",
+        value_delimiter = ',',
+        help = "Grant the agent an optional capability for this run only (repeatable)"
+    )]
+    pub grant: Vec<Capability>,
+
+    #[arg(
+        long = "notes-file",
+        global = true,
+        value_name = "PATH",
+        help = "Keep the agent's save_note scratchpad in a JSONL file, so notes persist \n\
+                across runs (notes are kept in memory only by default)"
+    )]
+    pub notes_file: Option<PathBuf>,
+
+    #[arg(
+        long = "vision-model",
+        global = true,
+        value_name = "MODEL",
+        help = "Local multimodal Ollama model (e.g. llava, qwen2.5vl) that lets the agent \n\
+                describe charts, screenshots, and other images with its describe_image tool"
+    )]
+    pub vision_model: Option<String>,
+
+    #[arg(
+        long = "ui-language",
+        global = true,
+        value_name = "LANG",
+        help = "Language for messages and help examples \n\
+                [default: from LC_ALL, LC_MESSAGES, or LANG, falling back to en]"
+    )]
+    pub ui_language: Option<Language>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "LANG",
+        env = "WEAVEX_ANSWER_LANG",
+        help = "Language code the agent writes its answer in (e.g. de, ja, pt-BR), \n\
+                whatever language the sources are in"
+    )]
+    pub lang: Option<AnswerLanguage>,
+
+    #[arg(
+        long,
+        global = true,
+        requires = "lang",
+        help = "Have the agent's model translate English pages it fetches into the --lang \n\
+                language before reading them (one extra model call per page)"
+    )]
+    pub translate_sources: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Tell the agent to prefer sources with a high credibility tier (see \n\
+                [credibility] in the config file for the domain list)"
+    )]
+    pub prefer_credible: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        value_enum,
+        help = "Export the pages an agent run read as a bibliography (bibtex or csl-json)"
+    )]
+    pub citations: Option<CitationFormat>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        requires = "citations",
+        help = "Where --citations writes the bibliography [default: citations.bib or \n\
+                citations.json]"
+    )]
+    pub citations_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "Also save the agent's answer as an EPUB e-book with one chapter per section"
+    )]
+    pub epub: Option<PathBuf>,
+
+    #[arg(
+        long = "audit-log",
+        global = true,
+        value_name = "PATH",
+        num_args = 0..=1,
+        require_equals = true,
+        help = "Append outbound requests, tool calls, and policy decisions to a JSONL \n\
+                audit log [default: audit.jsonl in the data directory]"
+    )]
+    pub audit_log: Option<Option<std::path::PathBuf>>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Keep fetched page content as returned by the API instead of extracting \n\
+                the main article from HTML and converting it to markdown"
+    )]
+    pub raw: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Annotate search results and knowledge base passages with why they were \n\
+                selected (provider ranks, fusion scores, duplicates, personalization)"
+    )]
+    pub explain: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Group search results by topic using local embeddings and model-generated labels \n\
+                (models and server are set in the [cluster] section of the config file)"
+    )]
+    pub cluster: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Reorder search results by embedding similarity to the query before showing them \n\
+                or handing them to the agent (model and server are set in the [rerank] section)"
+    )]
+    pub rerank: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SCORE",
+        help = "Drop search results whose provider relevance score is below SCORE; results \n\
+                without a score are kept (scores are shown with -v and in --json output)"
+    )]
+    pub min_score: Option<f64>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Let the agent plan tool calls but return stub results instead of executing them \n\
+                (searches and fetches are printed, not sent, so no API quota is spent)"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Serve searches and fetches only from results and pages cached by earlier runs; \n\
+                no live web access (the local Ollama server stays reachable)"
+    )]
+    pub offline: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "After a search or agent run, show what changed since the previous run of the same \n\
+                query: new and dropped sources and reworded claims"
+    )]
+    pub diff_last: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Exit with code 2 when a search, arXiv query, feed, history listing, or knowledge \n\
+                base query returns nothing"
+    )]
+    pub fail_on_empty: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "NUM",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Limit the agent to pages from this many distinct domains per run; beyond it, \n\
+                each new domain must be justified by the model or the agent has to finalize"
+    )]
+    pub max_domains: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "BYTES",
+        value_parser = clap::value_parser!(u64).range(500..),
+        help = "Maximum bytes of each tool result sent to the agent's model [default: 8000]"
+    )]
+    pub tool_result_limit: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "BYTES",
+        value_parser = clap::value_parser!(u64).range(100..),
+        help = "Maximum bytes of each fetched page sent to the agent's model \n\
+                [default: a quarter of --tool-result-limit]"
+    )]
+    pub page_limit: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "BYTES",
+        value_parser = clap::value_parser!(u64).range(100..),
+        help = "Bytes of fetched page content shown in terminal output [default: 1000]"
+    )]
+    pub preview_limit: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "BYTES",
+        value_parser = clap::value_parser!(u64).range(50..),
+        help = "Bytes of each search result snippet shown to the agent and in terminal \n\
+                output [default: 500 for the agent, 200 in the terminal]"
+    )]
+    pub snippet_length: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PER_MINUTE",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Send at most this many requests per minute to the Ollama web API, waiting \n\
+                when the limit is reached"
+    )]
+    pub rate_limit: Option<u32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "BACKEND",
+        value_enum,
+        default_value = "ollama",
+        help = "Where the agent's model runs: ollama (local), openai, or anthropic. \n\
+                Cloud backends read OPENAI_API_KEY or ANTHROPIC_API_KEY; web search \n\
+                and fetch still go through Ollama's API"
+    )]
+    pub backend: Backend,
+
     #[arg(short = 'j', long, help = "Output results as JSON")]
     pub json: bool,
 
     #[arg(long, help = "Disable browser preview (preview is enabled by default)")]
     pub no_preview: bool,
 
-    #[arg(short = 'v', long, help = "Enable verbose logging")]
-    pub verbose: bool,
+    #[arg(
+        long,
+        global = true,
+        help = "Download remote images into the browser preview as data URIs so the page works offline"
+    )]
+    pub embed_images: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "BROWSER",
+        help = "Open the preview in this browser (firefox, chrome, safari, opera) or with this program instead of the default browser"
+    )]
+    pub browser: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Write the preview to a temp file and print its path instead of opening a browser"
+    )]
+    pub print_preview_path: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Copy the final answer or the search results (as markdown) to the clipboard"
+    )]
+    pub copy: bool,
+
+    #[arg(
+        long,
+        help = "Disable colored output and terminal hyperlinks (also respects NO_COLOR)"
+    )]
+    pub no_color: bool,
+
+    #[arg(
+        long,
+        global = true,
+        env = "WEAVEX_ASCII",
+        value_parser = clap::builder::BoolishValueParser::new(),
+        help = "Use plain ASCII labels instead of emoji (for CI logs, screen readers, and fonts without emoji)"
+    )]
+    pub no_emoji: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "WEAVEX_SPINNER",
+        help = "Progress spinner style [default: dots, or line with --no-emoji]"
+    )]
+    pub spinner: Option<Spinner>,
+
+    #[arg(
+        short = 'v',
+        long,
+        global = true,
+        action = clap::ArgAction::Count,
+        help = "Increase logging: -v info, -vv debug, -vvv trace including dependencies (RUST_LOG overrides)"
+    )]
+    pub verbose: u8,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        value_parser = validate_timeout,
+        help = "Timeout for web search and fetch responses in seconds (1-600) [default: 30 for \n\
+                search, 120 for fetch, 90 for the agent's web tools]"
+    )]
+    pub timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        value_parser = validate_timeout,
+        help = "Timeout for establishing a connection in seconds (1-600) [default: 10]"
+    )]
+    pub connect_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        value_parser = validate_timeout,
+        help = "Fail a web search or fetch when no data arrives for this many seconds (1-600)"
+    )]
+    pub read_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        default_value = "300",
+        value_parser = validate_chat_timeout,
+        help = "Total timeout for each local model response in seconds (1-3600)"
+    )]
+    pub chat_timeout: u64,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Try IPv4 addresses before IPv6 (for networks with broken IPv6)"
+    )]
+    pub prefer_ipv4: bool,
+
+    #[arg(
+        long = "resolve",
+        global = true,
+        value_name = "HOST:IP",
+        value_parser = crate::network::parse_resolve,
+        help = "Connect to HOST at IP instead of resolving it through DNS (repeatable)"
+    )]
+    pub resolve: Vec<(String, std::net::IpAddr)>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        value_parser = crate::network::parse_proxy,
+        help = "Send all HTTP requests through this proxy (http://, https://, socks5://, or \n\
+                socks5h://). HTTP_PROXY, HTTPS_PROXY, and NO_PROXY are respected without it"
+    )]
+    pub proxy: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Trust the CA certificates in this PEM bundle in addition to the built-in roots \n\
+                (for TLS-intercepting proxies)"
+    )]
+    pub ca_bundle: Option<std::path::PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Skip TLS certificate verification entirely (insecure; prefer --ca-bundle)"
+    )]
+    pub insecure: bool,
 
     #[arg(
         long,
+        global = true,
         value_name = "SECONDS",
-        default_value = "30",
         value_parser = validate_timeout,
-        help = "Request timeout in seconds (1-600)"
+        help = "DNS lookup timeout in seconds (1-600) [default: 5]"
     )]
-    pub timeout: u64,
+    pub dns_timeout: Option<u64>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -85,7 +569,10 @@ pub enum Command {
     },
     #[command(about = "Run an AI agent with web search capabilities")]
     Agent {
-        #[arg(value_name = "QUERY", help = "Question or task for the agent")]
+        #[arg(
+            value_name = "QUERY",
+            help = "Question or task for the agent (- reads it from stdin)"
+        )]
         query: String,
 
         #[arg(
@@ -132,21 +619,1039 @@ pub enum Command {
 
         #[arg(long, help = "Disable browser preview (preview is enabled by default)")]
         no_preview: bool,
-    },
-}
 
-fn validate_timeout(s: &str) -> Result<u64, String> {
-    let timeout: u64 = s
-        .parse()
-        .map_err(|_| format!("'{}' is not a valid number", s))?;
-    if !(1..=600).contains(&timeout) {
-        return Err("timeout must be between 1 and 600 seconds".to_string());
-    }
-    Ok(timeout)
-}
+        #[arg(
+            long,
+            value_name = "MODEL",
+            default_value = crate::kb::DEFAULT_EMBED_MODEL,
+            help = "Local Ollama embedding model used by the kb_search tool"
+        )]
+        embed_model: String,
 
-impl Cli {
-    pub fn get_query(&self) -> Option<&str> {
-        self.query.as_deref()
+        #[arg(
+            long,
+            value_name = "NUM",
+            default_value = "1",
+            value_parser = clap::value_parser!(u8).range(1..=8),
+            help = "Split the task across this many worker agents researching \n\
+                    sub-topics concurrently, then merge their findings into one report"
+        )]
+        workers: u8,
+
+        #[arg(
+            long,
+            value_name = "NUM",
+            default_value_t = crate::orchestrate::DEFAULT_WORKER_ITERATIONS,
+            help = "Maximum iterations for each worker agent when --workers is above 1"
+        )]
+        worker_iterations: usize,
+
+        #[arg(
+            long,
+            value_name = "MODEL",
+            help = "Model that plans sub-topics and synthesizes the report (defaults to --model)"
+        )]
+        planner_model: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "NUM",
+            default_value = "1",
+            value_parser = clap::value_parser!(u8).range(1..=8),
+            conflicts_with = "workers",
+            help = "Answer the question this many times independently, then reconcile the \n\
+                    answers and flag the points where the runs disagree"
+        )]
+        samples: u8,
+
+        #[arg(
+            long,
+            value_name = "MODEL",
+            value_delimiter = ',',
+            help = "Models to cycle through for --samples runs (comma-separated, defaults to --model)"
+        )]
+        sample_models: Vec<String>,
+
+        #[arg(
+            long,
+            help = "Print each agent event (iterations, tool calls and results, thinking, answer) \n\
+                    as one JSON object per line on stdout instead of the usual output"
+        )]
+        json_stream: bool,
+    },
+    #[command(about = "Search arXiv for academic papers")]
+    Arxiv {
+        #[arg(
+            value_name = "QUERY",
+            help = "Search terms, or an arXiv query such as 'au:hinton AND ti:capsule'"
+        )]
+        query: String,
+
+        #[arg(
+            short = 'n',
+            long,
+            value_name = "NUM",
+            default_value = "10",
+            help = "Number of papers to return (max 50)"
+        )]
+        limit: usize,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value = "relevance",
+            help = "Order papers by relevance or by newest submitted/updated date"
+        )]
+        sort: SortBy,
+    },
+    #[command(about = "Read the latest entries of an RSS or Atom feed")]
+    Feed {
+        #[arg(
+            value_name = "URL",
+            help = "Feed URL, or a page that links to its feed"
+        )]
+        url: String,
+
+        #[arg(
+            short = 'n',
+            long,
+            value_name = "NUM",
+            default_value = "10",
+            help = "Number of entries to show (max 50)"
+        )]
+        limit: usize,
+    },
+    #[command(about = "Summarize a web page, local file, or standard input")]
+    Summarize(SummarizeArgs),
+    #[command(about = "Give a two-sentence TL;DR of a web page, local file, or standard input")]
+    Tldr(SummarizeArgs),
+    #[command(about = "Browse search results interactively in the terminal")]
+    Browse {
+        #[arg(value_name = "QUERY", help = "Search query")]
+        query: String,
+
+        #[arg(
+            short = 'm',
+            long,
+            value_name = "MODEL",
+            default_value = "gpt-oss:20b",
+            help = "Local Ollama model used when sending a result to the agent"
+        )]
+        model: String,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "http://localhost:11434",
+            help = "Local Ollama server URL"
+        )]
+        ollama_url: String,
+
+        #[arg(
+            long,
+            value_name = "MODEL",
+            default_value = crate::kb::DEFAULT_EMBED_MODEL,
+            help = "Local Ollama embedding model used when saving results to the knowledge base"
+        )]
+        embed_model: String,
+    },
+    #[command(about = "Answer a question by following relevant links from a starting page")]
+    Trail {
+        #[arg(value_name = "URL", help = "Page to start from")]
+        url: String,
+
+        #[arg(
+            value_name = "QUESTION",
+            help = "Question to answer from the pages reached"
+        )]
+        question: String,
+
+        #[arg(
+            short = 'm',
+            long,
+            value_name = "MODEL",
+            default_value = "gpt-oss:20b",
+            help = "Local Ollama model that picks links and writes the answer"
+        )]
+        model: String,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "http://localhost:11434",
+            help = "Local Ollama server URL"
+        )]
+        ollama_url: String,
+
+        #[arg(
+            long,
+            value_name = "NUM",
+            default_value_t = crate::trail::DEFAULT_MAX_PAGES,
+            help = "Maximum number of pages to read, including the starting page"
+        )]
+        max_pages: usize,
+
+        #[arg(
+            long,
+            value_name = "NUM",
+            default_value_t = crate::trail::DEFAULT_MAX_DEPTH,
+            help = "Maximum number of links to follow away from the starting page"
+        )]
+        max_depth: usize,
+
+        #[arg(long, help = "Disable model reasoning (thinking mode)")]
+        disable_reasoning: bool,
+
+        #[arg(long, help = "Disable browser preview (preview is enabled by default)")]
+        no_preview: bool,
+    },
+    #[command(about = "Rate a search result to adjust future ranking of its domain")]
+    Feedback {
+        #[arg(
+            value_name = "RESULT_ID",
+            required_unless_present = "export",
+            help = "Result id shown next to each search result (or the result URL)"
+        )]
+        result_id: Option<String>,
+
+        #[arg(
+            value_name = "RATING",
+            value_enum,
+            required_unless_present = "export",
+            help = "Whether the result was relevant"
+        )]
+        rating: Option<Rating>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["result_id", "rating"],
+            help = "Export all ratings as a JSONL evaluation set"
+        )]
+        export: Option<PathBuf>,
+    },
+    #[command(
+        about = "List and search past searches, fetches, and agent runs",
+        args_conflicts_with_subcommands = true
+    )]
+    History(HistoryArgs),
+    #[command(about = "Compare two stored answers or result sets from history")]
+    Diff {
+        #[arg(value_name = "OLD_ID", help = "History id of the earlier entry")]
+        old: i64,
+
+        #[arg(value_name = "NEW_ID", help = "History id of the later entry")]
+        new: i64,
+    },
+    #[command(about = "Export a stored answer as a note into an Obsidian vault")]
+    Export {
+        #[arg(
+            value_name = "ID",
+            help = "History id to export [default: the latest agent answer]"
+        )]
+        id: Option<i64>,
+
+        #[arg(
+            long,
+            value_name = "VAULT_DIR",
+            required = true,
+            help = "Vault directory (or a folder inside it) to write the note to"
+        )]
+        obsidian: PathBuf,
+
+        #[arg(
+            long = "tag",
+            value_name = "TAG",
+            help = "Extra tag for the note's frontmatter (repeatable)"
+        )]
+        tags: Vec<String>,
+
+        #[arg(
+            long,
+            help = "Prefix the file name with a Zettelkasten id (YYYYMMDDHHMM)"
+        )]
+        zettel: bool,
+    },
+    #[command(about = "Manage the personal knowledge base the agent can consult")]
+    Kb(KbArgs),
+    #[command(about = "Re-run a search or agent query on a schedule and report what changed")]
+    Watch {
+        #[arg(
+            value_name = "QUERY",
+            help = "Search query or research question to monitor"
+        )]
+        query: String,
+
+        #[arg(
+            short = 'i',
+            long,
+            value_name = "INTERVAL",
+            default_value = "6h",
+            value_parser = crate::watch::parse_interval,
+            help = "Time between checks, e.g. 30m, 6h, 1d"
+        )]
+        interval: std::time::Duration,
+
+        #[arg(
+            long,
+            help = "Monitor the agent's answer instead of raw search results"
+        )]
+        agent: bool,
+
+        #[arg(long, help = "Check once and exit (useful from cron)")]
+        once: bool,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write a side-by-side HTML diff to FILE whenever something changes"
+        )]
+        html: Option<PathBuf>,
+
+        #[arg(
+            short = 'm',
+            long,
+            value_name = "MODEL",
+            default_value = "gpt-oss:20b",
+            help = "Local Ollama model used with --agent"
+        )]
+        model: String,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "http://localhost:11434",
+            help = "Local Ollama server URL"
+        )]
+        ollama_url: String,
+
+        #[arg(
+            long,
+            value_name = "MODEL",
+            default_value = crate::kb::DEFAULT_EMBED_MODEL,
+            help = "Local Ollama embedding model used by the kb_search tool"
+        )]
+        embed_model: String,
+    },
+    #[command(about = "Run scheduled jobs from the config file and write reports")]
+    Daemon {
+        #[arg(
+            short = 'm',
+            long,
+            value_name = "MODEL",
+            default_value = "gpt-oss:20b",
+            help = "Local Ollama model for agent jobs that don't set one"
+        )]
+        model: String,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "http://localhost:11434",
+            help = "Local Ollama server URL"
+        )]
+        ollama_url: String,
+
+        #[arg(
+            long,
+            value_name = "MODEL",
+            default_value = crate::kb::DEFAULT_EMBED_MODEL,
+            help = "Local Ollama embedding model used by the kb_search tool"
+        )]
+        embed_model: String,
+    },
+    #[command(about = "List, add, and remove scheduled jobs in the config file")]
+    Jobs {
+        #[command(subcommand)]
+        command: JobsCommand,
+    },
+    #[command(about = "Save, tag, and list bookmarked URLs")]
+    Bookmark {
+        #[command(subcommand)]
+        command: BookmarkCommand,
+    },
+    #[command(about = "Grant, revoke, and list persisted agent tool permissions")]
+    Permissions {
+        #[command(subcommand)]
+        command: PermissionsCommand,
+    },
+    #[command(about = "Report or enforce retention policies for the local store")]
+    Clean {
+        #[arg(
+            long,
+            help = "Delete entries that exceed the configured retention policies \n\
+                    (without this flag, only report what would be removed)"
+        )]
+        enforce_policy: bool,
+    },
+    #[command(about = "Report or delete files cached outside the local store")]
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    #[command(about = "Browse, resume, export, and delete past sessions")]
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommand,
+    },
+    #[command(about = "Show web API calls and model tokens used against the configured budget")]
+    Usage,
+    #[command(
+        about = "Report searches, fetches, agent runs, cache hits, and tokens per day from local history"
+    )]
+    Stats {
+        #[arg(
+            long,
+            value_name = "NUM",
+            default_value = "14",
+            value_parser = clap::value_parser!(u64).range(1..=3650),
+            help = "Number of days to report, ending today"
+        )]
+        days: u64,
+    },
+    #[command(
+        about = "Print a shell completion script that also completes installed models and job names"
+    )]
+    Completions {
+        #[arg(value_name = "SHELL", help = "Shell to generate the script for")]
+        shell: clap_complete::Shell,
+    },
+    #[command(about = "Run multi-step research pipelines defined in YAML")]
+    Pipeline {
+        #[command(subcommand)]
+        command: PipelineCommand,
+    },
+    #[command(about = "Save, list, and remove query aliases run as 'weavex <ALIAS> [ARGS]...'")]
+    Alias {
+        #[command(subcommand)]
+        command: AliasCommand,
+    },
+    #[command(
+        about = "List and run query templates from the templates directory next to the config file"
+    )]
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PipelineCommand {
+    #[command(about = "Run the steps of a pipeline file in order")]
+    Run {
+        #[arg(value_name = "FILE", help = "Pipeline definition (YAML)")]
+        file: PathBuf,
+
+        #[arg(
+            long = "var",
+            value_name = "NAME=VALUE",
+            value_parser = parse_var,
+            help = "Value for a {NAME} placeholder, overriding the pipeline's vars (repeatable)"
+        )]
+        vars: Vec<(String, String)>,
+
+        #[arg(
+            short = 'm',
+            long,
+            value_name = "MODEL",
+            default_value = "gpt-oss:20b",
+            help = "Local Ollama model for agent and summarize steps"
+        )]
+        model: String,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "http://localhost:11434",
+            help = "Local Ollama server URL"
+        )]
+        ollama_url: String,
+
+        #[arg(
+            long,
+            value_name = "MODEL",
+            default_value = crate::kb::DEFAULT_EMBED_MODEL,
+            help = "Local Ollama embedding model used by the kb_search tool"
+        )]
+        embed_model: String,
+
+        #[arg(
+            long,
+            value_enum,
+            help = "Backend for summarize steps: the local Ollama model, the OpenAI backend, \n\
+                    or a fast extractive summarizer without a model (defaults to --backend)"
+        )]
+        summarizer: Option<Summarizer>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AliasCommand {
+    #[command(about = "List saved aliases")]
+    List,
+    #[command(about = "Save an alias for a query")]
+    Add {
+        #[arg(value_name = "NAME", help = "Alias name, used as 'weavex NAME'")]
+        name: String,
+
+        #[arg(
+            value_name = "QUERY",
+            help = "Query text; {1}, {2}, ... take the alias arguments and {*} takes all of them"
+        )]
+        query: String,
+
+        #[arg(long, help = "Run the agent instead of a plain search")]
+        agent: bool,
+
+        #[arg(
+            value_name = "FLAGS",
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            help = "weavex flags to add whenever the alias runs, e.g. --max-results 5"
+        )]
+        flags: Vec<String>,
+    },
+    #[command(about = "Remove an alias")]
+    Remove {
+        #[arg(value_name = "NAME", help = "Alias name")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateCommand {
+    #[command(about = "List templates with their description and variables")]
+    List,
+    #[command(about = "Fill in a template's variables and run it")]
+    Run {
+        #[arg(
+            value_name = "NAME",
+            help = "Template name (its file name without .toml)"
+        )]
+        name: String,
+
+        #[arg(
+            long = "var",
+            value_name = "NAME=VALUE",
+            value_parser = parse_var,
+            help = "Value for a {NAME} placeholder, overriding the template's default (repeatable)"
+        )]
+        vars: Vec<(String, String)>,
+
+        #[arg(
+            last = true,
+            value_name = "FLAGS",
+            help = "Extra weavex flags appended after the template's own flags"
+        )]
+        flags: Vec<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum JobsCommand {
+    #[command(about = "List scheduled jobs and their next run")]
+    List,
+    #[command(about = "Add a scheduled job")]
+    Add {
+        #[arg(value_name = "NAME", help = "Unique job name")]
+        name: String,
+
+        #[arg(value_name = "QUERY", help = "Search query or research question")]
+        query: String,
+
+        #[arg(
+            short = 's',
+            long,
+            value_name = "CRON",
+            help = "Cron expression, e.g. \"0 8 * * *\" for every day at 08:00"
+        )]
+        schedule: String,
+
+        #[arg(long, help = "Run the agent instead of a plain search")]
+        agent: bool,
+
+        #[arg(
+            short = 'm',
+            long,
+            value_name = "MODEL",
+            help = "Local Ollama model for this job"
+        )]
+        model: Option<String>,
+    },
+    #[command(about = "Remove a scheduled job")]
+    Remove {
+        #[arg(value_name = "NAME", help = "Job name")]
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BookmarkCommand {
+    #[command(about = "Bookmark a URL")]
+    Add {
+        #[arg(value_name = "URL", help = "URL to bookmark")]
+        url: String,
+
+        #[arg(
+            short = 't',
+            long,
+            value_name = "TAGS",
+            value_delimiter = ',',
+            help = "Comma-separated tags"
+        )]
+        tags: Vec<String>,
+
+        #[arg(long, value_name = "TITLE", help = "Title to show in listings")]
+        title: Option<String>,
+    },
+    #[command(about = "List bookmarks")]
+    List {
+        #[arg(
+            short = 't',
+            long,
+            value_name = "TAG",
+            help = "Only show bookmarks with this tag"
+        )]
+        tag: Option<String>,
+    },
+    #[command(about = "Remove a bookmark")]
+    Remove {
+        #[arg(value_name = "URL", help = "Bookmarked URL to remove")]
+        url: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PermissionsCommand {
+    #[command(about = "Allow a capability in every future agent run")]
+    Grant {
+        #[arg(value_name = "CAPABILITY", help = "Capability to grant")]
+        capability: Capability,
+    },
+    #[command(about = "Withdraw a persisted grant")]
+    Revoke {
+        #[arg(value_name = "CAPABILITY", help = "Capability to revoke")]
+        capability: Capability,
+    },
+    #[command(about = "List capabilities and whether they are granted")]
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    #[command(about = "Report cached browser preview files, or delete them with --previews")]
+    Clean {
+        #[arg(
+            long,
+            help = "Delete all browser preview files in your preview directory"
+        )]
+        previews: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SessionsCommand {
+    #[command(about = "Open an interactive session picker with fuzzy search")]
+    Tui {
+        #[arg(
+            short = 'n',
+            long,
+            value_name = "NUM",
+            default_value = "500",
+            help = "Maximum number of sessions to load"
+        )]
+        limit: usize,
+
+        #[arg(
+            short = 'm',
+            long,
+            value_name = "MODEL",
+            default_value = "gpt-oss:20b",
+            help = "Local Ollama model used when resuming a session"
+        )]
+        model: String,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            default_value = "http://localhost:11434",
+            help = "Local Ollama server URL"
+        )]
+        ollama_url: String,
+
+        #[arg(
+            long,
+            value_name = "MODEL",
+            default_value = crate::kb::DEFAULT_EMBED_MODEL,
+            help = "Local Ollama embedding model used by the kb_search tool"
+        )]
+        embed_model: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct SummarizeArgs {
+    #[arg(
+        value_name = "SOURCE",
+        help = "URL, path to a local text/markdown file, or - to read standard input"
+    )]
+    pub source: String,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "ollama",
+        help = "Summarize with the local Ollama model, an OpenAI-compatible endpoint, \n\
+                or a fast extractive summarizer that picks key sentences without a model"
+    )]
+    pub summarizer: Summarizer,
+
+    #[arg(
+        short = 'm',
+        long,
+        value_name = "MODEL",
+        default_value = "gpt-oss:20b",
+        help = "Model to summarize with (--summarizer openai uses the [openai] model \n\
+                from the config file, or gpt-4.1, unless this is set)"
+    )]
+    pub model: String,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        default_value = "http://localhost:11434",
+        help = "Local Ollama server URL"
+    )]
+    pub ollama_url: String,
+
+    #[arg(
+        long,
+        value_name = "URL",
+        env = "OPENAI_BASE_URL",
+        help = "Base URL of the OpenAI-compatible endpoint for --summarizer openai \n\
+                (defaults to base_url in the [openai] section, then https://api.openai.com/v1)"
+    )]
+    pub openai_url: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct KbArgs {
+    #[command(subcommand)]
+    pub command: KbCommand,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODEL",
+        default_value = crate::kb::DEFAULT_EMBED_MODEL,
+        help = "Local Ollama embedding model"
+    )]
+    pub embed_model: String,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        default_value = "http://localhost:11434",
+        help = "Local Ollama server URL"
+    )]
+    pub ollama_url: String,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Collection to add to or scope to (add defaults to 'default'; \n\
+                list, query, and remove span all collections when omitted)"
+    )]
+    pub collection: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum KbCommand {
+    #[command(about = "Fetch a URL or read a local file, chunk it, and embed it")]
+    Add {
+        #[arg(
+            value_name = "SOURCE",
+            help = "URL or path to a local text/markdown file"
+        )]
+        source: String,
+
+        #[arg(
+            long,
+            value_name = "STRATEGY",
+            value_enum,
+            default_value = "paragraph",
+            help = "How to split the document: paragraph, heading (markdown sections), \n\
+                    tokens (fixed windows with overlap), or semantic (split on topic shifts)"
+        )]
+        chunking: Strategy,
+
+        #[arg(
+            long,
+            value_name = "TOKENS",
+            default_value_t = crate::chunking::DEFAULT_CHUNK_TOKENS,
+            help = "Target chunk size in tokens (approximated as words, or 4 characters each)"
+        )]
+        chunk_size: usize,
+
+        #[arg(
+            long,
+            value_name = "TOKENS",
+            default_value_t = crate::chunking::DEFAULT_OVERLAP_TOKENS,
+            help = "Tokens shared between consecutive chunks with --chunking tokens"
+        )]
+        overlap: usize,
+
+        #[arg(long, help = "Title to store instead of the page or file name")]
+        title: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "Original URL of a local file, shown as the passage source"
+        )]
+        url: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "DATE",
+            value_parser = parse_date,
+            help = "Fetch date (YYYY-MM-DD); defaults to now for URLs and the file's modification time"
+        )]
+        fetched: Option<i64>,
+    },
+    #[command(about = "List documents in the knowledge base")]
+    List,
+    #[command(about = "Remove a document and its chunks from the knowledge base")]
+    Remove {
+        #[arg(
+            value_name = "SOURCE",
+            help = "URL or path the document was added from"
+        )]
+        source: String,
+    },
+    #[command(about = "List knowledge base collections with document and chunk counts")]
+    Collections,
+    #[command(about = "Rebuild the vector index and reclaim unused space")]
+    Compact,
+    #[command(about = "Find the passages most relevant to a query (keyword + vector)")]
+    Query {
+        #[arg(value_name = "QUERY", help = "What to look up")]
+        query: String,
+
+        #[arg(
+            short = 'n',
+            long,
+            value_name = "NUM",
+            default_value = "5",
+            help = "Number of passages to return"
+        )]
+        limit: usize,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub command: Option<HistoryCommand>,
+
+    #[arg(
+        value_name = "TERM",
+        help = "Full-text search over queries and answers"
+    )]
+    pub term: Option<String>,
+
+    #[arg(
+        short = 'n',
+        long,
+        value_name = "NUM",
+        default_value = "20",
+        help = "Maximum number of entries to list"
+    )]
+    pub limit: usize,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommand {
+    #[command(about = "Show a past entry in full")]
+    Show {
+        #[arg(value_name = "ID", help = "History entry id")]
+        id: i64,
+
+        #[arg(long, help = "Open the entry in the browser preview")]
+        preview: bool,
+    },
+}
+
+fn validate_timeout(s: &str) -> Result<u64, String> {
+    let timeout: u64 = s
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid number", s))?;
+    if !(1..=600).contains(&timeout) {
+        return Err("timeout must be between 1 and 600 seconds".to_string());
+    }
+    Ok(timeout)
+}
+
+fn validate_chat_timeout(s: &str) -> Result<u64, String> {
+    let timeout: u64 = s
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid number", s))?;
+    if !(1..=3600).contains(&timeout) {
+        return Err("chat timeout must be between 1 and 3600 seconds".to_string());
+    }
+    Ok(timeout)
+}
+
+fn parse_var(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("'{}' is not in NAME=VALUE format", s)),
+    }
+}
+
+fn parse_date(s: &str) -> Result<i64, String> {
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(|dt| dt.and_local_timezone(chrono::Local).earliest())
+        .map(|dt| dt.timestamp())
+        .ok_or_else(|| format!("'{}' is not a date in YYYY-MM-DD format", s))
+}
+
+impl Cli {
+    pub fn get_query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    pub fn result_offset(&self, page_size: usize) -> usize {
+        match (self.page, self.offset) {
+            (Some(page), _) => (page as usize - 1) * page_size,
+            (None, offset) => offset.unwrap_or(0),
+        }
+    }
+
+    pub fn log_filter(&self) -> &'static str {
+        match self.verbose {
+            0 => "weavex=warn",
+            1 => "weavex=info,warn",
+            2 => "weavex=debug,info",
+            _ => "trace",
+        }
+    }
+
+    pub fn read_stdin_query(&mut self, stdin: impl Read, piped: bool) -> io::Result<()> {
+        let query = match &mut self.command {
+            None => {
+                if self.query.is_none() && piped || self.query.as_deref() == Some(STDIN_QUERY) {
+                    self.query = read_query(stdin)?;
+                }
+                return Ok(());
+            }
+            Some(Command::Agent { query, .. })
+            | Some(Command::Arxiv { query, .. })
+            | Some(Command::Browse { query, .. })
+            | Some(Command::Watch { query, .. })
+            | Some(Command::Trail {
+                question: query, ..
+            }) => query,
+            _ => return Ok(()),
+        };
+        if query == STDIN_QUERY {
+            *query = read_query(stdin)?.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "No query received on stdin")
+            })?;
+        }
+        Ok(())
+    }
+
+    pub fn parse_localized(args: Vec<std::ffi::OsString>) -> Self {
+        Self::command()
+            .after_help(i18n::tr("help-examples"))
+            .try_get_matches_from(args)
+            .and_then(|matches| Self::from_arg_matches(&matches))
+            .unwrap_or_else(|e| exit_on_usage_error(e))
+    }
+}
+
+fn exit_on_usage_error(error: clap::Error) -> ! {
+    let _ = error.print();
+    let code = if error.use_stderr() {
+        exit_code::FAILURE
+    } else {
+        exit_code::SUCCESS
+    };
+    std::process::exit(code.into())
+}
+
+fn read_query(mut stdin: impl Read) -> io::Result<Option<String>> {
+    let mut query = String::new();
+    stdin.read_to_string(&mut query)?;
+    let query = query.trim();
+    Ok((!query.is_empty()).then(|| query.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("weavex").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_reads_search_query_from_piped_stdin() {
+        let mut cli = parse(&[]);
+        cli.read_stdin_query("  rust async runtimes\n".as_bytes(), true)
+            .unwrap();
+        assert_eq!(cli.get_query(), Some("rust async runtimes"));
+
+        let mut cli = parse(&["tokio"]);
+        cli.read_stdin_query("ignored".as_bytes(), true).unwrap();
+        assert_eq!(cli.get_query(), Some("tokio"));
+
+        let mut cli = parse(&[]);
+        cli.read_stdin_query("ignored".as_bytes(), false).unwrap();
+        assert_eq!(cli.get_query(), None);
+    }
+
+    #[test]
+    fn test_page_and_offset_map_to_result_offset() {
+        assert_eq!(parse(&["tokio"]).result_offset(10), 0);
+        assert_eq!(parse(&["--page", "3", "tokio"]).result_offset(5), 10);
+        assert_eq!(parse(&["--offset", "7", "tokio"]).result_offset(5), 7);
+        assert!(Cli::try_parse_from(["weavex", "--page", "0", "tokio"]).is_err());
+        assert!(Cli::try_parse_from(["weavex", "--page", "2", "--offset", "4", "x"]).is_err());
+    }
+
+    #[test]
+    fn test_dash_reads_agent_query_from_stdin() {
+        let mut cli = parse(&["agent", "-"]);
+        cli.read_stdin_query("Compare\nsmol and tokio\n".as_bytes(), false)
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Agent { ref query, .. }) if query == "Compare\nsmol and tokio"
+        ));
+
+        let mut cli = parse(&["agent", "-"]);
+        assert!(cli.read_stdin_query("  \n".as_bytes(), true).is_err());
+    }
+
+    #[test]
+    fn test_verbosity_levels() {
+        assert_eq!(parse(&["tokio"]).log_filter(), "weavex=warn");
+        assert_eq!(parse(&["-v", "tokio"]).log_filter(), "weavex=info,warn");
+        assert_eq!(
+            parse(&["agent", "-vv", "tokio"]).log_filter(),
+            "weavex=debug,info"
+        );
+        assert_eq!(
+            parse(&["-v", "agent", "-vvv", "tokio"]).log_filter(),
+            "trace"
+        );
     }
 }
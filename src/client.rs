@@ -1,8 +1,22 @@
+use crate::breaker::{self, CircuitBreaker};
 use crate::config::Config;
+use crate::credibility::Tier;
 use crate::error::{OllamaError, Result};
+use crate::metadata;
+use crate::mock::Fixtures;
+use crate::network::{self, AuditedSend};
+use crate::pdf;
+use crate::ratelimit;
+use crate::readability;
+use crate::usage;
+use crate::vcr;
+use crate::wayback;
+use reqwest::header::CONTENT_TYPE;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, instrument};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, instrument, warn};
 use url::Url;
 
 #[derive(Debug, Serialize)]
@@ -10,6 +24,8 @@ struct SearchRequest {
     query: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_results: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
@@ -17,11 +33,29 @@ struct FetchRequest {
     url: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct SearchResult {
     pub title: String,
     pub url: String,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub explain: Vec<String>,
+    #[serde(
+        default,
+        alias = "relevance",
+        alias = "relevance_score",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credibility: Option<Tier>,
+    #[serde(
+        default,
+        alias = "publishedDate",
+        alias = "page_age",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub published: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -29,7 +63,7 @@ pub struct SearchResponse {
     pub results: Vec<SearchResult>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct FetchResponse {
     #[serde(default)]
     pub title: String,
@@ -37,25 +71,131 @@ pub struct FetchResponse {
     pub content: String,
     #[serde(default)]
     pub links: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_url: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_archive: bool,
+}
+
+impl FetchResponse {
+    pub fn attribution(&self) -> String {
+        let archived = self.archived_at.as_ref().map(|date| {
+            format!(
+                "Wayback Machine snapshot from {} ({})",
+                date,
+                self.archive_url.as_deref().unwrap_or_default()
+            )
+        });
+        [
+            ("Archived", &archived),
+            ("Author", &self.author),
+            ("Published", &self.published),
+            ("Description", &self.description),
+            ("Canonical URL", &self.canonical_url),
+        ]
+        .iter()
+        .filter_map(|(label, value)| {
+            value
+                .as_ref()
+                .map(|value| format!("{}: {}\n", label, value))
+        })
+        .collect()
+    }
 }
 
+#[derive(Clone)]
 pub struct OllamaClient {
     client: Client,
     config: Config,
+    breaker: Arc<CircuitBreaker>,
+    fixtures: Option<Arc<Fixtures>>,
 }
 
 impl OllamaClient {
     pub fn new(config: Config) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(config.timeout)
-            .build()
-            .map_err(OllamaError::RequestFailed)?;
+        let mut builder = network::client_builder().connect_timeout(config.connect_timeout);
+        if let Some(read_timeout) = config.read_timeout {
+            builder = builder.read_timeout(read_timeout);
+        }
+        let client = builder.build().map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self {
+            client,
+            breaker: breaker::shared(&config.base_url),
+            config,
+            fixtures: None,
+        })
+    }
+
+    pub fn with_fixtures(mut self, fixtures: Fixtures) -> Self {
+        self.fixtures = Some(Arc::new(fixtures));
+        self
+    }
 
-        Ok(Self { client, config })
+    async fn post_api<T: Serialize>(
+        &self,
+        url: &str,
+        request: &T,
+        timeout: Duration,
+    ) -> Result<String> {
+        vcr::exchange(url, request, || async {
+            usage::check(usage::Kind::WebCalls)?;
+            ratelimit::acquire().await;
+            usage::add(usage::Kind::WebCalls, 1);
+            let response = self
+                .client
+                .post(url)
+                .header("Authorization", format!("Bearer {}", self.config.api_key))
+                .header("Content-Type", "application/json")
+                .timeout(timeout)
+                .json(request)
+                .send_audited()
+                .await?;
+
+            let status = response.status();
+
+            if !status.is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                return Err(OllamaError::ApiError {
+                    status: status.as_u16(),
+                    message: error_text,
+                });
+            }
+
+            Ok(response.text().await?)
+        })
+        .await
     }
 
     #[instrument(skip(self))]
-    pub async fn search(&self, query: &str) -> Result<SearchResponse> {
+    pub async fn search_with_limit(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+    ) -> Result<SearchResponse> {
+        self.search_from(query, max_results, 0).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn search_from(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+        offset: usize,
+    ) -> Result<SearchResponse> {
         if query.trim().is_empty() {
             return Err(OllamaError::InvalidResponse(
                 "Search query cannot be empty".to_string(),
@@ -68,32 +208,16 @@ impl OllamaClient {
 
         let request = SearchRequest {
             query: query.to_string(),
-            max_results: self.config.max_results,
+            max_results,
+            offset: Some(offset).filter(|offset| *offset > 0),
         };
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+            .breaker
+            .call(self.post_api(&url, &request, self.config.search_timeout))
             .await?;
 
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(OllamaError::ApiError {
-                status: status.as_u16(),
-                message: error_text,
-            });
-        }
-
-        let search_response = response.json::<SearchResponse>().await.map_err(|e| {
+        let search_response = serde_json::from_str::<SearchResponse>(&response).map_err(|e| {
             OllamaError::InvalidResponse(format!("Failed to parse search response: {}", e))
         })?;
 
@@ -112,6 +236,31 @@ impl OllamaClient {
             )));
         }
 
+        if let Some(fixtures) = &self.fixtures {
+            return fixtures.fetch(target_url);
+        }
+
+        match self.fetch_live(target_url, &parsed_url).await {
+            Ok(response) if !response.content.trim().is_empty() => Ok(response),
+            Ok(response) => Ok(self.fetch_archived(target_url).await.unwrap_or(response)),
+            Err(
+                e @ OllamaError::ApiError {
+                    status: 403 | 404, ..
+                },
+            ) => self.fetch_archived(target_url).await.ok_or(e),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn fetch_live(&self, target_url: &str, parsed_url: &Url) -> Result<FetchResponse> {
+        let pdf_url = pdf::is_pdf_url(parsed_url);
+        if pdf_url {
+            match self.fetch_pdf(target_url).await {
+                Ok(response) => return Ok(response),
+                Err(e) => debug!("Local PDF extraction failed for {}: {}", target_url, e),
+            }
+        }
+
         let url = format!("{}/web_fetch", self.config.base_url);
 
         debug!("Sending fetch request to: {}", url);
@@ -121,39 +270,146 @@ impl OllamaClient {
         };
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
+            .breaker
+            .call(self.post_api(&url, &request, self.config.fetch_timeout))
             .await?;
 
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(OllamaError::ApiError {
-                status: status.as_u16(),
-                message: error_text,
-            });
-        }
-
-        let fetch_response = response.json::<FetchResponse>().await.map_err(|e| {
+        let fetch_response = serde_json::from_str::<FetchResponse>(&response).map_err(|e| {
             OllamaError::InvalidResponse(format!("Failed to parse fetch response: {}", e))
         })?;
 
-        Ok(fetch_response)
+        let unusable = fetch_response.content.trim().is_empty()
+            || pdf::is_pdf_bytes(fetch_response.content.as_bytes());
+        if unusable && !pdf_url {
+            match self.fetch_pdf(target_url).await {
+                Ok(response) => return Ok(response),
+                Err(e) => debug!("No PDF to extract at {}: {}", target_url, e),
+            }
+        }
+
+        Ok(self.process(fetch_response))
+    }
+
+    async fn fetch_archived(&self, target_url: &str) -> Option<FetchResponse> {
+        let snapshot = match self.find_snapshot(target_url).await {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => {
+                debug!("No Wayback Machine snapshot of {}", target_url);
+                return None;
+            }
+            Err(e) => {
+                warn!("Wayback Machine lookup failed for {}: {}", target_url, e);
+                return None;
+            }
+        };
+
+        info!(
+            "Falling back to Wayback Machine snapshot of {} from {}",
+            target_url,
+            snapshot.date()
+        );
+        let content = match self.get_text(&snapshot.raw_url()).await {
+            Ok(content) if !content.trim().is_empty() => content,
+            Ok(_) => return None,
+            Err(e) => {
+                warn!("Failed to fetch Wayback Machine snapshot: {}", e);
+                return None;
+            }
+        };
+
+        let mut response = self.process(FetchResponse {
+            content,
+            ..Default::default()
+        });
+        if response.title.is_empty() {
+            response.title = target_url.to_string();
+        }
+        response.archived_at = Some(snapshot.date());
+        response.archive_url = Some(snapshot.url);
+        Some(response)
+    }
+
+    async fn find_snapshot(&self, target_url: &str) -> Result<Option<wayback::Snapshot>> {
+        let body = self
+            .get_text(&wayback::availability_url(target_url))
+            .await?;
+        Ok(wayback::parse_snapshot(&body))
+    }
+
+    async fn get_text(&self, url: &str) -> Result<String> {
+        let response = self
+            .client
+            .get(url)
+            .timeout(self.config.fetch_timeout)
+            .send_audited()
+            .await?
+            .error_for_status()?;
+        Ok(response.text().await?)
+    }
+
+    fn process(&self, mut fetch_response: FetchResponse) -> FetchResponse {
+        if readability::looks_like_html(&fetch_response.content) {
+            let page = metadata::extract(&fetch_response.content);
+            fetch_response.author = fetch_response.author.or(page.author);
+            fetch_response.published = fetch_response.published.or(page.published);
+            fetch_response.description = fetch_response.description.or(page.description);
+            fetch_response.canonical_url = fetch_response.canonical_url.or(page.canonical_url);
+            fetch_response.no_archive |= page.no_archive;
+        }
+
+        if !self.config.raw {
+            fetch_response.content = readability::clean(&fetch_response.content);
+        }
+
+        fetch_response
+    }
+
+    async fn fetch_pdf(&self, target_url: &str) -> Result<FetchResponse> {
+        debug!("Downloading {} for local PDF extraction", target_url);
+        let response = self
+            .client
+            .get(target_url)
+            .timeout(self.config.fetch_timeout)
+            .send_audited()
+            .await?
+            .error_for_status()?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if content_type.starts_with("text/") {
+            return Err(OllamaError::InvalidResponse(format!(
+                "Expected a PDF, got {}",
+                content_type
+            )));
+        }
+
+        let bytes = response.bytes().await?;
+        if !pdf::is_pdf_bytes(&bytes) {
+            return Err(OllamaError::InvalidResponse(
+                "Response is not a PDF document".to_string(),
+            ));
+        }
+
+        let content = pdf::extract_text(bytes.to_vec()).await?;
+        info!(
+            "Extracted {} chars of text from PDF at {}",
+            content.len(),
+            target_url
+        );
+        Ok(FetchResponse {
+            title: pdf::title(&content),
+            content,
+            ..Default::default()
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
 
     fn create_test_client() -> OllamaClient {
         let config = Config::new("test_key".to_string()).with_timeout(Duration::from_secs(30));
@@ -166,7 +422,7 @@ mod tests {
         let rt = Runtime::new().unwrap();
         let client = create_test_client();
 
-        let result = rt.block_on(client.search(""));
+        let result = rt.block_on(client.search_with_limit("", None));
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("empty"));
     }
@@ -177,7 +433,7 @@ mod tests {
         let rt = Runtime::new().unwrap();
         let client = create_test_client();
 
-        let result = rt.block_on(client.search("   "));
+        let result = rt.block_on(client.search_with_limit("   ", None));
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("empty"));
     }
@@ -0,0 +1,39 @@
+use arboard::Clipboard;
+use tracing::{debug, warn};
+
+pub fn copy(text: &str) -> bool {
+    match Clipboard::new().and_then(|mut clipboard| clipboard.set_text(payload(text))) {
+        Ok(()) => {
+            debug!("Copied {} bytes to the clipboard", text.len());
+            true
+        }
+        Err(e) => {
+            warn!("Failed to copy to the clipboard: {}", e);
+            false
+        }
+    }
+}
+
+fn payload(text: &str) -> String {
+    let text = text.trim();
+    if cfg!(windows) {
+        text.replace("\r\n", "\n").replace('\n', "\r\n")
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_is_trimmed_with_platform_line_endings() {
+        let copied = payload("\n# Answer\nTokio.\n\n");
+        if cfg!(windows) {
+            assert_eq!(copied, "# Answer\r\nTokio.");
+        } else {
+            assert_eq!(copied, "# Answer\nTokio.");
+        }
+    }
+}
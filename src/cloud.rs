@@ -0,0 +1,625 @@
+use crate::breaker::{self, CircuitBreaker};
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use crate::ollama_local::{
+    ChatBackend, ChatMessage, ChatResponse, FunctionCall, Tool, ToolCall, DEFAULT_CHAT_TIMEOUT,
+};
+use crate::usage;
+use crate::vcr;
+use crate::vision;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::ValueEnum;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+pub const DEFAULT_OLLAMA_MODEL: &str = "gpt-oss:20b";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const ANTHROPIC_MAX_TOKENS: u64 = 8192;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Backend {
+    #[default]
+    Ollama,
+    Openai,
+    Anthropic,
+}
+
+impl Backend {
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Ollama => "ollama",
+            Backend::Openai => "openai",
+            Backend::Anthropic => "anthropic",
+        }
+    }
+
+    pub fn key_var(self) -> &'static str {
+        match self {
+            Backend::Ollama => "OLLAMA_API_KEY",
+            Backend::Openai => "OPENAI_API_KEY",
+            Backend::Anthropic => "ANTHROPIC_API_KEY",
+        }
+    }
+
+    fn default_base_url(self) -> &'static str {
+        match self {
+            Backend::Ollama => "http://localhost:11434",
+            Backend::Openai => "https://api.openai.com/v1",
+            Backend::Anthropic => "https://api.anthropic.com",
+        }
+    }
+
+    pub fn model(self, configured: Option<&str>, requested: &str) -> String {
+        if self == Backend::Ollama || requested != DEFAULT_OLLAMA_MODEL {
+            return requested.to_string();
+        }
+        configured.unwrap_or(self.default_model()).to_string()
+    }
+
+    fn default_model(self) -> &'static str {
+        match self {
+            Backend::Ollama => DEFAULT_OLLAMA_MODEL,
+            Backend::Openai => "gpt-4.1",
+            Backend::Anthropic => "claude-sonnet-4-5",
+        }
+    }
+}
+
+pub struct CloudChat {
+    client: Client,
+    backend: Backend,
+    api_key: String,
+    base_url: String,
+    default_model: Option<String>,
+    breaker: Arc<CircuitBreaker>,
+    chat_timeout: Duration,
+    seed: Option<u64>,
+}
+
+impl CloudChat {
+    pub fn new(
+        backend: Backend,
+        api_key: String,
+        base_url: Option<String>,
+        default_model: Option<String>,
+    ) -> Result<Self> {
+        if backend == Backend::Ollama {
+            return Err(OllamaError::ConfigError(
+                "The ollama backend runs locally; use OllamaLocal instead".to_string(),
+            ));
+        }
+        let client = network::client_builder()
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+        let base_url = base_url
+            .unwrap_or_else(|| backend.default_base_url().to_string())
+            .trim_end_matches('/')
+            .to_string();
+
+        Ok(Self {
+            client,
+            backend,
+            api_key,
+            breaker: breaker::shared(&base_url),
+            base_url,
+            default_model,
+            chat_timeout: DEFAULT_CHAT_TIMEOUT,
+            seed: None,
+        })
+    }
+
+    pub fn with_chat_timeout(mut self, chat_timeout: Duration) -> Self {
+        self.chat_timeout = chat_timeout;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    fn model(&self, requested: &str) -> String {
+        self.backend.model(self.default_model.as_deref(), requested)
+    }
+
+    async fn post(&self, url: &str, request: &Value) -> Result<String> {
+        vcr::exchange(url, request, || async {
+            let builder = self
+                .client
+                .post(url)
+                .timeout(self.chat_timeout)
+                .json(request);
+            let builder = match self.backend {
+                Backend::Anthropic => builder
+                    .header("x-api-key", &self.api_key)
+                    .header("anthropic-version", ANTHROPIC_VERSION),
+                _ => builder.header("Authorization", format!("Bearer {}", self.api_key)),
+            };
+            let response = builder.send_audited().await?;
+
+            let status = response.status();
+
+            if !status.is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                return Err(OllamaError::ApiError {
+                    status: status.as_u16(),
+                    message: error_text,
+                });
+            }
+
+            Ok(response.text().await?)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ChatBackend for CloudChat {
+    #[instrument(skip(self, messages, tools))]
+    async fn chat(
+        &self,
+        model: &str,
+        messages: Vec<Value>,
+        tools: Option<Vec<Tool>>,
+        _think: bool,
+    ) -> Result<ChatResponse> {
+        usage::check(usage::Kind::ModelTokens)?;
+        let model = self.model(model);
+
+        let (url, request) = match self.backend {
+            Backend::Anthropic => (
+                format!("{}/v1/messages", self.base_url),
+                anthropic_request(&model, &messages, tools.as_deref()),
+            ),
+            _ => (
+                format!("{}/chat/completions", self.base_url),
+                openai_request(&model, &messages, tools.as_deref(), self.seed),
+            ),
+        };
+        debug!(
+            "Sending chat request to {} at: {}",
+            self.backend.name(),
+            url
+        );
+
+        let body = self.breaker.call(self.post(&url, &request)).await?;
+        let response = match self.backend {
+            Backend::Anthropic => parse_anthropic(&body),
+            _ => parse_openai(&body),
+        }?;
+        usage::add(
+            usage::Kind::ModelTokens,
+            response.prompt_eval_count + response.eval_count,
+        );
+        Ok(response)
+    }
+}
+
+fn tool_calls(message: &Value) -> Vec<ToolCall> {
+    message
+        .get("tool_calls")
+        .cloned()
+        .and_then(|calls| serde_json::from_value(calls).ok())
+        .unwrap_or_default()
+}
+
+fn content(message: &Value) -> &str {
+    message["content"].as_str().unwrap_or_default()
+}
+
+fn images(message: &Value) -> Vec<(String, &str)> {
+    message["images"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(|data| {
+            let head = STANDARD.decode(&data[..data.len().min(64) / 4 * 4]);
+            let format = head
+                .ok()
+                .and_then(|bytes| vision::sniff_format(&bytes))
+                .unwrap_or("png");
+            (format!("image/{}", format), data)
+        })
+        .collect()
+}
+
+pub fn openai_request(
+    model: &str,
+    messages: &[Value],
+    tools: Option<&[Tool]>,
+    seed: Option<u64>,
+) -> Value {
+    let mut pending: VecDeque<String> = VecDeque::new();
+    let mut next_id = 0;
+    let messages: Vec<Value> = messages
+        .iter()
+        .map(|message| match message["role"].as_str().unwrap_or("user") {
+            "assistant" => {
+                let calls: Vec<Value> = tool_calls(message)
+                    .into_iter()
+                    .map(|call| {
+                        next_id += 1;
+                        let id = format!("call_{}", next_id);
+                        pending.push_back(id.clone());
+                        json!({
+                            "id": id,
+                            "type": "function",
+                            "function": {
+                                "name": call.function.name,
+                                "arguments": call.function.arguments.to_string(),
+                            },
+                        })
+                    })
+                    .collect();
+                let mut converted = json!({"role": "assistant", "content": content(message)});
+                if !calls.is_empty() {
+                    converted["tool_calls"] = Value::Array(calls);
+                }
+                converted
+            }
+            "tool" => json!({
+                "role": "tool",
+                "tool_call_id": pending.pop_front().unwrap_or_default(),
+                "content": content(message),
+            }),
+            role => {
+                let images = images(message);
+                if images.is_empty() {
+                    json!({"role": role, "content": content(message)})
+                } else {
+                    let mut parts = vec![json!({"type": "text", "text": content(message)})];
+                    parts.extend(images.into_iter().map(|(media_type, data)| {
+                        json!({
+                            "type": "image_url",
+                            "image_url": {"url": format!("data:{};base64,{}", media_type, data)},
+                        })
+                    }));
+                    json!({"role": role, "content": parts})
+                }
+            }
+        })
+        .collect();
+
+    let mut request = json!({"model": model, "messages": messages});
+    if let Some(tools) = tools.filter(|tools| !tools.is_empty()) {
+        request["tools"] = json!(tools);
+    }
+    if let Some(seed) = seed {
+        request["seed"] = json!(seed);
+    }
+    request
+}
+
+pub fn anthropic_request(model: &str, messages: &[Value], tools: Option<&[Tool]>) -> Value {
+    let mut system: Vec<&str> = Vec::new();
+    let mut turns: Vec<(&str, Vec<Value>)> = Vec::new();
+    let mut pending: VecDeque<String> = VecDeque::new();
+    let mut next_id = 0;
+
+    for message in messages {
+        let (role, blocks) = match message["role"].as_str().unwrap_or("user") {
+            "system" => {
+                system.push(content(message));
+                continue;
+            }
+            "assistant" => {
+                let mut blocks = Vec::new();
+                if !content(message).trim().is_empty() {
+                    blocks.push(json!({"type": "text", "text": content(message)}));
+                }
+                for call in tool_calls(message) {
+                    next_id += 1;
+                    let id = format!("toolu_{}", next_id);
+                    pending.push_back(id.clone());
+                    blocks.push(json!({
+                        "type": "tool_use",
+                        "id": id,
+                        "name": call.function.name,
+                        "input": call.function.arguments,
+                    }));
+                }
+                ("assistant", blocks)
+            }
+            "tool" => (
+                "user",
+                vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": pending.pop_front().unwrap_or_default(),
+                    "content": content(message),
+                })],
+            ),
+            _ => {
+                let mut blocks: Vec<Value> = images(message)
+                    .into_iter()
+                    .map(|(media_type, data)| {
+                        json!({
+                            "type": "image",
+                            "source": {"type": "base64", "media_type": media_type, "data": data},
+                        })
+                    })
+                    .collect();
+                blocks.push(json!({"type": "text", "text": content(message)}));
+                ("user", blocks)
+            }
+        };
+        if blocks.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some((last, existing)) if *last == role => existing.extend(blocks),
+            _ => turns.push((role, blocks)),
+        }
+    }
+
+    let messages: Vec<Value> = turns
+        .into_iter()
+        .map(|(role, content)| json!({"role": role, "content": content}))
+        .collect();
+    let mut request = json!({
+        "model": model,
+        "max_tokens": ANTHROPIC_MAX_TOKENS,
+        "messages": messages,
+    });
+    if !system.is_empty() {
+        request["system"] = json!(system.join("\n\n"));
+    }
+    if let Some(tools) = tools.filter(|tools| !tools.is_empty()) {
+        request["tools"] = tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "name": tool.function.name,
+                    "description": tool.function.description,
+                    "input_schema": tool.function.parameters,
+                })
+            })
+            .collect();
+    }
+    request
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiResponse {
+    choices: Vec<OpenAiChoice>,
+    #[serde(default)]
+    usage: OpenAiUsage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAiToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiToolCall {
+    function: OpenAiFunction,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiFunction {
+    name: String,
+    #[serde(default)]
+    arguments: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct OpenAiUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+pub fn parse_openai(body: &str) -> Result<ChatResponse> {
+    let response: OpenAiResponse = serde_json::from_str(body).map_err(|e| {
+        OllamaError::InvalidResponse(format!("Failed to parse OpenAI response: {}", e))
+    })?;
+    let message = response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message)
+        .ok_or_else(|| OllamaError::InvalidResponse("OpenAI returned no choices".to_string()))?;
+
+    let tool_calls: Vec<ToolCall> = message
+        .tool_calls
+        .into_iter()
+        .map(|call| ToolCall {
+            function: FunctionCall {
+                arguments: serde_json::from_str(&call.function.arguments)
+                    .unwrap_or_else(|_| json!({})),
+                name: call.function.name,
+            },
+        })
+        .collect();
+
+    Ok(ChatResponse {
+        message: ChatMessage {
+            role: "assistant".to_string(),
+            content: message.content.unwrap_or_default(),
+            tool_calls: Some(tool_calls),
+            thinking: None,
+        },
+        done: true,
+        prompt_eval_count: response.usage.prompt_tokens,
+        eval_count: response.usage.completion_tokens,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicBlock>,
+    #[serde(default)]
+    usage: AnthropicUsage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        name: String,
+        input: Value,
+    },
+    Thinking {
+        thinking: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+pub fn parse_anthropic(body: &str) -> Result<ChatResponse> {
+    let response: AnthropicResponse = serde_json::from_str(body).map_err(|e| {
+        OllamaError::InvalidResponse(format!("Failed to parse Anthropic response: {}", e))
+    })?;
+
+    let mut text: Vec<String> = Vec::new();
+    let mut thinking: Vec<String> = Vec::new();
+    let mut tool_calls: Vec<ToolCall> = Vec::new();
+    for block in response.content {
+        match block {
+            AnthropicBlock::Text { text: part } => text.push(part),
+            AnthropicBlock::Thinking { thinking: part } => thinking.push(part),
+            AnthropicBlock::ToolUse { name, input } => tool_calls.push(ToolCall {
+                function: FunctionCall {
+                    name,
+                    arguments: input,
+                },
+            }),
+            AnthropicBlock::Other => {}
+        }
+    }
+
+    Ok(ChatResponse {
+        message: ChatMessage {
+            role: "assistant".to_string(),
+            content: text.join("\n"),
+            tool_calls: Some(tool_calls),
+            thinking: (!thinking.is_empty()).then(|| thinking.join("\n")),
+        },
+        done: true,
+        prompt_eval_count: response.usage.input_tokens,
+        eval_count: response.usage.output_tokens,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ollama_local::create_web_search_tool;
+
+    fn conversation() -> Vec<Value> {
+        vec![
+            json!({"role": "system", "content": "Be brief."}),
+            json!({"role": "user", "content": "compare tokio and smol"}),
+            json!({
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [
+                    {"function": {"name": "web_search", "arguments": {"query": "tokio"}}},
+                    {"function": {"name": "web_search", "arguments": {"query": "smol"}}},
+                ],
+            }),
+            json!({"role": "tool", "content": "tokio results", "tool_name": "web_search"}),
+            json!({"role": "tool", "content": "smol results", "tool_name": "web_search"}),
+        ]
+    }
+
+    #[test]
+    fn test_openai_request_pairs_tool_results_with_calls() {
+        let tools = [create_web_search_tool()];
+        let request = openai_request("gpt-4.1", &conversation(), Some(&tools), Some(7));
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 5);
+        assert_eq!(messages[2]["tool_calls"][1]["id"], "call_2");
+        assert_eq!(
+            messages[2]["tool_calls"][0]["function"]["arguments"],
+            r#"{"query":"tokio"}"#
+        );
+        assert_eq!(messages[4]["tool_call_id"], "call_2");
+        assert_eq!(request["tools"][0]["function"]["name"], "web_search");
+        assert_eq!(request["seed"], 7);
+
+        let response = parse_openai(
+            r#"{"choices": [{"message": {"role": "assistant", "content": null, "tool_calls": [
+                {"id": "x", "type": "function", "function": {"name": "web_fetch", "arguments": "{\"url\": \"https://tokio.rs\"}"}}]}}],
+               "usage": {"prompt_tokens": 12, "completion_tokens": 3}}"#,
+        )
+        .unwrap();
+        let calls = response.message.tool_calls.unwrap();
+        assert_eq!(calls[0].function.arguments["url"], "https://tokio.rs");
+        assert_eq!(response.message.content, "");
+        assert_eq!(response.prompt_eval_count + response.eval_count, 15);
+    }
+
+    #[test]
+    fn test_anthropic_request_merges_tool_results_into_one_turn() {
+        let tools = [create_web_search_tool()];
+        let request = anthropic_request("claude-sonnet-4-5", &conversation(), Some(&tools));
+        assert_eq!(request["system"], "Be brief.");
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(messages[1]["content"][1]["id"], "toolu_2");
+        assert_eq!(messages[1]["content"][0]["input"]["query"], "tokio");
+        assert_eq!(messages[2]["role"], "user");
+        assert_eq!(messages[2]["content"][1]["tool_use_id"], "toolu_2");
+        assert_eq!(messages[2]["content"][1]["content"], "smol results");
+        assert_eq!(request["tools"][0]["input_schema"]["type"], "object");
+
+        let response = parse_anthropic(
+            r#"{"content": [{"type": "text", "text": "Checking."},
+                            {"type": "tool_use", "id": "t", "name": "web_search", "input": {"query": "smol"}},
+                            {"type": "redacted_thinking", "data": "x"}],
+               "usage": {"input_tokens": 20, "output_tokens": 5}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.message.content, "Checking.");
+        assert_eq!(
+            response.message.tool_calls.unwrap()[0].function.name,
+            "web_search"
+        );
+        assert_eq!(response.eval_count, 5);
+    }
+
+    #[test]
+    fn test_default_ollama_model_maps_to_backend_default() {
+        let chat = CloudChat::new(Backend::Openai, "key".to_string(), None, None).unwrap();
+        assert_eq!(chat.model(DEFAULT_OLLAMA_MODEL), "gpt-4.1");
+        assert_eq!(chat.model("gpt-4o-mini"), "gpt-4o-mini");
+        assert_eq!(
+            Backend::Anthropic.model(Some("claude-opus-4-1"), DEFAULT_OLLAMA_MODEL),
+            "claude-opus-4-1"
+        );
+        assert_eq!(Backend::Ollama.model(None, "qwen3:14b"), "qwen3:14b");
+        assert!(CloudChat::new(Backend::Ollama, String::new(), None, None).is_err());
+    }
+}
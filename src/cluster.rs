@@ -0,0 +1,233 @@
+use crate::client::SearchResult;
+use crate::error::Result;
+use crate::ivf;
+use crate::ollama_local::{ChatBackend, OllamaLocal};
+use futures::future::join_all;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use tracing::{debug, warn};
+
+pub const DEFAULT_LABEL_MODEL: &str = "gpt-oss:20b";
+pub const MIN_CLUSTERED_RESULTS: usize = 6;
+const RESULTS_PER_TOPIC: usize = 6;
+const MAX_TOPICS: usize = 6;
+const MAX_LABEL_CHARS: usize = 48;
+const STOPWORDS: &[&str] = &[
+    "about", "after", "also", "best", "does", "from", "guide", "have", "into", "more", "most",
+    "only", "over", "than", "that", "their", "them", "then", "there", "these", "they", "this",
+    "using", "what", "when", "where", "which", "while", "will", "with", "your",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Topic {
+    pub label: String,
+    pub results: Vec<SearchResult>,
+}
+
+pub struct Clusterer {
+    ollama: OllamaLocal,
+    embed_model: String,
+    label_model: String,
+}
+
+impl Clusterer {
+    pub fn new(ollama: OllamaLocal, embed_model: String, label_model: String) -> Self {
+        Self {
+            ollama,
+            embed_model,
+            label_model,
+        }
+    }
+
+    pub async fn cluster(&self, query: &str, results: &[SearchResult]) -> Result<Vec<Topic>> {
+        if results.len() < MIN_CLUSTERED_RESULTS {
+            return Ok(Vec::new());
+        }
+
+        let vectors = join_all(results.iter().map(|result| {
+            let text = format!("{}\n{}", result.title, result.content);
+            async move { self.ollama.embed(&self.embed_model, &text).await }
+        }))
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let mut topics: Vec<Topic> = group(&vectors, topic_count(results.len()))
+            .into_iter()
+            .map(|members| {
+                let results: Vec<SearchResult> = members
+                    .into_iter()
+                    .map(|idx| results[idx].clone())
+                    .collect();
+                Topic {
+                    label: keyword_label(query, &results),
+                    results,
+                }
+            })
+            .collect();
+        debug!(
+            "Grouped {} results into {} topics",
+            results.len(),
+            topics.len()
+        );
+
+        match self.labels(query, &topics).await {
+            Ok(labels) => {
+                for (topic, label) in topics.iter_mut().zip(labels) {
+                    if !label.is_empty() {
+                        topic.label = label;
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to label topics, using keywords instead: {}", e),
+        }
+        Ok(topics)
+    }
+
+    async fn labels(&self, query: &str, topics: &[Topic]) -> Result<Vec<String>> {
+        let response = self
+            .ollama
+            .chat(
+                &self.label_model,
+                vec![json!({"role": "user", "content": label_prompt(query, topics)})],
+                None,
+                false,
+            )
+            .await?;
+        Ok(parse_labels(&response.message.content))
+    }
+}
+
+pub fn topic_count(results: usize) -> usize {
+    results.div_ceil(RESULTS_PER_TOPIC).clamp(2, MAX_TOPICS)
+}
+
+pub fn group(vectors: &[Vec<f32>], topics: usize) -> Vec<Vec<usize>> {
+    let centroids = ivf::train(vectors, topics);
+    let mut groups = vec![Vec::new(); centroids.len()];
+    for (idx, vector) in vectors.iter().enumerate() {
+        groups[ivf::nearest(&centroids, vector)].push(idx);
+    }
+    groups.retain(|members: &Vec<usize>| !members.is_empty());
+    groups.sort_by_key(|members| (Reverse(members.len()), members[0]));
+    groups
+}
+
+fn label_prompt(query: &str, topics: &[Topic]) -> String {
+    let mut prompt = format!(
+        "These web search results for \"{query}\" were grouped by topic. Give each group a \
+         short label of two to five words that names what sets it apart from the others.\n\
+         Reply with only a JSON array of {} strings, one per group, in order.\n",
+        topics.len()
+    );
+    for (idx, topic) in topics.iter().enumerate() {
+        prompt.push_str(&format!("\nGroup {}:\n", idx + 1));
+        for result in &topic.results {
+            prompt.push_str(&format!("- {}\n", result.title));
+        }
+    }
+    prompt
+}
+
+fn parse_labels(content: &str) -> Vec<String> {
+    let labels = content
+        .find('[')
+        .zip(content.rfind(']'))
+        .filter(|(start, end)| start < end)
+        .and_then(|(start, end)| serde_json::from_str::<Vec<Value>>(&content[start..=end]).ok())
+        .unwrap_or_default();
+    labels
+        .iter()
+        .map(|label| {
+            label
+                .as_str()
+                .unwrap_or_default()
+                .trim()
+                .chars()
+                .take(MAX_LABEL_CHARS)
+                .collect()
+        })
+        .collect()
+}
+
+pub fn keyword_label(query: &str, results: &[SearchResult]) -> String {
+    let query_words: Vec<String> = words(query).collect();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for result in results {
+        let mut seen: Vec<String> = words(&result.title).collect();
+        seen.sort();
+        seen.dedup();
+        for word in seen {
+            if !query_words.contains(&word) && !STOPWORDS.contains(&word.as_str()) {
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+    }
+
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let label: Vec<String> = ranked.into_iter().take(2).map(|(word, _)| word).collect();
+    if label.is_empty() {
+        "Other".to_string()
+    } else {
+        label.join(", ")
+    }
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 3)
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(title: &str) -> SearchResult {
+        SearchResult {
+            title: title.to_string(),
+            url: format!("https://example.com/{}", title.len()),
+            content: String::new(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_groups_similar_vectors_largest_first() {
+        let vectors = vec![
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![0.9, 0.1],
+            vec![0.1, 0.9],
+            vec![0.95, 0.05],
+        ];
+        assert_eq!(group(&vectors, 2), vec![vec![0, 2, 4], vec![1, 3]]);
+        assert!(group(&[], 2).is_empty());
+        assert_eq!(topic_count(8), 2);
+        assert_eq!(topic_count(30), 5);
+        assert_eq!(topic_count(100), MAX_TOPICS);
+    }
+
+    #[test]
+    fn test_labels_from_model_reply_or_title_keywords() {
+        assert_eq!(
+            parse_labels("```json\n[\"Benchmarks\", \" Runtime internals \", 3]\n```"),
+            ["Benchmarks", "Runtime internals", ""]
+        );
+        assert!(parse_labels("no idea").is_empty());
+
+        let results = [
+            result("Tokio scheduler benchmarks"),
+            result("Benchmarks: tokio vs smol scheduler"),
+            result("Why the tokio scheduler is fast"),
+        ];
+        assert_eq!(
+            keyword_label("tokio performance", &results),
+            "scheduler, benchmarks"
+        );
+        assert_eq!(keyword_label("rust", &[result("A B")]), "Other");
+    }
+}
@@ -0,0 +1,172 @@
+use crate::cli::Cli;
+use crate::settings::Settings;
+use crate::store::Store;
+use clap::{Arg, CommandFactory};
+use clap_complete::env::Shells;
+use clap_complete::{ArgValueCandidates, CompletionCandidate, Shell};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub const ENV_VAR: &str = "COMPLETE";
+const BIN: &str = "weavex";
+const DEFAULT_REGISTRY: &str = "registry.ollama.ai";
+const DEFAULT_NAMESPACE: &str = "library";
+
+pub fn command() -> clap::Command {
+    with_candidates(Cli::command())
+        .mut_subcommand("jobs", |jobs| {
+            jobs.mut_subcommand("remove", |remove| {
+                remove.mut_arg("name", |arg| arg.add(ArgValueCandidates::new(job_names)))
+            })
+        })
+        .mut_arg("kb_collection", |arg| {
+            arg.add(ArgValueCandidates::new(collection_names))
+        })
+}
+
+pub fn write_registration(shell: Shell, out: &mut dyn Write) -> io::Result<()> {
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&shell.to_string())
+        .ok_or_else(|| io::Error::other(format!("No completer for {}", shell)))?;
+    completer.write_registration(ENV_VAR, BIN, BIN, BIN, out)
+}
+
+fn with_candidates(command: clap::Command) -> clap::Command {
+    command
+        .mut_args(|arg| {
+            if takes_model(&arg) {
+                arg.add(ArgValueCandidates::new(model_names))
+            } else {
+                arg
+            }
+        })
+        .mut_subcommands(with_candidates)
+}
+
+fn takes_model(arg: &Arg) -> bool {
+    arg.get_value_names()
+        .is_some_and(|names| names.iter().any(|name| name == "MODEL"))
+}
+
+fn model_names() -> Vec<CompletionCandidate> {
+    installed_models(&models_dir())
+        .into_iter()
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+fn job_names() -> Vec<CompletionCandidate> {
+    Settings::load()
+        .map(|settings| settings.jobs)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|job| CompletionCandidate::new(job.name))
+        .collect()
+}
+
+fn collection_names() -> Vec<CompletionCandidate> {
+    let Ok(store) = Store::open() else {
+        return Vec::new();
+    };
+    let Ok(mut stmt) = store
+        .conn()
+        .prepare("SELECT DISTINCT collection FROM kb_documents ORDER BY collection")
+    else {
+        return Vec::new();
+    };
+    stmt.query_map([], |row| row.get::<_, String>(0))
+        .map(|rows| rows.flatten().map(CompletionCandidate::new).collect())
+        .unwrap_or_default()
+}
+
+fn models_dir() -> PathBuf {
+    match std::env::var_os("OLLAMA_MODELS") {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::home_dir()
+            .unwrap_or_default()
+            .join(".ollama")
+            .join("models"),
+    }
+}
+
+pub fn installed_models(models_dir: &Path) -> Vec<String> {
+    let mut models = Vec::new();
+    for registry in entries(&models_dir.join("manifests")) {
+        for namespace in entries(&registry) {
+            for model in entries(&namespace) {
+                for tag in entries(&model) {
+                    let mut name = String::new();
+                    let registry = file_name(&registry);
+                    let namespace = file_name(&namespace);
+                    if registry != DEFAULT_REGISTRY {
+                        name.push_str(&format!("{}/{}/", registry, namespace));
+                    } else if namespace != DEFAULT_NAMESPACE {
+                        name.push_str(&format!("{}/", namespace));
+                    }
+                    name.push_str(&format!("{}:{}", file_name(&model), file_name(&tag)));
+                    models.push(name);
+                }
+            }
+        }
+    }
+    models.sort();
+    models
+}
+
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_installed_models_from_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifests = dir.path().join("manifests");
+        for path in [
+            "registry.ollama.ai/library/gpt-oss/20b",
+            "registry.ollama.ai/library/nomic-embed-text/latest",
+            "registry.ollama.ai/someone/tuned/q4",
+            "hf.co/org/repo/Q8_0",
+        ] {
+            let path = manifests.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "{}").unwrap();
+        }
+
+        assert_eq!(
+            installed_models(dir.path()),
+            [
+                "gpt-oss:20b",
+                "hf.co/org/repo:Q8_0",
+                "nomic-embed-text:latest",
+                "someone/tuned:q4",
+            ]
+        );
+        assert!(installed_models(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_writes_registration_for_each_shell() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let mut script = Vec::new();
+            write_registration(shell, &mut script).unwrap();
+            let script = String::from_utf8(script).unwrap();
+            assert!(script.contains(ENV_VAR), "{}", shell);
+            assert!(script.contains(BIN), "{}", shell);
+        }
+    }
+}
@@ -1,11 +1,20 @@
+use crate::network::DEFAULT_CONNECT_TIMEOUT;
 use std::time::Duration;
 
+pub const DEFAULT_SEARCH_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(120);
+pub const DEFAULT_AGENT_TIMEOUT: Duration = Duration::from_secs(90);
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub api_key: String,
     pub base_url: String,
-    pub timeout: Duration,
+    pub connect_timeout: Duration,
+    pub search_timeout: Duration,
+    pub fetch_timeout: Duration,
+    pub read_timeout: Option<Duration>,
     pub max_results: Option<usize>,
+    pub raw: bool,
 }
 
 impl Config {
@@ -13,13 +22,37 @@ impl Config {
         Self {
             api_key,
             base_url: "https://ollama.com/api".to_string(),
-            timeout: Duration::from_secs(30),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            search_timeout: DEFAULT_SEARCH_TIMEOUT,
+            fetch_timeout: DEFAULT_FETCH_TIMEOUT,
+            read_timeout: None,
             max_results: None,
+            raw: false,
         }
     }
 
-    pub fn with_timeout(mut self, timeout: Duration) -> Self {
-        self.timeout = timeout;
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_search_timeout(timeout)
+            .with_fetch_timeout(timeout)
+    }
+
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    pub fn with_search_timeout(mut self, search_timeout: Duration) -> Self {
+        self.search_timeout = search_timeout;
+        self
+    }
+
+    pub fn with_fetch_timeout(mut self, fetch_timeout: Duration) -> Self {
+        self.fetch_timeout = fetch_timeout;
+        self
+    }
+
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = Some(read_timeout);
         self
     }
 
@@ -27,4 +60,9 @@ impl Config {
         self.max_results = Some(max_results);
         self
     }
+
+    pub fn with_raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
 }
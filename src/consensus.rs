@@ -0,0 +1,202 @@
+use crate::agent::Agent;
+use crate::error::{OllamaError, Result};
+use crate::glyphs::Glyph;
+use crate::loading::LoadingAnimation;
+use crate::ollama_local::ChatBackend;
+use crate::orchestrate::Researcher;
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde_json::json;
+use tracing::warn;
+
+pub struct Sample {
+    pub label: String,
+    pub agent: Agent,
+}
+
+pub struct Consensus {
+    chat: Box<dyn ChatBackend>,
+    model: String,
+    samples: Vec<Sample>,
+    enable_reasoning: bool,
+}
+
+#[derive(Debug)]
+pub struct Answer {
+    pub label: String,
+    pub outcome: std::result::Result<String, String>,
+}
+
+impl Consensus {
+    pub fn new(chat: Box<dyn ChatBackend>, model: String, samples: Vec<Sample>) -> Self {
+        Self {
+            chat,
+            model,
+            samples: samples
+                .into_iter()
+                .map(|sample| Sample {
+                    agent: sample.agent.with_spinner(false).with_exit_code(false),
+                    ..sample
+                })
+                .collect(),
+            enable_reasoning: true,
+        }
+    }
+
+    pub fn with_reasoning(mut self, enable_reasoning: bool) -> Self {
+        self.enable_reasoning = enable_reasoning;
+        self
+    }
+
+    pub async fn run(&self, query: &str) -> Result<String> {
+        println!(
+            "{}Sampling {} independent runs\n",
+            Glyph::Dice,
+            self.samples.len()
+        );
+
+        let loading = LoadingAnimation::start();
+        let loader = &loading;
+        let answers = join_all(
+            self.samples
+                .iter()
+                .enumerate()
+                .map(|(i, sample)| async move {
+                    let outcome = sample.agent.run(query).await.map_err(|e| e.to_string());
+                    loader.suspend(|| match &outcome {
+                        Ok(_) => println!(
+                            "{}Run {} finished ({})",
+                            Glyph::Success,
+                            i + 1,
+                            sample.label
+                        ),
+                        Err(e) => println!(
+                            "{}Run {} failed ({}): {}",
+                            Glyph::Warning,
+                            i + 1,
+                            sample.label,
+                            e
+                        ),
+                    });
+                    Answer {
+                        label: sample.label.clone(),
+                        outcome,
+                    }
+                }),
+        )
+        .await;
+        loading.stop();
+
+        let succeeded: Vec<&String> = answers
+            .iter()
+            .filter_map(|answer| answer.outcome.as_ref().ok())
+            .collect();
+        match succeeded.as_slice() {
+            [] => {
+                let first = answers.iter().find_map(|a| a.outcome.as_ref().err());
+                return Err(OllamaError::InvalidResponse(format!(
+                    "All {} runs failed: {}",
+                    answers.len(),
+                    first.map(String::as_str).unwrap_or_default()
+                )));
+            }
+            [only] => {
+                warn!("Only one run succeeded; nothing to compare it against");
+                return Ok((*only).clone());
+            }
+            _ => {}
+        }
+
+        println!(
+            "\n{}Reconciling {} answers\n",
+            Glyph::Judge,
+            succeeded.len()
+        );
+        let response = self
+            .chat
+            .chat(
+                &self.model,
+                vec![json!({"role": "user", "content": reconcile_prompt(query, &answers)})],
+                None,
+                self.enable_reasoning,
+            )
+            .await?;
+        Ok(response.message.content)
+    }
+}
+
+#[async_trait(?Send)]
+impl Researcher for Consensus {
+    async fn research(&self, query: &str) -> Result<String> {
+        self.run(query).await
+    }
+}
+
+pub fn reconcile_prompt(query: &str, answers: &[Answer]) -> String {
+    let mut prompt = format!(
+        "The same research question was answered by several independent runs: {query}\n\n\
+         Write one answer that keeps only the claims the runs support, preferring what most \
+         runs agree on and keeping their source citations. Then add a section titled \
+         \"## Disagreements\" listing every point where the runs contradict each other or where \
+         a specific claim (a number, date, name, or quote) appears in only one run, saying which \
+         run said what. Treat those points as possible hallucinations. If the runs fully agree, \
+         say so in that section.\n"
+    );
+    for (i, answer) in answers.iter().enumerate() {
+        if let Ok(text) = &answer.outcome {
+            prompt.push_str(&format!(
+                "\n## Run {} ({})\n\n{}\n",
+                i + 1,
+                answer.label,
+                text.trim()
+            ));
+        }
+    }
+    prompt
+}
+
+pub fn sample_models(model: &str, models: &[String], samples: usize) -> Vec<String> {
+    if models.is_empty() {
+        return vec![model.to_string(); samples];
+    }
+    models.iter().cycle().take(samples).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconcile_prompt_lists_successful_runs() {
+        let answers = vec![
+            Answer {
+                label: "qwen3:14b, seed 1".to_string(),
+                outcome: Ok("Tokio 1.0 shipped in 2020 [1].\n".to_string()),
+            },
+            Answer {
+                label: "qwen3:14b, seed 2".to_string(),
+                outcome: Err("Maximum iterations reached".to_string()),
+            },
+            Answer {
+                label: "gpt-oss:20b, seed 3".to_string(),
+                outcome: Ok("Tokio 1.0 shipped in 2021.".to_string()),
+            },
+        ];
+        let prompt = reconcile_prompt("When did tokio 1.0 ship?", &answers);
+        assert!(prompt.contains("independent runs: When did tokio 1.0 ship?"));
+        assert!(prompt.contains("## Run 1 (qwen3:14b, seed 1)\n\nTokio 1.0 shipped in 2020 [1].\n"));
+        assert!(prompt.contains("## Run 3 (gpt-oss:20b, seed 3)\n\nTokio 1.0 shipped in 2021.\n"));
+        assert!(!prompt.contains("Run 2"));
+        assert!(prompt.contains("## Disagreements"));
+    }
+
+    #[test]
+    fn test_sample_models_cycle_through_the_list() {
+        assert_eq!(sample_models("gpt-oss:20b", &[], 2), ["gpt-oss:20b"; 2]);
+        let models = ["qwen3:14b".to_string(), "llama3.1".to_string()];
+        assert_eq!(
+            sample_models("gpt-oss:20b", &models, 3),
+            ["qwen3:14b", "llama3.1", "qwen3:14b"]
+        );
+    }
+}
@@ -0,0 +1,202 @@
+use crate::client::SearchResult;
+use crate::feedback::domain_of;
+use crate::settings::CredibilitySettings;
+use crate::text;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+pub const PREFER_INSTRUCTION: &str = "Search results carry a credibility tier (high, medium, or \
+    low). Prefer high-tier sources such as official documentation, standards bodies, and \
+    journals; use medium-tier sources to fill gaps, and only rely on low-tier sources when \
+    nothing better exists, saying so in the answer.";
+
+const DEFAULTS: &[(&str, Tier)] = &[
+    ("ac.uk", Tier::High),
+    ("acm.org", Tier::High),
+    ("developer.apple.com", Tier::High),
+    ("developer.mozilla.org", Tier::High),
+    ("doc.rust-lang.org", Tier::High),
+    ("docs.python.org", Tier::High),
+    ("docs.rs", Tier::High),
+    ("edu", Tier::High),
+    ("gov", Tier::High),
+    ("gov.uk", Tier::High),
+    ("ietf.org", Tier::High),
+    ("ieee.org", Tier::High),
+    ("kernel.org", Tier::High),
+    ("learn.microsoft.com", Tier::High),
+    ("nature.com", Tier::High),
+    ("nejm.org", Tier::High),
+    ("nih.gov", Tier::High),
+    ("pnas.org", Tier::High),
+    ("postgresql.org", Tier::High),
+    ("python.org", Tier::High),
+    ("rfc-editor.org", Tier::High),
+    ("rust-lang.org", Tier::High),
+    ("science.org", Tier::High),
+    ("springer.com", Tier::High),
+    ("thelancet.com", Tier::High),
+    ("w3.org", Tier::High),
+    ("whatwg.org", Tier::High),
+    ("who.int", Tier::High),
+    ("apnews.com", Tier::Medium),
+    ("arstechnica.com", Tier::Medium),
+    ("arxiv.org", Tier::Medium),
+    ("bbc.co.uk", Tier::Medium),
+    ("bbc.com", Tier::Medium),
+    ("github.com", Tier::Medium),
+    ("lwn.net", Tier::Medium),
+    ("reuters.com", Tier::Medium),
+    ("stackexchange.com", Tier::Medium),
+    ("stackoverflow.com", Tier::Medium),
+    ("wikipedia.org", Tier::Medium),
+    ("answers.com", Tier::Low),
+    ("ask.com", Tier::Low),
+    ("ehow.com", Tier::Low),
+    ("ezinearticles.com", Tier::Low),
+    ("hubpages.com", Tier::Low),
+    ("livestrong.com", Tier::Low),
+    ("wikihow.com", Tier::Low),
+];
+
+static REPUTATION: OnceLock<Reputation> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tier {
+    High,
+    Medium,
+    Low,
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Tier::High => "high",
+            Tier::Medium => "medium",
+            Tier::Low => "low",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Reputation {
+    domains: HashMap<String, Tier>,
+}
+
+impl Reputation {
+    pub fn new(settings: &CredibilitySettings) -> Self {
+        let mut domains: HashMap<String, Tier> = if settings.replace_defaults {
+            HashMap::new()
+        } else {
+            DEFAULTS
+                .iter()
+                .map(|(domain, tier)| (domain.to_string(), *tier))
+                .collect()
+        };
+        domains.extend(
+            settings
+                .domains
+                .iter()
+                .map(|(domain, tier)| (domain.trim_start_matches('.').to_lowercase(), *tier)),
+        );
+        Self { domains }
+    }
+
+    pub fn tier(&self, url: &str) -> Option<Tier> {
+        let domain = domain_of(url);
+        let mut suffix = domain.as_str();
+        loop {
+            if let Some(tier) = self.domains.get(suffix) {
+                return Some(*tier);
+            }
+            suffix = suffix.split_once('.')?.1;
+        }
+    }
+}
+
+pub fn init(settings: &CredibilitySettings) {
+    let _ = REPUTATION.set(Reputation::new(settings));
+}
+
+pub fn tier(url: &str) -> Option<Tier> {
+    REPUTATION
+        .get_or_init(|| Reputation::new(&CredibilitySettings::default()))
+        .tier(url)
+}
+
+pub fn annotate(results: &mut [SearchResult]) {
+    for result in results {
+        result.credibility = tier(&result.url);
+    }
+}
+
+pub fn cited(answer: &str) -> Vec<(String, Tier)> {
+    let mut cited: Vec<(String, Tier)> = Vec::new();
+    for url in text::urls(answer) {
+        let domain = domain_of(&url);
+        if let Some(tier) = tier(&url) {
+            if !cited.iter().any(|(seen, _)| *seen == domain) {
+                cited.push((domain, tier));
+            }
+        }
+    }
+    cited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiers_match_domain_suffixes_and_overrides() {
+        let settings = CredibilitySettings {
+            domains: HashMap::from([
+                ("medium.com".to_string(), Tier::Low),
+                ("docs.rs".to_string(), Tier::Medium),
+            ]),
+            ..Default::default()
+        };
+        let reputation = Reputation::new(&settings);
+
+        assert_eq!(
+            reputation.tier("https://www.nature.com/articles/1"),
+            Some(Tier::High)
+        );
+        assert_eq!(
+            reputation.tier("https://en.wikipedia.org/wiki/Rust"),
+            Some(Tier::Medium)
+        );
+        assert_eq!(
+            reputation.tier("https://cs.stanford.edu/people"),
+            Some(Tier::High)
+        );
+        assert_eq!(
+            reputation.tier("https://someone.medium.com/post"),
+            Some(Tier::Low)
+        );
+        assert_eq!(reputation.tier("https://docs.rs/tokio"), Some(Tier::Medium));
+        assert_eq!(reputation.tier("https://tokio.rs"), None);
+
+        let only_mine = Reputation::new(&CredibilitySettings {
+            replace_defaults: true,
+            ..settings
+        });
+        assert_eq!(only_mine.tier("https://www.nature.com/articles/1"), None);
+    }
+
+    #[test]
+    fn test_lists_cited_domains_once() {
+        let answer = "See https://docs.rs/tokio and https://docs.rs/smol, \
+                      or https://www.ehow.com/async and https://tokio.rs.";
+        assert_eq!(
+            cited(answer),
+            [
+                ("docs.rs".to_string(), Tier::High),
+                ("ehow.com".to_string(), Tier::Low)
+            ]
+        );
+    }
+}
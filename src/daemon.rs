@@ -0,0 +1,134 @@
+use crate::error::{OllamaError, Result};
+use crate::settings::{DaemonSettings, Job};
+use crate::store;
+use chrono::{DateTime, Local};
+use cron::Schedule;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const REPORTS_DIR: &str = "reports";
+
+pub fn parse_schedule(expression: &str) -> Result<Schedule> {
+    let expression = expression.trim();
+    let expression = match expression.split_whitespace().count() {
+        5 => format!("0 {}", expression),
+        _ => expression.to_string(),
+    };
+
+    Schedule::from_str(&expression)
+        .map_err(|e| OllamaError::ConfigError(format!("Invalid schedule '{}': {}", expression, e)))
+}
+
+pub fn next_run(job: &Job, after: &DateTime<Local>) -> Option<DateTime<Local>> {
+    parse_schedule(&job.schedule).ok()?.after(after).next()
+}
+
+pub fn due_jobs<'a>(
+    jobs: &'a [Job],
+    since: &DateTime<Local>,
+    now: &DateTime<Local>,
+) -> Vec<&'a Job> {
+    jobs.iter()
+        .filter(|job| next_run(job, since).is_some_and(|next| next <= *now))
+        .collect()
+}
+
+pub fn report_dir(settings: &DaemonSettings) -> Result<PathBuf> {
+    match &settings.report_dir {
+        Some(dir) => match (dir.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(rest), Some(home)) => Ok(home.join(rest)),
+            _ => Ok(dir.clone()),
+        },
+        None => Ok(store::data_dir()?.join(REPORTS_DIR)),
+    }
+}
+
+pub fn write_report(dir: &Path, job: &Job, content: &str, at: &DateTime<Local>) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!(
+        "{}-{}.md",
+        slug(&job.name),
+        at.format("%Y%m%d-%H%M")
+    ));
+    std::fs::write(&path, report_markdown(job, content, at))?;
+    Ok(path)
+}
+
+fn report_markdown(job: &Job, content: &str, at: &DateTime<Local>) -> String {
+    format!(
+        "# {}\n\n_Job `{}` · {} · {}_\n\n{}\n",
+        job.query,
+        job.name,
+        if job.agent { "agent" } else { "search" },
+        at.format("%Y-%m-%d %H:%M"),
+        content.trim()
+    )
+}
+
+fn slug(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn job(schedule: &str) -> Job {
+        Job {
+            name: "Daily CVEs".to_string(),
+            schedule: schedule.to_string(),
+            query: "openssl CVE".to_string(),
+            agent: false,
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_schedule_accepts_five_and_six_fields() {
+        assert!(parse_schedule("0 8 * * *").is_ok());
+        assert!(parse_schedule("30 0 8 * * *").is_ok());
+        assert!(parse_schedule("every day").is_err());
+    }
+
+    #[test]
+    fn test_due_jobs_between_ticks() {
+        let jobs = vec![job("0 8 * * *"), job("0 9 * * *")];
+        let since = Local.with_ymd_and_hms(2025, 1, 1, 7, 59, 30).unwrap();
+        let now = Local.with_ymd_and_hms(2025, 1, 1, 8, 0, 30).unwrap();
+
+        let due = due_jobs(&jobs, &since, &now);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].schedule, "0 8 * * *");
+    }
+
+    #[test]
+    fn test_report_dir_expands_home() {
+        let settings = DaemonSettings {
+            report_dir: Some(PathBuf::from("~/reports")),
+        };
+        let dir = report_dir(&settings).unwrap();
+        assert!(!dir.starts_with("~"));
+        assert!(dir.ends_with("reports"));
+    }
+
+    #[test]
+    fn test_write_report_uses_slugged_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let at = Local.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap();
+        let path = write_report(dir.path(), &job("0 8 * * *"), "Findings", &at).unwrap();
+
+        assert!(path.ends_with("daily-cves-20250101-0800.md"));
+        let report = std::fs::read_to_string(path).unwrap();
+        assert!(report.starts_with("# openssl CVE"));
+        assert!(report.contains("Findings"));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::client::SearchResult;
+use std::collections::HashSet;
+use url::Url;
+
+const SHINGLE_WORDS: usize = 3;
+const MIN_SNIPPET_WORDS: usize = 8;
+const NEAR_DUPLICATE_SIMILARITY: f64 = 0.8;
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid",
+    "gclid",
+    "dclid",
+    "msclkid",
+    "yclid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_ga",
+    "_gl",
+    "ref_src",
+    "ref_url",
+    "spm",
+    "share_source",
+    "cmpid",
+    "ncid",
+    "amp",
+];
+const AMP_CACHES: &[&str] = &["cdn.ampproject.org", "www.google.com", "google.com"];
+const MIRROR_PREFIXES: &[&str] = &["www.", "m.", "mobile.", "amp.", "old."];
+
+pub fn canonical_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    if let Some(origin) = amp_cache_origin(&parsed) {
+        return canonical_url(&origin);
+    }
+
+    parsed.set_fragment(None);
+    let kept: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(name, _)| !is_tracking(name))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    if kept.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(&kept);
+    }
+
+    if let Some(host) = parsed.host_str().map(str::to_lowercase) {
+        if let Some(bare) = host.strip_prefix("amp.") {
+            let _ = parsed.set_host(Some(bare));
+        }
+    }
+    let path = parsed.path();
+    let path = path
+        .strip_suffix("/amp/")
+        .or_else(|| path.strip_suffix("/amp"))
+        .or_else(|| path.strip_suffix(".amp"))
+        .unwrap_or(path);
+    let path = path.replacen("/amp/", "/", 1);
+    let path = match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    };
+    parsed.set_path(&path);
+
+    let canonical = parsed.to_string();
+    match parsed.path() {
+        "/" if parsed.query().is_none() => canonical.trim_end_matches('/').to_string(),
+        _ => canonical,
+    }
+}
+
+pub fn key(url: &str) -> String {
+    let canonical = canonical_url(url);
+    match Url::parse(&canonical) {
+        Ok(parsed) => {
+            let mut host = parsed.host_str().unwrap_or_default().to_lowercase();
+            while let Some(bare) = MIRROR_PREFIXES
+                .iter()
+                .find_map(|prefix| host.strip_prefix(prefix))
+                .filter(|bare| bare.contains('.'))
+            {
+                host = bare.to_string();
+            }
+            let path = parsed.path().trim_end_matches('/');
+            match parsed.query() {
+                Some(query) => format!("{}{}?{}", host, path, query),
+                None => format!("{}{}", host, path),
+            }
+        }
+        Err(_) => canonical.trim_end_matches('/').to_lowercase(),
+    }
+}
+
+pub fn dedup(results: Vec<SearchResult>, explain: bool) -> Vec<SearchResult> {
+    let mut kept: Vec<(String, HashSet<String>, SearchResult)> = Vec::new();
+
+    for mut result in results {
+        result.url = canonical_url(&result.url);
+        let key = key(&result.url);
+        let shingles = shingles(&result.content);
+        let duplicate = kept.iter_mut().find(|(kept_key, kept_shingles, _)| {
+            *kept_key == key || similarity(kept_shingles, &shingles) >= NEAR_DUPLICATE_SIMILARITY
+        });
+        match duplicate {
+            Some((kept_key, _, original)) => {
+                if explain {
+                    let reason = if *kept_key == key {
+                        "same page"
+                    } else {
+                        "near-identical snippet"
+                    };
+                    original
+                        .explain
+                        .push(format!("dropped duplicate {} ({})", result.url, reason));
+                }
+            }
+            None => kept.push((key, shingles, result)),
+        }
+    }
+    kept.into_iter().map(|(_, _, result)| result).collect()
+}
+
+fn is_tracking(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.starts_with("utm_") || TRACKING_PARAMS.contains(&name.as_str())
+}
+
+fn amp_cache_origin(parsed: &Url) -> Option<String> {
+    let host = parsed.host_str()?;
+    if !AMP_CACHES.iter().any(|cache| host.ends_with(cache)) {
+        return None;
+    }
+    let path = parsed.path();
+    let rest = path
+        .strip_prefix("/amp/s/")
+        .or_else(|| path.strip_prefix("/c/s/"))?;
+    Some(format!("https://{}", rest))
+}
+
+fn shingles(text: &str) -> HashSet<String> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    if words.len() < MIN_SNIPPET_WORDS {
+        return HashSet::new();
+    }
+    words
+        .windows(SHINGLE_WORDS)
+        .map(|window| window.join(" "))
+        .collect()
+}
+
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str, content: &str) -> SearchResult {
+        SearchResult {
+            title: url.to_string(),
+            url: url.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_canonical_url_strips_tracking_and_amp() {
+        assert_eq!(
+            canonical_url("https://example.com/post/?utm_source=x&id=3&fbclid=y#top"),
+            "https://example.com/post?id=3"
+        );
+        assert_eq!(
+            canonical_url("https://www.google.com/amp/s/example.com/news/rust/amp"),
+            "https://example.com/news/rust"
+        );
+        assert_eq!(
+            canonical_url("https://amp.example.com/news/rust?amp=1"),
+            "https://example.com/news/rust"
+        );
+        assert_eq!(canonical_url("https://tokio.rs/"), "https://tokio.rs");
+        assert_eq!(
+            key("https://m.Example.com/a/"),
+            key("http://www.example.com/a")
+        );
+        assert_ne!(key("https://docs.rs/tokio"), key("https://docs.rs/smol"));
+    }
+
+    #[test]
+    fn test_key_ignores_www_fragment_and_trailing_slash() {
+        assert_eq!(
+            key("https://www.Example.com/docs/#intro"),
+            key("http://example.com/docs")
+        );
+    }
+
+    #[test]
+    fn test_dedup_drops_mirrors_and_near_identical_snippets() {
+        let snippet =
+            "Tokio is an asynchronous runtime for the Rust programming language with a scheduler";
+        let results = vec![
+            result("https://tokio.rs/?utm_medium=feed", snippet),
+            result("https://m.tokio.rs", "Mobile page"),
+            result(
+                "https://mirror.example.org/tokio",
+                &format!("{} and timers", snippet),
+            ),
+            result("https://smol.rs", "A small and fast async runtime"),
+        ];
+
+        let deduped = dedup(results, true);
+        let urls: Vec<&str> = deduped.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, ["https://tokio.rs", "https://smol.rs"]);
+        assert_eq!(
+            deduped[0].explain,
+            [
+                "dropped duplicate https://m.tokio.rs (same page)",
+                "dropped duplicate https://mirror.example.org/tokio (near-identical snippet)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use crate::markdown_preview::escape_html;
+use crate::terminal;
+use crate::text::urls;
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+
+const HTML_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Default, Serialize)]
+pub struct Comparison {
+    pub new_sources: Vec<String>,
+    pub dropped_sources: Vec<String>,
+    pub changes: Vec<String>,
+}
+
+impl Comparison {
+    pub fn is_empty(&self) -> bool {
+        self.new_sources.is_empty() && self.dropped_sources.is_empty() && self.changes.is_empty()
+    }
+
+    pub fn render(&self, color: bool) -> String {
+        let mut sections = Vec::new();
+        if !self.new_sources.is_empty() {
+            let lines: Vec<String> = self
+                .new_sources
+                .iter()
+                .map(|url| paint(&format!("  + {}", url), color, terminal::inserted))
+                .collect();
+            sections.push(format!(
+                "New sources ({}):\n{}",
+                lines.len(),
+                lines.join("\n")
+            ));
+        }
+        if !self.dropped_sources.is_empty() {
+            let lines: Vec<String> = self
+                .dropped_sources
+                .iter()
+                .map(|url| paint(&format!("  - {}", url), color, terminal::deleted))
+                .collect();
+            sections.push(format!(
+                "Dropped sources ({}):\n{}",
+                lines.len(),
+                lines.join("\n")
+            ));
+        }
+        if !self.changes.is_empty() {
+            sections.push(format!(
+                "Changed claims ({}):\n\n{}",
+                self.changes.len(),
+                self.changes.join("\n\n⋯\n\n")
+            ));
+        }
+        sections.join("\n\n")
+    }
+}
+
+fn paint(text: &str, color: bool, style: fn(&str) -> String) -> String {
+    if color {
+        style(text)
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn compare(old: &str, new: &str, color: bool) -> Comparison {
+    let (old_sources, new_sources) = (urls(old), urls(new));
+    Comparison {
+        new_sources: new_sources
+            .iter()
+            .filter(|url| !old_sources.contains(url))
+            .cloned()
+            .collect(),
+        dropped_sources: old_sources
+            .iter()
+            .filter(|url| !new_sources.contains(url))
+            .cloned()
+            .collect(),
+        changes: changed_hunks(old, new, color),
+    }
+}
+
+pub fn word_diff(old: &str, new: &str, color: bool) -> String {
+    let diff = TextDiff::from_words(old, new);
+    let mut runs: Vec<(ChangeTag, String)> = Vec::new();
+
+    for change in diff.iter_all_changes() {
+        match runs.last_mut() {
+            Some((tag, text)) if *tag == change.tag() => text.push_str(change.value()),
+            _ => runs.push((change.tag(), change.value().to_string())),
+        }
+    }
+
+    runs.into_iter()
+        .map(|(tag, text)| match (tag, color) {
+            (ChangeTag::Equal, _) => text,
+            (ChangeTag::Delete, true) => terminal::deleted(&text),
+            (ChangeTag::Insert, true) => terminal::inserted(&text),
+            (ChangeTag::Delete, false) => format!("[-{}-]", text),
+            (ChangeTag::Insert, false) => format!("{{+{}+}}", text),
+        })
+        .collect()
+}
+
+pub fn changed_hunks(old: &str, new: &str, color: bool) -> Vec<String> {
+    let diff = TextDiff::from_lines(old, new);
+
+    diff.grouped_ops(0)
+        .iter()
+        .filter_map(|group| {
+            let (first, last) = (group.first()?, group.last()?);
+            let old_text =
+                diff.old_slices()[first.old_range().start..last.old_range().end].concat();
+            let new_text =
+                diff.new_slices()[first.new_range().start..last.new_range().end].concat();
+            Some(word_diff(old_text.trim_end(), new_text.trim_end(), color))
+        })
+        .filter(|hunk| !hunk.is_empty())
+        .collect()
+}
+
+pub fn side_by_side_html(
+    title: &str,
+    old_label: &str,
+    new_label: &str,
+    old: &str,
+    new: &str,
+) -> String {
+    let diff = TextDiff::from_lines(old, new);
+    let mut rows = String::new();
+
+    for (idx, group) in diff.grouped_ops(HTML_CONTEXT_LINES).iter().enumerate() {
+        if idx > 0 {
+            rows.push_str("<tr class=\"gap\"><td colspan=\"2\">⋯</td></tr>\n");
+        }
+
+        for op in group {
+            let mut left = Vec::new();
+            let mut right = Vec::new();
+
+            for change in diff.iter_inline_changes(op) {
+                let mut cell = String::new();
+                for (emphasized, value) in change.iter_strings_lossy() {
+                    let value = escape_html(value.trim_end_matches('\n'));
+                    if emphasized {
+                        cell.push_str(&format!("<mark>{}</mark>", value));
+                    } else {
+                        cell.push_str(&value);
+                    }
+                }
+
+                match change.tag() {
+                    ChangeTag::Equal => {
+                        left.push(("", cell.clone()));
+                        right.push(("", cell));
+                    }
+                    ChangeTag::Delete => left.push(("del", cell)),
+                    ChangeTag::Insert => right.push(("ins", cell)),
+                }
+            }
+
+            for row in 0..left.len().max(right.len()) {
+                let (left_class, left_cell) =
+                    left.get(row).cloned().unwrap_or(("empty", String::new()));
+                let (right_class, right_cell) =
+                    right.get(row).cloned().unwrap_or(("empty", String::new()));
+                rows.push_str(&format!(
+                    "<tr><td class=\"{}\">{}</td><td class=\"{}\">{}</td></tr>\n",
+                    left_class, left_cell, right_class, right_cell
+                ));
+            }
+        }
+    }
+
+    if rows.is_empty() {
+        rows.push_str("<tr class=\"gap\"><td colspan=\"2\">No changes</td></tr>\n");
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="UTF-8">
+    <meta name="color-scheme" content="light dark">
+    <title>{title}</title>
+    <style>
+        body {{ font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Helvetica, Arial, sans-serif; margin: 2rem; }}
+        table {{ width: 100%; border-collapse: collapse; table-layout: fixed; }}
+        th, td {{ padding: 4px 8px; vertical-align: top; text-align: left; white-space: pre-wrap; word-wrap: break-word; }}
+        th {{ border-bottom: 1px solid #8b949e; }}
+        td {{ font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 0.9em; }}
+        td.del {{ background: rgba(248, 81, 73, 0.15); }}
+        td.ins {{ background: rgba(63, 185, 80, 0.15); }}
+        td.del mark {{ background: rgba(248, 81, 73, 0.4); color: inherit; }}
+        td.ins mark {{ background: rgba(63, 185, 80, 0.4); color: inherit; }}
+        td.empty {{ background: rgba(139, 148, 158, 0.08); }}
+        tr.gap td {{ text-align: center; color: #8b949e; }}
+    </style>
+</head>
+<body>
+    <h1>{title}</h1>
+    <table>
+        <tr><th>{old_label}</th><th>{new_label}</th></tr>
+{rows}    </table>
+</body>
+</html>
+"#,
+        title = escape_html(title),
+        old_label = escape_html(old_label),
+        new_label = escape_html(new_label),
+        rows = rows
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_diff_without_color() {
+        assert_eq!(
+            word_diff("tokio is fast", "tokio is very fast", false),
+            "tokio is {+very +}fast"
+        );
+        assert_eq!(word_diff("same text", "same text", false), "same text");
+    }
+
+    #[test]
+    fn test_changed_hunks_skip_unchanged_lines() {
+        let old = "one\ntwo\nthree\nfour\n";
+        let new = "one\ntwo\nTHREE\nfour\n";
+        assert_eq!(changed_hunks(old, new, false), vec!["[-three-]{+THREE+}"]);
+        assert!(changed_hunks(old, old, false).is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_sources_and_claims() {
+        let old = "Tokio is fast.\nSee https://tokio.rs and https://smol.rs.\n";
+        let new = "Tokio is very fast.\nSee https://tokio.rs and https://glommio.rs.\n";
+        let comparison = compare(old, new, false);
+        assert_eq!(comparison.new_sources, ["https://glommio.rs"]);
+        assert_eq!(comparison.dropped_sources, ["https://smol.rs"]);
+        assert_eq!(comparison.changes.len(), 1);
+        assert!(comparison.render(false).contains("  + https://glommio.rs"));
+        assert!(compare(old, old, false).is_empty());
+    }
+
+    #[test]
+    fn test_side_by_side_html_marks_changes_and_escapes() {
+        let html = side_by_side_html("Diff", "Before", "After", "a <b>\n", "a <i>\n");
+        assert!(html.contains("class=\"del\""));
+        assert!(html.contains("class=\"ins\""));
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(!html.contains("<b>"));
+    }
+}
@@ -0,0 +1,502 @@
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use crate::text::ellipsize;
+use chrono::DateTime;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, instrument};
+use url::Url;
+
+const HN_API: &str = "https://hn.algolia.com/api/v1";
+const REDDIT_URL: &str = "https://www.reddit.com";
+const TIMEOUT: Duration = Duration::from_secs(20);
+const USER_AGENT: &str = concat!(
+    "weavex/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/guitaripod/weavex)"
+);
+const MAX_RESULTS: usize = 25;
+const MAX_COMMENTS: usize = 10;
+const MAX_POST_CHARS: usize = 1500;
+const MAX_COMMENT_CHARS: usize = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Source {
+    #[default]
+    All,
+    HackerNews,
+    Reddit,
+}
+
+impl Source {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "all" | "both" => Some(Source::All),
+            "hn" | "hackernews" | "hacker_news" | "hacker news" => Some(Source::HackerNews),
+            "reddit" => Some(Source::Reddit),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discussion {
+    pub source: &'static str,
+    pub title: String,
+    pub url: String,
+    pub link: Option<String>,
+    pub community: Option<String>,
+    pub points: i64,
+    pub comments: u64,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub author: String,
+    pub points: Option<i64>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thread {
+    pub title: String,
+    pub url: String,
+    pub body: String,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HnSearch {
+    #[serde(default)]
+    hits: Vec<HnHit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HnHit {
+    #[serde(rename = "objectID")]
+    object_id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    points: Option<i64>,
+    #[serde(default)]
+    num_comments: Option<u64>,
+    #[serde(default)]
+    created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HnItem {
+    id: u64,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    points: Option<i64>,
+    #[serde(default)]
+    children: Vec<HnItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Listing {
+    data: ListingData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListingData {
+    #[serde(default)]
+    children: Vec<Thing>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thing {
+    kind: String,
+    data: serde_json::Value,
+}
+
+pub struct DiscussionClient {
+    client: Client,
+}
+
+impl DiscussionClient {
+    pub fn new() -> Result<Self> {
+        let client = network::client_builder()
+            .timeout(TIMEOUT)
+            .user_agent(network::user_agent(USER_AGENT))
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self { client })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn search(
+        &self,
+        query: &str,
+        source: Source,
+        subreddit: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Discussion>> {
+        let limit = limit.clamp(1, MAX_RESULTS);
+        match source {
+            Source::HackerNews => self.hacker_news(query, limit).await,
+            Source::Reddit => self.reddit(query, subreddit, limit).await,
+            Source::All => {
+                let (hn, reddit) = tokio::join!(
+                    self.hacker_news(query, limit),
+                    self.reddit(query, subreddit, limit)
+                );
+                match (hn, reddit) {
+                    (Err(e), Err(_)) => Err(e),
+                    (hn, reddit) => Ok(interleave(
+                        hn.unwrap_or_default(),
+                        reddit.unwrap_or_default(),
+                    )),
+                }
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn thread(&self, url: &str) -> Result<Thread> {
+        let parsed = Url::parse(url).map_err(|e| OllamaError::InvalidUrl(e.to_string()))?;
+        let host = parsed.host_str().unwrap_or_default();
+
+        if host.ends_with("ycombinator.com") {
+            let id = parsed
+                .query_pairs()
+                .find(|(key, _)| key == "id")
+                .map(|(_, id)| id.to_string())
+                .ok_or_else(|| OllamaError::InvalidUrl(format!("No item id in {}", url)))?;
+            let item: HnItem = self.get_json(&format!("{}/items/{}", HN_API, id)).await?;
+            return Ok(hn_thread(item));
+        }
+
+        if host.ends_with("reddit.com") && parsed.path().contains("/comments/") {
+            let listings: Vec<Listing> = self
+                .get_json(&format!(
+                    "{}{}.json?sort=top&limit={}",
+                    REDDIT_URL,
+                    parsed.path().trim_end_matches('/'),
+                    MAX_COMMENTS
+                ))
+                .await?;
+            return reddit_thread(listings);
+        }
+
+        Err(OllamaError::InvalidUrl(format!(
+            "Not a Hacker News item or Reddit thread: {}",
+            url
+        )))
+    }
+
+    async fn hacker_news(&self, query: &str, limit: usize) -> Result<Vec<Discussion>> {
+        let mut url = Url::parse(&format!("{}/search", HN_API)).unwrap();
+        url.query_pairs_mut()
+            .append_pair("query", query)
+            .append_pair("tags", "story")
+            .append_pair("hitsPerPage", &limit.to_string());
+        let search: HnSearch = self.get_json(url.as_str()).await?;
+        Ok(search.hits.into_iter().filter_map(hn_discussion).collect())
+    }
+
+    async fn reddit(
+        &self,
+        query: &str,
+        subreddit: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Discussion>> {
+        let base = match subreddit.map(|s| s.trim_start_matches("r/")) {
+            Some(subreddit) if !subreddit.is_empty() => {
+                format!("{}/r/{}/search.json", REDDIT_URL, subreddit)
+            }
+            _ => format!("{}/search.json", REDDIT_URL),
+        };
+        let mut url = Url::parse(&base).map_err(|e| OllamaError::InvalidUrl(e.to_string()))?;
+        url.query_pairs_mut()
+            .append_pair("q", query)
+            .append_pair("limit", &limit.to_string())
+            .append_pair("sort", "relevance")
+            .append_pair("restrict_sr", if subreddit.is_some() { "1" } else { "0" });
+        let listing: Listing = self.get_json(url.as_str()).await?;
+        Ok(reddit_discussions(listing))
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        debug!("Fetching discussion data: {}", url);
+        let response = self.client.get(url).send_audited().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(OllamaError::ApiError {
+                status: status.as_u16(),
+                message: format!("Discussion request failed for {}", url),
+            });
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| OllamaError::InvalidResponse(format!("Unexpected response: {}", e)))
+    }
+}
+
+fn hn_discussion(hit: HnHit) -> Option<Discussion> {
+    Some(Discussion {
+        source: "Hacker News",
+        title: hit.title.filter(|title| !title.is_empty())?,
+        url: format!("https://news.ycombinator.com/item?id={}", hit.object_id),
+        link: hit.url.filter(|url| !url.is_empty()),
+        community: None,
+        points: hit.points.unwrap_or(0),
+        comments: hit.num_comments.unwrap_or(0),
+        date: hit.created_at.get(..10).unwrap_or_default().to_string(),
+    })
+}
+
+fn reddit_discussions(listing: Listing) -> Vec<Discussion> {
+    listing
+        .data
+        .children
+        .into_iter()
+        .filter(|thing| thing.kind == "t3")
+        .filter_map(|thing| {
+            let post = thing.data;
+            let permalink = post["permalink"].as_str()?;
+            let link = post["url"]
+                .as_str()
+                .filter(|url| !url.contains(permalink) && post["is_self"] != true)
+                .map(str::to_string);
+            Some(Discussion {
+                source: "Reddit",
+                title: post["title"].as_str()?.to_string(),
+                url: format!("{}{}", REDDIT_URL, permalink),
+                link,
+                community: post["subreddit_name_prefixed"].as_str().map(str::to_string),
+                points: post["score"].as_i64().unwrap_or(0),
+                comments: post["num_comments"].as_u64().unwrap_or(0),
+                date: post["created_utc"]
+                    .as_f64()
+                    .and_then(|at| DateTime::from_timestamp(at as i64, 0))
+                    .map(|at| at.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn hn_thread(item: HnItem) -> Thread {
+    Thread {
+        title: item.title.unwrap_or_default(),
+        url: format!("https://news.ycombinator.com/item?id={}", item.id),
+        body: item.text.as_deref().map(html_to_text).unwrap_or_default(),
+        comments: item
+            .children
+            .into_iter()
+            .filter_map(|child| {
+                Some(Comment {
+                    text: html_to_text(child.text.as_deref()?),
+                    author: child.author?,
+                    points: child.points,
+                })
+            })
+            .take(MAX_COMMENTS)
+            .collect(),
+    }
+}
+
+fn reddit_thread(listings: Vec<Listing>) -> Result<Thread> {
+    let mut listings = listings.into_iter();
+    let post = listings
+        .next()
+        .and_then(|listing| listing.data.children.into_iter().next())
+        .map(|thing| thing.data)
+        .ok_or_else(|| OllamaError::InvalidResponse("Reddit thread has no post".to_string()))?;
+
+    let comments = listings
+        .next()
+        .map(|listing| listing.data.children)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|thing| thing.kind == "t1")
+        .filter_map(|thing| {
+            let body = thing.data["body"].as_str()?;
+            if matches!(body, "[deleted]" | "[removed]") {
+                return None;
+            }
+            Some(Comment {
+                author: thing.data["author"].as_str()?.to_string(),
+                points: thing.data["score"].as_i64(),
+                text: body.trim().to_string(),
+            })
+        })
+        .take(MAX_COMMENTS)
+        .collect();
+
+    Ok(Thread {
+        title: post["title"].as_str().unwrap_or_default().to_string(),
+        url: format!(
+            "{}{}",
+            REDDIT_URL,
+            post["permalink"].as_str().unwrap_or_default()
+        ),
+        body: post["selftext"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        comments,
+    })
+}
+
+fn html_to_text(html: &str) -> String {
+    htmd::convert(html)
+        .unwrap_or_else(|_| html.to_string())
+        .trim()
+        .to_string()
+}
+
+fn interleave(a: Vec<Discussion>, b: Vec<Discussion>) -> Vec<Discussion> {
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let (mut a, mut b) = (a.into_iter(), b.into_iter());
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return merged,
+            (x, y) => merged.extend(x.into_iter().chain(y)),
+        }
+    }
+}
+
+pub fn format_discussions(discussions: &[Discussion]) -> String {
+    if discussions.is_empty() {
+        return "No discussions found.".to_string();
+    }
+
+    let mut output = String::new();
+    for (idx, discussion) in discussions.iter().enumerate() {
+        let community = discussion
+            .community
+            .as_deref()
+            .map(|c| format!(" {}", c))
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "{}. [{}{}] {}\n   {} points · {} comments · {}\n   Thread: {}\n",
+            idx + 1,
+            discussion.source,
+            community,
+            discussion.title,
+            discussion.points,
+            discussion.comments,
+            discussion.date,
+            discussion.url
+        ));
+        if let Some(link) = &discussion.link {
+            output.push_str(&format!("   Link: {}\n", link));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+pub fn format_thread(thread: &Thread) -> String {
+    let mut output = format!("Title: {}\nURL: {}\n", thread.title, thread.url);
+    if !thread.body.is_empty() {
+        output.push_str(&format!("\n{}\n", ellipsize(&thread.body, MAX_POST_CHARS)));
+    }
+    if thread.comments.is_empty() {
+        output.push_str("\nNo comments yet.\n");
+        return output;
+    }
+
+    output.push_str(&format!("\nTop {} comments:\n", thread.comments.len()));
+    for comment in &thread.comments {
+        let points = comment
+            .points
+            .map(|p| format!(" ({} points)", p))
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "\n- {}{}: {}\n",
+            comment.author,
+            points,
+            ellipsize(&comment.text, MAX_COMMENT_CHARS).replace('\n', "\n  ")
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parses_search_results() {
+        let hn: HnSearch = serde_json::from_value(json!({"hits": [
+            {"objectID": "1", "title": "Tokio 1.0", "url": "https://tokio.rs/blog",
+             "points": 500, "num_comments": 120, "created_at": "2020-12-23T18:00:00Z"},
+            {"objectID": "2", "title": null, "created_at": "2020-12-24T00:00:00Z"}
+        ]}))
+        .unwrap();
+        let hn: Vec<Discussion> = hn.hits.into_iter().filter_map(hn_discussion).collect();
+        assert_eq!(hn.len(), 1);
+        assert_eq!(hn[0].url, "https://news.ycombinator.com/item?id=1");
+        assert_eq!(hn[0].date, "2020-12-23");
+
+        let reddit: Listing = serde_json::from_value(json!({"data": {"children": [
+            {"kind": "t3", "data": {"title": "Tokio vs async-std?", "permalink": "/r/rust/comments/abc/tokio/",
+             "url": "https://www.reddit.com/r/rust/comments/abc/tokio/", "is_self": true,
+             "subreddit_name_prefixed": "r/rust", "score": 42, "num_comments": 7, "created_utc": 1700000000.0}}
+        ]}}))
+        .unwrap();
+        let reddit = reddit_discussions(reddit);
+        assert_eq!(reddit[0].community.as_deref(), Some("r/rust"));
+        assert_eq!(reddit[0].link, None);
+        assert_eq!(reddit[0].date, "2023-11-14");
+
+        let merged = interleave(hn, reddit);
+        assert_eq!(
+            merged.iter().map(|d| d.source).collect::<Vec<_>>(),
+            vec!["Hacker News", "Reddit"]
+        );
+        assert!(format_discussions(&merged)
+            .contains("2. [Reddit r/rust] Tokio vs async-std?\n   42 points · 7 comments"));
+    }
+
+    #[test]
+    fn test_parses_threads() {
+        let item: HnItem = serde_json::from_value(json!({
+            "id": 1, "title": "Ask HN: Tokio?", "text": "<p>Which runtime?</p>",
+            "children": [
+                {"id": 2, "author": "pg", "text": "<p>Use <i>tokio</i>.</p>", "children": []},
+                {"id": 3, "author": null, "text": null, "children": []}
+            ]
+        }))
+        .unwrap();
+        let thread = hn_thread(item);
+        assert_eq!(thread.body, "Which runtime?");
+        assert_eq!(thread.comments.len(), 1);
+        assert_eq!(thread.comments[0].text, "Use *tokio*.");
+
+        let listings: Vec<Listing> = serde_json::from_value(json!([
+            {"data": {"children": [{"kind": "t3", "data": {"title": "Tokio?", "permalink": "/r/rust/comments/abc/tokio/", "selftext": ""}}]}},
+            {"data": {"children": [
+                {"kind": "t1", "data": {"author": "ferris", "body": "tokio\nevery time", "score": 10}},
+                {"kind": "t1", "data": {"author": "x", "body": "[deleted]", "score": 1}},
+                {"kind": "more", "data": {"count": 5}}
+            ]}}
+        ]))
+        .unwrap();
+        let output = format_thread(&reddit_thread(listings).unwrap());
+        assert_eq!(
+            output,
+            "Title: Tokio?\nURL: https://www.reddit.com/r/rust/comments/abc/tokio/\n\n\
+             Top 1 comments:\n\n- ferris (10 points): tokio\n  every time\n"
+        );
+    }
+}
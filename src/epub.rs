@@ -0,0 +1,336 @@
+use crate::error::{OllamaError, Result};
+use crate::markdown_preview::escape_html;
+use pulldown_cmark::{html, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Seek, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+const STYLE: &str = "body { font-family: serif; line-height: 1.5; }
+h1, h2, h3 { font-family: sans-serif; }
+pre { white-space: pre-wrap; font-size: 0.85em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #999; padding: 0.2em 0.4em; }
+";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    pub title: String,
+    pub markdown: String,
+}
+
+struct Heading {
+    level: HeadingLevel,
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+pub fn chapters(markdown: &str) -> (Option<String>, Vec<Chapter>) {
+    let headings = headings(markdown);
+    let top_level: Vec<&Heading> = headings
+        .iter()
+        .filter(|heading| heading.level == HeadingLevel::H1)
+        .collect();
+    let title = match top_level.as_slice() {
+        [only] if markdown[..only.start].trim().is_empty() => Some(*only),
+        _ => None,
+    };
+    let split_level = match title {
+        Some(_) => HeadingLevel::H2,
+        None => headings
+            .iter()
+            .map(|heading| heading.level)
+            .min()
+            .unwrap_or(HeadingLevel::H1),
+    };
+
+    let mut chapters = Vec::new();
+    let splits: Vec<&Heading> = headings
+        .iter()
+        .filter(|heading| heading.level == split_level)
+        .collect();
+    let body_start = title.map(|heading| heading.end).unwrap_or(0);
+    let first_split = splits.first().map(|heading| heading.start);
+    let preamble = &markdown[body_start..first_split.unwrap_or(markdown.len())];
+    if !preamble.trim().is_empty() {
+        chapters.push(Chapter {
+            title: match (title, first_split) {
+                (_, Some(_)) => "Introduction".to_string(),
+                (Some(title), None) => title.text.clone(),
+                (None, None) => "Answer".to_string(),
+            },
+            markdown: preamble.trim().to_string(),
+        });
+    }
+    for (idx, heading) in splits.iter().enumerate() {
+        let end = splits
+            .get(idx + 1)
+            .map(|next| next.start)
+            .unwrap_or(markdown.len());
+        chapters.push(Chapter {
+            title: heading.text.clone(),
+            markdown: markdown[heading.start..end].trim().to_string(),
+        });
+    }
+    (title.map(|heading| heading.text.clone()), chapters)
+}
+
+pub fn write(path: &Path, title: &str, markdown: &str, language: &str) -> Result<()> {
+    let file = File::create(path)?;
+    build(file, title, markdown, language)?;
+    Ok(())
+}
+
+pub fn build<W: Write + Seek>(writer: W, title: &str, markdown: &str, language: &str) -> Result<W> {
+    let (heading, chapters) = chapters(markdown);
+    let title = heading.as_deref().unwrap_or(title);
+    let identifier = format!("urn:weavex:{:x}", Sha256::digest(markdown.as_bytes()));
+    let modified = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+
+    let mut zip = ZipWriter::new(writer);
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    add(&mut zip, "mimetype", "application/epub+zip", stored)?;
+    add(&mut zip, "META-INF/container.xml", CONTAINER, deflated)?;
+    add(&mut zip, "OEBPS/style.css", STYLE, deflated)?;
+    add(
+        &mut zip,
+        "OEBPS/content.opf",
+        &package(title, &identifier, &modified, language, chapters.len()),
+        deflated,
+    )?;
+    add(
+        &mut zip,
+        "OEBPS/nav.xhtml",
+        &navigation(title, &chapters, language),
+        deflated,
+    )?;
+    for (idx, chapter) in chapters.iter().enumerate() {
+        add(
+            &mut zip,
+            &format!("OEBPS/chapter-{}.xhtml", idx + 1),
+            &xhtml(
+                &chapter.title,
+                &markdown_to_xhtml(&chapter.markdown),
+                language,
+            ),
+            deflated,
+        )?;
+    }
+    zip.finish().map_err(zip_error)
+}
+
+fn headings(markdown: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut current: Option<Heading> = None;
+    for (event, range) in Parser::new_ext(markdown, Options::empty()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                current = Some(Heading {
+                    level,
+                    start: range.start,
+                    end: range.end,
+                    text: String::new(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = current.as_mut() {
+                    heading.text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => headings.extend(current.take()),
+            _ => {}
+        }
+    }
+    headings
+}
+
+fn markdown_to_xhtml(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }),
+        Event::End(TagEnd::Image) => Event::End(TagEnd::Link),
+        event => event,
+    });
+    let mut output = String::new();
+    html::push_html(&mut output, events);
+    output
+}
+
+fn xhtml(title: &str, body: &str, language: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops" xml:lang="{language}" lang="{language}">
+<head>
+  <title>{title}</title>
+  <link rel="stylesheet" type="text/css" href="style.css"/>
+</head>
+<body>
+{body}
+</body>
+</html>
+"#,
+        language = escape_html(language),
+        title = escape_html(title),
+        body = body
+    )
+}
+
+fn navigation(title: &str, chapters: &[Chapter], language: &str) -> String {
+    let items: String = chapters
+        .iter()
+        .enumerate()
+        .map(|(idx, chapter)| {
+            format!(
+                "    <li><a href=\"chapter-{}.xhtml\">{}</a></li>\n",
+                idx + 1,
+                escape_html(&chapter.title)
+            )
+        })
+        .collect();
+    let body = format!(
+        "<nav epub:type=\"toc\" id=\"toc\">\n  <h1>{}</h1>\n  <ol>\n{}  </ol>\n</nav>",
+        escape_html(title),
+        items
+    );
+    xhtml(title, &body, language)
+}
+
+fn package(
+    title: &str,
+    identifier: &str,
+    modified: &str,
+    language: &str,
+    chapters: usize,
+) -> String {
+    let manifest: String = (1..=chapters)
+        .map(|n| {
+            format!(
+                "    <item id=\"chapter-{n}\" href=\"chapter-{n}.xhtml\" media-type=\"application/xhtml+xml\"/>\n"
+            )
+        })
+        .collect();
+    let spine: String = (1..=chapters)
+        .map(|n| format!("    <itemref idref=\"chapter-{n}\"/>\n"))
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="book-id">
+  <metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+    <dc:identifier id="book-id">{identifier}</dc:identifier>
+    <dc:title>{title}</dc:title>
+    <dc:language>{language}</dc:language>
+    <dc:creator>Weavex</dc:creator>
+    <meta property="dcterms:modified">{modified}</meta>
+  </metadata>
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="style" href="style.css" media-type="text/css"/>
+{manifest}  </manifest>
+  <spine>
+{spine}  </spine>
+</package>
+"#,
+        identifier = identifier,
+        title = escape_html(title),
+        language = escape_html(language),
+        modified = modified,
+        manifest = manifest,
+        spine = spine
+    )
+}
+
+fn add<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    content: &str,
+    options: SimpleFileOptions,
+) -> Result<()> {
+    zip.start_file(name, options).map_err(zip_error)?;
+    zip.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+fn zip_error(e: zip::result::ZipError) -> OllamaError {
+    OllamaError::InvalidResponse(format!("Failed to write EPUB archive: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+
+    const REPORT: &str = "# Rust async runtimes\n\nA short overview.\n\n\
+                          ## Tokio\n\nThe default runtime.\n\n### Scheduler\n\nWork stealing.\n\n\
+                          ## smol\n\nSmall & fast <b>runtime</b>.\n\n\
+                          ![logo](https://smol.rs/logo.png)\n";
+
+    #[test]
+    fn test_splits_chapters_at_sections_below_the_title() {
+        let (title, sections) = chapters(REPORT);
+        assert_eq!(title.as_deref(), Some("Rust async runtimes"));
+        let titles: Vec<&str> = sections.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Introduction", "Tokio", "smol"]);
+        assert!(sections[1].markdown.contains("### Scheduler"));
+
+        let (title, sections) = chapters("Just an answer without headings.");
+        assert_eq!(title, None);
+        assert_eq!(sections[0].title, "Answer");
+    }
+
+    #[test]
+    fn test_builds_a_stored_mimetype_and_one_file_per_chapter() {
+        let bytes = build(Cursor::new(Vec::new()), "query", REPORT, "en")
+            .unwrap()
+            .into_inner();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+
+        let mimetype = archive.by_index(0).unwrap();
+        assert_eq!(mimetype.name().unwrap(), "mimetype");
+        assert_eq!(mimetype.compression(), CompressionMethod::Stored);
+        drop(mimetype);
+
+        let mut chapter = String::new();
+        archive
+            .by_name("OEBPS/chapter-3.xhtml")
+            .unwrap()
+            .read_to_string(&mut chapter)
+            .unwrap();
+        assert!(chapter.contains("<h2>smol</h2>"));
+        assert!(chapter.contains("Small &amp; fast &lt;b&gt;runtime&lt;/b&gt;."));
+        assert!(chapter.contains(r#"<a href="https://smol.rs/logo.png">logo</a>"#));
+
+        let mut package = String::new();
+        archive
+            .by_name("OEBPS/content.opf")
+            .unwrap()
+            .read_to_string(&mut package)
+            .unwrap();
+        assert!(package.contains("<dc:title>Rust async runtimes</dc:title>"));
+        assert_eq!(package.matches("<itemref ").count(), 3);
+    }
+}
@@ -14,6 +14,29 @@ pub enum OllamaError {
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
 
+    #[error("{upstream} upstream degraded after repeated failures; retrying in {retry_in}s")]
+    UpstreamDegraded { upstream: String, retry_in: u64 },
+
+    #[error("Usage budget exceeded: {used} of {limit} {kind} used {period}")]
+    BudgetExceeded {
+        kind: String,
+        period: String,
+        used: u64,
+        limit: u64,
+    },
+
+    #[error("{0}")]
+    OllamaUnreachable(String),
+
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("Local store error: {0}")]
+    StoreError(#[from] rusqlite::Error),
+
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 }
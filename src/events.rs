@@ -0,0 +1,155 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+use tracing::warn;
+
+const SUMMARY_CHARS: usize = 200;
+
+static STREAM: OnceLock<EventStream> = OnceLock::new();
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    RunStart {
+        query: &'a str,
+        model: &'a str,
+    },
+    IterationStart {
+        iteration: usize,
+        max_iterations: usize,
+    },
+    Thinking {
+        text: &'a str,
+    },
+    ToolCall {
+        name: &'a str,
+        arguments: &'a Value,
+    },
+    ToolResult {
+        name: &'a str,
+        chars: usize,
+        truncated: bool,
+        summary: &'a str,
+    },
+    AnswerChunk {
+        text: &'a str,
+    },
+    Final {
+        status: &'a str,
+        iterations: usize,
+        answer: &'a str,
+    },
+    Error {
+        message: &'a str,
+    },
+    Notice {
+        message: &'a str,
+    },
+    Diff {
+        changes: &'a Value,
+    },
+}
+
+struct EventStream {
+    out: Mutex<Box<dyn Write + Send>>,
+    started: Instant,
+}
+
+pub fn enable() {
+    let stream = EventStream {
+        out: Mutex::new(Box::new(std::io::stdout())),
+        started: Instant::now(),
+    };
+    if STREAM.set(stream).is_err() {
+        warn!("JSON event streaming was already enabled");
+    }
+}
+
+pub fn enabled() -> bool {
+    STREAM.get().is_some()
+}
+
+pub fn emit(event: Event) {
+    let Some(stream) = STREAM.get() else {
+        return;
+    };
+    let line = line(&event, stream.started.elapsed().as_millis());
+    let mut out = stream.out.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = writeln!(out, "{}", line).and_then(|_| out.flush()) {
+        warn!("Failed to write agent event: {}", e);
+    }
+}
+
+pub fn summary(text: &str) -> &str {
+    crate::text::truncate_utf8(text, SUMMARY_CHARS)
+}
+
+fn line(event: &Event, elapsed_ms: u128) -> String {
+    let mut value = serde_json::to_value(event).unwrap_or_default();
+    if let Some(object) = value.as_object_mut() {
+        object.insert("elapsed_ms".to_string(), Value::from(elapsed_ms as u64));
+    }
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_events_are_tagged_single_lines() {
+        let arguments = json!({"query": "tokio\nsmol"});
+        let line = line(
+            &Event::ToolCall {
+                name: "web_search",
+                arguments: &arguments,
+            },
+            42,
+        );
+        assert!(!line.contains('\n'));
+        let parsed: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            parsed,
+            json!({
+                "event": "tool_call",
+                "name": "web_search",
+                "arguments": {"query": "tokio\nsmol"},
+                "elapsed_ms": 42,
+            })
+        );
+    }
+
+    #[test]
+    fn test_final_event_fields() {
+        let line = line(
+            &Event::Final {
+                status: "completed",
+                iterations: 3,
+                answer: "Tokio.",
+            },
+            7,
+        );
+        let parsed: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed["event"], "final");
+        assert_eq!(parsed["iterations"], 3);
+        assert_eq!(parsed["answer"], "Tokio.");
+        assert_eq!(summary(&"x".repeat(500)).len(), SUMMARY_CHARS);
+    }
+
+    #[test]
+    fn test_notice_and_diff_events() {
+        let notice = line(&Event::Notice { message: "Copied" }, 1);
+        let parsed: Value = serde_json::from_str(&notice).unwrap();
+        assert_eq!(parsed["event"], "notice");
+        assert_eq!(parsed["message"], "Copied");
+
+        let changes = json!({"old": 4, "new": null});
+        let diff = line(&Event::Diff { changes: &changes }, 2);
+        let parsed: Value = serde_json::from_str(&diff).unwrap();
+        assert_eq!(parsed["event"], "diff");
+        assert_eq!(parsed["changes"]["old"], 4);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::error::OllamaError;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+pub const SUCCESS: u8 = 0;
+pub const FAILURE: u8 = 1;
+pub const NO_RESULTS: u8 = 2;
+pub const API_ERROR: u8 = 3;
+pub const OLLAMA_UNREACHABLE: u8 = 4;
+pub const MAX_ITERATIONS: u8 = 5;
+pub const BUDGET_EXCEEDED: u8 = 6;
+
+static REPORTED: AtomicU8 = AtomicU8::new(SUCCESS);
+
+pub fn report(code: u8) {
+    let _ = REPORTED.compare_exchange(SUCCESS, code, Ordering::Relaxed, Ordering::Relaxed);
+}
+
+pub fn reported() -> u8 {
+    REPORTED.load(Ordering::Relaxed)
+}
+
+pub fn for_error(error: &OllamaError) -> u8 {
+    match error {
+        OllamaError::RequestFailed(_)
+        | OllamaError::InvalidResponse(_)
+        | OllamaError::ApiError { .. }
+        | OllamaError::UpstreamDegraded { .. } => API_ERROR,
+        OllamaError::OllamaUnreachable(_) => OLLAMA_UNREACHABLE,
+        OllamaError::BudgetExceeded { .. } => BUDGET_EXCEEDED,
+        _ => FAILURE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breaker;
+    use crate::ollama_local::{ChatBackend, OllamaLocal};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_maps_errors_to_codes() {
+        let api = OllamaError::ApiError {
+            status: 500,
+            message: "boom".to_string(),
+        };
+        let budget = OllamaError::BudgetExceeded {
+            kind: "web calls".to_string(),
+            period: "today".to_string(),
+            used: 10,
+            limit: 10,
+        };
+        assert_eq!(for_error(&api), API_ERROR);
+        assert_eq!(for_error(&budget), BUDGET_EXCEEDED);
+        assert_eq!(for_error(&OllamaError::ConfigError("x".into())), FAILURE);
+    }
+
+    #[tokio::test]
+    async fn test_local_chat_connection_failure_is_ollama_unreachable() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let url = format!("http://127.0.0.1:{}", port);
+        breaker::shared(&url).reset();
+        let ollama = OllamaLocal::new(Some(url)).unwrap();
+        let error = ollama
+            .chat(
+                "llama3",
+                vec![serde_json::json!({"role": "user", "content": "hi"})],
+                None,
+                false,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(for_error(&error), OLLAMA_UNREACHABLE);
+    }
+}
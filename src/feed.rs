@@ -0,0 +1,379 @@
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use crate::text::ellipsize;
+use chrono::DateTime;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, instrument};
+use url::Url;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+const USER_AGENT: &str = concat!(
+    "weavex/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/guitaripod/weavex)"
+);
+pub const MAX_ENTRIES: usize = 50;
+const MAX_SUMMARY_CHARS: usize = 400;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Feed {
+    pub title: String,
+    pub url: String,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    pub title: String,
+    pub link: Option<String>,
+    pub date: Option<String>,
+    pub summary: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Document {
+    title: Text,
+    channel: Option<Channel>,
+    #[serde(rename = "item")]
+    items: Vec<Item>,
+    #[serde(rename = "entry")]
+    entries: Vec<AtomEntry>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Channel {
+    title: Text,
+    #[serde(rename = "item")]
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Item {
+    title: Text,
+    #[serde(rename = "link")]
+    links: Vec<Text>,
+    guid: Text,
+    #[serde(rename = "pubDate")]
+    pub_date: Text,
+    date: Text,
+    description: Text,
+    encoded: Text,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AtomEntry {
+    title: Text,
+    #[serde(rename = "link")]
+    links: Vec<AtomLink>,
+    updated: Text,
+    published: Text,
+    summary: Text,
+    content: Text,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AtomLink {
+    #[serde(rename = "@href")]
+    href: String,
+    #[serde(rename = "@rel")]
+    rel: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Text {
+    #[serde(rename = "$text")]
+    value: String,
+}
+
+impl Text {
+    fn get(&self) -> Option<&str> {
+        Some(self.value.trim()).filter(|value| !value.is_empty())
+    }
+}
+
+pub struct FeedClient {
+    client: Client,
+}
+
+impl FeedClient {
+    pub fn new() -> Result<Self> {
+        let client = network::client_builder()
+            .timeout(TIMEOUT)
+            .user_agent(network::user_agent(USER_AGENT))
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self { client })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn fetch(&self, url: &str, limit: usize) -> Result<Feed> {
+        let (body, is_html) = self.get(url).await?;
+        if !is_html {
+            return parse_feed(&body, url, limit);
+        }
+
+        let feed_url = discover_feed(&body, url).ok_or_else(|| {
+            OllamaError::InvalidResponse(format!("No RSS or Atom feed found at {}", url))
+        })?;
+        debug!("Discovered feed {} from {}", feed_url, url);
+        let (body, _) = self.get(&feed_url).await?;
+        parse_feed(&body, &feed_url, limit)
+    }
+
+    async fn get(&self, url: &str) -> Result<(String, bool)> {
+        let response = self.client.get(url).send_audited().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(OllamaError::ApiError {
+                status: status.as_u16(),
+                message: format!("Feed request failed for {}", url),
+            });
+        }
+
+        let is_html = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("text/html"));
+        Ok((response.text().await?, is_html))
+    }
+}
+
+pub fn parse_feed(body: &str, url: &str, limit: usize) -> Result<Feed> {
+    let document: Document = quick_xml::de::from_str(body)
+        .map_err(|e| OllamaError::InvalidResponse(format!("Invalid RSS or Atom feed: {}", e)))?;
+
+    let limit = limit.clamp(1, MAX_ENTRIES);
+    let (title, entries) = match document.channel {
+        Some(channel) => {
+            let items = if channel.items.is_empty() {
+                document.items
+            } else {
+                channel.items
+            };
+            (
+                channel.title,
+                items.into_iter().take(limit).map(rss_entry).collect(),
+            )
+        }
+        None if !document.entries.is_empty() || document.title.get().is_some() => (
+            document.title,
+            document
+                .entries
+                .into_iter()
+                .take(limit)
+                .map(atom_entry)
+                .collect(),
+        ),
+        None => {
+            return Err(OllamaError::InvalidResponse(format!(
+                "{} is not an RSS or Atom feed",
+                url
+            )))
+        }
+    };
+
+    Ok(Feed {
+        title: title.get().unwrap_or(url).to_string(),
+        url: url.to_string(),
+        entries,
+    })
+}
+
+fn rss_entry(item: Item) -> Entry {
+    let link = item
+        .links
+        .iter()
+        .find_map(Text::get)
+        .or_else(|| item.guid.get().filter(|guid| guid.starts_with("http")))
+        .map(str::to_string);
+    let date = item.pub_date.get().or(item.date.get()).map(format_date);
+    let summary = item.description.get().or(item.encoded.get());
+
+    Entry {
+        title: item.title.get().unwrap_or("(untitled)").to_string(),
+        link,
+        date,
+        summary: summary.map(summarize).unwrap_or_default(),
+    }
+}
+
+fn atom_entry(entry: AtomEntry) -> Entry {
+    let link = entry
+        .links
+        .iter()
+        .find(|link| matches!(link.rel.as_deref(), None | Some("alternate")))
+        .or(entry.links.first())
+        .map(|link| link.href.clone());
+    let date = entry
+        .published
+        .get()
+        .or(entry.updated.get())
+        .map(format_date);
+    let summary = entry.summary.get().or(entry.content.get());
+
+    Entry {
+        title: entry.title.get().unwrap_or("(untitled)").to_string(),
+        link,
+        date,
+        summary: summary.map(summarize).unwrap_or_default(),
+    }
+}
+
+fn format_date(date: &str) -> String {
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .map(|at| at.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+fn summarize(html: &str) -> String {
+    let text = htmd::convert(html).unwrap_or_else(|_| html.to_string());
+    ellipsize(
+        &text.split_whitespace().collect::<Vec<_>>().join(" "),
+        MAX_SUMMARY_CHARS,
+    )
+}
+
+pub fn discover_feed(html: &str, base: &str) -> Option<String> {
+    let selector = Selector::parse(
+        r#"link[rel~="alternate"][type="application/rss+xml"][href], link[rel~="alternate"][type="application/atom+xml"][href]"#,
+    )
+    .ok()?;
+    let href = Html::parse_document(html)
+        .select(&selector)
+        .next()?
+        .value()
+        .attr("href")?
+        .to_string();
+    Url::parse(base)
+        .and_then(|base| base.join(&href))
+        .map(String::from)
+        .ok()
+}
+
+pub fn format_feed(feed: &Feed) -> String {
+    if feed.entries.is_empty() {
+        return format!("{} has no entries.", feed.title);
+    }
+
+    let mut output = format!("{} ({})\n\n", feed.title, feed.url);
+    for (idx, entry) in feed.entries.iter().enumerate() {
+        output.push_str(&format!("{}. {}\n", idx + 1, entry.title));
+        if let Some(date) = &entry.date {
+            output.push_str(&format!("   Date: {}\n", date));
+        }
+        if let Some(link) = &entry.link {
+            output.push_str(&format!("   URL: {}\n", link));
+        }
+        if !entry.summary.is_empty() {
+            output.push_str(&format!("   {}\n", entry.summary));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Rust Blog</title>
+    <atom:link href="https://blog.rust-lang.org/feed.xml" rel="self" type="application/rss+xml"/>
+    <link>https://blog.rust-lang.org/</link>
+    <item>
+      <title>Announcing Rust 1.80</title>
+      <link>https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html</link>
+      <pubDate>Thu, 25 Jul 2024 00:00:00 +0000</pubDate>
+      <description><![CDATA[<p>The Rust team is happy to announce <b>LazyCell</b>.</p>]]></description>
+    </item>
+    <item>
+      <title>Untitled link post</title>
+      <guid>https://example.com/post</guid>
+      <content:encoded>Plain   text</content:encoded>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">tokio releases</title>
+  <link href="https://github.com/tokio-rs/tokio/releases.atom" rel="self"/>
+  <entry>
+    <title>tokio-1.40.0</title>
+    <link rel="alternate" type="text/html" href="https://github.com/tokio-rs/tokio/releases/tag/tokio-1.40.0"/>
+    <updated>2024-08-30T12:00:00Z</updated>
+    <content type="html">&lt;h2&gt;Added&lt;/h2&gt;&lt;p&gt;task: add &lt;code&gt;JoinSet::spawn_blocking&lt;/code&gt;&lt;/p&gt;</content>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parses_rss_and_atom() {
+        let feed = parse_feed(RSS, "https://blog.rust-lang.org/feed.xml", 10).unwrap();
+        assert_eq!(feed.title, "Rust Blog");
+        assert_eq!(
+            feed.entries[0],
+            Entry {
+                title: "Announcing Rust 1.80".to_string(),
+                link: Some("https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html".to_string()),
+                date: Some("2024-07-25".to_string()),
+                summary: "The Rust team is happy to announce **LazyCell**.".to_string(),
+            }
+        );
+        assert_eq!(
+            feed.entries[1].link.as_deref(),
+            Some("https://example.com/post")
+        );
+        assert_eq!(feed.entries[1].summary, "Plain text");
+
+        let feed = parse_feed(ATOM, "https://github.com/tokio-rs/tokio/releases.atom", 10).unwrap();
+        assert_eq!(feed.title, "tokio releases");
+        assert_eq!(feed.entries[0].date.as_deref(), Some("2024-08-30"));
+        assert_eq!(
+            feed.entries[0].link.as_deref(),
+            Some("https://github.com/tokio-rs/tokio/releases/tag/tokio-1.40.0")
+        );
+        assert_eq!(
+            feed.entries[0].summary,
+            "## Added task: add `JoinSet::spawn_blocking`"
+        );
+        assert!(parse_feed("<html><body>hi</body></html>", "https://x.dev", 10).is_err());
+    }
+
+    #[test]
+    fn test_discovers_feed_links_and_formats() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <link rel="alternate" type="application/atom+xml" title="Blog" href="/atom.xml">
+            </head></html>"#;
+        assert_eq!(
+            discover_feed(html, "https://example.com/blog/").as_deref(),
+            Some("https://example.com/atom.xml")
+        );
+        assert_eq!(discover_feed("<html></html>", "https://example.com"), None);
+
+        let feed = parse_feed(RSS, "https://blog.rust-lang.org/feed.xml", 1).unwrap();
+        assert_eq!(
+            format_feed(&feed),
+            "Rust Blog (https://blog.rust-lang.org/feed.xml)\n\n\
+             1. Announcing Rust 1.80\n   \
+             Date: 2024-07-25\n   \
+             URL: https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html\n   \
+             The Rust team is happy to announce **LazyCell**.\n\n"
+        );
+    }
+}
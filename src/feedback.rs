@@ -0,0 +1,244 @@
+use crate::client::SearchResult;
+use crate::dedup;
+use crate::error::{OllamaError, Result};
+use crate::store::{self, Store};
+use clap::ValueEnum;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+const MAX_DOMAIN_SCORE: i64 = 5;
+const POSITIONS_PER_VOTE: i64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Rating {
+    Good,
+    Bad,
+}
+
+impl Rating {
+    fn value(self) -> i64 {
+        match self {
+            Rating::Good => 1,
+            Rating::Bad => -1,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EvalExample {
+    pub query: String,
+    pub url: String,
+    pub title: String,
+    pub relevant: bool,
+    pub rated_at: i64,
+}
+
+pub fn result_id(url: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in dedup::key(url).bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:08x}", hash as u32)
+}
+
+pub fn domain_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.host_str()
+                .map(|h| h.trim_start_matches("www.").to_lowercase())
+        })
+        .unwrap_or_default()
+}
+
+pub fn record_seen(store: &Store, query: &str, results: &[SearchResult]) -> Result<()> {
+    let now = store::now();
+    for result in results {
+        store.conn().execute(
+            "INSERT INTO seen_results (id, url, title, query, seen_at) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(id) DO UPDATE SET title = excluded.title, query = excluded.query, seen_at = excluded.seen_at",
+            params![result_id(&result.url), result.url, result.title, query, now],
+        )?;
+    }
+    Ok(())
+}
+
+pub fn rate(store: &Store, id_or_url: &str, rating: Rating) -> Result<String> {
+    let (id, url, title, query) = if id_or_url.contains("://") {
+        let id = result_id(id_or_url);
+        let seen = lookup_seen(store, &id)?;
+        let (title, query) = seen
+            .map(|(_, title, query)| (title, query))
+            .unwrap_or_default();
+        (id, id_or_url.to_string(), title, query)
+    } else {
+        let (url, title, query) = lookup_seen(store, id_or_url)?.ok_or_else(|| {
+            OllamaError::InvalidResponse(format!(
+                "Unknown result id '{}'. Result ids are shown next to each search result.",
+                id_or_url
+            ))
+        })?;
+        (id_or_url.to_string(), url, title, query)
+    };
+
+    let domain = domain_of(&url);
+    store.conn().execute(
+        "INSERT INTO feedback (result_id, url, domain, title, query, rating, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![id, url, domain, title, query, rating.value(), store::now()],
+    )?;
+
+    Ok(domain)
+}
+
+fn lookup_seen(store: &Store, id: &str) -> Result<Option<(String, String, String)>> {
+    let row = store
+        .conn()
+        .query_row(
+            "SELECT url, title, query FROM seen_results WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+    Ok(row)
+}
+
+pub fn seen_for_query(store: &Store, query: &str) -> Result<Vec<SearchResult>> {
+    let mut stmt = store.conn().prepare(
+        "SELECT url, title FROM seen_results WHERE query = ?1 ORDER BY seen_at DESC, rowid",
+    )?;
+    let rows = stmt.query_map(params![query], |row| {
+        Ok(SearchResult {
+            url: row.get(0)?,
+            title: row.get(1)?,
+            content: String::new(),
+            ..Default::default()
+        })
+    })?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+pub fn domain_scores(store: &Store) -> Result<HashMap<String, i64>> {
+    let mut stmt = store
+        .conn()
+        .prepare("SELECT domain, SUM(rating) FROM feedback GROUP BY domain")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    let mut scores = HashMap::new();
+    for row in rows {
+        let (domain, score): (String, i64) = row?;
+        scores.insert(domain, score);
+    }
+    Ok(scores)
+}
+
+pub fn domain_score(scores: &HashMap<String, i64>, url: &str) -> i64 {
+    scores
+        .get(&domain_of(url))
+        .copied()
+        .unwrap_or_default()
+        .clamp(-MAX_DOMAIN_SCORE, MAX_DOMAIN_SCORE)
+}
+
+pub fn apply_domain_scores(results: &mut Vec<SearchResult>, scores: &HashMap<String, i64>) {
+    if scores.is_empty() {
+        return;
+    }
+
+    let mut ranked: Vec<(i64, SearchResult)> = results
+        .drain(..)
+        .enumerate()
+        .map(|(rank, result)| {
+            let adjusted = rank as i64 - POSITIONS_PER_VOTE * domain_score(scores, &result.url);
+            (adjusted, result)
+        })
+        .collect();
+
+    ranked.sort_by_key(|(adjusted, _)| *adjusted);
+    results.extend(ranked.into_iter().map(|(_, result)| result));
+}
+
+pub fn eval_set(store: &Store) -> Result<Vec<EvalExample>> {
+    let mut stmt = store.conn().prepare(
+        "SELECT query, url, title, rating, created_at FROM feedback ORDER BY created_at",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(EvalExample {
+            query: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            relevant: row.get::<_, i64>(3)? > 0,
+            rated_at: row.get(4)?,
+        })
+    })?;
+
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            title: url.to_string(),
+            url: url.to_string(),
+            content: String::new(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_result_id_is_stable_across_url_variants() {
+        assert_eq!(
+            result_id("https://www.example.com/page/"),
+            result_id("https://example.com/page")
+        );
+        assert_eq!(result_id("https://example.com").len(), 8);
+    }
+
+    #[test]
+    fn test_rating_by_id_boosts_domain() {
+        let store = Store::open_in_memory().unwrap();
+        let results = vec![result("https://good.dev/a"), result("https://spam.io/b")];
+        record_seen(&store, "query", &results).unwrap();
+
+        rate(&store, &result_id("https://good.dev/a"), Rating::Good).unwrap();
+        rate(&store, "https://spam.io/b", Rating::Bad).unwrap();
+
+        let scores = domain_scores(&store).unwrap();
+        assert_eq!(scores["good.dev"], 1);
+        assert_eq!(scores["spam.io"], -1);
+        assert_eq!(eval_set(&store).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rating_unknown_id_fails() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(rate(&store, "deadbeef", Rating::Good).is_err());
+    }
+
+    #[test]
+    fn test_apply_domain_scores_reorders_results() {
+        let mut results = vec![
+            result("https://spam.io/1"),
+            result("https://neutral.org/2"),
+            result("https://good.dev/3"),
+        ];
+        let scores = HashMap::from([("spam.io".to_string(), -1), ("good.dev".to_string(), 1)]);
+
+        apply_domain_scores(&mut results, &scores);
+
+        let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://good.dev/3",
+                "https://neutral.org/2",
+                "https://spam.io/1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use crate::error::{OllamaError, Result};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+pub const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_LINES: usize = 400;
+const MAX_ENTRIES: usize = 200;
+
+pub struct FileSandbox {
+    roots: Vec<PathBuf>,
+}
+
+impl FileSandbox {
+    pub fn new(roots: &[PathBuf]) -> Result<Self> {
+        let roots = roots
+            .iter()
+            .map(|root| {
+                let canonical = root.canonicalize().map_err(|e| {
+                    OllamaError::ConfigError(format!("Cannot allow {}: {}", root.display(), e))
+                })?;
+                if !canonical.is_dir() {
+                    return Err(OllamaError::ConfigError(format!(
+                        "Cannot allow {}: not a directory",
+                        root.display()
+                    )));
+                }
+                Ok(canonical)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { roots })
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    pub fn resolve(&self, path: &str) -> Result<PathBuf> {
+        let requested = Path::new(path.trim());
+        let candidates: Vec<PathBuf> = if requested.is_absolute() {
+            vec![requested.to_path_buf()]
+        } else {
+            self.roots.iter().map(|root| root.join(requested)).collect()
+        };
+
+        for candidate in candidates {
+            let Ok(canonical) = candidate.canonicalize() else {
+                continue;
+            };
+            if self.roots.iter().any(|root| canonical.starts_with(root)) {
+                return Ok(canonical);
+            }
+            debug!("Rejected {} outside allowed roots", canonical.display());
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} is outside the allowed directories", path),
+            )
+            .into());
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} does not exist in the allowed directories", path),
+        )
+        .into())
+    }
+
+    pub fn read(&self, path: &str, start_line: usize) -> Result<String> {
+        let resolved = self.resolve(path)?;
+        if resolved.is_dir() {
+            return list_dir(&resolved);
+        }
+
+        let size = fs::metadata(&resolved)?.len();
+        let mut bytes = Vec::new();
+        File::open(&resolved)?
+            .take(MAX_FILE_BYTES)
+            .read_to_end(&mut bytes)?;
+        if bytes.iter().take(8192).any(|&b| b == 0) {
+            return Err(OllamaError::InvalidResponse(format!(
+                "{} is a binary file",
+                resolved.display()
+            )));
+        }
+
+        let text = String::from_utf8_lossy(&bytes);
+        let start = start_line.max(1);
+        let lines: Vec<&str> = text.lines().collect();
+
+        let mut output = format!("File: {}\n", resolved.display());
+        if start > lines.len() {
+            output.push_str(&format!(
+                "Line {} is past the end of the file ({} lines).\n",
+                start,
+                lines.len()
+            ));
+            return Ok(output);
+        }
+        let end = start.saturating_add(MAX_LINES - 1).min(lines.len());
+        output.push_str(&format!(
+            "Lines {}-{} of {}{}:\n\n",
+            start,
+            end,
+            lines.len(),
+            if size > MAX_FILE_BYTES {
+                " (file truncated at 1 MiB)"
+            } else {
+                ""
+            }
+        ));
+        for (idx, line) in lines[start - 1..end].iter().enumerate() {
+            output.push_str(&format!("{:>5}  {}\n", start + idx, line));
+        }
+        if end < lines.len() {
+            output.push_str(&format!(
+                "\n[{} more lines; call read_file again with start_line {}]\n",
+                lines.len() - end,
+                end + 1
+            ));
+        }
+        Ok(output)
+    }
+}
+
+fn list_dir(dir: &Path) -> Result<String> {
+    let mut entries: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() => format!("{}/", name),
+                _ => name,
+            }
+        })
+        .collect();
+    entries.sort();
+
+    let mut output = format!("Directory: {}\n\n", dir.display());
+    for entry in entries.iter().take(MAX_ENTRIES) {
+        output.push_str(&format!("{}\n", entry));
+    }
+    if entries.len() > MAX_ENTRIES {
+        output.push_str(&format!("[{} more entries]\n", entries.len() - MAX_ENTRIES));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_files_and_directories_under_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("notes")).unwrap();
+        fs::write(dir.path().join("notes/todo.md"), "one\ntwo\nthree\n").unwrap();
+        let sandbox = FileSandbox::new(&[dir.path().to_path_buf()]).unwrap();
+
+        let output = sandbox.read("notes/todo.md", 2).unwrap();
+        assert!(output.ends_with("Lines 2-3 of 3:\n\n    2  two\n    3  three\n"));
+
+        let absolute = dir.path().join("notes/todo.md");
+        assert!(sandbox.read(absolute.to_str().unwrap(), 1).is_ok());
+        assert!(sandbox.read("notes", 1).unwrap().ends_with("\n\ntodo.md\n"));
+
+        fs::write(dir.path().join("blob.bin"), [0u8, 159, 146, 150]).unwrap();
+        assert!(sandbox
+            .read("blob.bin", 1)
+            .unwrap_err()
+            .to_string()
+            .contains("binary"));
+    }
+
+    #[test]
+    fn test_start_line_past_the_end_does_not_overflow() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("todo.md"), "one\ntwo\n").unwrap();
+        let sandbox = FileSandbox::new(&[dir.path().to_path_buf()]).unwrap();
+
+        let output = sandbox.read("todo.md", u64::MAX as usize).unwrap();
+        assert!(output.ends_with(&format!(
+            "Line {} is past the end of the file (2 lines).\n",
+            u64::MAX
+        )));
+    }
+
+    #[test]
+    fn test_rejects_paths_outside_roots() {
+        let outside = tempfile::tempdir().unwrap();
+        fs::write(outside.path().join("secret.txt"), "token").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let sandbox = FileSandbox::new(&[dir.path().to_path_buf()]).unwrap();
+
+        let secret = outside.path().join("secret.txt");
+        assert!(sandbox.read(secret.to_str().unwrap(), 1).is_err());
+        let traversal = format!(
+            "../{}/secret.txt",
+            outside.path().file_name().unwrap().to_str().unwrap()
+        );
+        assert!(sandbox
+            .read(&traversal, 1)
+            .unwrap_err()
+            .to_string()
+            .contains("outside"));
+        assert!(sandbox.read("missing.txt", 1).is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&secret, dir.path().join("link.txt")).unwrap();
+            assert!(sandbox.read("link.txt", 1).is_err());
+        }
+        assert!(FileSandbox::new(&[secret]).is_err());
+    }
+}
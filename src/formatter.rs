@@ -1,117 +1,633 @@
-use crate::client::{FetchResponse, SearchResponse};
+use crate::client::{FetchResponse, SearchResponse, SearchResult};
+use crate::cluster::Topic;
+use crate::credibility;
+use crate::feedback::{domain_of, result_id};
+use crate::glyphs::Glyph;
+use crate::i18n;
+use crate::markdown_preview::{self, escape_html};
+use crate::network;
+use crate::terminal;
+use crate::text::{sanitize, truncate_at_sentence, truncate_utf8, Limits};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use url::Url;
 
-fn truncate_utf8(s: &str, max_bytes: usize) -> &str {
-    if s.len() <= max_bytes {
-        return s;
+const MIN_TERM_CHARS: usize = 2;
+const CARD_SNIPPET_BYTES: usize = 300;
+const STOPWORDS: &[&str] = &[
+    "an", "and", "are", "as", "at", "by", "for", "from", "how", "in", "is", "of", "on", "or",
+    "the", "to", "vs", "what", "when", "where", "which", "who", "why", "with",
+];
+
+pub trait Renderer {
+    fn search_results(&self, response: &SearchResponse) -> String;
+    fn clustered_results(&self, topics: &[Topic]) -> String;
+    fn fetch_response(&self, response: &FetchResponse) -> String;
+    fn answer(&self, query: &str, answer: &str) -> String;
+}
+
+pub struct Terminal {
+    pub color: bool,
+    pub limits: Limits,
+    pub highlight: Option<Regex>,
+    pub scores: bool,
+}
+
+pub struct Json;
+
+pub struct Markdown;
+
+pub struct Html;
+
+pub fn for_terminal(
+    json: bool,
+    color: bool,
+    limits: Limits,
+    query: Option<&str>,
+    scores: bool,
+) -> Box<dyn Renderer> {
+    if json {
+        Box::new(Json)
+    } else {
+        Box::new(Terminal {
+            color,
+            limits,
+            highlight: query.and_then(highlighter),
+            scores,
+        })
     }
+}
 
-    let mut end = max_bytes;
-    while end > 0 && !s.is_char_boundary(end) {
-        end -= 1;
+pub fn highlighter(query: &str) -> Option<Regex> {
+    let mut terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|term| {
+            term.chars().count() >= MIN_TERM_CHARS && !STOPWORDS.contains(&term.as_str())
+        })
+        .collect();
+    if terms.is_empty() {
+        return None;
     }
-    &s[..end]
+    terms.sort_by_key(|term| std::cmp::Reverse(term.len()));
+    terms.dedup();
+    let alternatives: Vec<String> = terms.iter().map(|term| regex::escape(term)).collect();
+    Regex::new(&format!(r"(?i)\b(?:{})\w*", alternatives.join("|"))).ok()
 }
 
-pub fn format_search_results(response: &SearchResponse, as_json: bool) -> String {
-    if as_json {
-        return serde_json::to_string_pretty(response).unwrap_or_else(|_| "{}".to_string());
+impl Terminal {
+    fn highlighted(&self, text: &str) -> String {
+        match &self.highlight {
+            Some(pattern) if self.color => pattern
+                .replace_all(text, |caps: &Captures| terminal::highlight(&caps[0]))
+                .into_owned(),
+            _ => text.to_string(),
+        }
     }
 
-    let mut output = String::new();
+    fn result_entry(&self, idx: usize, result: &SearchResult) -> String {
+        let mut output = String::new();
+        let mut id = match result.score.filter(|_| self.scores) {
+            Some(score) => format!("[{}] score {:.3}", result_id(&result.url), score),
+            None => format!("[{}]", result_id(&result.url)),
+        };
+        if let Some(tier) = result.credibility {
+            id.push_str(&format!(" · {} credibility", tier));
+        }
+        if self.color {
+            output.push_str(&format!(
+                "{} {}\n",
+                terminal::cyan(&format!("{}.", idx + 1)),
+                terminal::bold(&sanitize(&result.title))
+            ));
+            output.push_str(&format!(
+                "   {} {}\n",
+                terminal::hyperlink(&result.url, &sanitize(&result.url)),
+                terminal::dim(&id)
+            ));
+        } else {
+            output.push_str(&format!("{}. {}\n", idx + 1, sanitize(&result.title)));
+            output.push_str(&format!("   {} {}\n", sanitize(&result.url), id));
+        }
 
-    if response.results.is_empty() {
-        output.push_str("No results found.\n");
-        return output;
+        let snippet = self.limits.preview_snippet();
+        let content_preview = if result.content.len() > snippet {
+            format!("{}...", truncate_utf8(&result.content, snippet))
+        } else {
+            result.content.clone()
+        };
+
+        output.push_str(&format!(
+            "   {}\n",
+            self.highlighted(&sanitize(&content_preview))
+        ));
+        if !result.explain.is_empty() {
+            let why = format!("why: {}", result.explain.join(" · "));
+            if self.color {
+                output.push_str(&format!("   {}\n", terminal::dim(&why)));
+            } else {
+                output.push_str(&format!("   {}\n", why));
+            }
+        }
+        output.push('\n');
+        output
     }
+}
 
-    output.push_str(&format!("Found {} results:\n\n", response.results.len()));
+impl Renderer for Terminal {
+    fn search_results(&self, response: &SearchResponse) -> String {
+        let mut output = String::new();
 
-    for (idx, result) in response.results.iter().enumerate() {
-        output.push_str(&format!("{}. {}\n", idx + 1, result.title));
-        output.push_str(&format!("   {}\n", result.url));
+        if response.results.is_empty() {
+            output.push_str(&format!("{}\n", i18n::tr("no-results")));
+            return output;
+        }
+
+        output.push_str(&format!(
+            "{}\n\n",
+            i18n::tr_args(
+                "found-results",
+                &[("count", &response.results.len().to_string())]
+            )
+        ));
+
+        for (idx, result) in response.results.iter().enumerate() {
+            output.push_str(&self.result_entry(idx, result));
+        }
+
+        output
+    }
+
+    fn clustered_results(&self, topics: &[Topic]) -> String {
+        let total: usize = topics.iter().map(|topic| topic.results.len()).sum();
+        let mut output = format!(
+            "{}\n\n",
+            i18n::tr_args(
+                "found-topics",
+                &[
+                    ("count", &total.to_string()),
+                    ("topics", &topics.len().to_string())
+                ]
+            )
+        );
 
-        let content_preview = if result.content.len() > 200 {
-            format!("{}...", truncate_utf8(&result.content, 200))
+        let mut idx = 0;
+        for topic in topics {
+            let heading = format!(
+                "{}{} ({})",
+                Glyph::Topic,
+                sanitize(&topic.label),
+                topic.results.len()
+            );
+            if self.color {
+                output.push_str(&format!("{}\n\n", terminal::bold(&heading)));
+            } else {
+                output.push_str(&format!("{}\n\n", heading));
+            }
+            for result in &topic.results {
+                output.push_str(&self.result_entry(idx, result));
+                idx += 1;
+            }
+        }
+
+        output
+    }
+
+    fn fetch_response(&self, response: &FetchResponse) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("Title: {}\n", sanitize(&response.title)));
+        output.push_str(&sanitize(&response.attribution()));
+        output.push('\n');
+
+        let preview = self.limits.preview_page();
+        let content_preview = if response.content.len() > preview {
+            format!(
+                "{}...\n\n[Content truncated. Use --json for full content]",
+                truncate_utf8(&response.content, preview)
+            )
         } else {
-            result.content.clone()
+            response.content.clone()
         };
 
-        output.push_str(&format!("   {}\n\n", content_preview));
+        output.push_str(&format!("Content:\n{}\n\n", sanitize(&content_preview)));
+
+        if !response.links.is_empty() {
+            output.push_str(&format!("Found {} links:\n", response.links.len()));
+            for (idx, link) in response.links.iter().take(10).enumerate() {
+                output.push_str(&format!("  {}. {}\n", idx + 1, link));
+            }
+            if response.links.len() > 10 {
+                output.push_str(&format!("  ... and {} more\n", response.links.len() - 10));
+            }
+        }
+
+        output
     }
 
-    output
+    fn answer(&self, _query: &str, answer: &str) -> String {
+        let heading = format!("{}{}", Glyph::Note, i18n::tr("final-answer"));
+        let mut output = if self.color {
+            format!("{}\n{}", terminal::bold(&heading), answer)
+        } else {
+            format!("{}\n{}", heading, answer)
+        };
+        let cited: Vec<String> = credibility::cited(answer)
+            .into_iter()
+            .map(|(domain, tier)| format!("{} ({})", domain, tier))
+            .collect();
+        if !cited.is_empty() {
+            let footer = format!("Source credibility: {}", cited.join(", "));
+            if self.color {
+                output.push_str(&format!("\n\n{}", terminal::dim(&footer)));
+            } else {
+                output.push_str(&format!("\n\n{}", footer));
+            }
+        }
+        output
+    }
 }
 
-pub fn format_fetch_response(response: &FetchResponse, as_json: bool) -> String {
-    if as_json {
-        return serde_json::to_string_pretty(response).unwrap_or_else(|_| "{}".to_string());
+impl Renderer for Json {
+    fn search_results(&self, response: &SearchResponse) -> String {
+        to_json(response)
     }
 
-    let mut output = String::new();
+    fn clustered_results(&self, topics: &[Topic]) -> String {
+        to_json(&serde_json::json!({ "topics": topics }))
+    }
 
-    output.push_str(&format!("Title: {}\n\n", response.title));
+    fn fetch_response(&self, response: &FetchResponse) -> String {
+        to_json(response)
+    }
 
-    let content_preview = if response.content.len() > 1000 {
-        format!(
-            "{}...\n\n[Content truncated. Use --json for full content]",
-            truncate_utf8(&response.content, 1000)
-        )
-    } else {
+    fn answer(&self, query: &str, answer: &str) -> String {
+        to_json(&serde_json::json!({"query": query, "answer": answer}))
+    }
+}
+
+impl Renderer for Markdown {
+    fn search_results(&self, response: &SearchResponse) -> String {
+        results_page(response, |idx, result| markdown_entry("##", idx, result))
+    }
+
+    fn clustered_results(&self, topics: &[Topic]) -> String {
+        topics_page(topics, |idx, result| markdown_entry("###", idx, result))
+    }
+
+    fn fetch_response(&self, response: &FetchResponse) -> String {
         response.content.clone()
-    };
+    }
 
-    output.push_str(&format!("Content:\n{}\n\n", content_preview));
+    fn answer(&self, _query: &str, answer: &str) -> String {
+        answer.to_string()
+    }
+}
 
-    if !response.links.is_empty() {
-        output.push_str(&format!("Found {} links:\n", response.links.len()));
-        for (idx, link) in response.links.iter().take(10).enumerate() {
-            output.push_str(&format!("  {}. {}\n", idx + 1, link));
-        }
-        if response.links.len() > 10 {
-            output.push_str(&format!("  ... and {} more\n", response.links.len() - 10));
+impl Renderer for Html {
+    fn search_results(&self, response: &SearchResponse) -> String {
+        markdown_preview::html_document(&results_page(response, card_entry))
+    }
+
+    fn clustered_results(&self, topics: &[Topic]) -> String {
+        markdown_preview::html_document(&topics_page(topics, card_entry))
+    }
+
+    fn fetch_response(&self, response: &FetchResponse) -> String {
+        markdown_preview::html_document(&Markdown.fetch_response(response))
+    }
+
+    fn answer(&self, query: &str, answer: &str) -> String {
+        markdown_preview::html_document(&Markdown.answer(query, answer))
+    }
+}
+
+fn results_page(
+    response: &SearchResponse,
+    entry: impl Fn(usize, &SearchResult) -> String,
+) -> String {
+    let mut markdown = format!(
+        "# {}\n\n{}\n\n",
+        i18n::tr("search-results-title"),
+        i18n::tr_args(
+            "found-results",
+            &[("count", &response.results.len().to_string())]
+        )
+    );
+    for (idx, result) in response.results.iter().enumerate() {
+        markdown.push_str(&entry(idx, result));
+    }
+    markdown
+}
+
+fn topics_page(topics: &[Topic], entry: impl Fn(usize, &SearchResult) -> String) -> String {
+    let total: usize = topics.iter().map(|topic| topic.results.len()).sum();
+    let mut markdown = format!(
+        "# {}\n\n{}\n\n",
+        i18n::tr("search-results-title"),
+        i18n::tr_args(
+            "found-topics",
+            &[
+                ("count", &total.to_string()),
+                ("topics", &topics.len().to_string())
+            ]
+        )
+    );
+    let mut idx = 0;
+    for topic in topics {
+        markdown.push_str(&format!("## {} ({})\n\n", topic.label, topic.results.len()));
+        for result in &topic.results {
+            markdown.push_str(&entry(idx, result));
+            idx += 1;
         }
     }
+    markdown
+}
+
+fn card_entry(idx: usize, result: &SearchResult) -> String {
+    let url = Url::parse(&result.url).ok();
+    let favicon = url
+        .as_ref()
+        .filter(|_| !network::private())
+        .and_then(|url| {
+            Some(format!(
+                "{}://{}/favicon.ico",
+                url.scheme(),
+                url.host_str()?
+            ))
+        })
+        .map(|src| {
+            format!(
+                "<img class=\"card-favicon\" src=\"{}\" alt=\"\" width=\"16\" height=\"16\">",
+                escape_html(&src)
+            )
+        })
+        .unwrap_or_default();
+
+    let mut meta = vec![format!(
+        "<span class=\"card-domain\">{}</span>",
+        escape_html(&domain_of(&result.url))
+    )];
+    if let Some(published) = result.published.as_deref().map(str::trim) {
+        let date = published
+            .get(..10)
+            .filter(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok());
+        meta.push(format!(
+            "<span>{}</span>",
+            escape_html(date.unwrap_or(published))
+        ));
+    }
+    if let Some(tier) = result.credibility {
+        meta.push(format!("<span>{} credibility</span>", tier));
+    }
+
+    let snippet = truncate_at_sentence(&result.content, CARD_SNIPPET_BYTES)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "<div class=\"result-card\">{}<div class=\"card-body\">\
+         <div class=\"card-meta\">{}</div>\
+         <a class=\"card-title\" href=\"{}\">{}. {}</a>\
+         <p class=\"card-snippet\">{}</p></div></div>\n\n",
+        favicon,
+        meta.join(" · "),
+        escape_html(&result.url),
+        idx + 1,
+        escape_html(result.title.trim()),
+        escape_html(&snippet)
+    )
+}
+
+fn markdown_entry(heading: &str, idx: usize, result: &SearchResult) -> String {
+    let credibility = result
+        .credibility
+        .map(|tier| format!(" · **Credibility:** {}", tier))
+        .unwrap_or_default();
+    format!(
+        "{} {}. {}\n\n**URL:** [{}]({}){}\n\n{}\n\n",
+        heading,
+        idx + 1,
+        result.title,
+        result.url,
+        result.url,
+        credibility,
+        result.content
+    )
+}
 
-    output
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| "{}".to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const PLAIN: Terminal = Terminal {
+        color: false,
+        limits: Limits::DEFAULT,
+        highlight: None,
+        scores: false,
+    };
+    const COLOR: Terminal = Terminal {
+        color: true,
+        limits: Limits::DEFAULT,
+        highlight: None,
+        scores: false,
+    };
+
+    fn sample_response() -> SearchResponse {
+        SearchResponse {
+            results: vec![crate::client::SearchResult {
+                title: "Rust".to_string(),
+                url: "https://www.rust-lang.org".to_string(),
+                content: "A language empowering everyone".to_string(),
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn test_terminal_search_results_plain_has_no_escapes() {
+        let output = PLAIN.search_results(&sample_response());
+        assert!(!output.contains('\x1b'));
+        assert!(output.contains(&format!(
+            "1. Rust\n   https://www.rust-lang.org [{}]\n",
+            result_id("https://www.rust-lang.org")
+        )));
+
+        let mut scored = sample_response();
+        scored.results[0].score = Some(0.83);
+        assert!(!PLAIN.search_results(&scored).contains("score"));
+        let verbose = Terminal {
+            scores: true,
+            ..PLAIN
+        };
+        assert!(verbose.search_results(&scored).contains("] score 0.830\n"));
+    }
+
+    fn result(title: &str, url: &str, content: &str) -> crate::client::SearchResult {
+        crate::client::SearchResult {
+            title: title.to_string(),
+            url: url.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn fetch_response(content: &str, links: usize) -> FetchResponse {
+        FetchResponse {
+            title: "Pin and suffering".to_string(),
+            content: content.to_string(),
+            links: (1..=links)
+                .map(|n| format!("https://without.boats/blog/{}", n))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_snapshot_search_results() {
+        let empty = SearchResponse { results: vec![] };
+        insta::assert_snapshot!("search_empty", PLAIN.search_results(&empty));
+
+        let mut explained = result(
+            "Tokio tutorial",
+            "https://tokio.rs/tokio/tutorial",
+            "Async runtime.",
+        );
+        explained.explain = vec![
+            "ollama rank #2".to_string(),
+            "fused RRF score 0.0325".to_string(),
+        ];
+        let response = SearchResponse {
+            results: vec![
+                result(
+                    "Rust 程序设计语言 🦀",
+                    "https://kaisery.github.io/trpl-zh-cn/",
+                    &"所有权是 Rust 最独特的功能。".repeat(12),
+                ),
+                explained,
+            ],
+        };
+        insta::assert_snapshot!("search_unicode", PLAIN.search_results(&response));
+        insta::assert_snapshot!("search_color", COLOR.search_results(&response));
+        insta::assert_snapshot!("search_json", Json.search_results(&response));
+    }
+
+    #[test]
+    fn test_snapshot_fetch_response() {
+        let mut response = fetch_response("Short body.", 0);
+        response.author = Some("Without Boats".to_string());
+        response.published = Some("2024-03-01".to_string());
+        response.archived_at = Some("2024-06-02".to_string());
+        response.archive_url =
+            Some("http://web.archive.org/web/20240602000000/https://without.boats/".to_string());
+        insta::assert_snapshot!("fetch_attribution", PLAIN.fetch_response(&response));
+        insta::assert_snapshot!("fetch_json", Json.fetch_response(&response));
+
+        let long = "Pinning is a guarantee about memory. ".repeat(40) + "Ünïcödé tail";
+        insta::assert_snapshot!(
+            "fetch_truncated_many_links",
+            PLAIN.fetch_response(&fetch_response(&long, 25))
+        );
+    }
+
     #[test]
-    fn test_truncate_utf8_ascii() {
-        let text = "Hello, World!";
-        assert_eq!(truncate_utf8(text, 5), "Hello");
-        assert_eq!(truncate_utf8(text, 100), text);
+    fn test_terminal_search_results_color_emits_hyperlinks() {
+        let output = COLOR.search_results(&sample_response());
+        assert!(output.contains("\x1b]8;;https://www.rust-lang.org\x1b\\"));
+        assert!(output.contains("\x1b[1mRust\x1b[0m"));
     }
 
     #[test]
-    fn test_truncate_utf8_emoji() {
-        let text = "Hello 👋 World 🌍";
-        let result = truncate_utf8(text, 10);
-        assert!(result.len() <= 10);
-        assert!(result.is_char_boundary(result.len()));
+    fn test_highlights_query_terms_in_colored_snippets() {
+        let terminal = Terminal {
+            highlight: highlighter("how to empower a Language?"),
+            ..COLOR
+        };
+        let output = terminal.search_results(&sample_response());
+        assert!(output.contains("A \x1b[1;33mlanguage\x1b[0m \x1b[1;33mempowering\x1b[0m everyone"));
+        assert!(highlighter("what is the").is_none());
+
+        let plain = Terminal {
+            highlight: highlighter("language"),
+            ..PLAIN
+        };
+        assert!(!plain.search_results(&sample_response()).contains('\x1b'));
     }
 
     #[test]
-    fn test_truncate_utf8_chinese() {
-        let text = "你好世界";
-        let result = truncate_utf8(text, 6);
-        assert!(result.len() <= 6);
-        assert!(result.is_char_boundary(result.len()));
+    fn test_clustered_results_number_across_topics() {
+        let topics = vec![
+            Topic {
+                label: "Benchmarks".to_string(),
+                results: vec![
+                    result("Tokio vs smol", "https://a.dev/1", "Numbers."),
+                    result("Runtime shootout", "https://b.dev/2", "More numbers."),
+                ],
+            },
+            Topic {
+                label: "Internals".to_string(),
+                results: vec![result("Work stealing", "https://c.dev/3", "Design.")],
+            },
+        ];
+        let plain = PLAIN.clustered_results(&topics);
+        assert!(plain.starts_with("Found 3 results in 2 topics:\n\n▸ Benchmarks (2)\n\n1. Tokio"));
+        assert!(plain.contains("▸ Internals (1)\n\n3. Work stealing\n"));
+
+        let markdown = Markdown.clustered_results(&topics);
+        assert!(markdown.contains("## Internals (1)\n\n### 3. Work stealing\n"));
+        let json: serde_json::Value =
+            serde_json::from_str(&Json.clustered_results(&topics)).unwrap();
+        assert_eq!(json["topics"][1]["results"][0]["url"], "https://c.dev/3");
     }
 
     #[test]
-    fn test_truncate_utf8_at_boundary() {
-        let text = "🚀";
-        let result = truncate_utf8(text, 2);
-        assert_eq!(result, "");
+    fn test_html_results_render_as_cards() {
+        let mut tokio = result(
+            "Tokio <runtime> & friends",
+            "https://docs.rs/tokio/latest",
+            "An asynchronous runtime.\nIt is fast. ",
+        );
+        tokio.published = Some("2024-03-01T09:00:00Z".to_string());
+        tokio.credibility = Some(credibility::Tier::High);
+        let html = Html.search_results(&SearchResponse {
+            results: vec![tokio, result("smol", "https://smol.rs", "")],
+        });
+
+        assert_eq!(html.matches(r#"<div class="result-card">"#).count(), 2);
+        assert!(html.contains(
+            r#"<img class="card-favicon" src="https://docs.rs/favicon.ico" alt="" width="16" height="16">"#
+        ));
+        assert!(html.contains(
+            r#"<div class="card-meta"><span class="card-domain">docs.rs</span> · <span>2024-03-01</span> · <span>high credibility</span></div>"#
+        ));
+        assert!(html.contains(">1. Tokio &lt;runtime&gt; &amp; friends</a>"));
+        assert!(
+            html.contains(r#"<p class="card-snippet">An asynchronous runtime. It is fast.</p>"#)
+        );
     }
 
     #[test]
-    fn test_truncate_utf8_empty() {
-        let text = "";
-        assert_eq!(truncate_utf8(text, 10), "");
+    fn test_renders_answers_for_each_target() {
+        let answer = "Tokio is an async runtime [1].";
+        assert_eq!(
+            PLAIN.answer("what is tokio", answer),
+            "📝 Final Answer:\nTokio is an async runtime [1]."
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&Json.answer("what is tokio", answer)).unwrap();
+        assert_eq!(json["query"], "what is tokio");
+        assert_eq!(json["answer"], answer);
+        assert_eq!(Markdown.answer("q", answer), answer);
+
+        let html = Html.search_results(&sample_response());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(r#"<a class="card-title" href="https://www.rust-lang.org" rel="noopener noreferrer">1. Rust</a>"#));
+        assert!(for_terminal(true, true, Limits::DEFAULT, None, false)
+            .search_results(&sample_response())
+            .starts_with('{'));
     }
 }
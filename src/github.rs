@@ -0,0 +1,324 @@
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use crate::text::ellipsize;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+const API_URL: &str = "https://api.github.com";
+const TIMEOUT: Duration = Duration::from_secs(20);
+const USER_AGENT: &str = concat!(
+    "weavex/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/guitaripod/weavex)"
+);
+const MAX_RESULTS: usize = 20;
+const MAX_FRAGMENT_CHARS: usize = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchKind {
+    #[default]
+    Repositories,
+    Code,
+    Issues,
+}
+
+impl SearchKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "repositories" | "repos" | "repo" => Some(SearchKind::Repositories),
+            "code" => Some(SearchKind::Code),
+            "issues" | "issue" | "prs" | "pulls" => Some(SearchKind::Issues),
+            _ => None,
+        }
+    }
+
+    fn path(self) -> &'static str {
+        match self {
+            SearchKind::Repositories => "repositories",
+            SearchKind::Code => "code",
+            SearchKind::Issues => "issues",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchPage {
+    #[serde(default)]
+    total_count: u64,
+    #[serde(default)]
+    items: Vec<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hit {
+    pub title: String,
+    pub url: String,
+    pub description: Option<String>,
+    pub details: Vec<String>,
+}
+
+pub struct GithubClient {
+    client: Client,
+    authenticated: bool,
+}
+
+impl GithubClient {
+    pub fn new(token: Option<String>) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github.text-match+json"),
+        );
+        headers.insert(
+            "X-GitHub-Api-Version",
+            HeaderValue::from_static("2022-11-28"),
+        );
+
+        let token = token.filter(|token| !token.trim().is_empty());
+        if let Some(token) = &token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", token.trim()))
+                .map_err(|_| OllamaError::ConfigError("Invalid GitHub token".to_string()))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        let client = network::client_builder()
+            .timeout(TIMEOUT)
+            .user_agent(network::user_agent(USER_AGENT))
+            .default_headers(headers)
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self {
+            client,
+            authenticated: token.is_some(),
+        })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn search(&self, kind: SearchKind, query: &str, limit: usize) -> Result<String> {
+        if query.trim().is_empty() {
+            return Err(OllamaError::InvalidResponse(
+                "GitHub search query cannot be empty".to_string(),
+            ));
+        }
+        if kind == SearchKind::Code && !self.authenticated {
+            return Err(OllamaError::ConfigError(
+                "GitHub code search requires a token; set GITHUB_TOKEN or [github] token in the config file".to_string(),
+            ));
+        }
+
+        let url = format!("{}/search/{}", API_URL, kind.path());
+        debug!("Searching GitHub: {}", url);
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("q", query.trim()),
+                ("per_page", &limit.clamp(1, MAX_RESULTS).to_string()),
+            ])
+            .send_audited()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body: Value = response.json().await.unwrap_or_default();
+            let message = body["message"].as_str().unwrap_or("request failed");
+            let message = match status {
+                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS if !self.authenticated => {
+                    format!("{} (set GITHUB_TOKEN for a higher rate limit)", message)
+                }
+                _ => message.to_string(),
+            };
+            return Err(OllamaError::ApiError {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        let page: SearchPage = response.json().await?;
+        Ok(format_hits(
+            kind,
+            page.total_count,
+            &parse_hits(kind, &page.items),
+        ))
+    }
+}
+
+pub fn parse_hits(kind: SearchKind, items: &[Value]) -> Vec<Hit> {
+    items
+        .iter()
+        .filter_map(|item| match kind {
+            SearchKind::Repositories => repository(item),
+            SearchKind::Code => code(item),
+            SearchKind::Issues => issue(item),
+        })
+        .collect()
+}
+
+fn repository(item: &Value) -> Option<Hit> {
+    let mut details = vec![format!("★ {}", item["stargazers_count"].as_u64()?)];
+    if let Some(language) = item["language"].as_str() {
+        details.push(language.to_string());
+    }
+    if let Some(updated) = item["pushed_at"].as_str().and_then(|at| at.get(..10)) {
+        details.push(format!("updated {}", updated));
+    }
+    if item["archived"].as_bool() == Some(true) {
+        details.push("archived".to_string());
+    }
+
+    Some(Hit {
+        title: item["full_name"].as_str()?.to_string(),
+        url: item["html_url"].as_str()?.to_string(),
+        description: item["description"].as_str().map(str::to_string),
+        details,
+    })
+}
+
+fn code(item: &Value) -> Option<Hit> {
+    let fragment = item["text_matches"]
+        .as_array()
+        .and_then(|matches| matches.first())
+        .and_then(|first| first["fragment"].as_str())
+        .map(|fragment| ellipsize(fragment.trim(), MAX_FRAGMENT_CHARS));
+
+    Some(Hit {
+        title: format!(
+            "{}: {}",
+            item["repository"]["full_name"].as_str()?,
+            item["path"].as_str()?
+        ),
+        url: item["html_url"].as_str()?.to_string(),
+        description: fragment,
+        details: Vec::new(),
+    })
+}
+
+fn issue(item: &Value) -> Option<Hit> {
+    let kind = if item.get("pull_request").is_some() {
+        "pull request"
+    } else {
+        "issue"
+    };
+    let repository = item["repository_url"]
+        .as_str()?
+        .trim_start_matches("https://api.github.com/repos/");
+
+    let mut details = vec![
+        format!("{} #{}", kind, item["number"].as_u64()?),
+        item["state"].as_str()?.to_string(),
+        format!("{} comments", item["comments"].as_u64().unwrap_or(0)),
+    ];
+    if let Some(created) = item["created_at"].as_str().and_then(|at| at.get(..10)) {
+        details.push(format!("opened {}", created));
+    }
+
+    Some(Hit {
+        title: format!("{}: {}", repository, item["title"].as_str()?),
+        url: item["html_url"].as_str()?.to_string(),
+        description: item["body"]
+            .as_str()
+            .map(|body| ellipsize(body.trim(), MAX_FRAGMENT_CHARS)),
+        details,
+    })
+}
+
+pub fn format_hits(kind: SearchKind, total: u64, hits: &[Hit]) -> String {
+    if hits.is_empty() {
+        return format!("No GitHub {} found.", kind.path());
+    }
+
+    let mut output = format!(
+        "Showing {} of {} GitHub {} results:\n\n",
+        hits.len(),
+        total,
+        kind.path()
+    );
+    for (idx, hit) in hits.iter().enumerate() {
+        output.push_str(&format!("{}. {}\nURL: {}\n", idx + 1, hit.title, hit.url));
+        if !hit.details.is_empty() {
+            output.push_str(&format!("{}\n", hit.details.join(" · ")));
+        }
+        if let Some(description) = &hit.description {
+            output.push_str(&format!("{}\n", description));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parses_repositories_and_code() {
+        let repos = vec![json!({
+            "full_name": "tokio-rs/tokio",
+            "html_url": "https://github.com/tokio-rs/tokio",
+            "description": "A runtime for writing reliable asynchronous applications",
+            "stargazers_count": 27000,
+            "language": "Rust",
+            "pushed_at": "2024-05-01T10:00:00Z",
+            "archived": false
+        })];
+        let hits = parse_hits(SearchKind::Repositories, &repos);
+        assert_eq!(hits[0].title, "tokio-rs/tokio");
+        assert_eq!(
+            hits[0].details,
+            vec!["★ 27000", "Rust", "updated 2024-05-01"]
+        );
+
+        let code = vec![
+            json!({
+                "path": "src/lib.rs",
+                "html_url": "https://github.com/a/b/blob/main/src/lib.rs",
+                "repository": {"full_name": "a/b"},
+                "text_matches": [{"fragment": "  pub fn select() {}  "}]
+            }),
+            json!({"path": "missing/repository.rs"}),
+        ];
+        let hits = parse_hits(SearchKind::Code, &code);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].title, "a/b: src/lib.rs");
+        assert_eq!(hits[0].description.as_deref(), Some("pub fn select() {}"));
+    }
+
+    #[test]
+    fn test_formats_issues_and_pull_requests() {
+        let items = vec![json!({
+            "title": "select! panics on cancellation",
+            "html_url": "https://github.com/tokio-rs/tokio/pull/42",
+            "repository_url": "https://api.github.com/repos/tokio-rs/tokio",
+            "number": 42,
+            "state": "closed",
+            "comments": 3,
+            "created_at": "2023-01-02T00:00:00Z",
+            "pull_request": {},
+            "body": null
+        })];
+        let output = format_hits(
+            SearchKind::Issues,
+            1,
+            &parse_hits(SearchKind::Issues, &items),
+        );
+        assert_eq!(
+            output,
+            "Showing 1 of 1 GitHub issues results:\n\n\
+             1. tokio-rs/tokio: select! panics on cancellation\n\
+             URL: https://github.com/tokio-rs/tokio/pull/42\n\
+             pull request #42 · closed · 3 comments · opened 2023-01-02\n\n"
+        );
+        assert_eq!(
+            format_hits(SearchKind::Code, 0, &[]),
+            "No GitHub code found."
+        );
+        assert_eq!(SearchKind::parse("PRs"), Some(SearchKind::Issues));
+    }
+}
@@ -0,0 +1,239 @@
+use clap::ValueEnum;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+const VARIATION_SELECTOR: char = '\u{FE0F}';
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+static SPINNER: OnceLock<Spinner> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Spinner {
+    Dots,
+    Line,
+    Arc,
+    None,
+}
+
+impl Spinner {
+    pub fn ticks(self) -> Option<&'static str> {
+        match self {
+            Spinner::Dots => Some("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏ "),
+            Spinner::Line => Some("-\\|/ "),
+            Spinner::Arc => Some("◜◠◝◞◡◟ "),
+            Spinner::None => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyph {
+    Agent,
+    Arxiv,
+    Bullet,
+    Calculator,
+    Changed,
+    Clipboard,
+    Clock,
+    Daemon,
+    Denied,
+    Dice,
+    Discussion,
+    DryRun,
+    Error,
+    Export,
+    Feed,
+    Feedback,
+    File,
+    GitHub,
+    Image,
+    Judge,
+    Knowledge,
+    Location,
+    Note,
+    Notes,
+    Pages,
+    Paused,
+    Plan,
+    Pointer,
+    Removed,
+    Research,
+    Response,
+    Resume,
+    Save,
+    Saved,
+    Schedule,
+    Search,
+    Step,
+    Success,
+    Sweep,
+    Synthesis,
+    Thinking,
+    Topic,
+    Warning,
+    Watch,
+    Weather,
+    Weave,
+    Web,
+    Wikipedia,
+}
+
+impl Glyph {
+    fn emoji(self) -> &'static str {
+        match self {
+            Glyph::Agent => "🤖",
+            Glyph::Arxiv => "🎓",
+            Glyph::Bullet => "•",
+            Glyph::Calculator => "🧮",
+            Glyph::Changed => "✏️",
+            Glyph::Clipboard => "📋",
+            Glyph::Clock => "🕒",
+            Glyph::Daemon => "🕰️",
+            Glyph::Denied => "🚫",
+            Glyph::Dice => "🎲",
+            Glyph::Discussion => "🗣️",
+            Glyph::DryRun => "🧪",
+            Glyph::Error => "❌",
+            Glyph::Export => "📤",
+            Glyph::Feed => "📰",
+            Glyph::Feedback => "👍",
+            Glyph::File => "📄",
+            Glyph::GitHub => "🐙",
+            Glyph::Image => "🖼️",
+            Glyph::Judge => "⚖️",
+            Glyph::Knowledge => "📚",
+            Glyph::Location => "📍",
+            Glyph::Note => "📝",
+            Glyph::Notes => "🗒️",
+            Glyph::Pages => "📑",
+            Glyph::Paused => "⏸️",
+            Glyph::Plan => "🗺️",
+            Glyph::Pointer => "▶",
+            Glyph::Removed => "🗑️",
+            Glyph::Research => "🔍",
+            Glyph::Response => "💬",
+            Glyph::Resume => "↩️",
+            Glyph::Save => "💾",
+            Glyph::Saved => "🔖",
+            Glyph::Schedule => "🗓️",
+            Glyph::Search => "🔎",
+            Glyph::Step => "▶️",
+            Glyph::Success => "✅",
+            Glyph::Sweep => "🧹",
+            Glyph::Synthesis => "🧶",
+            Glyph::Thinking => "🧠",
+            Glyph::Topic => "▸",
+            Glyph::Warning => "⚠️",
+            Glyph::Watch => "👀",
+            Glyph::Weather => "🌦️",
+            Glyph::Weave => "🧵",
+            Glyph::Web => "🌐",
+            Glyph::Wikipedia => "📖",
+        }
+    }
+
+    fn ascii(self) -> &'static str {
+        match self {
+            Glyph::Agent => "[agent]",
+            Glyph::Arxiv => "[arxiv]",
+            Glyph::Bullet => "-",
+            Glyph::Calculator => "[calc]",
+            Glyph::Changed => "[changed]",
+            Glyph::Clipboard => "[copied]",
+            Glyph::Clock => "[time]",
+            Glyph::Daemon => "[daemon]",
+            Glyph::Denied => "[denied]",
+            Glyph::Dice => "[samples]",
+            Glyph::Discussion => "[discussion]",
+            Glyph::DryRun | Glyph::Weave => "",
+            Glyph::Error => "[error]",
+            Glyph::Export => "[export]",
+            Glyph::Feed => "[feed]",
+            Glyph::Feedback => "[feedback]",
+            Glyph::File => "[file]",
+            Glyph::GitHub => "[github]",
+            Glyph::Image => "[image]",
+            Glyph::Judge => "[judge]",
+            Glyph::Knowledge => "[kb]",
+            Glyph::Location => "[geo]",
+            Glyph::Note => "[note]",
+            Glyph::Notes => "[notes]",
+            Glyph::Pages => "[pages]",
+            Glyph::Paused => "[paused]",
+            Glyph::Plan => "[plan]",
+            Glyph::Pointer | Glyph::Topic => ">",
+            Glyph::Removed => "[removed]",
+            Glyph::Research | Glyph::Search => "[search]",
+            Glyph::Response => "[response]",
+            Glyph::Resume => "[resume]",
+            Glyph::Save | Glyph::Saved => "[saved]",
+            Glyph::Schedule => "[scheduled]",
+            Glyph::Step => "[step]",
+            Glyph::Success => "[ok]",
+            Glyph::Sweep => "[cleanup]",
+            Glyph::Synthesis => "[synthesis]",
+            Glyph::Thinking => "[thinking]",
+            Glyph::Warning => "[warning]",
+            Glyph::Watch => "[watch]",
+            Glyph::Weather => "[weather]",
+            Glyph::Web => "[web]",
+            Glyph::Wikipedia => "[wiki]",
+        }
+    }
+
+    pub fn render(self, ascii: bool) -> String {
+        let symbol = if ascii { self.ascii() } else { self.emoji() };
+        match symbol {
+            "" => String::new(),
+            _ if symbol.ends_with(VARIATION_SELECTOR) => format!("{}  ", symbol),
+            _ => format!("{} ", symbol),
+        }
+    }
+}
+
+impl fmt::Display for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(ascii()))
+    }
+}
+
+pub fn init(ascii: bool, spinner: Option<Spinner>) {
+    ASCII.store(ascii, Ordering::Relaxed);
+    if let Some(spinner) = spinner {
+        let _ = SPINNER.set(spinner);
+    }
+}
+
+pub fn ascii() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+pub fn spinner() -> Spinner {
+    match SPINNER.get() {
+        Some(spinner) => *spinner,
+        None if ascii() => Spinner::Line,
+        None => Spinner::Dots,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_emoji_and_ascii_with_spacing() {
+        assert_eq!(Glyph::Search.render(false), "🔎 ");
+        assert_eq!(Glyph::Warning.render(false), "⚠️  ");
+        assert_eq!(Glyph::Warning.render(true), "[warning] ");
+        assert_eq!(Glyph::DryRun.render(true), "");
+        assert_eq!(Glyph::Topic.render(true), "> ");
+    }
+
+    #[test]
+    fn test_spinner_styles() {
+        assert!(Spinner::Line.ticks().is_some_and(str::is_ascii));
+        assert_eq!(Spinner::None.ticks(), None);
+        assert!(Glyph::Weave.render(true).is_empty());
+    }
+}
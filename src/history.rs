@@ -0,0 +1,221 @@
+use crate::artifacts::{self, Owner};
+use crate::error::Result;
+use crate::store::{self, Store};
+use rusqlite::{params, OptionalExtension, Row};
+use tracing::debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Search,
+    Fetch,
+    Agent,
+}
+
+impl Kind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Kind::Search => "search",
+            Kind::Fetch => "fetch",
+            Kind::Agent => "agent",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub id: i64,
+    pub kind: String,
+    pub query: String,
+    pub summary: String,
+    pub answer: String,
+    pub created_at: i64,
+}
+
+impl Entry {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            query: row.get(2)?,
+            summary: row.get(3)?,
+            answer: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+
+    pub fn timestamp(&self) -> String {
+        store::format_timestamp(self.created_at)
+    }
+
+    fn hydrate(mut self, store: &Store) -> Self {
+        if self.answer.is_empty() {
+            match artifacts::load_for(store, Owner::History, self.id) {
+                Ok(Some(content)) => self.answer = content,
+                Ok(None) => {}
+                Err(e) => debug!("Failed to load artifact for entry {}: {}", self.id, e),
+            }
+        }
+        self
+    }
+}
+
+pub fn record(store: &Store, kind: Kind, query: &str, summary: &str, answer: &str) -> Result<i64> {
+    store.conn().execute(
+        "INSERT INTO history (kind, query, summary, answer, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![kind.as_str(), query, summary, answer, store::now()],
+    )?;
+    Ok(store.conn().last_insert_rowid())
+}
+
+pub fn list(store: &Store, term: Option<&str>, limit: usize) -> Result<Vec<Entry>> {
+    let entries = match term.map(fts_query).filter(|q| !q.is_empty()) {
+        Some(fts) => {
+            let mut stmt = store.conn().prepare(
+                "SELECT h.id, h.kind, h.query, h.summary, h.answer, h.created_at
+                 FROM history_fts f JOIN history h ON h.id = f.rowid
+                 WHERE history_fts MATCH ?1
+                 ORDER BY f.rank LIMIT ?2",
+            )?;
+            let rows = stmt.query_map(params![fts, limit as i64], Entry::from_row)?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        }
+        None => {
+            let mut stmt = store.conn().prepare(
+                "SELECT id, kind, query, summary, answer, created_at
+                 FROM history ORDER BY id DESC LIMIT ?1",
+            )?;
+            let rows = stmt.query_map(params![limit as i64], Entry::from_row)?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        }
+    };
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| entry.hydrate(store))
+        .collect())
+}
+
+pub fn get(store: &Store, id: i64) -> Result<Option<Entry>> {
+    let entry = store
+        .conn()
+        .query_row(
+            "SELECT id, kind, query, summary, answer, created_at FROM history WHERE id = ?1",
+            params![id],
+            Entry::from_row,
+        )
+        .optional()?;
+    Ok(entry.map(|entry| entry.hydrate(store)))
+}
+
+pub fn latest(store: &Store, kind: Kind, query: &str) -> Result<Option<Entry>> {
+    let entry = store
+        .conn()
+        .query_row(
+            "SELECT id, kind, query, summary, answer, created_at FROM history
+             WHERE kind = ?1 AND query = ?2 ORDER BY id DESC LIMIT 1",
+            params![kind.as_str(), query],
+            Entry::from_row,
+        )
+        .optional()?;
+    Ok(entry.map(|entry| entry.hydrate(store)))
+}
+
+pub fn most_recent(store: &Store, kind: Kind) -> Result<Option<Entry>> {
+    let entry = store
+        .conn()
+        .query_row(
+            "SELECT id, kind, query, summary, answer, created_at FROM history
+             WHERE kind = ?1 ORDER BY id DESC LIMIT 1",
+            params![kind.as_str()],
+            Entry::from_row,
+        )
+        .optional()?;
+    Ok(entry.map(|entry| entry.hydrate(store)))
+}
+
+pub fn delete(store: &Store, id: i64) -> Result<bool> {
+    let deleted = store
+        .conn()
+        .execute("DELETE FROM history WHERE id = ?1", params![id])?;
+    artifacts::release(store, Owner::History, id)?;
+    Ok(deleted > 0)
+}
+
+fn fts_query(term: &str) -> String {
+    term.split_whitespace()
+        .map(|token| format!("\"{}\"", token.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_search_history() {
+        let store = Store::open_in_memory().unwrap();
+        record(
+            &store,
+            Kind::Search,
+            "rust async runtimes",
+            "3 results",
+            "tokio",
+        )
+        .unwrap();
+        let id = record(
+            &store,
+            Kind::Agent,
+            "python packaging",
+            "uv is fast",
+            "Use uv.",
+        )
+        .unwrap();
+
+        let all = list(&store, None, 10).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].id, id);
+
+        let matches = list(&store, Some("packaging"), 10).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].kind, "agent");
+
+        assert_eq!(get(&store, id).unwrap().unwrap().answer, "Use uv.");
+    }
+
+    #[test]
+    fn test_delete_removes_entry_from_search() {
+        let store = Store::open_in_memory().unwrap();
+        let id = record(&store, Kind::Agent, "zig comptime", "", "Generics.").unwrap();
+
+        assert!(delete(&store, id).unwrap());
+        assert!(!delete(&store, id).unwrap());
+        assert!(get(&store, id).unwrap().is_none());
+        assert!(list(&store, Some("comptime"), 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_latest_finds_the_newest_entry_of_a_kind() {
+        let store = Store::open_in_memory().unwrap();
+        record(&store, Kind::Fetch, "https://tokio.rs", "Tokio", "old").unwrap();
+        let id = record(&store, Kind::Fetch, "https://tokio.rs", "Tokio", "new").unwrap();
+        record(&store, Kind::Search, "https://tokio.rs", "", "").unwrap();
+
+        let entry = latest(&store, Kind::Fetch, "https://tokio.rs")
+            .unwrap()
+            .unwrap();
+        assert_eq!((entry.id, entry.answer.as_str()), (id, "new"));
+        assert!(latest(&store, Kind::Agent, "https://tokio.rs")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_search_term_with_special_characters() {
+        let store = Store::open_in_memory().unwrap();
+        record(&store, Kind::Fetch, "https://example.com", "Example", "").unwrap();
+
+        assert_eq!(list(&store, Some("example.com\" OR"), 10).unwrap().len(), 0);
+        assert_eq!(list(&store, Some("example.com"), 10).unwrap().len(), 1);
+    }
+}
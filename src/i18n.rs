@@ -0,0 +1,220 @@
+use clap::ValueEnum;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use std::ffi::OsString;
+use std::sync::OnceLock;
+use tracing::debug;
+use unic_langid::LanguageIdentifier;
+
+const LOCALE_VARS: &[&str] = &["LC_ALL", "LC_MESSAGES", "LANG"];
+
+static LOCALIZER: OnceLock<Localizer> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Language {
+    #[default]
+    En,
+    Es,
+    De,
+    Ja,
+}
+
+struct Localizer {
+    bundle: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+impl Language {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::Es => "es",
+            Language::De => "de",
+            Language::Ja => "ja",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Language::En => include_str!("../locales/en.ftl"),
+            Language::Es => include_str!("../locales/es.ftl"),
+            Language::De => include_str!("../locales/de.ftl"),
+            Language::Ja => include_str!("../locales/ja.ftl"),
+        }
+    }
+
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale
+            .split(['.', '@'])
+            .next()?
+            .split(['_', '-'])
+            .next()?
+            .to_lowercase();
+        Self::from_str(&code, true).ok()
+    }
+}
+
+pub fn detect() -> Language {
+    LOCALE_VARS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| Language::from_locale(&value))
+        .unwrap_or_default()
+}
+
+pub fn requested<I: IntoIterator<Item = OsString>>(args: I) -> Option<Language> {
+    let mut args = args
+        .into_iter()
+        .map(|arg| arg.to_string_lossy().into_owned());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        let value = match arg.strip_prefix("--ui-language") {
+            Some("") => args.next(),
+            Some(rest) => rest.strip_prefix('=').map(str::to_string),
+            None => None,
+        };
+        if let Some(value) = value {
+            return Language::from_str(&value, true).ok();
+        }
+    }
+    None
+}
+
+pub fn init(language: Language) {
+    debug!("UI language: {}", language.code());
+    if LOCALIZER.set(Localizer::new(language)).is_err() {
+        debug!("UI language was already set");
+    }
+}
+
+pub fn tr(id: &str) -> String {
+    localizer().format(id, None)
+}
+
+pub fn tr_args(id: &str, args: &[(&str, &str)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, value.to_string());
+    }
+    localizer().format(id, Some(&fluent_args))
+}
+
+fn localizer() -> &'static Localizer {
+    LOCALIZER.get_or_init(|| Localizer::new(Language::default()))
+}
+
+fn bundle(language: Language) -> FluentBundle<FluentResource> {
+    let id: LanguageIdentifier = language
+        .code()
+        .parse()
+        .expect("language codes are valid identifiers");
+    let resource = FluentResource::try_new(language.source().to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            debug!("Errors in {} messages: {:?}", language.code(), errors);
+            resource
+        },
+    );
+
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    bundle.set_use_isolating(false);
+    if let Err(errors) = bundle.add_resource(resource) {
+        debug!("Duplicate {} messages: {:?}", language.code(), errors);
+    }
+    bundle
+}
+
+impl Localizer {
+    fn new(language: Language) -> Self {
+        Self {
+            bundle: bundle(language),
+            fallback: bundle(Language::En),
+        }
+    }
+
+    fn format(&self, id: &str, args: Option<&FluentArgs>) -> String {
+        [&self.bundle, &self.fallback]
+            .into_iter()
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                let text = bundle.format_pattern(pattern, args, &mut errors);
+                if !errors.is_empty() {
+                    debug!("Errors formatting message {}: {:?}", id, errors);
+                }
+                Some(text.into_owned())
+            })
+            .unwrap_or_else(|| id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_ids(language: Language) -> Vec<&'static str> {
+        language
+            .source()
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_language_translates_every_message() {
+        let english = message_ids(Language::En);
+        assert!(english.contains(&"help-examples"));
+        for language in [Language::Es, Language::De, Language::Ja] {
+            assert_eq!(message_ids(language), english, "{}", language.code());
+            let localizer = Localizer::new(language);
+            for id in &english {
+                assert!(
+                    localizer.bundle.has_message(id),
+                    "{} {}",
+                    language.code(),
+                    id
+                );
+            }
+        }
+
+        let spanish = Localizer::new(Language::Es);
+        assert_eq!(
+            spanish.format(
+                "researching",
+                Some(&FluentArgs::from_iter([("query", "tokio")]))
+            ),
+            "Investigando: tokio"
+        );
+        assert_eq!(spanish.format("missing-id", None), "missing-id");
+
+        let examples = Localizer::new(Language::En).format("help-examples", None);
+        assert!(examples.starts_with(
+            "EXAMPLES:\n    # Basic search (opens in browser by default)\n    \
+             weavex \"what is rust programming\"\n\n# Disable browser preview"
+        ));
+    }
+
+    #[test]
+    fn test_detects_language_from_locales_and_args() {
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), Some(Language::De));
+        assert_eq!(Language::from_locale("ja-JP"), Some(Language::Ja));
+        assert_eq!(Language::from_locale("es_MX@euro"), Some(Language::Es));
+        assert_eq!(Language::from_locale("C"), None);
+        assert_eq!(Language::from_locale("fr_FR.UTF-8"), None);
+
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(
+            requested(args(&["weavex", "--ui-language", "ja", "agent"])),
+            Some(Language::Ja)
+        );
+        assert_eq!(
+            requested(args(&["weavex", "--ui-language=DE", "q"])),
+            Some(Language::De)
+        );
+        assert_eq!(requested(args(&["weavex", "--", "--ui-language=es"])), None);
+        assert_eq!(requested(args(&["weavex", "q"])), None);
+    }
+}
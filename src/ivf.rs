@@ -0,0 +1,103 @@
+use crate::kb::cosine_similarity;
+
+const ITERATIONS: usize = 8;
+const MAX_TRAINING_VECTORS: usize = 10_000;
+const MIN_CLUSTERS: usize = 16;
+const MAX_CLUSTERS: usize = 1024;
+
+pub fn cluster_count(vectors: usize) -> usize {
+    ((vectors as f64).sqrt() as usize).clamp(MIN_CLUSTERS, MAX_CLUSTERS)
+}
+
+pub fn train(vectors: &[Vec<f32>], clusters: usize) -> Vec<Vec<f32>> {
+    let stride = (vectors.len() / MAX_TRAINING_VECTORS).max(1);
+    let sample: Vec<&Vec<f32>> = vectors.iter().step_by(stride).collect();
+    let clusters = clusters.min(sample.len());
+    if clusters == 0 {
+        return Vec::new();
+    }
+
+    let spacing = sample.len() / clusters;
+    let mut centroids: Vec<Vec<f32>> = (0..clusters)
+        .map(|idx| normalized(sample[idx * spacing]))
+        .collect();
+
+    for _ in 0..ITERATIONS {
+        let dims = centroids[0].len();
+        let mut sums = vec![vec![0.0f32; dims]; clusters];
+        let mut counts = vec![0usize; clusters];
+
+        for vector in &sample {
+            let cluster = nearest(&centroids, vector);
+            counts[cluster] += 1;
+            for (sum, value) in sums[cluster].iter_mut().zip(vector.iter()) {
+                *sum += value;
+            }
+        }
+
+        for (cluster, sum) in sums.into_iter().enumerate() {
+            if counts[cluster] > 0 {
+                centroids[cluster] = normalized(&sum);
+            }
+        }
+    }
+
+    centroids
+}
+
+pub fn nearest(centroids: &[Vec<f32>], vector: &[f32]) -> usize {
+    probe(centroids, vector, 1).first().copied().unwrap_or(0)
+}
+
+pub fn probe(centroids: &[Vec<f32>], vector: &[f32], count: usize) -> Vec<usize> {
+    let mut scored: Vec<(usize, f32)> = centroids
+        .iter()
+        .enumerate()
+        .map(|(idx, centroid)| (idx, cosine_similarity(centroid, vector)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().take(count).map(|(idx, _)| idx).collect()
+}
+
+fn normalized(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|v| v / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_train_separates_distinct_groups() {
+        let mut vectors = Vec::new();
+        for i in 0..20 {
+            let jitter = i as f32 * 0.001;
+            vectors.push(vec![1.0, jitter, 0.0]);
+            vectors.push(vec![0.0, jitter, 1.0]);
+        }
+
+        let centroids = train(&vectors, 2);
+        assert_eq!(centroids.len(), 2);
+        assert_ne!(
+            nearest(&centroids, &[1.0, 0.0, 0.0]),
+            nearest(&centroids, &[0.0, 0.0, 1.0])
+        );
+    }
+
+    #[test]
+    fn test_probe_orders_by_similarity() {
+        let centroids = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.7, 0.7]];
+        assert_eq!(probe(&centroids, &[0.9, 0.1], 2), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_cluster_count_is_clamped() {
+        assert_eq!(cluster_count(10), MIN_CLUSTERS);
+        assert_eq!(cluster_count(10_000), 100);
+        assert_eq!(cluster_count(10_000_000), MAX_CLUSTERS);
+    }
+}
@@ -0,0 +1,863 @@
+use crate::artifacts::{self, Owner};
+use crate::chunking::{self, ChunkOptions, Strategy};
+use crate::error::{OllamaError, Result};
+use crate::ivf;
+use crate::ollama_local::OllamaLocal;
+use crate::provider::RRF_K;
+use crate::store::{self, Store};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, OptionalExtension};
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+pub const DEFAULT_EMBED_MODEL: &str = "nomic-embed-text";
+pub const DEFAULT_COLLECTION: &str = "default";
+
+pub const INDEX_THRESHOLD: usize = 5_000;
+const INDEX_SIZE_KEY: &str = "kb_index_chunks";
+const CANDIDATE_FACTOR: usize = 4;
+const MIN_CANDIDATES: usize = 20;
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub id: i64,
+    pub collection: String,
+    pub source: String,
+    pub title: String,
+    pub chunks: i64,
+    pub added_at: i64,
+    pub url: Option<String>,
+    pub fetched_at: Option<i64>,
+    pub chunking: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Collection {
+    pub name: String,
+    pub documents: i64,
+    pub chunks: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Metadata {
+    pub url: Option<String>,
+    pub fetched_at: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Passage {
+    pub source: String,
+    pub title: String,
+    pub content: String,
+    pub score: f32,
+    pub url: Option<String>,
+    pub fetched_at: Option<i64>,
+    pub explain: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Added {
+    pub chunks: usize,
+    pub reused: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Compaction {
+    pub orphans: usize,
+    pub chunks: usize,
+    pub clusters: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IndexAction {
+    Keep,
+    Build,
+    Drop,
+}
+
+pub struct KnowledgeBase {
+    store: Store,
+    embedder: OllamaLocal,
+    embed_model: String,
+    collection: Option<String>,
+}
+
+impl KnowledgeBase {
+    pub fn new(store: Store, embedder: OllamaLocal, embed_model: String) -> Self {
+        Self {
+            store,
+            embedder,
+            embed_model,
+            collection: None,
+        }
+    }
+
+    pub fn with_collection(mut self, collection: impl Into<String>) -> Self {
+        self.collection = Some(collection.into());
+        self
+    }
+
+    fn target_collection(&self) -> &str {
+        self.collection.as_deref().unwrap_or(DEFAULT_COLLECTION)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        let count: i64 = self.store.conn().query_row(
+            "SELECT COUNT(*) FROM kb_chunks c JOIN kb_documents d ON d.id = c.document_id
+             WHERE ?1 IS NULL OR d.collection = ?1",
+            params![self.collection],
+            |row| row.get(0),
+        )?;
+        Ok(count == 0)
+    }
+
+    pub async fn add(
+        &self,
+        source: &str,
+        title: &str,
+        content: &str,
+        metadata: &Metadata,
+        options: &ChunkOptions,
+    ) -> Result<Added> {
+        let chunking = options.describe();
+        if let Some(added) = self.unchanged(source, title, content, &chunking)? {
+            self.update_metadata(source, metadata)?;
+            info!("{} is unchanged in the knowledge base", source);
+            return Ok(added);
+        }
+
+        let chunks = match options.strategy {
+            Strategy::Semantic => self.semantic_chunks(content, options).await?,
+            _ => chunking::chunk(content, options),
+        };
+        if chunks.is_empty() {
+            return Err(OllamaError::InvalidResponse(format!(
+                "No content to add from {}",
+                source
+            )));
+        }
+
+        let previous = self.existing_embeddings(source)?;
+        let mut embeddings = Vec::with_capacity(chunks.len());
+        let mut reused = 0;
+        for (idx, chunk) in chunks.iter().enumerate() {
+            match previous.get(chunk) {
+                Some(embedding) => {
+                    reused += 1;
+                    embeddings.push(embedding.clone());
+                }
+                None => {
+                    debug!("Embedding chunk {}/{} of {}", idx + 1, chunks.len(), source);
+                    let embedding = self.embedder.embed(&self.embed_model, chunk).await?;
+                    embeddings.push(encode_embedding(&embedding));
+                }
+            }
+        }
+
+        let centroids = self.centroids()?;
+        let tx = self.store.conn().unchecked_transaction()?;
+        self.delete_source(source, Some(self.target_collection()))?;
+        tx.execute(
+            "INSERT INTO kb_documents (collection, source, title, added_at, url, fetched_at, chunking)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.target_collection(),
+                source,
+                title,
+                store::now(),
+                metadata.url,
+                metadata.fetched_at,
+                chunking
+            ],
+        )?;
+        let document_id = tx.last_insert_rowid();
+        artifacts::put(
+            &self.store,
+            Owner::Kb,
+            document_id,
+            content.as_bytes(),
+            "text/markdown",
+        )?;
+
+        for (idx, (chunk, embedding)) in chunks.iter().zip(&embeddings).enumerate() {
+            tx.execute(
+                "INSERT INTO kb_chunks (document_id, chunk_index, content, embedding) VALUES (?1, ?2, ?3, ?4)",
+                params![document_id, idx as i64, chunk, embedding],
+            )?;
+            if !centroids.is_empty() {
+                let centroid = ivf::nearest(&centroids, &decode_embedding(embedding));
+                tx.execute(
+                    "INSERT INTO kb_assignments (chunk_id, centroid) VALUES (?1, ?2)",
+                    params![tx.last_insert_rowid(), centroid as i64],
+                )?;
+            }
+        }
+        tx.commit()?;
+
+        artifacts::collect_garbage(&self.store)?;
+        self.refresh_index()?;
+
+        info!(
+            "Added {} chunk(s) from {} to collection {} ({} reused)",
+            chunks.len(),
+            source,
+            self.target_collection(),
+            reused
+        );
+        Ok(Added {
+            chunks: chunks.len(),
+            reused,
+        })
+    }
+
+    async fn semantic_chunks(&self, content: &str, options: &ChunkOptions) -> Result<Vec<String>> {
+        let paragraphs = chunking::paragraphs(content, options.max_chars());
+        let mut embeddings = Vec::with_capacity(paragraphs.len());
+        for (idx, paragraph) in paragraphs.iter().enumerate() {
+            debug!(
+                "Embedding paragraph {}/{} for semantic splitting",
+                idx + 1,
+                paragraphs.len()
+            );
+            embeddings.push(self.embedder.embed(&self.embed_model, paragraph).await?);
+        }
+        Ok(chunking::semantic_groups(
+            &paragraphs,
+            &embeddings,
+            options.max_chars(),
+        ))
+    }
+
+    fn update_metadata(&self, source: &str, metadata: &Metadata) -> Result<()> {
+        self.store.conn().execute(
+            "UPDATE kb_documents SET url = COALESCE(?2, url), fetched_at = COALESCE(?3, fetched_at)
+             WHERE source = ?1 AND collection = ?4",
+            params![
+                source,
+                metadata.url,
+                metadata.fetched_at,
+                self.target_collection()
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove(&self, source: &str) -> Result<bool> {
+        let tx = self.store.conn().unchecked_transaction()?;
+        let removed = self.delete_source(source, self.collection.as_deref())?;
+        tx.commit()?;
+
+        if removed {
+            artifacts::collect_garbage(&self.store)?;
+            self.refresh_index()?;
+            info!("Removed {} from knowledge base", source);
+        }
+        Ok(removed)
+    }
+
+    pub fn compact(&self) -> Result<Compaction> {
+        let orphans = self.store.conn().execute(
+            "DELETE FROM kb_assignments WHERE chunk_id NOT IN (SELECT id FROM kb_chunks)",
+            [],
+        )?;
+
+        let chunks = self.chunk_count()?;
+        let clusters = if chunks >= INDEX_THRESHOLD {
+            self.build_index()?
+        } else {
+            self.drop_index()?;
+            0
+        };
+
+        artifacts::collect_garbage(&self.store)?;
+        self.store.conn().execute_batch("VACUUM")?;
+
+        Ok(Compaction {
+            orphans,
+            chunks,
+            clusters,
+        })
+    }
+
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<Passage>> {
+        let query_embedding = self.embedder.embed(&self.embed_model, query).await?;
+        self.hybrid_search(query, &query_embedding, limit)
+    }
+
+    pub fn hybrid_search(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        limit: usize,
+    ) -> Result<Vec<Passage>> {
+        let depth = (limit * CANDIDATE_FACTOR).max(MIN_CANDIDATES);
+        let vector = self.vector_candidates(query_embedding, depth)?;
+        let keyword = self.keyword_candidates(query, query_embedding, depth)?;
+        debug!(
+            "Fusing {} vector and {} keyword candidate(s)",
+            vector.len(),
+            keyword.len()
+        );
+
+        let mut passages = fuse(vec![("vector", vector), ("keyword", keyword)]);
+        passages.truncate(limit);
+        Ok(passages)
+    }
+
+    fn vector_candidates(&self, query_embedding: &[f32], depth: usize) -> Result<Vec<Candidate>> {
+        let centroids = self.centroids()?;
+        let mut sql = "SELECT c.id, d.source, d.title, c.content, c.embedding, d.url, d.fetched_at
+             FROM kb_chunks c JOIN kb_documents d ON d.id = c.document_id"
+            .to_string();
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(collection) = &self.collection {
+            sql.push_str(" WHERE d.collection = ?");
+            values.push(Value::Text(collection.clone()));
+        } else if !centroids.is_empty() {
+            let probes = ivf::probe(&centroids, query_embedding, probe_count(centroids.len()));
+            debug!("Probing {} of {} cluster(s)", probes.len(), centroids.len());
+            sql.push_str(&format!(
+                " JOIN kb_assignments a ON a.chunk_id = c.id WHERE a.centroid IN ({})",
+                vec!["?"; probes.len()].join(", ")
+            ));
+            values.extend(probes.iter().map(|&p| Value::Integer(p as i64)));
+        }
+
+        let mut stmt = self.store.conn().prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), |row| {
+            Candidate::from_row(row, query_embedding)
+        })?;
+        let mut candidates = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+
+        candidates.sort_by(|a, b| b.passage.score.total_cmp(&a.passage.score));
+        candidates.truncate(depth);
+        Ok(candidates)
+    }
+
+    fn keyword_candidates(
+        &self,
+        query: &str,
+        query_embedding: &[f32],
+        depth: usize,
+    ) -> Result<Vec<Candidate>> {
+        let fts = keyword_query(query);
+        if fts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut stmt = self.store.conn().prepare(
+            "SELECT c.id, d.source, d.title, c.content, c.embedding, d.url, d.fetched_at
+             FROM kb_chunks_fts f
+             JOIN kb_chunks c ON c.id = f.rowid
+             JOIN kb_documents d ON d.id = c.document_id
+             WHERE kb_chunks_fts MATCH ?1 AND (?3 IS NULL OR d.collection = ?3)
+             ORDER BY f.rank LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![fts, depth as i64, self.collection], |row| {
+            Candidate::from_row(row, query_embedding)
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    pub fn documents(&self) -> Result<Vec<Document>> {
+        let mut stmt = self.store.conn().prepare(
+            "SELECT d.id, d.collection, d.source, d.title, COUNT(c.id), d.added_at, d.url,
+                    d.fetched_at, d.chunking
+             FROM kb_documents d LEFT JOIN kb_chunks c ON c.document_id = d.id
+             WHERE ?1 IS NULL OR d.collection = ?1
+             GROUP BY d.id ORDER BY d.added_at DESC",
+        )?;
+        let rows = stmt.query_map(params![self.collection], |row| {
+            Ok(Document {
+                id: row.get(0)?,
+                collection: row.get(1)?,
+                source: row.get(2)?,
+                title: row.get(3)?,
+                chunks: row.get(4)?,
+                added_at: row.get(5)?,
+                url: row.get(6)?,
+                fetched_at: row.get(7)?,
+                chunking: row.get(8)?,
+            })
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    pub fn collections(&self) -> Result<Vec<Collection>> {
+        let mut stmt = self.store.conn().prepare(
+            "SELECT d.collection, COUNT(DISTINCT d.id), COUNT(c.id)
+             FROM kb_documents d LEFT JOIN kb_chunks c ON c.document_id = d.id
+             GROUP BY d.collection ORDER BY d.collection",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Collection {
+                name: row.get(0)?,
+                documents: row.get(1)?,
+                chunks: row.get(2)?,
+            })
+        })?;
+
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn chunk_count(&self) -> Result<usize> {
+        let count: i64 =
+            self.store
+                .conn()
+                .query_row("SELECT COUNT(*) FROM kb_chunks", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    fn unchanged(
+        &self,
+        source: &str,
+        title: &str,
+        content: &str,
+        chunking: &str,
+    ) -> Result<Option<Added>> {
+        let existing: Option<(i64, String, Option<String>, i64)> = self
+            .store
+            .conn()
+            .query_row(
+                "SELECT d.id, d.title, d.chunking, COUNT(c.id)
+                 FROM kb_documents d LEFT JOIN kb_chunks c ON c.document_id = d.id
+                 WHERE d.source = ?1 AND d.collection = ?2 GROUP BY d.id",
+                params![source, self.target_collection()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?;
+
+        let Some((document_id, existing_title, existing_chunking, chunks)) = existing else {
+            return Ok(None);
+        };
+        if existing_title != title || existing_chunking.as_deref() != Some(chunking) || chunks == 0
+        {
+            return Ok(None);
+        }
+
+        let hash = artifacts::hash_for(&self.store, Owner::Kb, document_id)?;
+        if hash.as_deref() != Some(artifacts::hash(content.as_bytes()).as_str()) {
+            return Ok(None);
+        }
+
+        Ok(Some(Added {
+            chunks: chunks as usize,
+            reused: chunks as usize,
+        }))
+    }
+
+    fn existing_embeddings(&self, source: &str) -> Result<HashMap<String, Vec<u8>>> {
+        let mut stmt = self.store.conn().prepare(
+            "SELECT c.content, c.embedding
+             FROM kb_chunks c JOIN kb_documents d ON d.id = c.document_id
+             WHERE d.source = ?1 AND d.collection = ?2",
+        )?;
+        let rows = stmt.query_map(params![source, self.target_collection()], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<HashMap<_, _>>>()?)
+    }
+
+    fn delete_source(&self, source: &str, collection: Option<&str>) -> Result<bool> {
+        let conn = self.store.conn();
+        conn.execute(
+            "DELETE FROM kb_assignments WHERE chunk_id IN (
+                 SELECT c.id FROM kb_chunks c JOIN kb_documents d ON d.id = c.document_id
+                 WHERE d.source = ?1 AND (?2 IS NULL OR d.collection = ?2))",
+            params![source, collection],
+        )?;
+        conn.execute(
+            "DELETE FROM kb_chunks WHERE document_id IN (
+                 SELECT id FROM kb_documents WHERE source = ?1 AND (?2 IS NULL OR collection = ?2))",
+            params![source, collection],
+        )?;
+        let removed = conn.execute(
+            "DELETE FROM kb_documents WHERE source = ?1 AND (?2 IS NULL OR collection = ?2)",
+            params![source, collection],
+        )?;
+        Ok(removed > 0)
+    }
+
+    fn centroids(&self) -> Result<Vec<Vec<f32>>> {
+        let mut stmt = self
+            .store
+            .conn()
+            .prepare("SELECT embedding FROM kb_centroids ORDER BY id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+        rows.map(|row| Ok(decode_embedding(&row?))).collect()
+    }
+
+    fn indexed_size(&self) -> Result<Option<usize>> {
+        let value: Option<String> = self
+            .store
+            .conn()
+            .query_row(
+                "SELECT value FROM meta WHERE key = ?1",
+                params![INDEX_SIZE_KEY],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value.and_then(|v| v.parse().ok()))
+    }
+
+    fn refresh_index(&self) -> Result<()> {
+        match index_action(self.chunk_count()?, self.indexed_size()?) {
+            IndexAction::Keep => {}
+            IndexAction::Build => {
+                self.build_index()?;
+            }
+            IndexAction::Drop => self.drop_index()?,
+        }
+        Ok(())
+    }
+
+    fn build_index(&self) -> Result<usize> {
+        let mut stmt = self
+            .store
+            .conn()
+            .prepare("SELECT id, embedding FROM kb_chunks")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+        let (ids, vectors): (Vec<i64>, Vec<Vec<f32>>) = rows
+            .map(|row| row.map(|(id, embedding)| (id, decode_embedding(&embedding))))
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .unzip();
+
+        let centroids = ivf::train(&vectors, ivf::cluster_count(vectors.len()));
+
+        let tx = self.store.conn().unchecked_transaction()?;
+        tx.execute_batch("DELETE FROM kb_centroids; DELETE FROM kb_assignments;")?;
+        for (idx, centroid) in centroids.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO kb_centroids (id, embedding) VALUES (?1, ?2)",
+                params![idx as i64, encode_embedding(centroid)],
+            )?;
+        }
+        for (id, vector) in ids.iter().zip(&vectors) {
+            tx.execute(
+                "INSERT INTO kb_assignments (chunk_id, centroid) VALUES (?1, ?2)",
+                params![id, ivf::nearest(&centroids, vector) as i64],
+            )?;
+        }
+        tx.execute(
+            "INSERT INTO meta (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![INDEX_SIZE_KEY, vectors.len().to_string()],
+        )?;
+        tx.commit()?;
+
+        info!(
+            "Built knowledge base index with {} cluster(s) over {} chunk(s)",
+            centroids.len(),
+            vectors.len()
+        );
+        Ok(centroids.len())
+    }
+
+    fn drop_index(&self) -> Result<()> {
+        self.store.conn().execute_batch(&format!(
+            "DELETE FROM kb_centroids; DELETE FROM kb_assignments;
+             DELETE FROM meta WHERE key = '{}';",
+            INDEX_SIZE_KEY
+        ))?;
+        Ok(())
+    }
+}
+
+fn index_action(chunks: usize, indexed: Option<usize>) -> IndexAction {
+    match indexed {
+        None if chunks >= INDEX_THRESHOLD => IndexAction::Build,
+        None => IndexAction::Keep,
+        Some(_) if chunks < INDEX_THRESHOLD / 2 => IndexAction::Drop,
+        Some(size) if chunks > size * 2 || chunks * 2 < size => IndexAction::Build,
+        Some(_) => IndexAction::Keep,
+    }
+}
+
+struct Candidate {
+    id: i64,
+    passage: Passage,
+}
+
+impl Candidate {
+    fn from_row(row: &rusqlite::Row, query_embedding: &[f32]) -> rusqlite::Result<Self> {
+        let embedding: Vec<u8> = row.get(4)?;
+        Ok(Self {
+            id: row.get(0)?,
+            passage: Passage {
+                source: row.get(1)?,
+                title: row.get(2)?,
+                content: row.get(3)?,
+                score: cosine_similarity(query_embedding, &decode_embedding(&embedding)),
+                url: row.get(5)?,
+                fetched_at: row.get(6)?,
+                explain: Vec::new(),
+            },
+        })
+    }
+}
+
+fn fuse(rankings: Vec<(&str, Vec<Candidate>)>) -> Vec<Passage> {
+    let mut fused: Vec<(f64, usize, i64, Passage)> = Vec::new();
+
+    for (name, ranking) in rankings {
+        for (rank, candidate) in ranking.into_iter().enumerate() {
+            let score = 1.0 / (RRF_K + rank as f64 + 1.0);
+            let note = format!("{} rank #{}", name, rank + 1);
+            match fused.iter_mut().find(|entry| entry.2 == candidate.id) {
+                Some(entry) => {
+                    entry.0 += score;
+                    entry.3.explain.push(note);
+                }
+                None => {
+                    let mut passage = candidate.passage;
+                    passage.explain.push(note);
+                    fused.push((score, fused.len(), candidate.id, passage));
+                }
+            }
+        }
+    }
+
+    fused.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    fused
+        .into_iter()
+        .map(|(score, _, _, mut passage)| {
+            passage
+                .explain
+                .push(format!("fused RRF score {:.4}", score));
+            passage
+        })
+        .collect()
+}
+
+fn keyword_query(query: &str) -> String {
+    query
+        .split(|c: char| c.is_whitespace() || c == '"')
+        .filter(|token| token.chars().any(char::is_alphanumeric))
+        .map(|token| format!("\"{}\"", token))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+fn probe_count(clusters: usize) -> usize {
+    (clusters / 8).max(4)
+}
+
+pub fn format_passages(passages: &[Passage], explain: bool) -> String {
+    if passages.is_empty() {
+        return "No matching passages in the knowledge base.".to_string();
+    }
+
+    let mut output = String::new();
+    for (idx, passage) in passages.iter().enumerate() {
+        let fetched = passage
+            .fetched_at
+            .map(|at| format!("Fetched: {}\n", store::format_timestamp(at)))
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "Passage {} (similarity {:.2}):\nTitle: {}\nSource: {}\n{}Content: {}\n\n",
+            idx + 1,
+            passage.score,
+            passage.title,
+            passage.url.as_deref().unwrap_or(&passage.source),
+            fetched,
+            passage.content
+        ));
+        if explain && !passage.explain.is_empty() {
+            output.truncate(output.len() - 1);
+            output.push_str(&format!("Why: {}\n\n", passage.explain.join(" · ")));
+        }
+    }
+    output
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+pub fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+    }
+
+    #[test]
+    fn test_index_action_thresholds() {
+        assert_eq!(index_action(100, None), IndexAction::Keep);
+        assert_eq!(index_action(INDEX_THRESHOLD, None), IndexAction::Build);
+        assert_eq!(
+            index_action(INDEX_THRESHOLD + 10, Some(INDEX_THRESHOLD)),
+            IndexAction::Keep
+        );
+        assert_eq!(
+            index_action(INDEX_THRESHOLD * 2 + 1, Some(INDEX_THRESHOLD)),
+            IndexAction::Build
+        );
+        assert_eq!(index_action(10, Some(INDEX_THRESHOLD)), IndexAction::Drop);
+    }
+
+    #[test]
+    fn test_indexed_search_remove_and_compact() {
+        let kb = KnowledgeBase::new(
+            Store::open_in_memory().unwrap(),
+            OllamaLocal::new(None).unwrap(),
+            DEFAULT_EMBED_MODEL.to_string(),
+        );
+        let conn = kb.store.conn();
+        for (doc, direction) in [("rust", [1.0, 0.0, 0.0]), ("go", [0.0, 0.0, 1.0])] {
+            conn.execute(
+                "INSERT INTO kb_documents (source, title, added_at) VALUES (?1, ?1, 0)",
+                params![doc],
+            )
+            .unwrap();
+            let document_id = conn.last_insert_rowid();
+            for idx in 0..40 {
+                let embedding = [direction[0], idx as f32 * 0.001, direction[2]];
+                conn.execute(
+                    "INSERT INTO kb_chunks (document_id, chunk_index, content, embedding) VALUES (?1, ?2, ?3, ?4)",
+                    params![document_id, idx, format!("{} {}", doc, idx), encode_embedding(&embedding)],
+                )
+                .unwrap();
+            }
+        }
+
+        assert!(kb.build_index().unwrap() > 0);
+        let candidates = kb.vector_candidates(&[1.0, 0.0, 0.0], 5).unwrap();
+        assert!(!candidates.is_empty());
+        assert!(candidates.iter().all(|c| c.passage.source == "rust"));
+
+        assert!(kb.remove("rust").unwrap());
+        assert!(!kb.remove("rust").unwrap());
+        assert!(kb.centroids().unwrap().is_empty());
+
+        conn.execute(
+            "INSERT INTO kb_assignments (chunk_id, centroid) VALUES (999, 0)",
+            [],
+        )
+        .unwrap();
+        let compaction = kb.compact().unwrap();
+        assert_eq!(compaction.orphans, 1);
+        assert_eq!(compaction.chunks, 40);
+        assert_eq!(compaction.clusters, 0);
+    }
+
+    #[test]
+    fn test_hybrid_search_surfaces_exact_identifiers() {
+        let kb = KnowledgeBase::new(
+            Store::open_in_memory().unwrap(),
+            OllamaLocal::new(None).unwrap(),
+            DEFAULT_EMBED_MODEL.to_string(),
+        );
+        let conn = kb.store.conn();
+        conn.execute(
+            "INSERT INTO kb_documents (source, title, added_at) VALUES ('notes', 'Notes', 0)",
+            [],
+        )
+        .unwrap();
+        let chunks = [
+            ("Borrowing rules and lifetimes explained.", [1.0, 0.0]),
+            ("Ownership moves values between bindings.", [0.9, 0.1]),
+            ("error[E0502]: cannot borrow as mutable", [0.0, 1.0]),
+        ];
+        for (idx, (content, embedding)) in chunks.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO kb_chunks (document_id, chunk_index, content, embedding) VALUES (1, ?1, ?2, ?3)",
+                params![idx as i64, content, encode_embedding(embedding)],
+            )
+            .unwrap();
+        }
+
+        let passages = kb.hybrid_search("E0502 borrow", &[1.0, 0.0], 2).unwrap();
+        assert_eq!(passages.len(), 2);
+        assert!(passages.iter().any(|p| p.content.contains("E0502")));
+        assert!(passages
+            .iter()
+            .all(|p| p.explain.last().is_some_and(|n| n.starts_with("fused RRF"))));
+
+        conn.execute("DELETE FROM kb_chunks WHERE chunk_index = 2", [])
+            .unwrap();
+        assert!(kb
+            .keyword_candidates("E0502", &[1.0, 0.0], 5)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_collections_scope_search_and_removal() {
+        let store = Store::open_in_memory().unwrap();
+        let conn = store.conn();
+        for (collection, content) in [("default", "tokio runtime"), ("projx", "tokio design")] {
+            conn.execute(
+                "INSERT INTO kb_documents (collection, source, title, added_at) VALUES (?1, 'notes', ?1, 0)",
+                params![collection],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO kb_chunks (document_id, chunk_index, content, embedding) VALUES (?1, 0, ?2, ?3)",
+                params![conn.last_insert_rowid(), content, encode_embedding(&[1.0, 0.0])],
+            )
+            .unwrap();
+        }
+        let kb = KnowledgeBase::new(
+            store,
+            OllamaLocal::new(None).unwrap(),
+            DEFAULT_EMBED_MODEL.to_string(),
+        )
+        .with_collection("projx");
+
+        let passages = kb.hybrid_search("tokio", &[1.0, 0.0], 5).unwrap();
+        assert_eq!(passages.len(), 1);
+        assert_eq!(passages[0].content, "tokio design");
+        assert_eq!(kb.documents().unwrap().len(), 1);
+        assert_eq!(kb.collections().unwrap().len(), 2);
+
+        assert!(kb.remove("notes").unwrap());
+        assert!(kb.is_empty().unwrap());
+        assert_eq!(kb.collections().unwrap()[0].name, DEFAULT_COLLECTION);
+    }
+
+    #[test]
+    fn test_keyword_query_quotes_tokens() {
+        assert_eq!(
+            keyword_query("tokio 1.38 \"spawn\" -"),
+            "\"tokio\" OR \"1.38\" OR \"spawn\""
+        );
+        assert_eq!(keyword_query("  ?? "), "");
+    }
+
+    #[test]
+    fn test_embedding_roundtrip() {
+        let embedding = vec![0.5, -1.25, 3.0];
+        assert_eq!(decode_embedding(&encode_embedding(&embedding)), embedding);
+    }
+}
@@ -0,0 +1,80 @@
+pub mod agent;
+pub mod aliases;
+pub mod artifacts;
+pub mod arxiv;
+pub mod audit;
+pub mod bookmarks;
+pub mod brave;
+pub mod breaker;
+pub mod browse;
+pub mod calc;
+pub mod chunking;
+pub mod citations;
+pub mod cli;
+pub mod client;
+pub mod clipboard;
+pub mod cloud;
+pub mod cluster;
+pub mod completions;
+pub mod config;
+pub mod consensus;
+pub mod credibility;
+pub mod daemon;
+pub mod dedup;
+pub mod diff;
+pub mod discussions;
+pub mod epub;
+pub mod error;
+pub mod events;
+pub mod exit_code;
+pub mod feed;
+pub mod feedback;
+pub mod files;
+pub mod formatter;
+pub mod github;
+pub mod glyphs;
+pub mod history;
+pub mod i18n;
+pub mod ivf;
+pub mod kb;
+pub mod loading;
+pub mod markdown_preview;
+pub mod metadata;
+pub mod mock;
+pub mod network;
+pub mod notify;
+pub mod obsidian;
+pub mod offline;
+pub mod ollama_local;
+pub mod orchestrate;
+pub mod pdf;
+pub mod permissions;
+pub mod pipeline;
+pub mod privacy;
+pub mod provider;
+pub mod ratelimit;
+pub mod react;
+pub mod readability;
+pub mod rerank;
+pub mod retention;
+pub mod scratchpad;
+pub mod searxng;
+pub mod sessions;
+pub mod settings;
+pub mod speech;
+pub mod stats;
+pub mod store;
+pub mod summarizer;
+pub mod templates;
+pub mod terminal;
+pub mod text;
+pub mod trail;
+pub mod translate;
+pub mod usage;
+pub mod vcr;
+pub mod vision;
+pub mod watch;
+pub mod wayback;
+pub mod weather;
+pub mod webhook;
+pub mod wikipedia;
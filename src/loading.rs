@@ -1,78 +1,119 @@
-use std::io::{self, Write};
-use std::sync::{Arc, Mutex};
-use std::thread;
+use crate::glyphs::{self, Glyph};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::Mutex;
 use std::time::Duration;
 
-struct AnimationState {
-    running: bool,
-    visible: bool,
+const TICK: Duration = Duration::from_millis(120);
+const TEMPLATE: &str = "{spinner:.cyan} {prefix:.cyan} {elapsed:.dim} {msg}";
+const STILL_TEMPLATE: &str = "{prefix:.cyan} {elapsed:.dim} {msg}";
+const FETCH_TOOLS: &[&str] = &["web_fetch", "discussion_thread", "fetch_feed"];
+
+#[derive(Debug, Default)]
+struct Status {
+    iteration: usize,
+    max_iterations: usize,
+    last_tool: Option<String>,
+    searches: usize,
+    fetches: usize,
+}
+
+impl Status {
+    fn record(&mut self, tool: &str) {
+        if tool.ends_with("_search") {
+            self.searches += 1;
+        } else if FETCH_TOOLS.contains(&tool) {
+            self.fetches += 1;
+        }
+        self.last_tool = Some(tool.to_string());
+    }
+
+    fn message(&self) -> String {
+        let mut parts = Vec::new();
+        if self.max_iterations > 0 {
+            parts.push(format!("iter {}/{}", self.iteration, self.max_iterations));
+        }
+        if let Some(tool) = &self.last_tool {
+            parts.push(format!("last: {}", tool));
+        }
+        if self.searches + self.fetches > 0 {
+            parts.push(format!(
+                "{} search{}, {} fetch{}",
+                self.searches,
+                if self.searches == 1 { "" } else { "es" },
+                self.fetches,
+                if self.fetches == 1 { "" } else { "es" }
+            ));
+        }
+        parts.join(" · ")
+    }
 }
 
 pub struct LoadingAnimation {
-    state: Arc<Mutex<AnimationState>>,
-    handle: Option<thread::JoinHandle<()>>,
+    bar: ProgressBar,
+    status: Mutex<Status>,
 }
 
 impl LoadingAnimation {
     pub fn start() -> Self {
-        let state = Arc::new(Mutex::new(AnimationState {
-            running: true,
-            visible: true,
-        }));
-        let state_clone = Arc::clone(&state);
-
-        let handle = thread::spawn(move || {
-            let mut dot_count = 0;
-
-            loop {
-                {
-                    let state = state_clone.lock().unwrap();
-                    if !state.running {
-                        break;
-                    }
-
-                    if state.visible {
-                        let dots = ".".repeat(dot_count);
-                        print!("\r\x1b[36m🧵 Weaving{}\x1b[0m", dots);
-                        io::stdout().flush().unwrap();
-                    } else {
-                        print!("\r\x1b[K");
-                        io::stdout().flush().unwrap();
-                    }
-                }
-
-                thread::sleep(Duration::from_secs(5));
-                dot_count += 1;
+        let style = match glyphs::spinner().ticks() {
+            Some(ticks) => {
+                ProgressStyle::with_template(TEMPLATE).map(|style| style.tick_chars(ticks))
             }
-
-            print!("\r\x1b[K");
-            io::stdout().flush().unwrap();
-        });
-
+            None => ProgressStyle::with_template(STILL_TEMPLATE),
+        };
+        let bar = ProgressBar::new_spinner()
+            .with_style(style.unwrap_or_else(|_| ProgressStyle::default_spinner()))
+            .with_prefix(format!("{}Weaving", Glyph::Weave));
+        bar.enable_steady_tick(TICK);
         Self {
-            state,
-            handle: Some(handle),
+            bar,
+            status: Mutex::new(Status::default()),
         }
     }
 
-    pub fn stop(mut self) {
-        {
-            let mut state = self.state.lock().unwrap();
-            state.running = false;
-        }
+    pub fn stop(self) {
+        self.bar.finish_and_clear();
+    }
 
-        if let Some(handle) = self.handle.take() {
-            let _ = handle.join();
-        }
+    pub fn iteration(&self, iteration: usize, max_iterations: usize) {
+        self.update(|status| {
+            status.iteration = iteration;
+            status.max_iterations = max_iterations;
+        });
+    }
+
+    pub fn tool(&self, name: &str) {
+        self.update(|status| status.record(name));
+    }
+
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.bar.suspend(f)
     }
 
-    pub fn pause(&self) {
-        let mut state = self.state.lock().unwrap();
-        state.visible = false;
+    fn update(&self, f: impl FnOnce(&mut Status)) {
+        let mut status = self.status.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut status);
+        self.bar.set_message(status.message());
     }
+}
 
-    pub fn resume(&self) {
-        let mut state = self.state.lock().unwrap();
-        state.visible = true;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_counts_searches_and_fetches() {
+        let mut status = Status::default();
+        assert_eq!(status.message(), "");
+
+        status.iteration = 3;
+        status.max_iterations = 50;
+        for tool in ["web_search", "kb_search", "web_fetch", "calculate"] {
+            status.record(tool);
+        }
+        assert_eq!(
+            status.message(),
+            "iter 3/50 · last: calculate · 2 searches, 1 fetch"
+        );
     }
 }
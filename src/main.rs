@@ -1,60 +1,206 @@
-mod agent;
-mod cli;
-mod client;
-mod config;
-mod error;
-mod formatter;
-mod loading;
-mod markdown_preview;
-mod ollama_local;
-
 use anyhow::{Context, Result};
-use clap::Parser;
-use cli::{Cli, Command};
+use chunking::ChunkOptions;
+use clap::{Parser, ValueEnum};
+use cli::{
+    AliasCommand, BookmarkCommand, CacheCommand, Cli, Command, HistoryArgs, HistoryCommand,
+    JobsCommand, KbArgs, KbCommand, PermissionsCommand, PipelineCommand, SessionsCommand,
+    SummarizeArgs, TemplateCommand,
+};
 use client::OllamaClient;
+use cloud::{Backend, CloudChat};
 use config::Config;
-use formatter::{format_fetch_response, format_search_results};
+use formatter::{Html, Markdown, Renderer};
+use glyphs::Glyph;
+use kb::KnowledgeBase;
+use markdown_preview::PreviewTarget;
+use ollama_local::{ChatBackend, OllamaLocal};
+use orchestrate::Researcher;
+use permissions::{Capability, Permissions};
+use provider::{ProviderKind, SearchProvider};
+use react::ReactChat;
+use settings::{CloudSettings, RerankSettings, Settings, Webhook};
+use std::io::{IsTerminal, Write};
+use std::path::Path;
 use std::time::Duration;
-use tracing::info;
+use store::Store;
+use summarizer::{Length, Summarizer};
+use text::Limits;
+use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
+use translate::AnswerLanguage;
+use weavex::{
+    agent, aliases, artifacts, arxiv, audit, bookmarks, browse, chunking, citations, cli, client,
+    clipboard, cloud, cluster, completions, config, consensus, credibility, daemon, dedup, diff,
+    discussions, epub, error,
+    events::{self, Event},
+    exit_code, feed, feedback, files, formatter, github, glyphs, history, i18n, kb,
+    markdown_preview, mock, network, notify, obsidian, offline, ollama_local, orchestrate,
+    permissions, pipeline, privacy, provider, ratelimit, react, rerank, retention, scratchpad,
+    sessions, settings, speech, stats, store, summarizer, templates, terminal, text, trail,
+    translate, usage, vcr, vision, watch, weather, webhook, wikipedia,
+};
+
+const DAEMON_TICK: Duration = Duration::from_secs(30);
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
+async fn main() -> std::process::ExitCode {
+    match run().await {
+        Ok(()) => exit_code::reported().into(),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            error_exit_code(&e).into()
+        }
+    }
+}
+
+fn error_exit_code(error: &anyhow::Error) -> u8 {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<error::OllamaError>())
+        .map_or(exit_code::FAILURE, exit_code::for_error)
+}
+
+fn report_if_empty(cli: &Cli, empty: bool) {
+    if cli.fail_on_empty && empty {
+        exit_code::report(exit_code::NO_RESULTS);
+    }
+}
+
+async fn run() -> Result<()> {
+    clap_complete::CompleteEnv::with_factory(completions::command)
+        .var(completions::ENV_VAR)
+        .complete();
+    i18n::init(i18n::requested(std::env::args_os()).unwrap_or_else(i18n::detect));
+    let aliases = Settings::load()
+        .map(|settings| settings.aliases)
+        .unwrap_or_default();
+    let mut cli = Cli::parse_localized(aliases::expand(std::env::args_os().collect(), &aliases)?);
+    if let Some(Command::Template { command }) = &cli.command {
+        match command {
+            TemplateCommand::List => return run_template_list(),
+            TemplateCommand::Run { name, vars, flags } => {
+                cli = expand_template(name, vars, flags)?;
+            }
+        }
+    }
+    let stdin = std::io::stdin();
+    let piped = !stdin.is_terminal();
+    cli.read_stdin_query(stdin.lock(), piped)
+        .context("Failed to read the query from stdin")?;
+    if let Some(Command::Completions { shell }) = &cli.command {
+        completions::write_registration(*shell, &mut std::io::stdout())
+            .context("Failed to write completion script")?;
+        return Ok(());
+    }
 
-    init_logging(cli.verbose);
+    init_logging(&cli);
+    glyphs::init(cli.no_emoji, cli.spinner);
 
     if let Err(e) = dotenvy::dotenv() {
         tracing::debug!("No .env file found: {}", e);
     }
 
-    let api_key = cli
-        .api_key
-        .clone()
-        .or_else(|| std::env::var("OLLAMA_API_KEY").ok())
-        .context("API key not found. Set OLLAMA_API_KEY environment variable or use --api-key flag.\nGet your key at: https://ollama.com")?;
+    let settings = Settings::load().with_context(|| i18n::tr("error-load-config"))?;
+    network::configure(build_network(&cli, &settings)?);
+    enable_audit(&cli, &settings)?;
+    enable_vcr()?;
+    enable_usage(&settings);
+    credibility::init(&settings.credibility);
+    markdown_preview::configure(preview_target(&cli, &settings), settings.preview.max_age());
+    if cli.citations.is_some() {
+        citations::enable();
+    }
+    if let Some(per_minute) = cli.rate_limit.or(settings.budget.requests_per_minute) {
+        ratelimit::enable(per_minute);
+    }
 
-    let mut config = Config::new(api_key);
+    match &cli.command {
+        Some(Command::Clean { enforce_policy }) => return run_clean(&settings, *enforce_policy),
+        _ => retention::enforce_on_startup(settings.retention.clone()),
+    }
 
-    if let Some(max_results) = cli.max_results {
-        config = config.with_max_results(max_results);
+    match &cli.command {
+        Some(Command::Feedback {
+            result_id,
+            rating,
+            export,
+        }) => return run_feedback(result_id.as_deref(), *rating, export.as_deref()),
+        Some(Command::History(args)) => return run_history(&cli, args),
+        Some(Command::Diff { old, new }) => return run_diff(&cli, *old, *new),
+        Some(Command::Export {
+            id,
+            obsidian,
+            tags,
+            zettel,
+        }) => return run_export(*id, obsidian, tags, *zettel),
+        Some(Command::Kb(args)) => return run_kb(&cli, &settings, args).await,
+        Some(Command::Arxiv { query, limit, sort }) => {
+            return run_arxiv(&cli, query, *limit, *sort).await
+        }
+        Some(Command::Feed { url, limit }) => return run_feed(&cli, url, *limit).await,
+        Some(Command::Summarize(args)) => {
+            return run_summarize(&cli, &settings, args, Length::Summary).await
+        }
+        Some(Command::Tldr(args)) => {
+            return run_summarize(&cli, &settings, args, Length::Tldr).await
+        }
+        Some(Command::Bookmark { command }) => return run_bookmark(command),
+        Some(Command::Permissions { command }) => return run_permissions(command),
+        Some(Command::Jobs { command }) => return run_jobs(&settings, command),
+        Some(Command::Alias { command }) => return run_alias(&settings, command),
+        Some(Command::Sessions { command }) => return run_sessions(&cli, &settings, command).await,
+        Some(Command::Cache { command }) => return run_cache(command),
+        Some(Command::Usage) => return run_usage(&settings),
+        Some(Command::Stats { days }) => return run_stats(&cli, *days),
+        _ => {}
     }
 
-    config = config.with_timeout(Duration::from_secs(cli.timeout));
+    let store = Store::open()
+        .map_err(|e| warn!("Local store unavailable: {}", e))
+        .ok();
+
+    let config = build_config(&cli, &settings)?;
 
-    let client = OllamaClient::new(config).context("Failed to create Ollama client")?;
+    let client = build_client(&cli, &config)?;
+    let mut sources = AgentSources::new(&cli, &settings);
+    let search = build_search(&cli, &sources, store.as_ref(), &config, &client)?;
+    let history = store.as_ref().filter(|_| !cli.private && !cli.dry_run);
+    let outputs = AnswerOutputs::new(&cli, &settings);
+
+    let renderer = terminal_renderer(&cli, &settings);
+    let extra_searches = match &cli.command {
+        Some(Command::Agent {
+            workers, samples, ..
+        }) => (1..*workers.max(samples))
+            .map(|_| build_search(&cli, &sources, store.as_ref(), &config, &client))
+            .collect::<Result<Vec<_>>>()?,
+        _ => Vec::new(),
+    };
 
     match cli.command {
         Some(Command::Fetch { url }) => {
             info!("Fetching URL: {}", url);
-            let response = client.fetch(&url).await.context("Failed to fetch URL")?;
+            if cli.dry_run {
+                println!("{}[dry-run] fetch not executed: {}", Glyph::DryRun, url);
+                return Ok(());
+            }
+            let response = if cli.offline {
+                cached_page(store.as_ref(), &url)?
+            } else {
+                let response = client
+                    .fetch(&url)
+                    .await
+                    .with_context(|| i18n::tr("error-fetch"))?;
+                record_fetch(history, &url, &response);
+                response
+            };
 
             if !cli.no_preview {
-                markdown_preview::open_markdown_in_browser(&response.content)
-                    .context("Failed to open browser")?;
-                println!("🌐 Opened result in browser");
+                if open_preview(&Html.fetch_response(&response), cli.embed_images).await? {
+                    println!("{}{}", Glyph::Web, i18n::tr("opened-result"));
+                }
             } else {
-                let output = format_fetch_response(&response, cli.json);
+                let output = renderer.fetch_response(&response);
                 println!("{}", output);
             }
         }
@@ -66,78 +212,2594 @@ async fn main() -> Result<()> {
             show_thinking,
             disable_reasoning,
             no_preview,
+            embed_model,
+            workers,
+            worker_iterations,
+            planner_model,
+            samples,
+            sample_models,
+            json_stream,
         }) => {
-            info!("Starting agent with model: {}", model);
-            println!("🤖 Initializing agent with model: {}\n", model);
-
-            let local_ollama = ollama_local::OllamaLocal::new(Some(ollama_url))
-                .context("Failed to create local Ollama client")?;
-
-            let agent = agent::Agent::new(
-                local_ollama,
-                client,
-                model,
-                show_thinking,
-                !disable_reasoning,
+            if json_stream {
+                if workers > 1 || samples > 1 {
+                    anyhow::bail!(
+                        "--json-stream follows a single agent and cannot be combined with --workers or --samples"
+                    );
+                }
+                events::enable();
+            }
+            let models: Vec<String> = std::iter::once(&model)
+                .chain(planner_model.iter())
+                .chain(sample_models.iter())
+                .cloned()
+                .collect();
+            ensure_ollama_reachable(&sources, &ollama_url, &models).await?;
+            sources.react_models = inspect_models(&sources, &ollama_url, &models).await;
+            let options = agent::AgentOptions {
+                model: sources.model(&model),
                 max_iterations,
-            );
+                show_thinking: show_thinking && !json_stream,
+                enable_reasoning: !disable_reasoning,
+            };
+            let researcher: Box<dyn Researcher> = if samples > 1 {
+                let models =
+                    consensus::sample_models(&options.model, &sample_models, samples as usize);
+                Box::new(build_consensus(
+                    client,
+                    std::iter::once(search)
+                        .chain(extra_searches)
+                        .zip(models)
+                        .collect(),
+                    ollama_url,
+                    embed_model,
+                    &sources,
+                    options,
+                )?)
+            } else if workers > 1 {
+                Box::new(build_orchestrator(
+                    client,
+                    std::iter::once(search).chain(extra_searches).collect(),
+                    ollama_url,
+                    embed_model,
+                    &sources,
+                    agent::AgentOptions {
+                        max_iterations: worker_iterations,
+                        show_thinking: false,
+                        ..options
+                    },
+                    planner_model,
+                )?)
+            } else {
+                Box::new(build_agent(
+                    client,
+                    Box::new(search),
+                    ollama_url,
+                    embed_model,
+                    &sources,
+                    options,
+                )?)
+            };
+            run_agent(
+                researcher.as_ref(),
+                history,
+                &query,
+                no_preview,
+                renderer.as_ref(),
+                &outputs,
+            )
+            .await?;
+        }
+        Some(Command::Trail {
+            url,
+            question,
+            model,
+            ollama_url,
+            max_pages,
+            max_depth,
+            disable_reasoning,
+            no_preview,
+        }) => {
+            let model = sources.model(&model);
+            info!("Following links from {} with model {}", url, model);
+            if cli.dry_run {
+                println!(
+                    "{}[dry-run] trail not executed: {} ({})",
+                    Glyph::DryRun,
+                    url,
+                    question
+                );
+                return Ok(());
+            }
+            ensure_ollama_reachable(&sources, &ollama_url, [&model]).await?;
+            let chat = chat_backend(&sources, ollama_url, None)?;
+            let trail = trail::Trail::new(chat, client, model, url)
+                .with_limits(max_pages, max_depth)
+                .with_reasoning(!disable_reasoning);
+            run_agent(
+                &trail,
+                history,
+                &question,
+                no_preview,
+                renderer.as_ref(),
+                &outputs,
+            )
+            .await?;
+        }
+        Some(Command::Browse {
+            query,
+            model,
+            ollama_url,
+            embed_model,
+        }) => {
+            info!("Browsing results for: {}", query);
+            let response = search
+                .search(&query, config.max_results)
+                .await
+                .with_context(|| i18n::tr("error-search"))?;
 
-            println!("🔍 Researching: {}\n", query);
+            record_search(history, &query, &response);
 
-            let result = agent.run(&query).await.context("Agent execution failed")?;
+            let embedder = OllamaLocal::new(Some(ollama_url.clone()))
+                .with_context(|| i18n::tr("error-local-ollama-client"))?;
+            let kb =
+                open_knowledge_base(embedder, embed_model.clone(), cli.kb_collection.as_deref());
+
+            let outcome = browse::run(&query, response.results, &client, kb.as_ref())
+                .await
+                .context("Results browser failed")?;
 
-            if !no_preview {
-                markdown_preview::open_markdown_in_browser(&result)
-                    .context("Failed to open browser")?;
-                println!("\n📝 Opened result in browser");
+            if let browse::Outcome::SendToAgent(context) = outcome {
+                let options = agent::AgentOptions {
+                    model,
+                    max_iterations: 50,
+                    show_thinking: false,
+                    enable_reasoning: true,
+                };
+                let agent_query = format!("{}\n\n{}", query, context);
+                let agent = build_agent(
+                    client,
+                    Box::new(search),
+                    ollama_url,
+                    embed_model,
+                    &sources,
+                    options,
+                )?;
+                run_agent(
+                    &agent,
+                    history,
+                    &agent_query,
+                    cli.no_preview,
+                    renderer.as_ref(),
+                    &outputs,
+                )
+                .await?;
+            }
+        }
+        Some(Command::Watch {
+            query,
+            interval,
+            agent,
+            once,
+            html,
+            model,
+            ollama_url,
+            embed_model,
+        }) => {
+            if cli.private {
+                anyhow::bail!("Watch mode stores snapshots and cannot run with --private");
+            }
+            if cli.dry_run {
+                anyhow::bail!("Watch mode compares real results and cannot run with --dry-run");
+            }
+            let store = store.context("Watch mode requires the local store")?;
+            let runner = if agent {
+                let options = agent::AgentOptions {
+                    model,
+                    max_iterations: 50,
+                    show_thinking: false,
+                    enable_reasoning: true,
+                };
+                let agent = build_agent(
+                    client,
+                    Box::new(search),
+                    ollama_url,
+                    embed_model,
+                    &sources,
+                    options,
+                )?;
+                watch::Runner::Agent(Box::new(agent))
+            } else {
+                watch::Runner::Search(Box::new(search))
+            };
+            let color = terminal::color_enabled(cli.no_color);
+            run_watch(
+                &store,
+                &runner,
+                &query,
+                interval,
+                once,
+                html.as_deref(),
+                color,
+            )
+            .await?;
+        }
+        Some(Command::Daemon {
+            ref model,
+            ref ollama_url,
+            ref embed_model,
+        }) => {
+            if cli.private {
+                anyhow::bail!(
+                    "The daemon writes reports and history and cannot run with --private"
+                );
+            }
+            if cli.dry_run {
+                anyhow::bail!("The daemon writes real reports and cannot run with --dry-run");
+            }
+            let defaults = DaemonDefaults {
+                model: model.clone(),
+                ollama_url: ollama_url.clone(),
+                embed_model: embed_model.clone(),
+            };
+            run_daemon(&cli, settings, store.as_ref(), &config, &client, &defaults).await?;
+        }
+        Some(Command::Pipeline {
+            command:
+                PipelineCommand::Run {
+                    ref file,
+                    ref vars,
+                    ref model,
+                    ref ollama_url,
+                    ref embed_model,
+                    summarizer,
+                },
+        }) => {
+            let plan = pipeline::Pipeline::load(file).context("Failed to load pipeline")?;
+            if cli.dry_run {
+                for (id, step) in plan.step_ids().iter().zip(&plan.steps) {
+                    println!(
+                        "{}[dry-run] {} ({}) not executed",
+                        Glyph::DryRun,
+                        id,
+                        step.action.kind()
+                    );
+                }
+                return Ok(());
+            }
+            let summary_sources = match summarizer {
+                Some(summarizer) => summary_sources(&sources, &settings, summarizer),
+                None => sources.clone(),
+            };
+            let summary_model = summary_sources.model(model);
+            let model = sources.model(model);
+            let summarize = plan.uses("summarize") && summarizer != Some(Summarizer::Extractive);
+            if plan.uses("agent") {
+                ensure_ollama_reachable(&sources, ollama_url, [&model]).await?;
+            }
+            if summarize {
+                ensure_ollama_reachable(&summary_sources, ollama_url, [&summary_model]).await?;
+            }
+            let researcher = if plan.uses("agent") {
+                let options = agent::AgentOptions {
+                    model: model.clone(),
+                    max_iterations: 50,
+                    show_thinking: false,
+                    enable_reasoning: true,
+                };
+                Some(build_agent(
+                    client.clone(),
+                    Box::new(build_search(
+                        &cli,
+                        &sources,
+                        store.as_ref(),
+                        &config,
+                        &client,
+                    )?),
+                    ollama_url.clone(),
+                    embed_model.clone(),
+                    &sources,
+                    options,
+                )?)
             } else {
-                println!("\n📝 Final Answer:\n{}", result);
+                None
+            };
+            let chat = if summarize {
+                Some(chat_backend(&summary_sources, ollama_url.clone(), None)?)
+            } else {
+                None
+            };
+            let tools = pipeline::Tools {
+                search: &search,
+                client: &client,
+                researcher: researcher.as_ref().map(|agent| agent as &dyn Researcher),
+                chat: chat.as_deref().map(|chat| (chat, summary_model.as_str())),
+                summarizer,
+            };
+            let outputs = pipeline::run(&plan, vars, &tools)
+                .await
+                .context("Pipeline failed")?;
+
+            let ends_with_export = matches!(
+                plan.steps.last().map(|step| &step.action),
+                Some(pipeline::Action::Export { .. })
+            );
+            if cli.json {
+                let outputs: serde_json::Map<String, serde_json::Value> = outputs
+                    .into_iter()
+                    .map(|(id, output)| Ok((id, serde_json::to_value(output)?)))
+                    .collect::<Result<_>>()?;
+                println!("{}", serde_json::to_string_pretty(&outputs)?);
+            } else if let Some((_, output)) = outputs.last().filter(|_| !ends_with_export) {
+                println!("\n{}", text::sanitize(&output.text));
             }
         }
+        Some(Command::Feedback { .. })
+        | Some(Command::Arxiv { .. })
+        | Some(Command::Feed { .. })
+        | Some(Command::Summarize(_))
+        | Some(Command::Tldr(_))
+        | Some(Command::History(_))
+        | Some(Command::Diff { .. })
+        | Some(Command::Export { .. })
+        | Some(Command::Kb(_))
+        | Some(Command::Bookmark { .. })
+        | Some(Command::Permissions { .. })
+        | Some(Command::Jobs { .. })
+        | Some(Command::Alias { .. })
+        | Some(Command::Clean { .. })
+        | Some(Command::Cache { .. })
+        | Some(Command::Sessions { .. })
+        | Some(Command::Usage)
+        | Some(Command::Stats { .. })
+        | Some(Command::Completions { .. })
+        | Some(Command::Template { .. }) => {
+            unreachable!("handled before client setup")
+        }
         None => {
             let query = cli.get_query().context(
                 "Query required. Use 'weavex <query>' or 'weavex --help' for usage information",
             )?;
 
             info!("Searching for: {}", query);
-            let response = client
-                .search(query)
-                .await
-                .context("Search request failed")?;
+            info!("Using search provider: {}", search.name());
+            if cli.dry_run {
+                println!(
+                    "{}[dry-run] {} search not executed: {}",
+                    Glyph::DryRun,
+                    search.name(),
+                    query
+                );
+                return Ok(());
+            }
+            let page_size = config.max_results.unwrap_or(provider::DEFAULT_PAGE_SIZE);
+            let offset = cli.result_offset(page_size);
+            let response = match search.search_page(query, config.max_results, offset).await {
+                Err(e @ error::OllamaError::BudgetExceeded { .. }) if usage::degrades() => {
+                    cached_search(store.as_ref(), query, e)?
+                }
+                result => result.with_context(|| i18n::tr("error-search"))?,
+            };
+
+            let previous = previous_run(
+                history.filter(|_| cli.diff_last),
+                history::Kind::Search,
+                query,
+            );
+            if !cli.offline {
+                record_search(history, query, &response);
+            }
+            report_if_empty(&cli, response.results.is_empty());
+
+            let topics = if cli.cluster {
+                cluster_results(&settings, query, &response.results).await
+            } else {
+                Vec::new()
+            };
+            let target: &dyn Renderer = if cli.no_preview {
+                renderer.as_ref()
+            } else {
+                &Html
+            };
+            let output = if topics.is_empty() {
+                target.search_results(&response)
+            } else {
+                target.clustered_results(&topics)
+            };
 
             if !cli.no_preview {
-                let mut markdown = format!(
-                    "# Search Results\n\nFound {} results:\n\n",
-                    response.results.len()
-                );
-                for (idx, result) in response.results.iter().enumerate() {
-                    markdown.push_str(&format!("## {}. {}\n\n", idx + 1, result.title));
-                    markdown.push_str(&format!("**URL:** [{}]({})\n\n", result.url, result.url));
-                    markdown.push_str(&format!("{}\n\n", result.content));
+                if open_preview(&output, cli.embed_images).await? {
+                    println!("{}{}", Glyph::Research, i18n::tr("opened-results"));
                 }
-                markdown_preview::open_markdown_in_browser(&markdown)
-                    .context("Failed to open browser")?;
-                println!("🔍 Opened results in browser");
             } else {
-                let output = format_search_results(&response, cli.json);
                 println!("{}", output);
             }
+
+            if cli.diff_last {
+                diff_with_previous(
+                    DiffView::new(&cli),
+                    previous.as_ref(),
+                    &Markdown.search_results(&response),
+                )?;
+            }
+            if cli.copy {
+                copy_to_clipboard(&if topics.is_empty() {
+                    Markdown.search_results(&response)
+                } else {
+                    Markdown.clustered_results(&topics)
+                });
+            }
+
+            let interactive = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+            if cli.paginate && cli.no_preview && !cli.json && interactive {
+                next_pages(
+                    &search,
+                    renderer.as_ref(),
+                    query,
+                    page_size,
+                    offset,
+                    &response,
+                )
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn next_pages(
+    search: &provider::MultiProvider,
+    renderer: &dyn Renderer,
+    query: &str,
+    page_size: usize,
+    mut offset: usize,
+    shown: &client::SearchResponse,
+) -> Result<()> {
+    let mut seen: std::collections::HashSet<String> = shown
+        .results
+        .iter()
+        .map(|result| dedup::key(&result.url))
+        .collect();
+    while !shown.results.is_empty() {
+        print!("{} ", i18n::tr("next-page-prompt"));
+        std::io::stdout().flush()?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !answer.trim().eq_ignore_ascii_case("n") {
+            return Ok(());
+        }
+
+        offset += page_size;
+        let mut response = search
+            .search_page(query, Some(page_size), offset)
+            .await
+            .with_context(|| i18n::tr("error-search"))?;
+        response
+            .results
+            .retain(|result| seen.insert(dedup::key(&result.url)));
+        if response.results.is_empty() {
+            println!("{}", i18n::tr("no-more-results"));
+            return Ok(());
+        }
+        println!("{}", renderer.search_results(&response));
+    }
+    Ok(())
+}
+
+fn run_alias(settings: &Settings, command: &AliasCommand) -> Result<()> {
+    match command {
+        AliasCommand::List => {
+            if settings.aliases.is_empty() {
+                println!("No aliases. Add one with 'weavex alias add'.");
+            }
+            for alias in &settings.aliases {
+                let kind = if alias.agent { "agent" } else { "search" };
+                println!(
+                    "{:<16} {:<6}  {} {}",
+                    alias.name,
+                    kind,
+                    alias.query,
+                    alias.flags.join(" ")
+                );
+            }
+        }
+        AliasCommand::Add {
+            name,
+            query,
+            agent,
+            flags,
+        } => {
+            aliases::validate_name(name)?;
+            let alias = settings::Alias {
+                name: name.clone(),
+                query: query.clone(),
+                agent: *agent,
+                flags: flags.clone(),
+            };
+            let path = settings::add_alias(&alias).context("Failed to add alias")?;
+            println!(
+                "{}Added alias '{}' to {}",
+                Glyph::Saved,
+                name,
+                path.display()
+            );
+        }
+        AliasCommand::Remove { name } => {
+            if !settings::remove_alias(name).context("Failed to remove alias")? {
+                anyhow::bail!("No alias named '{}'", name);
+            }
+            println!("{}Removed alias '{}'", Glyph::Removed, name);
         }
     }
+    Ok(())
+}
 
+fn run_template_list() -> Result<()> {
+    let dir =
+        templates::dir().context("Could not determine config directory. Set WEAVEX_CONFIG.")?;
+    let templates = templates::list(&dir).context("Failed to read templates")?;
+    if templates.is_empty() {
+        println!("No templates in {}", dir.display());
+    }
+    for template in templates {
+        let kind = if template.agent { "agent" } else { "search" };
+        println!(
+            "{:<20} {:<6}  {}",
+            template.name,
+            kind,
+            template.description.as_deref().unwrap_or(&template.query)
+        );
+        let variables = template.variables();
+        if !variables.is_empty() {
+            println!("{:<20} vars: {}", "", variables.join(", "));
+        }
+    }
     Ok(())
 }
 
-fn init_logging(verbose: bool) {
-    let filter = if verbose {
-        EnvFilter::new("weavex=debug,info")
+fn expand_template(name: &str, vars: &[(String, String)], flags: &[String]) -> Result<Cli> {
+    let dir =
+        templates::dir().context("Could not determine config directory. Set WEAVEX_CONFIG.")?;
+    let template = templates::load(&dir, name)?;
+    let args = template.args(vars, flags)?;
+    let cli = Cli::try_parse_from(std::iter::once("weavex".to_string()).chain(args))
+        .with_context(|| format!("Template '{}' expands to invalid arguments", name))?;
+    if matches!(cli.command, Some(Command::Template { .. })) {
+        anyhow::bail!("Template '{}' cannot run another template", name);
+    }
+    Ok(cli)
+}
+
+fn uses_mock(cli: &Cli) -> bool {
+    cli.providers.contains(&ProviderKind::Mock)
+}
+
+fn resolve_api_key(cli: &Cli) -> Result<String> {
+    cli.api_key
+        .clone()
+        .or_else(|| std::env::var("OLLAMA_API_KEY").ok())
+        .or_else(|| uses_mock(cli).then(|| "mock".to_string()))
+        .context("API key not found. Set OLLAMA_API_KEY environment variable or use --api-key flag.\nGet your key at: https://ollama.com")
+}
+
+fn build_config(cli: &Cli, settings: &Settings) -> Result<Config> {
+    let mut config = Config::new(resolve_api_key(cli)?);
+    let timeouts = &settings.timeouts;
+    let secs = |setting: Option<u64>, default: Duration| {
+        cli.timeout
+            .or(setting)
+            .map_or(default, |secs| Duration::from_secs(secs.max(1)))
+    };
+    config = if runs_agent(cli) {
+        config.with_timeout(secs(timeouts.agent, config::DEFAULT_AGENT_TIMEOUT))
     } else {
-        EnvFilter::new("weavex=warn")
+        config
+            .with_search_timeout(secs(timeouts.search, config::DEFAULT_SEARCH_TIMEOUT))
+            .with_fetch_timeout(secs(timeouts.fetch, config::DEFAULT_FETCH_TIMEOUT))
     };
+    if let Some(secs) = cli.connect_timeout.or(settings.network.connect_timeout) {
+        config = config.with_connect_timeout(Duration::from_secs(secs.max(1)));
+    }
 
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
-        .without_time()
-        .init();
+    if let Some(max_results) = cli.max_results {
+        config = config.with_max_results(max_results);
+    }
+    if let Some(read_timeout) = cli.read_timeout {
+        config = config.with_read_timeout(Duration::from_secs(read_timeout));
+    }
+
+    Ok(config.with_raw(cli.raw))
+}
+
+fn runs_agent(cli: &Cli) -> bool {
+    matches!(
+        cli.command,
+        Some(Command::Agent { .. })
+            | Some(Command::Trail { .. })
+            | Some(Command::Watch { agent: true, .. })
+            | Some(Command::Daemon { .. })
+            | Some(Command::Sessions { .. })
+    )
+}
+
+fn build_client(cli: &Cli, config: &Config) -> Result<OllamaClient> {
+    let client =
+        OllamaClient::new(config.clone()).with_context(|| i18n::tr("error-ollama-client"))?;
+    if !uses_mock(cli) {
+        return Ok(client);
+    }
+    let fixtures = mock::Fixtures::from_env().context("Failed to locate mock fixtures")?;
+    Ok(client.with_fixtures(fixtures))
+}
+
+fn build_network(cli: &Cli, settings: &Settings) -> Result<network::NetworkOptions> {
+    let mut options = network::NetworkOptions {
+        prefer_ipv4: cli.prefer_ipv4 || settings.network.prefer_ipv4,
+        private: cli.private,
+        offline: cli.offline,
+        ..Default::default()
+    };
+    if let Some(secs) = cli.dns_timeout.or(settings.network.dns_timeout) {
+        options.dns_timeout = Duration::from_secs(secs.max(1));
+    }
+    if let Some(secs) = cli.connect_timeout.or(settings.network.connect_timeout) {
+        options.connect_timeout = Duration::from_secs(secs.max(1));
+    }
+
+    for entry in &settings.network.resolve {
+        let (host, ip) = network::parse_resolve(entry)
+            .map_err(|e| anyhow::anyhow!("Invalid [network] resolve entry: {}", e))?;
+        options = options.with_override(&host, ip);
+    }
+    for (host, ip) in &cli.resolve {
+        options = options.with_override(host, *ip);
+    }
+    options.proxy = match (&cli.proxy, &settings.network.proxy) {
+        (Some(proxy), _) => Some(proxy.clone()),
+        (None, Some(proxy)) => Some(
+            network::parse_proxy(proxy)
+                .map_err(|e| anyhow::anyhow!("Invalid [network] proxy: {}", e))?,
+        ),
+        (None, None) => None,
+    };
+    if let Some(path) = cli
+        .ca_bundle
+        .as_ref()
+        .or(settings.network.ca_bundle.as_ref())
+    {
+        options.ca_certs = network::load_ca_bundle(path)
+            .map_err(|e| anyhow::anyhow!("Invalid CA bundle: {}", e))?;
+    }
+    options.insecure = cli.insecure || settings.network.insecure;
+    Ok(options)
+}
+
+fn enable_audit(cli: &Cli, settings: &Settings) -> Result<()> {
+    let path = match (&cli.audit_log, &settings.audit.path) {
+        (Some(Some(path)), _) => path.clone(),
+        (None, Some(path)) => path.clone(),
+        (Some(None), _) | (None, None) if cli.audit_log.is_some() || settings.audit.enabled => {
+            audit::default_path()?
+        }
+        _ => return Ok(()),
+    };
+    audit::enable(&path, cli.private)
+        .with_context(|| format!("Failed to open audit log {}", path.display()))
+}
+
+fn enable_vcr() -> Result<()> {
+    let Some(mode) = vcr::Mode::parse(&std::env::var("WEAVEX_VCR").unwrap_or_default())? else {
+        return Ok(());
+    };
+    let path = match std::env::var_os("WEAVEX_CASSETTE") {
+        Some(path) => std::path::PathBuf::from(path),
+        None => store::data_dir()?.join("cassette.jsonl"),
+    };
+    vcr::enable(mode, &path)
+        .with_context(|| format!("Failed to open HTTP cassette {}", path.display()))
+}
+
+fn enable_usage(settings: &Settings) {
+    match Store::open() {
+        Ok(store) => usage::enable(store, settings.budget.clone()),
+        Err(e) => warn!("Usage tracking unavailable: {}", e),
+    }
+}
+
+fn cached_search(
+    store: Option<&Store>,
+    query: &str,
+    budget: error::OllamaError,
+) -> Result<client::SearchResponse> {
+    let results = match store {
+        Some(store) => feedback::seen_for_query(store, query)?,
+        None => Vec::new(),
+    };
+    usage::cache_lookup(!results.is_empty());
+    if results.is_empty() {
+        return Err(budget).context("No cached results for this query");
+    }
+    println!(
+        "{}{}; showing {} cached result(s) from earlier searches\n",
+        Glyph::Warning,
+        budget,
+        results.len()
+    );
+    Ok(client::SearchResponse { results })
+}
+
+fn cached_page(store: Option<&Store>, url: &str) -> Result<client::FetchResponse> {
+    let cached = match store {
+        Some(store) => offline::page(store, url)?,
+        None => None,
+    };
+    usage::cache_lookup(cached.is_some());
+    let (response, fetched) = cached.with_context(|| {
+        format!(
+            "No cached copy of {} is available offline; fetch it once without --offline",
+            url
+        )
+    })?;
+    eprintln!(
+        "{}Offline: showing the copy cached {}\n",
+        Glyph::Save,
+        fetched
+    );
+    Ok(response)
+}
+
+async fn cluster_results(
+    settings: &Settings,
+    query: &str,
+    results: &[client::SearchResult],
+) -> Vec<cluster::Topic> {
+    if results.len() < cluster::MIN_CLUSTERED_RESULTS {
+        info!("Too few results to cluster: {}", results.len());
+        return Vec::new();
+    }
+    let ollama = match OllamaLocal::new(settings.cluster.ollama_url.clone()) {
+        Ok(ollama) => ollama,
+        Err(e) => {
+            warn!("Result clustering unavailable: {}", e);
+            return Vec::new();
+        }
+    };
+    let clusterer = cluster::Clusterer::new(
+        ollama,
+        settings
+            .cluster
+            .embed_model
+            .clone()
+            .unwrap_or_else(|| kb::DEFAULT_EMBED_MODEL.to_string()),
+        settings
+            .cluster
+            .model
+            .clone()
+            .unwrap_or_else(|| cluster::DEFAULT_LABEL_MODEL.to_string()),
+    );
+    clusterer.cluster(query, results).await.unwrap_or_else(|e| {
+        warn!("Failed to cluster results, showing them unsorted: {}", e);
+        Vec::new()
+    })
+}
+
+fn build_search(
+    cli: &Cli,
+    sources: &AgentSources,
+    store: Option<&Store>,
+    config: &Config,
+    client: &OllamaClient,
+) -> Result<provider::MultiProvider> {
+    let domain_scores = store
+        .and_then(|store| feedback::domain_scores(store).ok())
+        .unwrap_or_default();
+    let bookmarked_domains = store
+        .and_then(|store| bookmarks::domains(store).ok())
+        .unwrap_or_default();
+    let providers = if cli.offline {
+        let store = Store::open().context("Offline mode needs the local cache")?;
+        provider::MultiProvider::new(vec![Box::new(offline::CachedSearch::new(store))], false)
+    } else {
+        provider::build(&cli.providers, cli.blend, config, client)
+            .context("Failed to configure search providers")?
+    };
+    Ok(providers
+        .with_domain_scores(domain_scores)
+        .with_bookmarks(bookmarked_domains, cli.bookmark_mode)
+        .with_explain(cli.explain)
+        .with_min_score(cli.min_score)
+        .with_reranker(build_reranker(cli, &sources.rerank)))
+}
+
+fn build_reranker(cli: &Cli, settings: &RerankSettings) -> Option<rerank::Reranker> {
+    if !cli.rerank {
+        return None;
+    }
+    let ollama = OllamaLocal::new(settings.ollama_url.clone())
+        .map_err(|e| warn!("Result reranking unavailable: {}", e))
+        .ok()?;
+    let embed_model = settings
+        .embed_model
+        .clone()
+        .unwrap_or_else(|| kb::DEFAULT_EMBED_MODEL.to_string());
+    Some(rerank::Reranker::new(ollama, embed_model))
+}
+
+fn open_knowledge_base(
+    embedder: OllamaLocal,
+    embed_model: String,
+    collection: Option<&str>,
+) -> Option<KnowledgeBase> {
+    let store = Store::open()
+        .map_err(|e| warn!("Knowledge base unavailable: {}", e))
+        .ok()?;
+    let kb = KnowledgeBase::new(store, embedder, embed_model);
+    Some(match collection {
+        Some(collection) => kb.with_collection(collection),
+        None => kb,
+    })
+}
+
+#[derive(Clone)]
+struct AgentSources {
+    kb_collection: Option<String>,
+    learn: Option<String>,
+    github_token: Option<String>,
+    chat_timeout: Duration,
+    file_roots: Vec<std::path::PathBuf>,
+    grants: Vec<Capability>,
+    notes_file: Option<std::path::PathBuf>,
+    vision_model: Option<String>,
+    dry_run: bool,
+    max_domains: Option<usize>,
+    limits: Limits,
+    private: bool,
+    offline: bool,
+    language: Option<AnswerLanguage>,
+    translate_sources: bool,
+    prefer_credible: bool,
+    rerank: RerankSettings,
+    backend: Backend,
+    cloud: CloudSettings,
+    ollama_account: Option<Config>,
+    react_models: Vec<String>,
+}
+
+impl AgentSources {
+    fn new(cli: &Cli, settings: &Settings) -> Self {
+        Self {
+            kb_collection: cli.kb_collection.clone(),
+            learn: cli.learn.clone(),
+            github_token: settings.github.token(),
+            chat_timeout: Duration::from_secs(cli.chat_timeout),
+            file_roots: cli.allow_files.clone(),
+            grants: cli.grant.clone(),
+            notes_file: cli.notes_file.clone(),
+            vision_model: cli.vision_model.clone(),
+            dry_run: cli.dry_run,
+            max_domains: cli.max_domains.map(|n| n as usize),
+            limits: limits(cli, settings),
+            private: cli.private,
+            offline: cli.offline,
+            language: cli.lang.clone(),
+            translate_sources: cli.translate_sources,
+            prefer_credible: cli.prefer_credible || settings.credibility.prefer,
+            rerank: settings.rerank.clone(),
+            backend: cli.backend,
+            cloud: match cli.backend {
+                Backend::Openai => settings.openai.clone(),
+                Backend::Anthropic => settings.anthropic.clone(),
+                Backend::Ollama => CloudSettings::default(),
+            },
+            ollama_account: build_config(cli, settings).ok(),
+            react_models: Vec::new(),
+        }
+    }
+
+    fn model(&self, requested: &str) -> String {
+        self.backend.model(self.cloud.model.as_deref(), requested)
+    }
+
+    fn cloud_routed(&self, model: &str) -> bool {
+        self.ollama_account.is_some() && ollama_local::cloud_model(model).is_some()
+    }
+}
+
+struct AnswerOutputs {
+    webhooks: Vec<Webhook>,
+    desktop_notify: bool,
+    speaker: Option<speech::Speaker>,
+    diff_last: Option<DiffView>,
+    citations: Option<(citations::CitationFormat, std::path::PathBuf)>,
+    copy: bool,
+    epub: Option<(std::path::PathBuf, String)>,
+    embed_images: bool,
+}
+
+impl AnswerOutputs {
+    fn new(cli: &Cli, settings: &Settings) -> Self {
+        Self {
+            webhooks: webhook::targets(&cli.notify_webhooks, &settings.webhooks),
+            desktop_notify: cli.notify,
+            speaker: cli
+                .speak
+                .then(|| speech::Speaker::new(settings.speech.command.as_deref())),
+            diff_last: cli.diff_last.then(|| DiffView::new(cli)),
+            copy: cli.copy,
+            embed_images: cli.embed_images,
+            epub: cli.epub.clone().map(|path| {
+                let language = cli.lang.as_ref().map(ToString::to_string);
+                (path, language.unwrap_or_else(|| "en".to_string()))
+            }),
+            citations: cli.citations.map(|format| {
+                let path = cli
+                    .citations_file
+                    .clone()
+                    .unwrap_or_else(|| format.default_file().into());
+                (format, path)
+            }),
+        }
+    }
+}
+
+fn build_agent(
+    client: OllamaClient,
+    search: Box<dyn SearchProvider>,
+    ollama_url: String,
+    embed_model: String,
+    sources: &AgentSources,
+    options: agent::AgentOptions,
+) -> Result<agent::Agent> {
+    info!("Starting agent with model: {}", options.model);
+    if !events::enabled() {
+        println!(
+            "{}{}\n",
+            Glyph::Agent,
+            i18n::tr_args("initializing-agent", &[("model", &options.model)])
+        );
+    }
+    assemble_agent(
+        client,
+        search,
+        ollama_url,
+        embed_model,
+        sources,
+        options,
+        None,
+    )
+}
+
+fn build_orchestrator(
+    client: OllamaClient,
+    searches: Vec<provider::MultiProvider>,
+    ollama_url: String,
+    embed_model: String,
+    sources: &AgentSources,
+    options: agent::AgentOptions,
+    planner_model: Option<String>,
+) -> Result<orchestrate::Orchestrator> {
+    let planner_model = planner_model.unwrap_or_else(|| options.model.clone());
+    info!(
+        "Starting {} workers with model {} and planner {}",
+        searches.len(),
+        options.model,
+        planner_model
+    );
+    println!(
+        "{}{}\n",
+        Glyph::Agent,
+        i18n::tr_args("initializing-agent", &[("model", &options.model)])
+    );
+
+    let workers = searches
+        .into_iter()
+        .map(|search| {
+            assemble_agent(
+                client.clone(),
+                Box::new(search),
+                ollama_url.clone(),
+                embed_model.clone(),
+                sources,
+                agent::AgentOptions {
+                    model: options.model.clone(),
+                    ..options
+                },
+                None,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let planner = chat_backend(sources, ollama_url, None)?;
+    Ok(
+        orchestrate::Orchestrator::new(planner, planner_model, workers)
+            .with_reasoning(options.enable_reasoning),
+    )
+}
+
+fn build_consensus(
+    client: OllamaClient,
+    runs: Vec<(provider::MultiProvider, String)>,
+    ollama_url: String,
+    embed_model: String,
+    sources: &AgentSources,
+    options: agent::AgentOptions,
+) -> Result<consensus::Consensus> {
+    info!(
+        "Starting {} sampled runs reconciled by {}",
+        runs.len(),
+        options.model
+    );
+    println!(
+        "{}{}\n",
+        Glyph::Agent,
+        i18n::tr_args("initializing-agent", &[("model", &options.model)])
+    );
+
+    let samples = runs
+        .into_iter()
+        .zip(1u64..)
+        .map(|((search, model), seed)| {
+            let agent = assemble_agent(
+                client.clone(),
+                Box::new(search),
+                ollama_url.clone(),
+                embed_model.clone(),
+                sources,
+                agent::AgentOptions {
+                    model: model.clone(),
+                    show_thinking: false,
+                    ..options
+                },
+                Some(seed),
+            )?;
+            Ok(consensus::Sample {
+                label: format!("{}, seed {}", model, seed),
+                agent,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let reconciler = chat_backend(sources, ollama_url, None)?;
+    Ok(
+        consensus::Consensus::new(reconciler, options.model, samples)
+            .with_reasoning(options.enable_reasoning),
+    )
+}
+
+async fn ensure_ollama_reachable<'a>(
+    sources: &AgentSources,
+    ollama_url: &str,
+    models: impl IntoIterator<Item = &'a String>,
+) -> Result<()> {
+    if sources.backend != Backend::Ollama
+        || vcr::replaying()
+        || models.into_iter().all(|model| sources.cloud_routed(model))
+    {
+        return Ok(());
+    }
+
+    let result = match OllamaLocal::new(Some(ollama_url.to_string())) {
+        Ok(ollama) => ollama.version().await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(version) => {
+            info!("Local Ollama {} reachable at {}", version, ollama_url);
+            Ok(())
+        }
+        Err(e) => {
+            let mut message = format!(
+                "{}\n   {}\n",
+                i18n::tr_args("ollama-unreachable", &[("url", ollama_url)]),
+                e
+            );
+            for hint in ["ollama-fix-serve", "ollama-fix-url", "ollama-fix-cloud"] {
+                message.push_str(&format!("\n   {}{}", Glyph::Bullet, i18n::tr(hint)));
+            }
+            Err(error::OllamaError::OllamaUnreachable(message).into())
+        }
+    }
+}
+
+async fn inspect_models(
+    sources: &AgentSources,
+    ollama_url: &str,
+    models: &[String],
+) -> Vec<String> {
+    let mut react_models = Vec::new();
+    if sources.backend != Backend::Ollama {
+        return react_models;
+    }
+    let Ok(ollama) = OllamaLocal::new(Some(ollama_url.to_string())) else {
+        return react_models;
+    };
+
+    for model in models {
+        if sources.cloud_routed(model) || react_models.contains(model) {
+            continue;
+        }
+        let info = match ollama.show(model).await {
+            Ok(info) => info,
+            Err(error::OllamaError::ApiError { status: 404, .. }) => {
+                warn!(
+                    "Model {} is not available on {}; pull it with: ollama pull {}",
+                    model, ollama_url, model
+                );
+                continue;
+            }
+            Err(e) => {
+                tracing::debug!("Could not inspect model {}: {}", model, e);
+                continue;
+            }
+        };
+
+        if info.supports_tools() == Some(false) {
+            warn!(
+                "Model {} does not declare tool support; using text-based ReAct tool calls instead",
+                model
+            );
+            react_models.push(model.clone());
+        }
+        if let Some(context) = info
+            .context_length()
+            .filter(|context| *context < sources.limits.min_context_tokens())
+        {
+            warn!(
+                "Model {} has a {}-token context window, but each tool result can use up to {} \
+                 characters; earlier findings will be cut off. Raise num_ctx or use a model \
+                 with a larger context",
+                model, context, sources.limits.tool_result
+            );
+        }
+    }
+    react_models
+}
+
+fn summary_sources(
+    sources: &AgentSources,
+    settings: &Settings,
+    summarizer: Summarizer,
+) -> AgentSources {
+    let mut summary_sources = sources.clone();
+    match summarizer {
+        Summarizer::Ollama => {
+            summary_sources.backend = Backend::Ollama;
+            summary_sources.cloud = CloudSettings::default();
+        }
+        Summarizer::Openai => {
+            summary_sources.backend = Backend::Openai;
+            summary_sources.cloud = settings.openai.clone();
+        }
+        Summarizer::Extractive => {}
+    }
+    summary_sources
+}
+
+fn chat_backend(
+    sources: &AgentSources,
+    ollama_url: String,
+    seed: Option<u64>,
+) -> Result<Box<dyn ChatBackend>> {
+    if sources.backend == Backend::Ollama {
+        let mut local_ollama = OllamaLocal::new(Some(ollama_url))
+            .with_context(|| i18n::tr("error-local-ollama-client"))?
+            .with_chat_timeout(sources.chat_timeout);
+        if let Some(seed) = seed {
+            local_ollama = local_ollama.with_seed(seed);
+        }
+        if let Some(account) = &sources.ollama_account {
+            local_ollama =
+                local_ollama.with_cloud(account.base_url.clone(), account.api_key.clone());
+        }
+        if !sources.react_models.is_empty() {
+            return Ok(Box::new(ReactChat::new(
+                Box::new(local_ollama),
+                sources.react_models.clone(),
+            )));
+        }
+        return Ok(Box::new(local_ollama));
+    }
+
+    let backend = sources.backend.name();
+    let api_key = sources
+        .cloud
+        .api_key(sources.backend.key_var())
+        .with_context(|| {
+            format!(
+                "The {} backend needs an API key. Set {} or api_key in the [{}] section of the config file",
+                backend,
+                sources.backend.key_var(),
+                backend
+            )
+        })?;
+    let mut cloud = CloudChat::new(
+        sources.backend,
+        api_key,
+        sources.cloud.base_url.clone(),
+        sources.cloud.model.clone(),
+    )
+    .with_context(|| format!("Failed to create {} client", backend))?
+    .with_chat_timeout(sources.chat_timeout);
+    if let Some(seed) = seed {
+        cloud = cloud.with_seed(seed);
+    }
+    Ok(Box::new(cloud))
+}
+
+fn with_web_tools(mut agent: agent::Agent, sources: &AgentSources) -> agent::Agent {
+    match wikipedia::WikipediaClient::new() {
+        Ok(wikipedia) => agent = agent.with_wikipedia(wikipedia),
+        Err(e) => warn!("Wikipedia lookup unavailable: {}", e),
+    }
+
+    match weather::WeatherClient::new() {
+        Ok(weather) => agent = agent.with_weather(weather),
+        Err(e) => warn!("Weather lookup unavailable: {}", e),
+    }
+
+    match arxiv::ArxivClient::new() {
+        Ok(arxiv) => agent = agent.with_arxiv(arxiv),
+        Err(e) => warn!("arXiv search unavailable: {}", e),
+    }
+
+    match github::GithubClient::new(sources.github_token.clone()) {
+        Ok(github) => agent = agent.with_github(github),
+        Err(e) => warn!("GitHub search unavailable: {}", e),
+    }
+
+    match discussions::DiscussionClient::new() {
+        Ok(discussions) => agent = agent.with_discussions(discussions),
+        Err(e) => warn!("Discussion search unavailable: {}", e),
+    }
+
+    match feed::FeedClient::new() {
+        Ok(feeds) => agent = agent.with_feeds(feeds),
+        Err(e) => warn!("Feed reading unavailable: {}", e),
+    }
+    agent
+}
+
+fn assemble_agent(
+    client: OllamaClient,
+    search: Box<dyn SearchProvider>,
+    ollama_url: String,
+    embed_model: String,
+    sources: &AgentSources,
+    options: agent::AgentOptions,
+    seed: Option<u64>,
+) -> Result<agent::Agent> {
+    let chat = chat_backend(sources, ollama_url.clone(), seed)?;
+    let local_ollama = OllamaLocal::new(Some(ollama_url))
+        .with_context(|| i18n::tr("error-local-ollama-client"))?
+        .with_chat_timeout(sources.chat_timeout);
+
+    let mut agent = agent::Agent::new(chat, client, search, options)
+        .with_dry_run(sources.dry_run)
+        .with_offline(sources.offline)
+        .with_language(sources.language.clone(), sources.translate_sources)
+        .with_prefer_credible(sources.prefer_credible)
+        .with_max_domains(sources.max_domains)
+        .with_limits(sources.limits);
+    if !sources.private {
+        match Store::open() {
+            Ok(store) => agent = agent.with_cache(store),
+            Err(e) => warn!("Cached results unavailable to the agent: {}", e),
+        }
+    }
+
+    if !sources.offline {
+        agent = with_web_tools(agent, sources);
+    }
+
+    if let Some(model) = &sources.vision_model {
+        let vision = vision::VisionClient::new().context("Failed to create image client")?;
+        agent = agent.with_vision(vision, model.clone());
+    }
+
+    if !sources.file_roots.is_empty() {
+        let files = files::FileSandbox::new(&sources.file_roots)
+            .context("Invalid --allow-files directory")?;
+        agent = agent.with_files(files);
+    }
+
+    let permissions = Store::open()
+        .and_then(|store| Permissions::load(&store, &sources.grants))
+        .unwrap_or_else(|e| {
+            warn!("Failed to load persisted permissions: {}", e);
+            Permissions::new(sources.grants.iter().copied())
+        });
+    agent = agent.with_permissions(permissions);
+
+    if let Some(path) = &sources.notes_file {
+        let scratchpad = scratchpad::Scratchpad::open(path)
+            .with_context(|| format!("Failed to open notes file {}", path.display()))?;
+        agent = agent.with_scratchpad(scratchpad);
+    }
+
+    if let Some(kb) = open_knowledge_base(
+        local_ollama.clone(),
+        embed_model.clone(),
+        sources.kb_collection.as_deref(),
+    ) {
+        if matches!(kb.is_empty(), Ok(false)) {
+            info!("Knowledge base available to the agent");
+            agent = agent.with_knowledge_base(kb);
+        }
+    }
+
+    if let Some(collection) = sources.learn.as_deref() {
+        if let Some(kb) = open_knowledge_base(local_ollama, embed_model, Some(collection)) {
+            info!("Learning fetched pages into collection {}", collection);
+            agent = agent.with_learning(kb);
+        }
+    }
+
+    Ok(agent)
+}
+
+fn preview_target(cli: &Cli, settings: &Settings) -> PreviewTarget {
+    if cli.print_preview_path {
+        return PreviewTarget::PrintPath;
+    }
+    match (&cli.browser, &settings.preview) {
+        (Some(browser), _) => PreviewTarget::parse(browser),
+        (None, preview) if preview.print_path => PreviewTarget::PrintPath,
+        (None, preview) => preview
+            .browser
+            .as_deref()
+            .map(PreviewTarget::parse)
+            .unwrap_or_default(),
+    }
+}
+
+async fn open_preview(html: &str, embed_images: bool) -> Result<bool> {
+    let html = if embed_images {
+        markdown_preview::embed_images(html).await
+    } else {
+        html.to_string()
+    };
+    markdown_preview::open_html_in_browser(&html).with_context(|| i18n::tr("error-open-browser"))
+}
+
+async fn run_agent(
+    agent: &dyn Researcher,
+    store: Option<&Store>,
+    query: &str,
+    no_preview: bool,
+    renderer: &dyn Renderer,
+    outputs: &AnswerOutputs,
+) -> Result<()> {
+    let streaming = events::enabled();
+    if !streaming {
+        println!(
+            "{}{}\n",
+            Glyph::Research,
+            i18n::tr_args("researching", &[("query", query)])
+        );
+    }
+
+    let result = agent.research(query).await;
+    if outputs.desktop_notify {
+        let outcome = result
+            .as_ref()
+            .map(String::clone)
+            .map_err(|e| e.to_string());
+        notify::agent_finished(query, &outcome);
+    }
+    let result = result.with_context(|| i18n::tr("error-agent"))?;
+    webhook::deliver(
+        &outputs.webhooks,
+        &webhook::Report {
+            query,
+            answer: &result,
+            job: None,
+        },
+    )
+    .await;
+
+    let previous = previous_run(
+        store.filter(|_| outputs.diff_last.is_some()),
+        history::Kind::Agent,
+        query,
+    );
+    let summary = result.lines().find(|line| !line.trim().is_empty());
+    record_history(
+        store,
+        history::Kind::Agent,
+        query,
+        summary.unwrap_or_default(),
+        &result,
+    );
+
+    if !streaming {
+        if !no_preview {
+            if open_preview(&Html.answer(query, &result), outputs.embed_images).await? {
+                println!("\n{}{}", Glyph::Note, i18n::tr("opened-answer"));
+            }
+        } else {
+            println!("\n{}", renderer.answer(query, &result));
+        }
+    }
+
+    if let Some(speaker) = &outputs.speaker {
+        if let Err(e) = speaker.speak(&result) {
+            warn!("Failed to speak the answer: {}", e);
+        }
+    }
+
+    if let Some(view) = outputs.diff_last {
+        diff_with_previous(view, previous.as_ref(), &result)?;
+    }
+
+    if outputs.copy {
+        copy_to_clipboard(&result);
+    }
+
+    if let Some((path, language)) = &outputs.epub {
+        epub::write(path, query, &result, language)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        status(format!(
+            "{}Saved the answer as {}",
+            Glyph::Export,
+            path.display()
+        ));
+    }
+
+    if let Some((format, path)) = &outputs.citations {
+        export_citations(*format, path)?;
+    }
+
+    Ok(())
+}
+
+fn copy_to_clipboard(text: &str) {
+    if clipboard::copy(text) {
+        status(format!("{}Copied to the clipboard", Glyph::Clipboard));
+    }
+}
+
+fn export_citations(format: citations::CitationFormat, path: &Path) -> Result<()> {
+    let sources = citations::take();
+    if sources.is_empty() {
+        status("No pages were read; no citations to export.".to_string());
+        return Ok(());
+    }
+    std::fs::write(path, citations::render(format, &sources))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    status(format!(
+        "{}Exported {} source(s) to {}",
+        Glyph::Export,
+        sources.len(),
+        path.display()
+    ));
+    Ok(())
+}
+
+fn status(message: String) {
+    if events::enabled() {
+        events::emit(Event::Notice { message: &message });
+    } else {
+        println!("{}", message);
+    }
+}
+
+fn terminal_renderer(cli: &Cli, settings: &Settings) -> Box<dyn Renderer> {
+    let color = !cli.json && terminal::color_enabled(cli.no_color);
+    formatter::for_terminal(
+        cli.json,
+        color,
+        limits(cli, settings),
+        cli.get_query(),
+        cli.verbose > 0,
+    )
+}
+
+fn limits(cli: &Cli, settings: &Settings) -> Limits {
+    Limits {
+        tool_result: cli
+            .tool_result_limit
+            .map(|n| n as usize)
+            .or(settings.limits.tool_result)
+            .unwrap_or(text::DEFAULT_TOOL_RESULT_LIMIT),
+        page: cli.page_limit.map(|n| n as usize).or(settings.limits.page),
+        preview: cli
+            .preview_limit
+            .map(|n| n as usize)
+            .or(settings.limits.preview),
+        snippet: cli
+            .snippet_length
+            .map(|n| n as usize)
+            .or(settings.limits.snippet_length),
+    }
+}
+
+fn record_search(store: Option<&Store>, query: &str, response: &client::SearchResponse) {
+    let Some(store) = store else {
+        return;
+    };
+
+    if let Err(e) = feedback::record_seen(store, query, &response.results) {
+        warn!("Failed to record search results: {}", e);
+    }
+
+    let titles: Vec<&str> = response
+        .results
+        .iter()
+        .take(3)
+        .map(|r| r.title.as_str())
+        .collect();
+    let summary = format!("{} results: {}", response.results.len(), titles.join("; "));
+    record_history(
+        Some(store),
+        history::Kind::Search,
+        query,
+        &summary,
+        &Markdown.search_results(response),
+    );
+}
+
+fn record_fetch(store: Option<&Store>, url: &str, response: &client::FetchResponse) {
+    let Some(store) = store else {
+        return;
+    };
+
+    match offline::record(store, url, response) {
+        Ok(id) => markdown_preview::set_session(id),
+        Err(e) => warn!("Failed to record history: {}", e),
+    }
+}
+
+fn record_history(
+    store: Option<&Store>,
+    kind: history::Kind,
+    query: &str,
+    summary: &str,
+    answer: &str,
+) {
+    if let Some(store) = store {
+        match history::record(store, kind, query, summary, answer) {
+            Ok(id) => markdown_preview::set_session(id),
+            Err(e) => warn!("Failed to record history: {}", e),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct DiffView {
+    json: bool,
+    color: bool,
+}
+
+impl DiffView {
+    fn new(cli: &Cli) -> Self {
+        Self {
+            json: cli.json,
+            color: !cli.json && terminal::color_enabled(cli.no_color),
+        }
+    }
+}
+
+fn previous_run(store: Option<&Store>, kind: history::Kind, query: &str) -> Option<history::Entry> {
+    history::latest(store?, kind, query)
+        .inspect_err(|e| warn!("Failed to read history: {}", e))
+        .ok()
+        .flatten()
+}
+
+fn diff_with_previous(
+    view: DiffView,
+    previous: Option<&history::Entry>,
+    current: &str,
+) -> Result<()> {
+    match previous {
+        Some(previous) => print_comparison(
+            view,
+            &format!("#{} ({}) → this run", previous.id, previous.timestamp()),
+            (Some(previous.id), None),
+            &previous.answer,
+            current,
+        ),
+        None if view.json => Ok(()),
+        None => {
+            status("No earlier run of this query to compare with.".to_string());
+            Ok(())
+        }
+    }
+}
+
+fn print_comparison(
+    view: DiffView,
+    heading: &str,
+    (old_id, new_id): (Option<i64>, Option<i64>),
+    old: &str,
+    new: &str,
+) -> Result<()> {
+    let comparison = diff::compare(old, new, view.color);
+    if view.json || events::enabled() {
+        let mut value = serde_json::to_value(&comparison)?;
+        value["old"] = old_id.into();
+        value["new"] = new_id.into();
+        if events::enabled() {
+            events::emit(Event::Diff { changes: &value });
+        } else {
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        return Ok(());
+    }
+
+    println!("\n{}Changes {}", Glyph::Changed, heading);
+    if comparison.is_empty() {
+        println!("No changes.");
+    } else {
+        println!("\n{}", text::sanitize(&comparison.render(view.color)));
+    }
+    Ok(())
+}
+
+fn run_export(id: Option<i64>, vault: &Path, tags: &[String], zettel: bool) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+    let entry = match id {
+        Some(id) => history::get(&store, id)
+            .context("Failed to read history")?
+            .with_context(|| format!("No history entry with id {}", id))?,
+        None => history::most_recent(&store, history::Kind::Agent)
+            .context("Failed to read history")?
+            .context("No agent answers in history yet")?,
+    };
+
+    let path = obsidian::write(vault, &entry, tags, zettel)
+        .with_context(|| format!("Failed to write a note to {}", vault.display()))?;
+    println!(
+        "{}Exported #{} to {}",
+        Glyph::Export,
+        entry.id,
+        path.display()
+    );
+    Ok(())
+}
+
+fn run_diff(cli: &Cli, old: i64, new: i64) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+    let [old, new] = [old, new].map(|id| {
+        history::get(&store, id)
+            .context("Failed to read history")?
+            .with_context(|| format!("No history entry with id {}", id))
+    });
+    let (old, new) = (old?, new?);
+
+    if old.kind != new.kind || old.query != new.query {
+        eprintln!(
+            "{}#{} ({} \"{}\") and #{} ({} \"{}\") are not runs of the same query",
+            Glyph::Warning,
+            old.id,
+            old.kind,
+            old.query,
+            new.id,
+            new.kind,
+            new.query
+        );
+    }
+    print_comparison(
+        DiffView::new(cli),
+        &format!(
+            "#{} ({}) → #{} ({}): {}",
+            old.id,
+            old.timestamp(),
+            new.id,
+            new.timestamp(),
+            new.query
+        ),
+        (Some(old.id), Some(new.id)),
+        &old.answer,
+        &new.answer,
+    )
+}
+
+fn run_history(cli: &Cli, args: &HistoryArgs) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+
+    if let Some(HistoryCommand::Show { id, preview }) = &args.command {
+        let entry = history::get(&store, *id)
+            .context("Failed to read history")?
+            .with_context(|| format!("No history entry with id {}", id))?;
+
+        if *preview {
+            markdown_preview::set_session(entry.id);
+            if markdown_preview::open_markdown_in_browser(&entry.answer)
+                .with_context(|| i18n::tr("error-open-browser"))?
+            {
+                println!(
+                    "{}Opened history entry #{} in browser",
+                    Glyph::Web,
+                    entry.id
+                );
+            }
+        } else {
+            println!(
+                "#{} [{}] {} — {}\n",
+                entry.id,
+                entry.kind,
+                entry.timestamp(),
+                entry.query
+            );
+            println!("{}", entry.answer);
+        }
+        return Ok(());
+    }
+
+    let entries = history::list(&store, args.term.as_deref(), args.limit)
+        .context("Failed to read history")?;
+
+    if entries.is_empty() {
+        report_if_empty(cli, true);
+        println!("No history entries found.");
+        return Ok(());
+    }
+
+    for entry in entries {
+        println!(
+            "#{:<5} {}  {:<6}  {}",
+            entry.id,
+            entry.timestamp(),
+            entry.kind,
+            entry.query
+        );
+        if !entry.summary.is_empty() {
+            println!("       {}", entry.summary);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_kb(cli: &Cli, settings: &Settings, args: &KbArgs) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+    let embedder = OllamaLocal::new(Some(args.ollama_url.clone()))
+        .with_context(|| i18n::tr("error-local-ollama-client"))?;
+    let kb = KnowledgeBase::new(store, embedder, args.embed_model.clone());
+    let kb = match &args.collection {
+        Some(collection) => kb.with_collection(collection),
+        None => kb,
+    };
+    let collection = args.collection.as_deref().unwrap_or(kb::DEFAULT_COLLECTION);
+
+    match &args.command {
+        KbCommand::Add {
+            source,
+            chunking,
+            chunk_size,
+            overlap,
+            title,
+            url,
+            fetched,
+        } => {
+            let (source, default_title, content, mut metadata) =
+                if source.starts_with("http://") || source.starts_with("https://") {
+                    let client = build_client(cli, &build_config(cli, settings)?)?;
+                    let response = client
+                        .fetch(source)
+                        .await
+                        .with_context(|| i18n::tr("error-fetch"))?;
+                    let title = if response.title.is_empty() {
+                        source.clone()
+                    } else {
+                        response.title
+                    };
+                    let metadata = kb::Metadata {
+                        url: Some(source.clone()),
+                        fetched_at: Some(store::now()),
+                    };
+                    (source.clone(), title, response.content, metadata)
+                } else {
+                    let path = Path::new(source);
+                    let content = std::fs::read_to_string(path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?;
+                    let title = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| source.clone());
+                    let source = std::fs::canonicalize(path)
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|_| source.clone());
+                    let metadata = kb::Metadata {
+                        url: None,
+                        fetched_at: modified_at(path),
+                    };
+                    (source, title, content, metadata)
+                };
+            if url.is_some() {
+                metadata.url = url.clone();
+            }
+            if fetched.is_some() {
+                metadata.fetched_at = *fetched;
+            }
+            let title = title.clone().unwrap_or(default_title);
+            let options = ChunkOptions {
+                strategy: *chunking,
+                size: *chunk_size,
+                overlap: *overlap,
+            };
+
+            println!(
+                "{}Embedding {} with {}...",
+                Glyph::Knowledge,
+                source,
+                args.embed_model
+            );
+            let added = kb
+                .add(&source, &title, &content, &metadata, &options)
+                .await
+                .context("Failed to add to knowledge base")?;
+            if added.reused == added.chunks {
+                println!("{}{} is already up to date", Glyph::Knowledge, source);
+            } else {
+                println!(
+                    "{}Added {} chunk(s) from {} to {} ({} reused)",
+                    Glyph::Knowledge,
+                    added.chunks,
+                    source,
+                    collection,
+                    added.reused
+                );
+            }
+        }
+        KbCommand::List => {
+            let documents = kb.documents().context("Failed to read knowledge base")?;
+            if documents.is_empty() {
+                println!("The knowledge base is empty. Add documents with 'weavex kb add'.");
+            }
+            for document in documents {
+                println!(
+                    "#{:<5} {}  {:>3} chunk(s)  {}",
+                    document.id,
+                    store::format_timestamp(document.added_at),
+                    document.chunks,
+                    document.title
+                );
+                println!("       {}", document.source);
+                if let Some(url) = document.url.filter(|url| *url != document.source) {
+                    println!("       {}", url);
+                }
+                let mut details = vec![format!("collection {}", document.collection)];
+                if let Some(fetched_at) = document.fetched_at {
+                    details.push(format!("fetched {}", store::format_timestamp(fetched_at)));
+                }
+                if let Some(chunking) = document.chunking {
+                    details.push(format!("chunking {}", chunking));
+                }
+                println!("       {}", details.join(" · "));
+            }
+        }
+        KbCommand::Remove { source } => {
+            let source = std::fs::canonicalize(source)
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|_| source.clone());
+            if kb
+                .remove(&source)
+                .context("Failed to remove from knowledge base")?
+            {
+                println!("{}Removed {}", Glyph::Removed, source);
+            } else {
+                println!("No document from {} in the knowledge base.", source);
+            }
+        }
+        KbCommand::Collections => {
+            let collections = kb.collections().context("Failed to read knowledge base")?;
+            if collections.is_empty() {
+                println!("The knowledge base is empty. Add documents with 'weavex kb add'.");
+            }
+            for collection in collections {
+                println!(
+                    "{:<20} {:>4} document(s)  {:>5} chunk(s)",
+                    collection.name, collection.documents, collection.chunks
+                );
+            }
+        }
+        KbCommand::Compact => {
+            let compaction = kb.compact().context("Failed to compact knowledge base")?;
+            println!(
+                "{}Removed {} stale index entries; {} chunk(s) remain",
+                Glyph::Sweep,
+                compaction.orphans,
+                compaction.chunks
+            );
+            if compaction.clusters > 0 {
+                println!(
+                    "   Vector index rebuilt with {} cluster(s)",
+                    compaction.clusters
+                );
+            } else {
+                println!(
+                    "   Using exhaustive search (an index is built past {} chunks)",
+                    kb::INDEX_THRESHOLD
+                );
+            }
+        }
+        KbCommand::Query { query, limit } => {
+            let passages = kb
+                .search(query, *limit)
+                .await
+                .context("Knowledge base query failed")?;
+            report_if_empty(cli, passages.is_empty());
+            println!("{}", kb::format_passages(&passages, cli.explain));
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_arxiv(cli: &Cli, query: &str, limit: usize, sort: arxiv::SortBy) -> Result<()> {
+    let client = arxiv::ArxivClient::new().context("Failed to create arXiv client")?;
+    let papers = client
+        .search(query, limit, sort)
+        .await
+        .context("arXiv search failed")?;
+    report_if_empty(cli, papers.is_empty());
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&papers)?);
+    } else {
+        println!("{}", text::sanitize(&arxiv::format_papers(&papers)));
+    }
+    Ok(())
+}
+
+async fn run_feed(cli: &Cli, url: &str, limit: usize) -> Result<()> {
+    let client = feed::FeedClient::new().context("Failed to create feed client")?;
+    let feed = client
+        .fetch(url, limit)
+        .await
+        .context("Failed to read feed")?;
+    report_if_empty(cli, feed.entries.is_empty());
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&feed)?);
+    } else {
+        println!("{}", text::sanitize(&feed::format_feed(&feed)));
+    }
+    Ok(())
+}
+
+async fn run_summarize(
+    cli: &Cli,
+    settings: &Settings,
+    args: &SummarizeArgs,
+    length: Length,
+) -> Result<()> {
+    let source = args.source.as_str();
+    let text = if source == "-" {
+        std::io::read_to_string(std::io::stdin()).context("Failed to read standard input")?
+    } else if source.starts_with("http://") || source.starts_with("https://") {
+        let client = OllamaClient::new(build_config(cli, settings)?)
+            .with_context(|| i18n::tr("error-ollama-client"))?;
+        client
+            .fetch(source)
+            .await
+            .with_context(|| i18n::tr("error-fetch"))?
+            .content
+    } else {
+        std::fs::read_to_string(source).with_context(|| format!("Failed to read {}", source))?
+    };
+
+    let mut sources = summary_sources(&AgentSources::new(cli, settings), settings, args.summarizer);
+    if args.openai_url.is_some() {
+        sources.cloud.base_url = args.openai_url.clone();
+    }
+    let model = sources.model(&args.model);
+    let chat = match args.summarizer {
+        Summarizer::Extractive => None,
+        _ => Some(chat_backend(&sources, args.ollama_url.clone(), None)?),
+    };
+
+    let chat = chat.as_deref().map(|chat| (chat, model.as_str()));
+    let summary = summarizer::summarize(chat, &text, length)
+        .await
+        .context("Summarization failed")?;
+
+    if cli.json {
+        let output = serde_json::json!({ "source": source, "summary": summary });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("{}", text::sanitize(&summary));
+    }
+    Ok(())
+}
+
+fn modified_at(path: &Path) -> Option<i64> {
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let seconds = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some(seconds as i64)
+}
+
+async fn run_watch(
+    store: &Store,
+    runner: &watch::Runner,
+    query: &str,
+    interval: Duration,
+    once: bool,
+    html: Option<&Path>,
+    color: bool,
+) -> Result<()> {
+    loop {
+        println!(
+            "{}Checking \"{}\" at {}",
+            Glyph::Watch,
+            query,
+            store::format_timestamp(store::now())
+        );
+
+        match watch::check(store, runner, query).await {
+            Ok(report) => {
+                println!("{}\n", report.render(color)?);
+                if let (Some(path), true) = (html, report.has_changes()?) {
+                    if let Some(document) = report.html(query)? {
+                        std::fs::write(path, document)
+                            .with_context(|| format!("Failed to write {}", path.display()))?;
+                        println!("{}Wrote diff to {}\n", Glyph::File, path.display());
+                    }
+                }
+            }
+            Err(e) if !once => warn!("Watch check failed: {}", e),
+            Err(e) => return Err(e).context("Watch check failed"),
+        }
+
+        if once {
+            return Ok(());
+        }
+
+        info!("Next check in {:?}", interval);
+        tokio::time::sleep(interval).await;
+    }
+}
+
+struct DaemonDefaults {
+    model: String,
+    ollama_url: String,
+    embed_model: String,
+}
+
+async fn run_daemon(
+    cli: &Cli,
+    mut settings: Settings,
+    store: Option<&Store>,
+    config: &Config,
+    client: &OllamaClient,
+    defaults: &DaemonDefaults,
+) -> Result<()> {
+    for job in &settings.jobs {
+        daemon::parse_schedule(&job.schedule)
+            .with_context(|| format!("Job '{}' has an invalid schedule", job.name))?;
+    }
+
+    let report_dir = daemon::report_dir(&settings.daemon)?;
+    println!(
+        "{}Daemon running {} job(s); reports go to {}",
+        Glyph::Daemon,
+        settings.jobs.len(),
+        report_dir.display()
+    );
+
+    let mut last_tick = chrono::Local::now();
+    loop {
+        tokio::time::sleep(DAEMON_TICK).await;
+        let now = chrono::Local::now();
+
+        match Settings::load() {
+            Ok(reloaded) => settings = reloaded,
+            Err(e) => warn!("Keeping previous config: {}", e),
+        }
+        let report_dir = daemon::report_dir(&settings.daemon)?;
+
+        let webhooks = webhook::targets(&cli.notify_webhooks, &settings.webhooks);
+        let sources = AgentSources::new(cli, &settings);
+
+        for job in daemon::due_jobs(&settings.jobs, &last_tick, &now) {
+            info!("Running job {}", job.name);
+            match run_job(cli, &sources, store, config, client, defaults, job).await {
+                Ok(content) => {
+                    match daemon::write_report(&report_dir, job, &content, &now) {
+                        Ok(path) => {
+                            println!("{}{}: wrote {}", Glyph::Note, job.name, path.display())
+                        }
+                        Err(e) => warn!("Failed to write report for {}: {}", job.name, e),
+                    }
+                    let report = webhook::Report {
+                        query: &job.query,
+                        answer: &content,
+                        job: Some(&job.name),
+                    };
+                    webhook::deliver(&webhooks, &report).await;
+                }
+                Err(e) => match e.downcast_ref::<error::OllamaError>() {
+                    Some(
+                        skipped @ (error::OllamaError::UpstreamDegraded { .. }
+                        | error::OllamaError::BudgetExceeded { .. }),
+                    ) => println!("{}{}: skipped, {}", Glyph::Paused, job.name, skipped),
+                    _ => warn!("Job {} failed: {:#}", job.name, e),
+                },
+            }
+        }
+
+        last_tick = now;
+    }
+}
+
+async fn run_job(
+    cli: &Cli,
+    sources: &AgentSources,
+    store: Option<&Store>,
+    config: &Config,
+    client: &OllamaClient,
+    defaults: &DaemonDefaults,
+    job: &settings::Job,
+) -> Result<String> {
+    let search = build_search(cli, sources, store, config, client)?;
+
+    if !job.agent {
+        let response = search
+            .search(&job.query, None)
+            .await
+            .context("Search failed")?;
+        record_search(store, &job.query, &response);
+        return Ok(Markdown.search_results(&response));
+    }
+
+    let options = agent::AgentOptions {
+        model: job.model.clone().unwrap_or_else(|| defaults.model.clone()),
+        max_iterations: 50,
+        show_thinking: false,
+        enable_reasoning: true,
+    };
+    let agent = build_agent(
+        client.clone(),
+        Box::new(search),
+        defaults.ollama_url.clone(),
+        defaults.embed_model.clone(),
+        sources,
+        options,
+    )?;
+    let answer = agent
+        .run(&job.query)
+        .await
+        .with_context(|| i18n::tr("error-agent"))?;
+
+    let summary = answer.lines().find(|line| !line.trim().is_empty());
+    record_history(
+        store,
+        history::Kind::Agent,
+        &job.query,
+        summary.unwrap_or_default(),
+        &answer,
+    );
+    Ok(answer)
+}
+
+fn run_jobs(settings: &Settings, command: &JobsCommand) -> Result<()> {
+    match command {
+        JobsCommand::List => {
+            if settings.jobs.is_empty() {
+                println!("No scheduled jobs. Add one with 'weavex jobs add'.");
+            }
+            let now = chrono::Local::now();
+            for job in &settings.jobs {
+                let next = daemon::next_run(job, &now)
+                    .map(|next| next.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| "invalid schedule".to_string());
+                let kind = if job.agent { "agent" } else { "search" };
+                println!(
+                    "{:<16} {:<16} next {}  {:<6}  {}",
+                    job.name, job.schedule, next, kind, job.query
+                );
+            }
+        }
+        JobsCommand::Add {
+            name,
+            query,
+            schedule,
+            agent,
+            model,
+        } => {
+            daemon::parse_schedule(schedule)?;
+            let job = settings::Job {
+                name: name.clone(),
+                schedule: schedule.clone(),
+                query: query.clone(),
+                agent: *agent,
+                model: model.clone(),
+            };
+            let path = settings::add_job(&job).context("Failed to add job")?;
+            println!(
+                "{}Added job '{}' to {}",
+                Glyph::Schedule,
+                name,
+                path.display()
+            );
+        }
+        JobsCommand::Remove { name } => {
+            if !settings::remove_job(name).context("Failed to remove job")? {
+                anyhow::bail!("No job named '{}'", name);
+            }
+            println!("{}Removed job '{}'", Glyph::Removed, name);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_bookmark(command: &BookmarkCommand) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+
+    match command {
+        BookmarkCommand::Add { url, tags, title } => {
+            url::Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+            let title = title.clone().unwrap_or_else(|| url.clone());
+            bookmarks::add(&store, url, &title, tags).context("Failed to save bookmark")?;
+            println!("{}Bookmarked {}", Glyph::Saved, url);
+        }
+        BookmarkCommand::List { tag } => {
+            let bookmarks =
+                bookmarks::list(&store, tag.as_deref()).context("Failed to read bookmarks")?;
+            if bookmarks.is_empty() {
+                println!("No bookmarks found.");
+            }
+            for bookmark in bookmarks {
+                let tags = if bookmark.tags.is_empty() {
+                    String::new()
+                } else {
+                    format!("  [{}]", bookmark.tags.join(", "))
+                };
+                println!(
+                    "{}  {}{}",
+                    store::format_timestamp(bookmark.created_at),
+                    bookmark.title,
+                    tags
+                );
+                if bookmark.title != bookmark.url {
+                    println!("                  {}", bookmark.url);
+                }
+            }
+        }
+        BookmarkCommand::Remove { url } => {
+            if bookmarks::remove(&store, url).context("Failed to remove bookmark")? {
+                println!("{}Removed bookmark {}", Glyph::Removed, url);
+            } else {
+                anyhow::bail!("No bookmark for {}", url);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_usage(settings: &Settings) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+    let today = chrono::Local::now().date_naive();
+    let limit = |limit: Option<u64>| limit.map_or("no limit".to_string(), |l| l.to_string());
+
+    for kind in usage::Kind::BUDGETED {
+        let totals = usage::totals(&store, kind, today).context("Failed to read usage")?;
+        let (daily, monthly) = settings.budget.limits(kind);
+        println!(
+            "{:<14} today {} / {:<10} this month {} / {}",
+            kind.to_string(),
+            totals.today,
+            limit(daily),
+            totals.month,
+            limit(monthly)
+        );
+    }
+    println!(
+        "\nWhen a budget is exceeded: {}",
+        match settings.budget.on_exceeded {
+            settings::OnExceeded::Fail => "fail with a budget error",
+            settings::OnExceeded::Offline => "continue offline from cached results",
+        }
+    );
+    Ok(())
+}
+
+fn run_stats(cli: &Cli, days: u64) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+    let since = chrono::Local::now().date_naive() - chrono::Days::new(days - 1);
+    let stats = stats::collect(&store, since).context("Failed to read statistics")?;
+
+    if cli.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    println!(
+        "{:<10}  {:>8}  {:>7}  {:>10}  {:>9}  {:>12}",
+        "Day", "Searches", "Fetches", "Agent runs", "Web calls", "Model tokens"
+    );
+    for day in stats.days.iter().chain(std::iter::once(&stats.total)) {
+        println!(
+            "{:<10}  {:>8}  {:>7}  {:>10}  {:>9}  {:>12}",
+            day.day, day.searches, day.fetches, day.agent_runs, day.web_calls, day.model_tokens
+        );
+    }
+
+    println!();
+    match stats.cache_hit_rate {
+        Some(rate) => println!(
+            "Cache hit rate: {:.0}% ({} of {} lookups)",
+            rate * 100.0,
+            stats.cache_hits,
+            stats.cache_hits + stats.cache_misses
+        ),
+        None => println!("Cache hit rate: no cache lookups"),
+    }
+    match stats.average_agent_iterations {
+        Some(average) => println!(
+            "Average agent iterations: {:.1} (over {} runs)",
+            average, stats.tracked_agent_runs
+        ),
+        None => println!("Average agent iterations: no agent runs"),
+    }
+    Ok(())
+}
+
+fn run_permissions(command: &PermissionsCommand) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+
+    match command {
+        PermissionsCommand::Grant { capability } => {
+            permissions::grant(&store, *capability).context("Failed to save permission")?;
+            println!(
+                "{}Granted {} to every agent run",
+                Glyph::Success,
+                capability
+            );
+        }
+        PermissionsCommand::Revoke { capability } => {
+            if permissions::revoke(&store, *capability).context("Failed to revoke permission")? {
+                println!("{}Revoked {}", Glyph::Denied, capability);
+            } else {
+                anyhow::bail!("{} was not granted", capability);
+            }
+        }
+        PermissionsCommand::List => {
+            let grants = permissions::list(&store).context("Failed to read permissions")?;
+            for capability in Capability::value_variants() {
+                match grants.iter().find(|grant| grant.capability == *capability) {
+                    Some(grant) => println!(
+                        "{:<12} granted {}",
+                        capability,
+                        store::format_timestamp(grant.granted_at)
+                    ),
+                    None => println!("{:<12} not granted", capability),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_sessions(cli: &Cli, settings: &Settings, command: &SessionsCommand) -> Result<()> {
+    let SessionsCommand::Tui {
+        limit,
+        model,
+        ollama_url,
+        embed_model,
+    } = command;
+
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+    let entries = history::list(&store, None, *limit).context("Failed to read history")?;
+
+    let sessions::Outcome::Resume(entry) =
+        sessions::run(&store, entries).context("Session picker failed")?
+    else {
+        return Ok(());
+    };
+
+    println!(
+        "{}Resuming session #{}: {}",
+        Glyph::Resume,
+        entry.id,
+        entry.query
+    );
+    print!("Follow-up question: ");
+    std::io::stdout().flush()?;
+    let mut follow_up = String::new();
+    std::io::stdin().read_line(&mut follow_up)?;
+    let follow_up = follow_up.trim();
+    if follow_up.is_empty() {
+        println!("No follow-up given; nothing to do.");
+        return Ok(());
+    }
+
+    let config = build_config(cli, settings)?;
+    let client = build_client(cli, &config)?;
+    let sources = AgentSources::new(cli, settings);
+    let search = build_search(cli, &sources, Some(&store), &config, &client)?;
+    let options = agent::AgentOptions {
+        model: model.clone(),
+        max_iterations: 50,
+        show_thinking: false,
+        enable_reasoning: true,
+    };
+    let agent = build_agent(
+        client,
+        Box::new(search),
+        ollama_url.clone(),
+        embed_model.clone(),
+        &sources,
+        options,
+    )?;
+
+    let query = format!(
+        "{}\n\nContext from a previous session:\nQuestion: {}\nAnswer:\n{}",
+        follow_up, entry.query, entry.answer
+    );
+    run_agent(
+        &agent,
+        Some(&store).filter(|_| !cli.private),
+        &query,
+        cli.no_preview,
+        terminal_renderer(cli, settings).as_ref(),
+        &AnswerOutputs::new(cli, settings),
+    )
+    .await
+}
+
+fn run_clean(settings: &Settings, enforce: bool) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+    let reports = retention::enforce(&store, &settings.retention, !enforce)
+        .context("Failed to apply retention policies")?;
+
+    let verb = if enforce { "removed" } else { "would remove" };
+    for report in &reports {
+        println!(
+            "{:<10} {} entries kept ({}), {} {} ({})",
+            report.name,
+            report.kept,
+            retention::format_bytes(report.kept_bytes),
+            verb,
+            report.removed,
+            retention::format_bytes(report.freed_bytes)
+        );
+    }
+
+    let stats = artifacts::stats(&store).context("Failed to read artifact store")?;
+    println!(
+        "{:<10} {} stored ({} compressed from {}), {} reference(s)",
+        "artifacts",
+        stats.artifacts,
+        retention::format_bytes(stats.stored_bytes),
+        retention::format_bytes(stats.raw_bytes),
+        stats.references
+    );
+
+    let all_previews = markdown_preview::previews(None);
+    let expired = settings
+        .preview
+        .max_age()
+        .map(|max_age| markdown_preview::previews(Some(max_age)))
+        .unwrap_or_default();
+    let removed = if enforce {
+        markdown_preview::remove(&expired)
+    } else {
+        expired.len()
+    };
+    print_previews(&all_previews, verb, removed, &expired);
+
+    if !enforce && (reports.iter().any(|r| r.removed > 0) || removed > 0) {
+        println!("\nRun with --enforce-policy to apply.");
+    }
+
+    Ok(())
+}
+
+fn run_cache(command: &CacheCommand) -> Result<()> {
+    let CacheCommand::Clean { previews } = command;
+    let all_previews = markdown_preview::previews(None);
+    if *previews {
+        let removed = markdown_preview::remove(&all_previews);
+        print_previews(&all_previews, "removed", removed, &all_previews);
+    } else {
+        print_previews(
+            &all_previews,
+            "would remove",
+            all_previews.len(),
+            &all_previews,
+        );
+        if !all_previews.is_empty() {
+            println!("\nRun with --previews to delete them.");
+        }
+    }
+    Ok(())
+}
+
+fn print_previews(
+    all: &[(std::path::PathBuf, u64)],
+    verb: &str,
+    removed: usize,
+    expired: &[(std::path::PathBuf, u64)],
+) {
+    let size = |files: &[(std::path::PathBuf, u64)]| files.iter().map(|(_, len)| len).sum();
+    println!(
+        "{:<10} {} files ({}), {} {} ({})",
+        "previews",
+        all.len(),
+        retention::format_bytes(size(all)),
+        verb,
+        removed,
+        retention::format_bytes(size(expired))
+    );
+}
+
+fn run_feedback(
+    result_id: Option<&str>,
+    rating: Option<feedback::Rating>,
+    export: Option<&Path>,
+) -> Result<()> {
+    let store = Store::open().with_context(|| i18n::tr("error-open-store"))?;
+
+    if let Some(path) = export {
+        let examples = feedback::eval_set(&store).context("Failed to read feedback")?;
+        let mut file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        for example in &examples {
+            writeln!(file, "{}", serde_json::to_string(example)?)?;
+        }
+        println!(
+            "{}Exported {} rating(s) to {}",
+            Glyph::Export,
+            examples.len(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let (Some(result_id), Some(rating)) = (result_id, rating) else {
+        anyhow::bail!("Both a result id and a rating are required");
+    };
+
+    let domain = feedback::rate(&store, result_id, rating).context("Failed to record feedback")?;
+    let verb = match rating {
+        feedback::Rating::Good => "boosted",
+        feedback::Rating::Bad => "penalized",
+    };
+    println!(
+        "{}Recorded feedback; {} will be {} in future results",
+        Glyph::Feedback,
+        domain,
+        verb
+    );
+
+    Ok(())
+}
+
+fn init_logging(cli: &Cli) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(cli.log_filter()));
+
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(false)
+        .without_time();
+    if cli.private {
+        subscriber
+            .with_writer(privacy::Scrubber::new(private_query(cli)))
+            .init();
+    } else {
+        subscriber.with_writer(std::io::stderr).init();
+    }
+}
+
+fn private_query(cli: &Cli) -> Option<&str> {
+    match &cli.command {
+        Some(Command::Agent { query, .. })
+        | Some(Command::Browse { query, .. })
+        | Some(Command::Arxiv { query, .. })
+        | Some(Command::Trail {
+            question: query, ..
+        }) => Some(query),
+        Some(Command::Fetch { url }) => Some(url),
+        _ => cli.get_query(),
+    }
 }
@@ -1,14 +1,138 @@
+use crate::vision::{Image, VisionClient};
 use anyhow::{Context, Result};
 use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::join_all;
 use pulldown_cmark::{html, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
 use std::fs;
-use syntect::easy::HighlightLines;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
 use syntect::highlighting::ThemeSet;
-use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator};
 use syntect::parsing::SyntaxSet;
+use webbrowser::Browser;
 
-pub fn open_markdown_in_browser(markdown_content: &str) -> Result<()> {
-    let html = create_html_document(markdown_content);
+static TARGET: OnceLock<PreviewTarget> = OnceLock::new();
+static MAX_AGE: OnceLock<Duration> = OnceLock::new();
+static SESSION: Mutex<Option<(String, usize)>> = Mutex::new(None);
+
+const LIGHT_THEME: &str = "InspiredGitHub";
+const DARK_THEME: &str = "base16-ocean.dark";
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+const CODE_PLACEHOLDER: char = '\u{E000}';
+const ID_PREFIX: &str = "user-content-";
+const ALLOWED_CLASSES: &[(&str, &[&str])] = &[
+    ("a", &["card-title"]),
+    (
+        "div",
+        &[
+            "result-card",
+            "card-body",
+            "card-meta",
+            "footnote-definition",
+        ],
+    ),
+    ("img", &["card-favicon"]),
+    ("p", &["card-snippet"]),
+    ("section", &["footnotes"]),
+    ("span", &["card-domain"]),
+    ("sup", &["footnote-reference", "footnote-definition-label"]),
+];
+
+const PREVIEW_DIR: &str = "weavex-previews";
+
+const BROWSER_COMMANDS: &[(Browser, &[&str])] = &[
+    (Browser::Firefox, &["firefox"]),
+    (
+        Browser::Chrome,
+        &[
+            "google-chrome",
+            "google-chrome-stable",
+            "chromium",
+            "chromium-browser",
+        ],
+    ),
+    (Browser::Opera, &["opera"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreviewTarget {
+    Browser(Browser),
+    Command(String),
+    PrintPath,
+}
+
+impl PreviewTarget {
+    pub fn parse(browser: &str) -> Self {
+        match browser.trim().to_lowercase().parse::<Browser>() {
+            Ok(browser) => PreviewTarget::Browser(browser),
+            Err(_) => PreviewTarget::Command(browser.trim().to_string()),
+        }
+    }
+}
+
+impl Default for PreviewTarget {
+    fn default() -> Self {
+        PreviewTarget::Browser(Browser::Default)
+    }
+}
+
+pub fn configure(target: PreviewTarget, max_age: Option<Duration>) {
+    tracing::debug!("Preview target: {:?}", target);
+    let _ = TARGET.set(target);
+    if let Some(max_age) = max_age {
+        let _ = MAX_AGE.set(max_age);
+    }
+}
+
+pub fn set_session(id: i64) {
+    let name = format!("weavex-session-{}", id);
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    if session.as_ref().is_none_or(|(current, _)| *current != name) {
+        *session = Some((name, 0));
+    }
+}
+
+pub fn open_markdown_in_browser(markdown_content: &str) -> Result<bool> {
+    open_html_in_browser(&html_document(markdown_content))
+}
+
+pub fn open_html_in_browser(html: &str) -> Result<bool> {
+    match TARGET.get().cloned().unwrap_or_default() {
+        PreviewTarget::Browser(Browser::Default) => open_in_default_browser(html)?,
+        PreviewTarget::Browser(browser) => {
+            let path = write_temp_file(html)?;
+            let url = format!("file://{}", path.display());
+            if let Err(e) = webbrowser::open_browser(browser, &url) {
+                tracing::debug!("Falling back to a browser command for {}: {}", browser, e);
+                let commands = BROWSER_COMMANDS
+                    .iter()
+                    .find(|(known, _)| *known == browser)
+                    .map(|(_, commands)| *commands)
+                    .unwrap_or_default();
+                commands
+                    .iter()
+                    .find_map(|command| launch(command, &path).ok())
+                    .with_context(|| format!("Failed to open {}", browser))?;
+            }
+        }
+        PreviewTarget::Command(command) => {
+            let path = write_temp_file(html)?;
+            launch(&command, &path).with_context(|| format!("Failed to run '{}'", command))?;
+        }
+        PreviewTarget::PrintPath => {
+            println!("{}", write_temp_file(html)?.display());
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn open_in_default_browser(html: &str) -> Result<()> {
     let html_size = html.len();
 
     const MAX_DATA_URL_SIZE: usize = 2_000_000;
@@ -18,7 +142,7 @@ pub fn open_markdown_in_browser(markdown_content: &str) -> Result<()> {
             "HTML size ({} bytes) exceeds data URL limit, using temp file fallback",
             html_size
         );
-        open_html_via_temp_file(&html).context("Failed to open HTML via temp file")
+        open_html_via_temp_file(html).context("Failed to open HTML via temp file")
     } else {
         let encoded = STANDARD.encode(html.as_bytes());
         let data_url = format!("data:text/html;charset=utf-8;base64,{}", encoded);
@@ -28,7 +152,7 @@ pub fn open_markdown_in_browser(markdown_content: &str) -> Result<()> {
                 "Encoded data URL ({} bytes) exceeds limit, using temp file fallback",
                 data_url.len()
             );
-            open_html_via_temp_file(&html).context("Failed to open HTML via temp file")
+            open_html_via_temp_file(html).context("Failed to open HTML via temp file")
         } else {
             webbrowser::open(&data_url).context("Failed to open browser with data URL")
         }
@@ -36,17 +160,7 @@ pub fn open_markdown_in_browser(markdown_content: &str) -> Result<()> {
 }
 
 fn open_html_via_temp_file(html: &str) -> Result<()> {
-    let temp_dir = std::env::temp_dir();
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .context("System time error")?
-        .as_micros();
-    let pid = std::process::id();
-    let file_name = format!("weavex_result_{}_{}.html", timestamp, pid);
-    let temp_path = temp_dir.join(file_name);
-
-    fs::write(&temp_path, html).context("Failed to write HTML to temp file")?;
-
+    let temp_path = write_temp_file(html)?;
     let url = format!("file://{}", temp_path.display());
     webbrowser::open(&url).context("Failed to open browser with temp file")?;
 
@@ -54,7 +168,193 @@ fn open_html_via_temp_file(html: &str) -> Result<()> {
     Ok(())
 }
 
-fn create_html_document(markdown_content: &str) -> String {
+fn write_temp_file(html: &str) -> Result<PathBuf> {
+    let dir = preview_dir();
+    create_private_dir(&dir).context("Failed to create the preview directory")?;
+    if let Some(max_age) = MAX_AGE.get() {
+        let removed = remove(&previews(Some(*max_age)));
+        if removed > 0 {
+            tracing::debug!("Removed {} old preview file(s)", removed);
+        }
+    }
+
+    let file_name = {
+        let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+        let (name, written) = session.get_or_insert_with(|| {
+            let name = format!(
+                "weavex-{}-{}",
+                chrono::Local::now().format("%Y%m%d-%H%M%S"),
+                std::process::id()
+            );
+            (name, 0)
+        });
+        *written += 1;
+        session_file_name(name, *written - 1)
+    };
+    let temp_path = dir.join(file_name);
+
+    write_new_file(&temp_path, html).context("Failed to write HTML to temp file")?;
+    Ok(temp_path)
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        builder.mode(0o700).create(dir)?;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    #[cfg(not(unix))]
+    builder.create(dir)?;
+
+    if !fs::symlink_metadata(dir)?.is_dir() {
+        return Err(std::io::Error::other(format!(
+            "{} is not a directory",
+            dir.display()
+        )));
+    }
+    Ok(())
+}
+
+fn write_new_file(path: &Path, html: &str) -> std::io::Result<()> {
+    let staging = path.with_extension(format!("{}.tmp", std::process::id()));
+    let _ = fs::remove_file(&staging);
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&staging)?;
+    file.write_all(html.as_bytes())?;
+    drop(file);
+    fs::rename(&staging, path)
+}
+
+fn session_file_name(session: &str, written: usize) -> String {
+    match written {
+        0 => format!("{}.html", session),
+        n => format!("{}-{}.html", session, n + 1),
+    }
+}
+
+pub fn preview_dir() -> PathBuf {
+    match dirs::cache_dir() {
+        Some(dir) => dir.join("weavex").join("previews"),
+        None => std::env::temp_dir().join(PREVIEW_DIR),
+    }
+}
+
+pub fn previews(older_than: Option<Duration>) -> Vec<(PathBuf, u64)> {
+    html_files(&preview_dir(), older_than, SystemTime::now())
+}
+
+pub fn remove(files: &[(PathBuf, u64)]) -> usize {
+    files
+        .iter()
+        .filter(|(path, _)| match fs::remove_file(path) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::debug!("Could not remove {:?}: {}", path, e);
+                false
+            }
+        })
+        .count()
+}
+
+fn html_files(dir: &Path, older_than: Option<Duration>, now: SystemTime) -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".html"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let age = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .unwrap_or_default();
+            match older_than {
+                Some(max_age) if age <= max_age => None,
+                _ => Some((entry.path(), metadata.len())),
+            }
+        })
+        .collect()
+}
+
+fn launch(command: &str, path: &Path) -> std::io::Result<()> {
+    Command::new(command)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    tracing::debug!("Opened {:?} with {}", path, command);
+    Ok(())
+}
+
+pub async fn embed_images(html: &str) -> String {
+    let sources = image_sources(html);
+    if sources.is_empty() {
+        return html.to_string();
+    }
+    let client = match VisionClient::new() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Could not embed images: {}", e);
+            return html.to_string();
+        }
+    };
+    let downloads = join_all(sources.iter().map(|src| {
+        let url = src.replace("&amp;", "&");
+        let client = &client;
+        async move { client.download(&url).await }
+    }))
+    .await;
+
+    let mut images = Vec::new();
+    for (src, download) in sources.iter().zip(downloads) {
+        match download {
+            Ok(image) => images.push((src.as_str(), image)),
+            Err(e) => tracing::warn!("Could not embed image {}: {}", src, e),
+        }
+    }
+    inline_images(html, &images)
+}
+
+fn image_sources(html: &str) -> Vec<String> {
+    static IMG_SRC: OnceLock<Regex> = OnceLock::new();
+    let pattern =
+        IMG_SRC.get_or_init(|| Regex::new(r#"(?i)<img\s([^>]*?)src="(https?://[^"]+)""#).unwrap());
+    let mut sources: Vec<String> = Vec::new();
+    for captures in pattern.captures_iter(html) {
+        if captures[1].contains("class=\"card-favicon\"") {
+            continue;
+        }
+        let src = captures[2].to_string();
+        if !sources.contains(&src) {
+            sources.push(src);
+        }
+    }
+    sources
+}
+
+fn inline_images(html: &str, images: &[(&str, Image)]) -> String {
+    images.iter().fold(html.to_string(), |html, (src, image)| {
+        let data_uri = format!(
+            "data:image/{};base64,{}",
+            image.format,
+            STANDARD.encode(&image.bytes)
+        );
+        html.replace(
+            &format!("src=\"{}\"", src),
+            &format!("src=\"{}\"", data_uri),
+        )
+    })
+}
+
+pub fn html_document(markdown_content: &str) -> String {
     let html_content = markdown_to_html(markdown_content);
 
     format!(
@@ -252,17 +552,167 @@ fn create_html_document(markdown_content: &str) -> String {
             border-radius: 6px;
         }}
 
+        .result-card {{
+            display: flex;
+            gap: 12px;
+            padding: 12px 16px;
+            margin-bottom: 12px;
+            border: 1px solid var(--border);
+            border-radius: 8px;
+            background: var(--bg-secondary);
+        }}
+
+        .card-favicon {{
+            flex-shrink: 0;
+            margin-top: 4px;
+            border-radius: 2px;
+        }}
+
+        .card-body {{
+            min-width: 0;
+        }}
+
+        .card-meta {{
+            font-size: 0.85em;
+            color: var(--text-secondary);
+        }}
+
+        .card-title {{
+            font-weight: 600;
+            font-size: 1.05em;
+        }}
+
+        .card-snippet {{
+            margin: 4px 0 0 0;
+        }}
+
+        dt {{
+            font-weight: 600;
+            margin-top: 16px;
+        }}
+
+        dd {{
+            margin: 0 0 16px 2em;
+        }}
+
+        .footnote-reference {{
+            font-size: 0.75em;
+            line-height: 0;
+        }}
+
+        .footnotes {{
+            margin-top: 32px;
+            padding-top: 16px;
+            border-top: 1px solid var(--border-light);
+            font-size: 0.875em;
+            color: var(--text-secondary);
+        }}
+
+        .footnote-definition {{
+            display: flex;
+            gap: 0.5em;
+        }}
+
+        .footnote-definition p {{
+            margin: 0 0 8px 0;
+        }}
+
+        .footnote-definition-label {{
+            min-width: 1.5em;
+            color: var(--accent);
+        }}
+
         .meta {{
             color: var(--text-secondary);
             font-size: 0.9em;
             margin-bottom: 2rem;
             padding-bottom: 1rem;
             border-bottom: 1px solid var(--border-light);
+            display: flex;
+            justify-content: space-between;
+            align-items: center;
+        }}
+
+        .print-button {{
+            background: var(--bg-secondary);
+            border: 1px solid var(--border);
+            color: var(--text);
+            padding: 4px 10px;
+            border-radius: 4px;
+            cursor: pointer;
+            font-size: 0.85em;
         }}
+
+        .print-button:hover {{
+            background: var(--border-light);
+        }}
+
+        @media print {{
+            :root {{
+                --bg: #ffffff;
+                --bg-secondary: #f6f8fa;
+                --text: #000000;
+                --text-secondary: #57606a;
+                --accent: #000000;
+                --border: #d0d7de;
+                --border-light: #d8dee4;
+            }}
+
+            body {{
+                max-width: none;
+                padding: 0;
+                font-size: 11pt;
+            }}
+
+            .print-button, .copy-button {{
+                display: none;
+            }}
+
+            h2 {{
+                break-before: page;
+                page-break-before: always;
+            }}
+
+            h2:first-of-type {{
+                break-before: auto;
+                page-break-before: auto;
+            }}
+
+            h1, h2, h3, h4, h5, h6 {{
+                break-after: avoid;
+                page-break-after: avoid;
+            }}
+
+            pre, blockquote, table, img, .code-block-wrapper, .result-card {{
+                break-inside: avoid;
+                page-break-inside: avoid;
+            }}
+
+            pre {{
+                white-space: pre-wrap;
+                overflow-wrap: anywhere;
+            }}
+
+            table {{
+                display: table;
+            }}
+
+            a[href^="http"]::after {{
+                content: " (" attr(href) ")";
+                font-size: 0.85em;
+                color: var(--text-secondary);
+                overflow-wrap: anywhere;
+            }}
+        }}
+
+{}
     </style>
 </head>
 <body>
-    <div class="meta">🧵 Generated by Weavex</div>
+    <div class="meta">
+        <span>🧵 Generated by Weavex</span>
+        <button class="print-button" onclick="window.print()">Print / Save as PDF</button>
+    </div>
     {}
     <script>
         function copyCode(button) {{
@@ -279,24 +729,31 @@ fn create_html_document(markdown_content: &str) -> String {
                 }}, 2000);
             }});
         }}
+
+        document.querySelectorAll('.code-block-wrapper .copy-button').forEach((button) => {{
+            button.addEventListener('click', () => copyCode(button));
+        }});
     </script>
 </body>
 </html>"#,
+        syntax_css(),
         html_content
     )
 }
 
-fn markdown_to_html(markdown: &str) -> String {
+pub fn markdown_to_html(markdown: &str) -> String {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+    options.insert(Options::ENABLE_DEFINITION_LIST);
 
-    let parser = Parser::new_ext(markdown, options);
+    let markdown = markdown.replace(CODE_PLACEHOLDER, "");
+    let parser = Parser::new_ext(&markdown, options);
 
     let ss = SyntaxSet::load_defaults_newlines();
-    let ts = ThemeSet::load_defaults();
-    let theme = &ts.themes["base16-ocean.dark"];
 
     let mut in_code_block = false;
     let mut code_buffer = String::new();
@@ -304,9 +761,25 @@ fn markdown_to_html(markdown: &str) -> String {
 
     let events: Vec<Event> = parser.collect();
     let mut new_events = Vec::new();
+    let mut code_blocks = Vec::new();
+    let mut footnotes = Vec::new();
+    let mut in_footnote = false;
 
     for event in events.iter() {
+        let out = if in_footnote {
+            &mut footnotes
+        } else {
+            &mut new_events
+        };
         match event {
+            Event::Start(Tag::FootnoteDefinition(_)) => {
+                in_footnote = true;
+                footnotes.push(event.clone());
+            }
+            Event::End(TagEnd::FootnoteDefinition) => {
+                in_footnote = false;
+                footnotes.push(event.clone());
+            }
             Event::Start(Tag::CodeBlock(kind)) => {
                 in_code_block = true;
                 code_buffer.clear();
@@ -317,7 +790,7 @@ fn markdown_to_html(markdown: &str) -> String {
             }
             Event::End(TagEnd::CodeBlock) => {
                 if in_code_block {
-                    let highlighted = highlight_code(&code_buffer, &code_lang, &ss, theme);
+                    let highlighted = highlight_code(&code_buffer, &code_lang, &ss);
 
                     let wrapper = if code_lang.is_empty() {
                         format!(
@@ -326,13 +799,16 @@ fn markdown_to_html(markdown: &str) -> String {
                         )
                     } else {
                         format!(
-                            r#"<div class="code-block-wrapper"><div class="code-block-header"><span class="code-lang">{}</span><button class="copy-button" onclick="copyCode(this)">Copy</button></div><pre><code>{}</code></pre></div>"#,
+                            r#"<div class="code-block-wrapper"><div class="code-block-header"><span class="code-lang">{}</span><button class="copy-button">Copy</button></div><pre><code>{}</code></pre></div>"#,
                             escape_html(&code_lang),
                             highlighted
                         )
                     };
 
-                    new_events.push(Event::Html(wrapper.into()));
+                    out.push(Event::Html(
+                        format!("{0}{1}{0}", CODE_PLACEHOLDER, code_blocks.len()).into(),
+                    ));
+                    code_blocks.push(wrapper);
                     in_code_block = false;
                     code_buffer.clear();
                 }
@@ -342,39 +818,66 @@ fn markdown_to_html(markdown: &str) -> String {
             }
             _ => {
                 if !in_code_block {
-                    new_events.push(event.clone());
+                    out.push(event.clone());
                 }
             }
         }
     }
 
+    if !footnotes.is_empty() {
+        new_events.push(Event::Html("<section class=\"footnotes\">\n".into()));
+        new_events.extend(footnotes);
+        new_events.push(Event::Html("</section>\n".into()));
+    }
+
     let mut html_output = String::new();
     html::push_html(&mut html_output, new_events.into_iter());
-    html_output
+    let mut sanitized = sanitize(&html_output);
+    for (idx, block) in code_blocks.iter().enumerate() {
+        sanitized = sanitized.replacen(&format!("{0}{1}{0}", CODE_PLACEHOLDER, idx), block, 1);
+    }
+    sanitized
 }
 
-fn highlight_code(
-    code: &str,
-    lang: &str,
-    ss: &SyntaxSet,
-    theme: &syntect::highlighting::Theme,
-) -> String {
+fn sanitize(html: &str) -> String {
+    let mut builder = ammonia::Builder::default();
+    builder
+        .add_tags(&["input", "section"])
+        .add_generic_attributes(&["id"])
+        .id_prefix(Some(ID_PREFIX))
+        .add_tag_attributes("img", &["width", "height"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        .add_tag_attributes("th", &["style"])
+        .add_tag_attributes("td", &["style"])
+        .attribute_filter(|_, attribute, value| match value.strip_prefix('#') {
+            Some(fragment) if attribute == "href" => {
+                Some(format!("#{}{}", ID_PREFIX, fragment).into())
+            }
+            _ => Some(value.into()),
+        });
+    for (tag, classes) in ALLOWED_CLASSES {
+        builder.add_allowed_classes(*tag, *classes);
+    }
+    builder.clean(html).to_string()
+}
+
+fn highlight_code(code: &str, lang: &str, ss: &SyntaxSet) -> String {
     let syntax = ss
         .find_syntax_by_token(lang)
         .or_else(|| ss.find_syntax_by_extension(lang))
         .unwrap_or_else(|| ss.find_syntax_plain_text());
 
-    let mut highlighter = HighlightLines::new(syntax, theme);
-    let mut result = String::new();
-
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, ss, CLASS_STYLE);
     for line in code.lines() {
-        let ranges = highlighter.highlight_line(line, ss).unwrap_or_default();
-        let html = styled_line_to_highlighted_html(&ranges[..], IncludeBackground::No)
-            .unwrap_or_else(|_| escape_html(line));
-        result.push_str(&html);
-        result.push('\n');
+        if generator
+            .parse_html_for_line_which_includes_newline(&format!("{}\n", line))
+            .is_err()
+        {
+            return escape_html(code.trim_end_matches('\n'));
+        }
     }
 
+    let mut result = generator.finalize();
     if result.ends_with('\n') {
         result.pop();
     }
@@ -382,7 +885,23 @@ fn highlight_code(
     result
 }
 
-fn escape_html(text: &str) -> String {
+fn syntax_css() -> &'static str {
+    static CSS: OnceLock<String> = OnceLock::new();
+    CSS.get_or_init(|| {
+        let themes = ThemeSet::load_defaults();
+        let css = |name: &str| {
+            css_for_theme_with_class_style(&themes.themes[name], CLASS_STYLE).unwrap_or_default()
+        };
+        format!(
+            "@media print, (prefers-color-scheme: light) {{\n{}}}\n\
+             @media screen and (prefers-color-scheme: dark) {{\n{}}}\n",
+            css(LIGHT_THEME),
+            css(DARK_THEME)
+        )
+    })
+}
+
+pub fn escape_html(text: &str) -> String {
     text.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -413,6 +932,212 @@ mod tests {
         assert!(html.contains("<td"));
     }
 
+    #[test]
+    fn test_snapshot_html_documents() {
+        insta::assert_snapshot!(
+            "html_research_answer",
+            html_document(
+                "# Async runtimes\n\nTokio and **smol** compared — see [docs](https://tokio.rs).\n\n\
+                 | Runtime | Work stealing |\n|---|---|\n| tokio | ✓ |\n| smol | ✗ |\n\n\
+                 - [x] benchmark\n- [ ] ~~rewrite~~\n\n\
+                 ```rust\nasync fn main() {}\n```\n"
+            )
+        );
+        insta::assert_snapshot!(
+            "html_malformed_markdown",
+            html_document(
+                "## Unclosed *emphasis and [link](\n\n| a | b |\n|--|\n| 1 |\n\n\
+                 <script>alert('x')</script>\n\n```python\nprint(\"never closed\")\n"
+            )
+        );
+        insta::assert_snapshot!("html_empty", html_document(""));
+    }
+
+    #[test]
+    fn test_html_document_prints_cleanly() {
+        let html = html_document("# Report\n\n## First\n\n## Second\n");
+        assert!(html.contains(r#"onclick="window.print()">Print / Save as PDF</button>"#));
+        let print_css = &html[html.find("@media print").unwrap()..];
+        assert!(print_css.contains("page-break-before: always"));
+        assert!(print_css.contains(".print-button, .copy-button"));
+        assert!(print_css.contains(r#"content: " (" attr(href) ")";"#));
+    }
+
+    #[test]
+    fn test_inlines_remote_images_as_data_uris() {
+        let html = html_document(
+            "![chart](https://example.com/chart.png?w=1&h=2)\n\n\
+             ![chart again](https://example.com/chart.png?w=1&h=2)\n\n\
+             ![local](images/logo.png)\n\n\
+             <img class=\"card-favicon\" src=\"https://example.com/favicon.ico\" alt=\"\">\n",
+        );
+        assert!(html.contains(r#"<img class="card-favicon" src="https://example.com/favicon.ico""#));
+        let sources = image_sources(&html);
+        assert_eq!(sources, ["https://example.com/chart.png?w=1&amp;h=2"]);
+
+        let image = Image {
+            format: "png",
+            bytes: b"\x89PNG".to_vec(),
+        };
+        let embedded = inline_images(&html, &[(sources[0].as_str(), image)]);
+        assert_eq!(
+            embedded
+                .matches(r#"<img src="data:image/png;base64,iVBORw==""#)
+                .count(),
+            2
+        );
+        assert!(embedded.contains(r#"<img src="images/logo.png""#));
+    }
+
+    #[test]
+    fn test_parses_preview_targets() {
+        assert_eq!(
+            PreviewTarget::parse("Firefox"),
+            PreviewTarget::Browser(Browser::Firefox)
+        );
+        assert_eq!(PreviewTarget::parse("default"), PreviewTarget::default());
+        assert_eq!(
+            PreviewTarget::parse(" /opt/Vivaldi/vivaldi "),
+            PreviewTarget::Command("/opt/Vivaldi/vivaldi".to_string())
+        );
+    }
+
+    #[test]
+    fn test_lists_preview_files_by_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let now = SystemTime::now();
+        for (name, age_days) in [
+            ("weavex-session-42.html", 10),
+            ("weavex-session-42-2.html", 1),
+            ("weavex_result_1_2.html", 3),
+            ("notes.txt", 10),
+        ] {
+            let file = fs::File::create(dir.path().join(name)).unwrap();
+            file.set_modified(now - day * age_days).unwrap();
+        }
+
+        let mut all: Vec<String> = html_files(dir.path(), None, now)
+            .into_iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        all.sort();
+        assert_eq!(
+            all,
+            [
+                "weavex-session-42-2.html",
+                "weavex-session-42.html",
+                "weavex_result_1_2.html"
+            ]
+        );
+
+        let old = html_files(dir.path(), Some(day * 7), now);
+        assert_eq!(old.len(), 1);
+        assert!(old[0].0.ends_with("weavex-session-42.html"));
+        assert_eq!(remove(&old), 1);
+        assert_eq!(html_files(dir.path(), None, now).len(), 2);
+        assert_eq!(
+            session_file_name("weavex-session-7", 0),
+            "weavex-session-7.html"
+        );
+        assert_eq!(
+            session_file_name("weavex-session-7", 2),
+            "weavex-session-7-3.html"
+        );
+    }
+
+    #[test]
+    fn test_preview_files_replace_instead_of_following_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let previews = dir.path().join("previews");
+        create_private_dir(&previews).unwrap();
+        let path = previews.join("weavex-session-1.html");
+        write_new_file(&path, "<p>first</p>").unwrap();
+        write_new_file(&path, "<p>second</p>").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "<p>second</p>");
+        assert_eq!(fs::read_dir(&previews).unwrap().count(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&previews).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+
+            let target = dir.path().join("victim.txt");
+            fs::write(&target, "untouched").unwrap();
+            let link = previews.join("weavex-session-2.html");
+            std::os::unix::fs::symlink(&target, &link).unwrap();
+            write_new_file(&link, "<p>preview</p>").unwrap();
+            assert_eq!(fs::read_to_string(&target).unwrap(), "untouched");
+            assert!(!fs::symlink_metadata(&link).unwrap().is_symlink());
+
+            let linked_dir = dir.path().join("linked");
+            std::os::unix::fs::symlink(&previews, &linked_dir).unwrap();
+            assert!(create_private_dir(&linked_dir).is_err());
+        }
+    }
+
+    #[test]
+    fn test_code_highlighting_follows_color_scheme() {
+        let html = markdown_to_html("```rust\nfn main() {}\n```\n");
+        assert!(html.contains(r#"<span class="hl-source hl-rust">"#));
+        assert!(!html.contains("style=\"color"));
+
+        let css = syntax_css();
+        let dark = css
+            .find("@media screen and (prefers-color-scheme: dark)")
+            .unwrap();
+        let light = css
+            .find("@media print, (prefers-color-scheme: light)")
+            .unwrap();
+        assert!(light < dark);
+        assert!(css[light..dark].contains(".hl-storage"));
+        assert!(css[dark..].contains(".hl-storage"));
+    }
+
+    #[test]
+    fn test_footnotes_move_to_a_section_at_the_end() {
+        let md = "## Runtimes {#runtimes}\n\nTokio is the default[^tokio].\n\n\
+                  [^tokio]: <https://tokio.rs>\n\n\
+                  Work stealing\n: Idle workers take tasks from busy ones.\n";
+        let html = markdown_to_html(md);
+        assert!(html.contains(r#"<h2 id="user-content-runtimes">"#));
+        assert!(html.contains(r##"<sup class="footnote-reference"><a href="#user-content-tokio" rel="noopener noreferrer">1</a></sup>"##));
+        assert!(html.contains("<dt>Work stealing</dt>"));
+
+        let footnotes = html.find(r#"<section class="footnotes">"#).unwrap();
+        assert!(html.find("<dl>").unwrap() < footnotes);
+        assert!(html[footnotes..]
+            .contains(r#"<div class="footnote-definition" id="user-content-tokio">"#));
+        assert!(html.trim_end().ends_with("</section>"));
+    }
+
+    #[test]
+    fn test_strips_scripts_and_event_handlers() {
+        let md = "Hello <script>alert(1)</script> <img src=\"x.png\" onerror=\"alert(2)\">\n\n\
+                  <a href=\"javascript:alert(3)\" onclick=\"alert(4)\">link</a>\n\n\
+                  <iframe src=\"https://evil.example\"></iframe>\n\n\
+                  <div class=\"code-block-wrapper result-card\" id=\"copy\">\
+                  <button class=\"copy-button\">Copy</button></div>\n\n\
+                  - [x] done\n\n```rust\nlet x = 1;\n```\n";
+        let html = markdown_to_html(md);
+        for unsafe_fragment in [
+            "<script",
+            "alert(",
+            "onerror",
+            "onclick",
+            "javascript:",
+            "<iframe",
+        ] {
+            assert!(!html.contains(unsafe_fragment), "{}", html);
+        }
+        assert!(html.contains(r#"<img src="x.png">"#));
+        assert!(html.contains(r#"<input disabled="" type="checkbox" checked="">"#));
+        assert!(html.contains(r#"<div class="result-card" id="user-content-copy">Copy</div>"#));
+        assert_eq!(html.matches(r#"<button class="copy-button">"#).count(), 1);
+        assert!(html.contains(r#"<span class="hl-source hl-rust">"#));
+    }
+
     #[test]
     fn test_markdown_code_blocks() {
         let md = r#"
@@ -0,0 +1,227 @@
+use chrono::{DateTime, NaiveDate};
+use scraper::{Html, Selector};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageMetadata {
+    pub author: Option<String>,
+    pub published: Option<String>,
+    pub description: Option<String>,
+    pub canonical_url: Option<String>,
+    pub no_archive: bool,
+}
+
+pub fn extract(html: &str) -> PageMetadata {
+    let document = Html::parse_document(html);
+    let linked_data = linked_data(&document);
+
+    PageMetadata {
+        author: linked_data
+            .iter()
+            .find_map(|item| author(&item["author"]))
+            .or_else(|| {
+                meta(
+                    &document,
+                    &[
+                        "author",
+                        "article:author",
+                        "og:article:author",
+                        "dc.creator",
+                    ],
+                )
+            }),
+        published: linked_data
+            .iter()
+            .find_map(|item| text(&item["datePublished"]))
+            .or_else(|| {
+                meta(
+                    &document,
+                    &[
+                        "article:published_time",
+                        "og:published_time",
+                        "datepublished",
+                        "date",
+                        "dc.date",
+                        "dc.date.issued",
+                    ],
+                )
+            })
+            .map(|date| normalize_date(&date)),
+        description: meta(
+            &document,
+            &["og:description", "description", "twitter:description"],
+        )
+        .or_else(|| {
+            linked_data
+                .iter()
+                .find_map(|item| text(&item["description"]))
+        }),
+        canonical_url: canonical(&document).or_else(|| meta(&document, &["og:url"])),
+        no_archive: no_archive(&document),
+    }
+}
+
+fn no_archive(document: &Html) -> bool {
+    let robots = meta(document, &["robots"])
+        .unwrap_or_default()
+        .to_lowercase();
+    let opted_out = robots
+        .split([',', ' '])
+        .any(|directive| matches!(directive, "noindex" | "noarchive" | "none"));
+    opted_out || meta(document, &["tdm-reservation"]).as_deref() == Some("1")
+}
+
+fn meta(document: &Html, keys: &[&str]) -> Option<String> {
+    let selector = Selector::parse("meta[content]").unwrap();
+    keys.iter().find_map(|key| {
+        document.select(&selector).find_map(|element| {
+            let value = element.value();
+            let name = value
+                .attr("property")
+                .or_else(|| value.attr("name"))
+                .or_else(|| value.attr("itemprop"))?;
+            if !name.eq_ignore_ascii_case(key) {
+                return None;
+            }
+            non_empty(value.attr("content")?)
+        })
+    })
+}
+
+fn canonical(document: &Html) -> Option<String> {
+    let selector = Selector::parse("link[rel][href]").unwrap();
+    document.select(&selector).find_map(|element| {
+        let value = element.value();
+        let is_canonical = value
+            .attr("rel")?
+            .split_whitespace()
+            .any(|rel| rel.eq_ignore_ascii_case("canonical"));
+        if is_canonical {
+            non_empty(value.attr("href")?)
+        } else {
+            None
+        }
+    })
+}
+
+fn linked_data(document: &Html) -> Vec<Value> {
+    let selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    let mut items = Vec::new();
+
+    for script in document.select(&selector) {
+        let Ok(value) = serde_json::from_str::<Value>(&script.text().collect::<String>()) else {
+            continue;
+        };
+        flatten(value, &mut items);
+    }
+    items
+}
+
+fn flatten(value: Value, items: &mut Vec<Value>) {
+    match value {
+        Value::Array(values) => values.into_iter().for_each(|v| flatten(v, items)),
+        Value::Object(mut object) => {
+            if let Some(graph) = object.remove("@graph") {
+                flatten(graph, items);
+            }
+            items.push(Value::Object(object));
+        }
+        _ => {}
+    }
+}
+
+fn author(value: &Value) -> Option<String> {
+    match value {
+        Value::Array(authors) => {
+            let names: Vec<String> = authors.iter().filter_map(author).collect();
+            non_empty(&names.join(", "))
+        }
+        Value::Object(_) => text(&value["name"]),
+        _ => text(value),
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    value.as_str().and_then(non_empty)
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn normalize_date(date: &str) -> String {
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(date) {
+        return parsed.format("%Y-%m-%d").to_string();
+    }
+    match date
+        .get(..10)
+        .map(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d"))
+    {
+        Some(Ok(day)) => day.format("%Y-%m-%d").to_string(),
+        _ => date.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_meta_tags() {
+        let html = r#"<html><head>
+            <meta name="author" content="Ferris">
+            <meta property="article:published_time" content="2024-03-01T09:30:00+00:00">
+            <meta name="description" content="Plain description">
+            <meta property="og:description" content="  OpenGraph description ">
+            <link rel="canonical" href="https://example.com/post">
+        </head><body></body></html>"#;
+
+        assert_eq!(
+            extract(html),
+            PageMetadata {
+                author: Some("Ferris".to_string()),
+                published: Some("2024-03-01".to_string()),
+                description: Some("OpenGraph description".to_string()),
+                canonical_url: Some("https://example.com/post".to_string()),
+                no_archive: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_detects_archiving_opt_outs() {
+        let robots = r#"<html><head><meta name="robots" content="index, NoArchive"></head></html>"#;
+        let tdm = r#"<html><head><meta name="tdm-reservation" content="1"></head></html>"#;
+        let open = r#"<html><head><meta name="robots" content="index, follow"></head></html>"#;
+        assert!(extract(robots).no_archive);
+        assert!(extract(tdm).no_archive);
+        assert!(!extract(open).no_archive);
+    }
+
+    #[test]
+    fn test_prefers_json_ld_author_and_date() {
+        let html = r#"<html><head>
+            <meta name="author" content="Site Staff">
+            <meta property="og:url" content="https://example.com/og">
+            <script type="application/ld+json">
+              {"@context": "https://schema.org", "@graph": [
+                {"@type": "WebSite", "name": "Example"},
+                {"@type": "Article", "datePublished": "2023-11-20",
+                 "author": [{"@type": "Person", "name": "Ada"}, {"name": "Grace"}],
+                 "description": "From JSON-LD"}
+              ]}
+            </script>
+            <script type="application/ld+json">not json</script>
+        </head><body></body></html>"#;
+
+        let metadata = extract(html);
+        assert_eq!(metadata.author.as_deref(), Some("Ada, Grace"));
+        assert_eq!(metadata.published.as_deref(), Some("2023-11-20"));
+        assert_eq!(metadata.description.as_deref(), Some("From JSON-LD"));
+        assert_eq!(
+            metadata.canonical_url.as_deref(),
+            Some("https://example.com/og")
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::client::{FetchResponse, SearchResponse, SearchResult};
+use crate::error::{OllamaError, Result};
+use crate::provider::SearchProvider;
+use crate::store;
+use async_trait::async_trait;
+use std::fs;
+use std::path::PathBuf;
+use tracing::debug;
+use url::Url;
+
+const MAX_SLUG_CHARS: usize = 80;
+const CANNED_RESULTS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct Fixtures {
+    dir: PathBuf,
+}
+
+pub struct MockProvider {
+    fixtures: Fixtures,
+}
+
+impl Fixtures {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let dir = match std::env::var_os("WEAVEX_FIXTURES") {
+            Some(dir) => PathBuf::from(dir),
+            None => store::data_dir()?.join("fixtures"),
+        };
+        Ok(Self::new(dir))
+    }
+
+    pub fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse> {
+        let mut response = match self.read(&format!("search/{}.json", slug(query)))? {
+            Some(json) => serde_json::from_str::<SearchResponse>(&json).map_err(|e| {
+                OllamaError::InvalidResponse(format!("Invalid search fixture: {}", e))
+            })?,
+            None => canned_search(query),
+        };
+        if let Some(max) = max_results {
+            response.results.truncate(max);
+        }
+        Ok(response)
+    }
+
+    pub fn fetch(&self, url: &str) -> Result<FetchResponse> {
+        let name = slug(&page_name(url));
+        if let Some(json) = self.read(&format!("fetch/{}.json", name))? {
+            return serde_json::from_str::<FetchResponse>(&json).map_err(|e| {
+                OllamaError::InvalidResponse(format!("Invalid fetch fixture: {}", e))
+            });
+        }
+        if let Some(markdown) = self.read(&format!("fetch/{}.md", name))? {
+            let title = markdown
+                .lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .unwrap_or(url)
+                .trim()
+                .to_string();
+            return Ok(FetchResponse {
+                title,
+                content: markdown,
+                ..Default::default()
+            });
+        }
+        Ok(canned_page(url))
+    }
+
+    fn read(&self, name: &str) -> Result<Option<String>> {
+        let path = self.dir.join(name);
+        if !path.is_file() {
+            debug!("No fixture at {}, using canned response", path.display());
+            return Ok(None);
+        }
+        debug!("Serving fixture {}", path.display());
+        Ok(Some(fs::read_to_string(&path)?))
+    }
+}
+
+impl MockProvider {
+    pub fn new(fixtures: Fixtures) -> Self {
+        Self { fixtures }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for MockProvider {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse> {
+        self.fixtures.search(query, max_results)
+    }
+}
+
+pub fn slug(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let slug: String = words.join("-").chars().take(MAX_SLUG_CHARS).collect();
+    slug.trim_end_matches('-').to_string()
+}
+
+fn page_name(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(parsed) => format!(
+            "{}{}",
+            parsed
+                .host_str()
+                .unwrap_or_default()
+                .trim_start_matches("www."),
+            parsed.path()
+        ),
+        Err(_) => url.to_string(),
+    }
+}
+
+fn canned_search(query: &str) -> SearchResponse {
+    let slug = slug(query);
+    SearchResponse {
+        results: (1..=CANNED_RESULTS)
+            .map(|n| SearchResult {
+                title: format!("{} - mock result {}", query, n),
+                url: format!("https://example.com/{}/{}", slug, n),
+                content: format!(
+                    "Canned result {} for \"{}\", served by the mock provider.",
+                    n, query
+                ),
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+fn canned_page(url: &str) -> FetchResponse {
+    FetchResponse {
+        title: format!("Mock page for {}", url),
+        content: format!(
+            "# Mock page\n\nThis page stands in for {} while the mock provider is active. \
+             Add a fixture to serve real content.\n",
+            url
+        ),
+        links: vec![format!("{}/related", url.trim_end_matches('/'))],
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serves_fixtures_before_canned_responses() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("search")).unwrap();
+        fs::create_dir_all(dir.path().join("fetch")).unwrap();
+        fs::write(
+            dir.path().join("search/rust-async-runtimes.json"),
+            r#"{"results": [{"title": "Tokio", "url": "https://tokio.rs", "content": "Runtime"},
+                            {"title": "smol", "url": "https://github.com/smol-rs/smol", "content": ""}]}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("fetch/tokio-rs-blog.md"),
+            "# Tokio blog\n\nNews.\n",
+        )
+        .unwrap();
+        let fixtures = Fixtures::new(dir.path().to_path_buf());
+
+        let found = fixtures.search("Rust: async runtimes?", Some(1)).unwrap();
+        assert_eq!(found.results.len(), 1);
+        assert_eq!(found.results[0].url, "https://tokio.rs");
+        let canned = fixtures.search("anything else", None).unwrap();
+        assert_eq!(canned.results.len(), CANNED_RESULTS);
+        assert_eq!(canned.results[0].url, "https://example.com/anything-else/1");
+
+        let page = fixtures.fetch("https://www.tokio.rs/blog").unwrap();
+        assert_eq!(page.title, "Tokio blog");
+        let page = fixtures.fetch("https://example.com/a").unwrap();
+        assert_eq!(page.links, ["https://example.com/a/related"]);
+    }
+
+    #[test]
+    fn test_slugs_queries_and_urls() {
+        assert_eq!(slug("What's new in Rust 1.80?"), "what-s-new-in-rust-1-80");
+        assert_eq!(
+            slug(&page_name("https://docs.rs/tokio/latest/")),
+            "docs-rs-tokio-latest"
+        );
+        assert_eq!(slug(&"a ".repeat(100)).len(), MAX_SLUG_CHARS - 1);
+    }
+}
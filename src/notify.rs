@@ -0,0 +1,59 @@
+use crate::text::ellipsize;
+use notify_rust::Notification;
+use tracing::{debug, warn};
+
+const APP_NAME: &str = "Weavex";
+const MAX_BODY_CHARS: usize = 200;
+
+pub fn agent_finished(query: &str, outcome: &Result<String, String>) {
+    let (summary, body) = match outcome {
+        Ok(answer) => (
+            format!("Research finished: {}", query),
+            first_line(answer).unwrap_or_else(|| "The agent returned an empty answer.".to_string()),
+        ),
+        Err(error) => (format!("Research failed: {}", query), clip(error)),
+    };
+
+    match Notification::new()
+        .appname(APP_NAME)
+        .summary(&clip(&summary))
+        .body(&body)
+        .show()
+    {
+        Ok(_) => debug!("Sent desktop notification"),
+        Err(e) => warn!("Failed to show desktop notification: {}", e),
+    }
+}
+
+fn first_line(answer: &str) -> Option<String> {
+    answer
+        .lines()
+        .map(|line| line.trim().trim_start_matches('#').trim())
+        .find(|line| !line.is_empty())
+        .map(clip)
+}
+
+fn clip(text: &str) -> String {
+    ellipsize(text, MAX_BODY_CHARS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_line_skips_blank_lines_and_heading_markers() {
+        assert_eq!(
+            first_line("\n\n## Tokio is the default runtime\n\nDetails...").as_deref(),
+            Some("Tokio is the default runtime")
+        );
+        assert_eq!(first_line("   \n"), None);
+    }
+
+    #[test]
+    fn test_clip_long_text() {
+        let clipped = clip(&"a".repeat(500));
+        assert_eq!(clipped.chars().count(), MAX_BODY_CHARS);
+        assert!(clipped.ends_with('…'));
+    }
+}
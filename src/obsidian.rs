@@ -0,0 +1,155 @@
+use crate::error::{OllamaError, Result};
+use crate::history::Entry;
+use crate::mock::slug;
+use crate::text;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_FILE_STEM_CHARS: usize = 80;
+const FORBIDDEN_CHARS: &[char] = &[
+    '/', '\\', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']',
+];
+
+#[derive(Debug, Serialize)]
+struct Frontmatter<'a> {
+    query: &'a str,
+    date: String,
+    tags: Vec<String>,
+    sources: Vec<String>,
+}
+
+pub fn note(entry: &Entry, tags: &[String]) -> Result<String> {
+    let sources = text::urls(&entry.answer);
+    let mut all_tags = vec!["weavex".to_string(), entry.kind.clone()];
+    for tag in tags {
+        let tag = tag.trim_start_matches('#').replace(' ', "-");
+        if !tag.is_empty() && !all_tags.contains(&tag) {
+            all_tags.push(tag);
+        }
+    }
+    let frontmatter = Frontmatter {
+        query: &entry.query,
+        date: created(entry).format("%Y-%m-%dT%H:%M").to_string(),
+        tags: all_tags,
+        sources: sources.clone(),
+    };
+    let yaml = serde_yaml::to_string(&frontmatter)
+        .map_err(|e| OllamaError::InvalidResponse(format!("Invalid note frontmatter: {}", e)))?;
+
+    let mut note = format!(
+        "---\n{}---\n\n# {}\n\n{}\n",
+        yaml,
+        entry.query.trim(),
+        entry.answer.trim()
+    );
+    if !sources.is_empty() {
+        note.push_str("\n## Sources\n\n");
+        for url in &sources {
+            note.push_str(&format!("- [[{}|{}]]\n", slug(url), url));
+        }
+    }
+    Ok(note)
+}
+
+pub fn file_name(entry: &Entry, zettel: bool) -> String {
+    let title: String = entry
+        .query
+        .chars()
+        .map(|c| {
+            if FORBIDDEN_CHARS.contains(&c) || c.is_control() {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    let title: String = title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_FILE_STEM_CHARS)
+        .collect();
+    let title = match title.trim() {
+        "" => format!("weavex {}", entry.id),
+        title => title.to_string(),
+    };
+    if zettel {
+        format!("{} {}.md", created(entry).format("%Y%m%d%H%M"), title)
+    } else {
+        format!("{}.md", title)
+    }
+}
+
+pub fn write(vault: &Path, entry: &Entry, tags: &[String], zettel: bool) -> Result<PathBuf> {
+    fs::create_dir_all(vault)?;
+    let name = file_name(entry, zettel);
+    let stem = name.trim_end_matches(".md");
+    let path = (1..)
+        .map(|n| match n {
+            1 => vault.join(&name),
+            _ => vault.join(format!("{} ({}).md", stem, n)),
+        })
+        .find(|path| !path.exists())
+        .unwrap_or_else(|| vault.join(&name));
+    fs::write(&path, note(entry, tags)?)?;
+    Ok(path)
+}
+
+fn created(entry: &Entry) -> DateTime<Local> {
+    DateTime::from_timestamp(entry.created_at, 0)
+        .unwrap_or_default()
+        .with_timezone(&Local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> Entry {
+        Entry {
+            id: 7,
+            kind: "agent".to_string(),
+            query: "Tokio vs smol: which runtime?".to_string(),
+            summary: String::new(),
+            answer: "Tokio is the default choice (https://tokio.rs/blog). \
+                     smol is smaller (https://github.com/smol-rs/smol)."
+                .to_string(),
+            created_at: 1_760_000_000,
+        }
+    }
+
+    #[test]
+    fn test_note_has_frontmatter_and_wiki_links() {
+        let note = note(&entry(), &["#rust".to_string(), "async io".to_string()]).unwrap();
+        let (frontmatter, body) = note
+            .strip_prefix("---\n")
+            .and_then(|rest| rest.split_once("---\n"))
+            .unwrap();
+        let yaml: serde_yaml::Value = serde_yaml::from_str(frontmatter).unwrap();
+        assert_eq!(yaml["query"], "Tokio vs smol: which runtime?");
+        assert_eq!(
+            yaml["tags"],
+            serde_yaml::to_value(["weavex", "agent", "rust", "async-io"]).unwrap()
+        );
+        assert_eq!(yaml["sources"][1], "https://github.com/smol-rs/smol");
+        assert!(body.starts_with("\n# Tokio vs smol: which runtime?\n"));
+        assert!(body.contains("- [[https-tokio-rs-blog|https://tokio.rs/blog]]"));
+    }
+
+    #[test]
+    fn test_file_names_drop_forbidden_characters() {
+        assert_eq!(file_name(&entry(), false), "Tokio vs smol which runtime.md");
+        let zettel = file_name(&entry(), true);
+        assert!(zettel.ends_with(" Tokio vs smol which runtime.md"));
+        assert_eq!(zettel.split(' ').next().unwrap().len(), 12);
+
+        let dir = tempfile::tempdir().unwrap();
+        let first = write(dir.path(), &entry(), &[], false).unwrap();
+        let second = write(dir.path(), &entry(), &[], false).unwrap();
+        assert_ne!(first, second);
+        assert!(second.ends_with("Tokio vs smol which runtime (2).md"));
+    }
+}
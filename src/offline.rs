@@ -0,0 +1,166 @@
+use crate::artifacts::{self, Owner};
+use crate::client::{FetchResponse, SearchResponse, SearchResult};
+use crate::error::Result;
+use crate::feedback;
+use crate::history::{self, Kind};
+use crate::provider::SearchProvider;
+use crate::store::Store;
+use crate::text::truncate_at_sentence;
+use crate::usage;
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+const DEFAULT_RESULTS: usize = 10;
+const SNIPPET_CHARS: usize = 300;
+
+pub const AGENT_NOTICE: &str = "You are running offline: live web access is unavailable. \
+    web_search and web_fetch only return copies cached by earlier runs and often return \
+    nothing. Prefer the knowledge base and your notes, and tell the user which parts of the \
+    answer could not be checked against current sources.";
+
+pub struct CachedSearch {
+    store: Mutex<Store>,
+}
+
+impl CachedSearch {
+    pub fn new(store: Store) -> Self {
+        Self {
+            store: Mutex::new(store),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for CachedSearch {
+    fn name(&self) -> &str {
+        "cache"
+    }
+
+    async fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse> {
+        let store = self.store.lock().unwrap_or_else(|e| e.into_inner());
+        let results = search(&store, query, max_results)?;
+        usage::cache_lookup(!results.is_empty());
+        Ok(SearchResponse { results })
+    }
+}
+
+pub fn search(store: &Store, query: &str, max_results: Option<usize>) -> Result<Vec<SearchResult>> {
+    let limit = max_results.unwrap_or(DEFAULT_RESULTS);
+    let mut results = feedback::seen_for_query(store, query)?;
+    if results.is_empty() {
+        results = history::list(store, Some(query), limit)?
+            .into_iter()
+            .filter(|entry| entry.kind == Kind::Fetch.as_str())
+            .filter_map(|entry| Some((content(store, &entry)?, entry)))
+            .map(|(content, entry)| SearchResult {
+                content: truncate_at_sentence(&content, SNIPPET_CHARS).to_string(),
+                url: entry.query,
+                title: entry.summary,
+                ..Default::default()
+            })
+            .collect();
+    }
+    results.truncate(limit);
+    Ok(results)
+}
+
+pub fn page(store: &Store, url: &str) -> Result<Option<(FetchResponse, String)>> {
+    let Some(entry) = history::latest(store, Kind::Fetch, url)? else {
+        return Ok(None);
+    };
+    Ok(content(store, &entry).map(|content| {
+        let fetched = entry.timestamp();
+        let page = FetchResponse {
+            title: entry.summary,
+            content,
+            ..Default::default()
+        };
+        (page, fetched)
+    }))
+}
+
+pub fn record(store: &Store, url: &str, response: &FetchResponse) -> Result<i64> {
+    let id = history::record(store, Kind::Fetch, url, &response.title, "")?;
+    artifacts::put(
+        store,
+        Owner::History,
+        id,
+        response.content.as_bytes(),
+        "text/markdown",
+    )?;
+    Ok(id)
+}
+
+fn content(store: &Store, entry: &history::Entry) -> Option<String> {
+    if !entry.answer.is_empty() {
+        return Some(entry.answer.clone());
+    }
+    artifacts::load_for(store, Owner::History, entry.id)
+        .ok()
+        .flatten()
+        .filter(|content| !content.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> Store {
+        let store = Store::open_in_memory().unwrap();
+        let result = SearchResult {
+            title: "Tokio".to_string(),
+            url: "https://tokio.rs".to_string(),
+            content: String::new(),
+            ..Default::default()
+        };
+        feedback::record_seen(&store, "rust async", &[result]).unwrap();
+        history::record(
+            &store,
+            Kind::Fetch,
+            "https://smol.rs",
+            "smol",
+            "A small and fast async runtime. It is built from small crates.",
+        )
+        .unwrap();
+        store
+    }
+
+    #[test]
+    fn test_searches_seen_results_then_cached_pages() {
+        let store = store();
+        let seen = search(&store, "rust async", None).unwrap();
+        assert_eq!(seen[0].url, "https://tokio.rs");
+
+        let pages = search(&store, "fast runtime", Some(3)).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].url, "https://smol.rs");
+        assert_eq!(pages[0].title, "smol");
+        assert!(search(&store, "python", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_serves_cached_pages_only() {
+        let store = store();
+        let (cached, _) = page(&store, "https://smol.rs").unwrap().unwrap();
+        assert_eq!(cached.title, "smol");
+        assert!(cached.content.starts_with("A small and fast"));
+        assert!(page(&store, "https://tokio.rs").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_serves_pages_recorded_as_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(&dir.path().join("weavex.db")).unwrap();
+        let response = FetchResponse {
+            title: "Glommio".to_string(),
+            content: "A thread-per-core runtime for io_uring.".to_string(),
+            ..Default::default()
+        };
+        record(&store, "https://glommio.rs", &response).unwrap();
+
+        let (cached, _) = page(&store, "https://glommio.rs").unwrap().unwrap();
+        assert_eq!(cached.content, response.content);
+        let found = search(&store, "glommio", None).unwrap();
+        assert_eq!(found[0].content, response.content);
+    }
+}
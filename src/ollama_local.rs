@@ -1,6 +1,14 @@
+use crate::breaker::{self, CircuitBreaker};
 use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use crate::usage;
+use crate::vcr;
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, instrument};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,6 +45,13 @@ struct ChatRequest {
     stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     think: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<ChatOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatOptions {
+    seed: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -44,6 +59,10 @@ pub struct ChatResponse {
     pub message: ChatMessage,
     #[allow(dead_code)]
     pub done: bool,
+    #[serde(default)]
+    pub prompt_eval_count: u64,
+    #[serde(default)]
+    pub eval_count: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -57,67 +76,287 @@ pub struct ChatMessage {
     pub thinking: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct ShowRequest<'a> {
+    model: &'a str,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ModelInfo {
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    #[serde(default)]
+    model_info: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    parameters: String,
+}
+
+impl ModelInfo {
+    pub fn supports_tools(&self) -> Option<bool> {
+        if self.capabilities.is_empty() {
+            return None;
+        }
+        Some(self.capabilities.iter().any(|c| c == "tools"))
+    }
+
+    pub fn context_length(&self) -> Option<u64> {
+        let num_ctx = self.parameters.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            (parts.next() == Some("num_ctx"))
+                .then(|| parts.next()?.parse().ok())
+                .flatten()
+        });
+        num_ctx.or_else(|| {
+            self.model_info
+                .iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, value)| value.as_u64())
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+#[async_trait]
+pub trait ChatBackend: Send + Sync {
+    async fn chat(
+        &self,
+        model: &str,
+        messages: Vec<serde_json::Value>,
+        tools: Option<Vec<Tool>>,
+        think: bool,
+    ) -> Result<ChatResponse>;
+}
+
+pub const DEFAULT_CHAT_TIMEOUT: Duration = Duration::from_secs(300);
+const EMBED_TIMEOUT: Duration = Duration::from_secs(300);
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+const CLOUD_SUFFIX: &str = "-cloud";
+
+#[derive(Clone)]
 pub struct OllamaLocal {
     client: Client,
     base_url: String,
+    breaker: Arc<CircuitBreaker>,
+    chat_timeout: Duration,
+    seed: Option<u64>,
+    cloud: Option<CloudEndpoint>,
+}
+
+#[derive(Clone)]
+struct CloudEndpoint {
+    base_url: String,
+    api_key: String,
+    breaker: Arc<CircuitBreaker>,
 }
 
 impl OllamaLocal {
     pub fn new(base_url: Option<String>) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(300))
+        let client = network::client_builder()
             .build()
             .map_err(OllamaError::RequestFailed)?;
 
+        let base_url = base_url.unwrap_or_else(|| "http://localhost:11434".to_string());
         Ok(Self {
             client,
-            base_url: base_url.unwrap_or_else(|| "http://localhost:11434".to_string()),
+            breaker: breaker::shared(&base_url),
+            base_url,
+            chat_timeout: DEFAULT_CHAT_TIMEOUT,
+            seed: None,
+            cloud: None,
+        })
+    }
+
+    pub fn with_cloud(mut self, base_url: String, api_key: String) -> Self {
+        self.cloud = Some(CloudEndpoint {
+            breaker: breaker::shared(&base_url),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        });
+        self
+    }
+
+    pub fn with_chat_timeout(mut self, chat_timeout: Duration) -> Self {
+        self.chat_timeout = chat_timeout;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    async fn post<T: Serialize>(
+        &self,
+        url: &str,
+        request: &T,
+        timeout: Duration,
+        api_key: Option<&str>,
+    ) -> Result<String> {
+        vcr::exchange(url, request, || async {
+            let mut builder = self.client.post(url).timeout(timeout).json(request);
+            if let Some(api_key) = api_key {
+                builder = builder.header("Authorization", format!("Bearer {}", api_key));
+            }
+            let response = builder.send_audited().await?;
+
+            let status = response.status();
+
+            if !status.is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                return Err(OllamaError::ApiError {
+                    status: status.as_u16(),
+                    message: error_text,
+                });
+            }
+
+            Ok(response.text().await?)
         })
+        .await
     }
 
+    pub async fn version(&self) -> Result<String> {
+        let url = format!("{}/api/version", self.base_url);
+
+        debug!("Checking local Ollama at: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .timeout(VERSION_TIMEOUT)
+            .send_audited()
+            .await?
+            .error_for_status()?;
+        let version = response.json::<VersionResponse>().await.map_err(|e| {
+            OllamaError::InvalidResponse(format!("Failed to parse version response: {}", e))
+        })?;
+        Ok(version.version)
+    }
+
+    pub async fn show(&self, model: &str) -> Result<ModelInfo> {
+        let url = format!("{}/api/show", self.base_url);
+
+        debug!("Inspecting model {} at: {}", model, url);
+
+        let response = self
+            .breaker
+            .call(self.post(&url, &ShowRequest { model }, VERSION_TIMEOUT, None))
+            .await?;
+        serde_json::from_str::<ModelInfo>(&response).map_err(|e| {
+            OllamaError::InvalidResponse(format!("Failed to parse model details: {}", e))
+        })
+    }
+
+    #[instrument(skip(self, prompt))]
+    pub async fn embed(&self, model: &str, prompt: &str) -> Result<Vec<f32>> {
+        let url = format!("{}/api/embeddings", self.base_url);
+
+        debug!("Sending embedding request to local Ollama at: {}", url);
+
+        let request = EmbeddingRequest { model, prompt };
+
+        let response = self
+            .breaker
+            .call(self.post(&url, &request, EMBED_TIMEOUT, None))
+            .await?;
+
+        let embedding_response =
+            serde_json::from_str::<EmbeddingResponse>(&response).map_err(|e| {
+                OllamaError::InvalidResponse(format!("Failed to parse embedding response: {}", e))
+            })?;
+
+        if embedding_response.embedding.is_empty() {
+            return Err(OllamaError::InvalidResponse(format!(
+                "Model '{}' returned an empty embedding",
+                model
+            )));
+        }
+
+        Ok(embedding_response.embedding)
+    }
+}
+
+#[async_trait]
+impl ChatBackend for OllamaLocal {
     #[instrument(skip(self, messages, tools))]
-    pub async fn chat(
+    async fn chat(
         &self,
         model: &str,
         messages: Vec<serde_json::Value>,
         tools: Option<Vec<Tool>>,
         think: bool,
     ) -> Result<ChatResponse> {
-        let url = format!("{}/api/chat", self.base_url);
+        let cloud = cloud_model(model).zip(self.cloud.as_ref());
+        let (url, breaker, api_key) = match &cloud {
+            Some((_, cloud)) => (
+                format!("{}/chat", cloud.base_url),
+                &cloud.breaker,
+                Some(cloud.api_key.as_str()),
+            ),
+            None => (format!("{}/api/chat", self.base_url), &self.breaker, None),
+        };
+
+        debug!("Sending chat request to Ollama at: {}", url);
 
-        debug!("Sending chat request to local Ollama at: {}", url);
+        usage::check(usage::Kind::ModelTokens)?;
 
         let request = ChatRequest {
-            model: model.to_string(),
+            model: cloud.map_or(model, |(name, _)| name).to_string(),
             messages,
             tools,
             stream: false,
             think: if think { Some(true) } else { None },
+            options: self.seed.map(|seed| ChatOptions { seed }),
         };
 
-        let response = self.client.post(&url).json(&request).send().await?;
+        let response = breaker
+            .call(self.post(&url, &request, self.chat_timeout, api_key))
+            .await
+            .map_err(|e| match e {
+                OllamaError::RequestFailed(e) if cloud.is_none() && e.is_connect() => {
+                    OllamaError::OllamaUnreachable(format!(
+                        "Lost the connection to Ollama at {}: {}",
+                        self.base_url, e
+                    ))
+                }
+                e => e,
+            })?;
 
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(OllamaError::ApiError {
-                status: status.as_u16(),
-                message: error_text,
-            });
-        }
-
-        let chat_response = response.json::<ChatResponse>().await.map_err(|e| {
+        let chat_response = serde_json::from_str::<ChatResponse>(&response).map_err(|e| {
             OllamaError::InvalidResponse(format!("Failed to parse chat response: {}", e))
         })?;
+        usage::add(
+            usage::Kind::ModelTokens,
+            chat_response.prompt_eval_count + chat_response.eval_count,
+        );
 
         Ok(chat_response)
     }
 }
 
+pub fn cloud_model(model: &str) -> Option<&str> {
+    model
+        .strip_suffix(CLOUD_SUFFIX)
+        .filter(|name| !name.is_empty())
+}
+
 pub fn create_web_search_tool() -> Tool {
     Tool {
         tool_type: "function".to_string(),
@@ -142,18 +381,238 @@ pub fn create_web_search_tool() -> Tool {
     }
 }
 
-pub fn create_web_fetch_tool() -> Tool {
+pub fn create_web_fetch_tool(max_domains: Option<usize>) -> Tool {
+    let mut parameters = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "url": {
+                "type": "string",
+                "description": "The URL to fetch and parse"
+            }
+        },
+        "required": ["url"]
+    });
+    if let Some(max_domains) = max_domains {
+        parameters["properties"]["justification"] = serde_json::json!({
+            "type": "string",
+            "description": format!(
+                "This run may read pages from at most {} distinct domains. Only when fetching from \
+                 a new domain beyond that limit, explain why it is essential to the question",
+                max_domains
+            )
+        });
+    }
     Tool {
         tool_type: "function".to_string(),
         function: ToolFunction {
             name: "web_fetch".to_string(),
             description: "Fetch and parse content from a specific URL. Returns the page title, content, and links.".to_string(),
+            parameters,
+        },
+    }
+}
+
+pub fn create_kb_search_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "kb_search".to_string(),
+            description: "Search the user's personal knowledge base of previously saved documents. Consult it before searching the web; returns the most relevant saved passages with their sources.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "What to look up in the knowledge base"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+    }
+}
+
+pub fn create_arxiv_search_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "arxiv_search".to_string(),
+            description: "Search arXiv for academic papers. Returns titles, authors, publication dates, abstracts, and PDF links. Prefer this over web_search for literature reviews and research papers; use web_fetch on a PDF link to read a paper in full.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Search terms, or an arXiv query such as 'au:hinton AND ti:capsule'"
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": "Maximum number of papers to return (optional, defaults to 5)"
+                    },
+                    "sort": {
+                        "type": "string",
+                        "enum": ["relevance", "submitted", "updated"],
+                        "description": "Sort by relevance or by newest submitted/updated date (optional, defaults to relevance)"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+    }
+}
+
+pub fn create_calculate_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "calculate".to_string(),
+            description: "Evaluate an arithmetic expression or unit conversion exactly. Always use this instead of doing math in your head when combining numbers from sources. Supports + - * / ^, parentheses, percentages ('15% of 200'), mod, factorial, sqrt, ln, log, exp, trig, round, min, max, pi, e, and conversions such as '5 km to mi', '100 F to C', '1.5 GB to MiB', '60 mph to km/h'.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "expression": {
+                        "type": "string",
+                        "description": "Expression to evaluate, e.g. '(1200 - 950) / 950 * 100' or '3.5 gal to l'"
+                    }
+                },
+                "required": ["expression"]
+            }),
+        },
+    }
+}
+
+pub fn create_save_note_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "save_note".to_string(),
+            description: "Save a finding to your scratchpad so it survives long research sessions, where older tool results get truncated. Record facts, numbers, open questions, and the URL they came from as you go, then call list_notes before writing the final answer.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "topic": {
+                        "type": "string",
+                        "description": "Short heading to group related notes, e.g. 'benchmarks' or 'open questions'"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "The finding, stated on its own so it makes sense without the original page"
+                    },
+                    "source": {
+                        "type": "string",
+                        "description": "URL the finding came from (optional)"
+                    }
+                },
+                "required": ["topic", "content"]
+            }),
+        },
+    }
+}
+
+pub fn create_list_notes_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "list_notes".to_string(),
+            description: "List the notes saved with save_note, grouped by topic. Call this before answering to review everything you have found.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "topic": {
+                        "type": "string",
+                        "description": "Only list notes under this topic (optional)"
+                    }
+                }
+            }),
+        },
+    }
+}
+
+pub fn create_current_datetime_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "current_datetime".to_string(),
+            description: "Get today's date, the current time, and the local timezone. Call this before judging whether information is recent, 'latest', or out of date, since your training data has a cutoff.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+    }
+}
+
+pub fn create_discussion_search_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "discussion_search".to_string(),
+            description: "Search Hacker News and Reddit for community discussion threads about a topic. Returns thread titles, points, comment counts, dates, and thread URLs. Use it to gauge community sentiment, real-world experiences, and criticism that web search tends to bury.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Topic to find discussions about"
+                    },
+                    "source": {
+                        "type": "string",
+                        "enum": ["all", "hn", "reddit"],
+                        "description": "Where to search (optional, defaults to 'all')"
+                    },
+                    "subreddit": {
+                        "type": "string",
+                        "description": "Restrict Reddit results to one subreddit, e.g. 'rust' (optional)"
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": "Maximum number of threads per source (optional, defaults to 5)"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+    }
+}
+
+pub fn create_discussion_thread_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "discussion_thread".to_string(),
+            description:
+                "Read a Hacker News or Reddit thread: the original post and its top comments."
+                    .to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "Thread URL from discussion_search (news.ycombinator.com/item?id=... or reddit.com/r/.../comments/...)"
+                    }
+                },
+                "required": ["url"]
+            }),
+        },
+    }
+}
+
+pub fn create_fetch_feed_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "fetch_feed".to_string(),
+            description: "Read an RSS or Atom feed and return its latest entries with titles, dates, summaries, and links. Use it to check news sources, blogs, release notes, and changelogs for recent updates. A site's home page works too if it advertises a feed.".to_string(),
             parameters: serde_json::json!({
                 "type": "object",
                 "properties": {
                     "url": {
                         "type": "string",
-                        "description": "The URL to fetch and parse"
+                        "description": "Feed URL, or a page that links to its feed"
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": "Maximum number of entries to return (optional, defaults to 10)"
                     }
                 },
                 "required": ["url"]
@@ -161,3 +620,207 @@ pub fn create_web_fetch_tool() -> Tool {
         },
     }
 }
+
+pub fn create_read_file_tool(roots: &[std::path::PathBuf]) -> Tool {
+    let roots = roots
+        .iter()
+        .map(|root| root.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "read_file".to_string(),
+            description: format!("Read a local text file, or list a directory, inside the user's allowed directories: {}. Use it to combine the user's notes, logs, or code with web research. Returns numbered lines, 400 at a time.", roots),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "File or directory path, absolute or relative to an allowed directory"
+                    },
+                    "start_line": {
+                        "type": "integer",
+                        "description": "First line to return (optional, defaults to 1)"
+                    }
+                },
+                "required": ["path"]
+            }),
+        },
+    }
+}
+
+pub fn create_github_search_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "github_search".to_string(),
+            description: "Search GitHub for repositories, code, or issues and pull requests. Use it for questions like which crate or project implements something, where an API is used, or whether a bug is known. Returns linkable results with stars, languages, and states.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "GitHub search query; qualifiers such as 'language:rust', 'repo:owner/name', or 'is:open' are supported"
+                    },
+                    "kind": {
+                        "type": "string",
+                        "enum": ["repositories", "code", "issues"],
+                        "description": "What to search (optional, defaults to 'repositories'; 'issues' includes pull requests)"
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": "Maximum number of results to return (optional, defaults to 5)"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+    }
+}
+
+pub fn create_weather_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "get_weather".to_string(),
+            description: "Get current conditions and a daily forecast (up to 7 days) from Open-Meteo. Use this instead of web_search for weather questions; give either a place name or coordinates.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "location": {
+                        "type": "string",
+                        "description": "Place name, e.g. 'Kyoto, Japan' (optional if latitude and longitude are given)"
+                    },
+                    "latitude": {
+                        "type": "number",
+                        "description": "Latitude in decimal degrees (optional)"
+                    },
+                    "longitude": {
+                        "type": "number",
+                        "description": "Longitude in decimal degrees (optional)"
+                    },
+                    "days": {
+                        "type": "integer",
+                        "description": "Number of forecast days, 1-7 (optional, defaults to 3)"
+                    },
+                    "units": {
+                        "type": "string",
+                        "enum": ["metric", "imperial"],
+                        "description": "Unit system (optional, defaults to 'metric')"
+                    }
+                },
+                "required": []
+            }),
+        },
+    }
+}
+
+pub fn create_geocode_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "geocode".to_string(),
+            description: "Look up the coordinates and full name of a place or address with OpenStreetMap Nominatim. Use this for questions about where something is or to disambiguate place names.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Place name or address, e.g. 'Springfield, Illinois'"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum number of matches to return (optional, defaults to 3, at most 10)"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+    }
+}
+
+pub fn create_describe_image_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "describe_image".to_string(),
+            description: "Download an image (PNG, JPEG, GIF, or WebP) and have a vision model describe it. Use this for charts, diagrams, screenshots, and infographics found on fetched pages, whose content web_fetch cannot read.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "Absolute URL of the image"
+                    },
+                    "question": {
+                        "type": "string",
+                        "description": "What to look for in the image, e.g. 'What was the 2023 value?' (optional, defaults to a full description)"
+                    }
+                },
+                "required": ["url"]
+            }),
+        },
+    }
+}
+
+pub fn create_wikipedia_tool() -> Tool {
+    Tool {
+        tool_type: "function".to_string(),
+        function: ToolFunction {
+            name: "wikipedia_lookup".to_string(),
+            description: "Look up a Wikipedia article. Returns its summary and key sections. Prefer this over web_search for definitions, background, people, places, and other encyclopedic questions.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "title": {
+                        "type": "string",
+                        "description": "Article title or topic, e.g. 'Raft (algorithm)'"
+                    },
+                    "section": {
+                        "type": "string",
+                        "description": "Return only the section whose heading contains this text (optional)"
+                    },
+                    "language": {
+                        "type": "string",
+                        "description": "Wikipedia language code such as 'en' or 'de' (optional, defaults to 'en')"
+                    }
+                },
+                "required": ["title"]
+            }),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_tool_support_and_context_length() {
+        let info: ModelInfo = serde_json::from_str(
+            r#"{"capabilities": ["completion", "tools"],
+                "model_info": {"general.architecture": "llama", "llama.context_length": 131072},
+                "parameters": "stop \"<|eot_id|>\"\nnum_ctx                        4096"}"#,
+        )
+        .unwrap();
+        assert_eq!(info.supports_tools(), Some(true));
+        assert_eq!(info.context_length(), Some(4096));
+
+        let info: ModelInfo =
+            serde_json::from_str(r#"{"model_info": {"gemma3.context_length": 8192}}"#).unwrap();
+        assert_eq!(info.supports_tools(), None);
+        assert_eq!(info.context_length(), Some(8192));
+    }
+
+    #[test]
+    fn test_detects_cloud_models_by_suffix() {
+        assert_eq!(cloud_model("gpt-oss:120b-cloud"), Some("gpt-oss:120b"));
+        assert_eq!(
+            cloud_model("qwen3-coder:480b-cloud"),
+            Some("qwen3-coder:480b")
+        );
+        assert_eq!(cloud_model("gpt-oss:20b"), None);
+        assert_eq!(cloud_model("-cloud"), None);
+    }
+}
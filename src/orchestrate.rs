@@ -0,0 +1,269 @@
+use crate::agent::Agent;
+use crate::error::{OllamaError, Result};
+use crate::glyphs::Glyph;
+use crate::loading::LoadingAnimation;
+use crate::ollama_local::ChatBackend;
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+pub const DEFAULT_WORKER_ITERATIONS: usize = 10;
+const MAX_SUBTOPIC_CHARS: usize = 300;
+
+pub struct Orchestrator {
+    chat: Box<dyn ChatBackend>,
+    model: String,
+    workers: Vec<Agent>,
+    enable_reasoning: bool,
+}
+
+#[derive(Debug)]
+pub struct Finding {
+    pub subtopic: String,
+    pub outcome: std::result::Result<String, String>,
+}
+
+#[async_trait(?Send)]
+pub trait Researcher {
+    async fn research(&self, query: &str) -> Result<String>;
+}
+
+#[async_trait(?Send)]
+impl Researcher for Agent {
+    async fn research(&self, query: &str) -> Result<String> {
+        self.run(query).await
+    }
+}
+
+#[async_trait(?Send)]
+impl Researcher for Orchestrator {
+    async fn research(&self, query: &str) -> Result<String> {
+        self.run(query).await
+    }
+}
+
+impl Orchestrator {
+    pub fn new(chat: Box<dyn ChatBackend>, model: String, workers: Vec<Agent>) -> Self {
+        Self {
+            chat,
+            model,
+            workers: workers
+                .into_iter()
+                .map(|w| w.with_spinner(false).with_exit_code(false))
+                .collect(),
+            enable_reasoning: true,
+        }
+    }
+
+    pub fn with_reasoning(mut self, enable_reasoning: bool) -> Self {
+        self.enable_reasoning = enable_reasoning;
+        self
+    }
+
+    pub async fn run(&self, query: &str) -> Result<String> {
+        let subtopics = self.plan(query).await?;
+        println!("{}Research plan:", Glyph::Plan);
+        for (i, subtopic) in subtopics.iter().enumerate() {
+            println!("   {}. {}", i + 1, subtopic);
+        }
+        println!();
+
+        let loading = LoadingAnimation::start();
+        let loader = &loading;
+        let findings = join_all(self.workers.iter().zip(&subtopics).enumerate().map(
+            |(i, (worker, subtopic))| async move {
+                let outcome = worker
+                    .run(&worker_prompt(query, subtopic))
+                    .await
+                    .map_err(|e| e.to_string());
+                loader.suspend(|| match &outcome {
+                    Ok(_) => println!("{}Worker {} finished: {}", Glyph::Success, i + 1, subtopic),
+                    Err(e) => println!("{}Worker {} failed: {}", Glyph::Warning, i + 1, e),
+                });
+                Finding {
+                    subtopic: subtopic.clone(),
+                    outcome,
+                }
+            },
+        ))
+        .await;
+        loading.stop();
+
+        if findings.iter().all(|f| f.outcome.is_err()) {
+            let first = findings.iter().find_map(|f| f.outcome.as_ref().err());
+            return Err(OllamaError::InvalidResponse(format!(
+                "All {} workers failed: {}",
+                findings.len(),
+                first.map(String::as_str).unwrap_or_default()
+            )));
+        }
+
+        println!(
+            "\n{}Synthesizing {} findings\n",
+            Glyph::Synthesis,
+            findings.len()
+        );
+        let response = self
+            .chat
+            .chat(
+                &self.model,
+                vec![json!({"role": "user", "content": synthesis_prompt(query, &findings)})],
+                None,
+                self.enable_reasoning,
+            )
+            .await?;
+        Ok(response.message.content)
+    }
+
+    async fn plan(&self, query: &str) -> Result<Vec<String>> {
+        let response = self
+            .chat
+            .chat(
+                &self.model,
+                vec![json!({"role": "user", "content": planner_prompt(query, self.workers.len())})],
+                None,
+                self.enable_reasoning,
+            )
+            .await?;
+
+        let subtopics = parse_plan(&response.message.content, self.workers.len());
+        if subtopics.is_empty() {
+            warn!("Planner returned no usable subtopics; researching the task as a whole");
+            return Ok(vec![query.to_string()]);
+        }
+        info!("Planned {} subtopics", subtopics.len());
+        Ok(subtopics)
+    }
+}
+
+fn planner_prompt(query: &str, workers: usize) -> String {
+    format!(
+        "You are planning a research task for {workers} independent researchers working in parallel.\n\
+         Split the task below into at most {workers} distinct, non-overlapping subtopics that \
+         together cover it. Each subtopic must be a self-contained research question.\n\
+         Reply with only a JSON array of strings.\n\n\
+         Task: {query}"
+    )
+}
+
+fn worker_prompt(query: &str, subtopic: &str) -> String {
+    format!(
+        "You are one of several researchers working on: {query}\n\n\
+         Research only this part: {subtopic}\n\n\
+         Report concrete findings with source URLs. Other researchers cover the remaining parts."
+    )
+}
+
+pub fn synthesis_prompt(query: &str, findings: &[Finding]) -> String {
+    let mut prompt = format!(
+        "Several researchers investigated parts of this task in parallel: {query}\n\n\
+         Merge their findings into one well-structured report that answers the task. \
+         Remove duplication, reconcile contradictions, keep the source URLs they cite, and \
+         note any part that could not be researched.\n"
+    );
+    for (i, finding) in findings.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\n## Researcher {}: {}\n\n",
+            i + 1,
+            finding.subtopic
+        ));
+        match &finding.outcome {
+            Ok(report) => prompt.push_str(report.trim()),
+            Err(e) => prompt.push_str(&format!("(No findings: {})", e)),
+        }
+        prompt.push('\n');
+    }
+    prompt
+}
+
+pub fn parse_plan(content: &str, max: usize) -> Vec<String> {
+    let from_json = content
+        .find('[')
+        .zip(content.rfind(']'))
+        .filter(|(start, end)| start < end)
+        .and_then(|(start, end)| serde_json::from_str::<Vec<Value>>(&content[start..=end]).ok());
+
+    let candidates: Vec<String> = match from_json {
+        Some(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(s.clone()),
+                Value::Object(map) => ["subtopic", "topic", "task", "question"]
+                    .iter()
+                    .find_map(|key| map.get(*key).and_then(Value::as_str))
+                    .map(str::to_string),
+                _ => None,
+            })
+            .collect(),
+        None => content
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .trim_start_matches(|c: char| {
+                        c.is_ascii_digit() || matches!(c, '-' | '*' | '.' | ')' | '•')
+                    })
+                    .to_string()
+            })
+            .collect(),
+    };
+
+    let mut subtopics: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let subtopic: String = candidate
+            .trim()
+            .trim_matches('"')
+            .chars()
+            .take(MAX_SUBTOPIC_CHARS)
+            .collect();
+        if subtopic.is_empty() || subtopic.starts_with("```") || subtopic.ends_with(':') {
+            continue;
+        }
+        if !subtopics.iter().any(|s| s.eq_ignore_ascii_case(&subtopic)) {
+            subtopics.push(subtopic);
+        }
+    }
+    subtopics.truncate(max);
+    subtopics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_plans_in_json_and_list_form() {
+        let fenced = "Here is the plan:\n```json\n[\"Tokio scheduler design\", \
+                      {\"topic\": \"async-std status\"}, \"tokio scheduler design\", 3]\n```";
+        assert_eq!(
+            parse_plan(fenced, 4),
+            ["Tokio scheduler design", "async-std status"]
+        );
+
+        let listed = "1. History of Rust\n\n- Ownership model\n* Borrow checker\n2) Editions";
+        assert_eq!(
+            parse_plan(listed, 3),
+            ["History of Rust", "Ownership model", "Borrow checker"]
+        );
+        assert!(parse_plan("", 3).is_empty());
+    }
+
+    #[test]
+    fn test_synthesis_prompt_includes_every_finding() {
+        let findings = vec![
+            Finding {
+                subtopic: "Pricing".to_string(),
+                outcome: Ok(" $10/month [1]\n".to_string()),
+            },
+            Finding {
+                subtopic: "Licensing".to_string(),
+                outcome: Err("Maximum iterations reached".to_string()),
+            },
+        ];
+        let prompt = synthesis_prompt("Compare plans", &findings);
+        assert!(prompt.contains("in parallel: Compare plans"));
+        assert!(prompt.contains("## Researcher 1: Pricing\n\n$10/month [1]\n"));
+        assert!(prompt
+            .contains("## Researcher 2: Licensing\n\n(No findings: Maximum iterations reached)"));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::error::{OllamaError, Result};
+use url::Url;
+
+const MAX_TITLE_CHARS: usize = 200;
+
+pub fn is_pdf_url(url: &Url) -> bool {
+    url.path().to_ascii_lowercase().ends_with(".pdf")
+}
+
+pub fn is_pdf_bytes(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"%PDF-")
+}
+
+pub async fn extract_text(bytes: Vec<u8>) -> Result<String> {
+    let text = tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+        .await
+        .map_err(|e| OllamaError::InvalidResponse(format!("PDF extraction panicked: {}", e)))?
+        .map_err(|e| OllamaError::InvalidResponse(format!("Failed to extract PDF text: {}", e)))?;
+    Ok(tidy(&text))
+}
+
+pub fn title(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .find(|line| line.chars().any(char::is_alphabetic))
+        .map(|line| line.chars().take(MAX_TITLE_CHARS).collect())
+        .unwrap_or_default()
+}
+
+fn tidy(text: &str) -> String {
+    let mut output = String::new();
+    let mut blank = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank += 1;
+            if blank > 1 {
+                continue;
+            }
+        } else {
+            blank = 0;
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+    output.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pdf(text: &str) -> Vec<u8> {
+        let stream = format!("BT /F1 24 Tf 72 700 Td ({}) Tj ET", text);
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R \
+             /Resources << /Font << /F1 5 0 R >> >> >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                stream.len(),
+                stream
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+
+        let mut output = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (idx, object) in objects.iter().enumerate() {
+            offsets.push(output.len());
+            output.push_str(&format!("{} 0 obj\n{}\nendobj\n", idx + 1, object));
+        }
+        let xref = output.len();
+        output.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            output.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        output.push_str(&format!(
+            "trailer\n<< /Root 1 0 R /Size {} >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        ));
+        output.into_bytes()
+    }
+
+    #[test]
+    fn test_detects_pdfs() {
+        assert!(is_pdf_url(
+            &Url::parse("https://arxiv.org/pdf/1234.PDF").unwrap()
+        ));
+        assert!(!is_pdf_url(
+            &Url::parse("https://arxiv.org/abs/1234").unwrap()
+        ));
+        assert!(is_pdf_bytes(&pdf("Raft")));
+    }
+
+    #[test]
+    fn test_extracts_text() {
+        use tokio::runtime::Runtime;
+
+        let rt = Runtime::new().unwrap();
+        let text = rt.block_on(extract_text(pdf("Consensus in Raft"))).unwrap();
+        assert!(text.contains("Consensus in Raft"));
+        assert_eq!(title(&format!("\n  12\n{}", text)), "Consensus in Raft");
+    }
+}
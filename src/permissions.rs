@@ -0,0 +1,158 @@
+use crate::error::Result;
+use crate::store::{self, Store};
+use clap::ValueEnum;
+use rusqlite::params;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Capability {
+    #[value(name = "read_file")]
+    ReadFile,
+}
+
+#[derive(Debug, Clone)]
+pub struct Grant {
+    pub capability: Capability,
+    pub granted_at: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Permissions {
+    granted: HashSet<Capability>,
+}
+
+impl Capability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Capability::ReadFile => "read_file",
+        }
+    }
+
+    pub fn for_tool(tool: &str) -> Option<Self> {
+        match tool {
+            "read_file" => Some(Capability::ReadFile),
+            _ => None,
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        Self::from_str(name, false).ok()
+    }
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub fn grant(store: &Store, capability: Capability) -> Result<()> {
+    store.conn().execute(
+        "INSERT INTO permissions (capability, granted_at) VALUES (?1, ?2)
+         ON CONFLICT(capability) DO NOTHING",
+        params![capability.as_str(), store::now()],
+    )?;
+    Ok(())
+}
+
+pub fn revoke(store: &Store, capability: Capability) -> Result<bool> {
+    let removed = store.conn().execute(
+        "DELETE FROM permissions WHERE capability = ?1",
+        params![capability.as_str()],
+    )?;
+    Ok(removed > 0)
+}
+
+pub fn list(store: &Store) -> Result<Vec<Grant>> {
+    let mut stmt = store
+        .conn()
+        .prepare("SELECT capability, granted_at FROM permissions ORDER BY capability")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+    })?;
+
+    let mut grants = Vec::new();
+    for row in rows {
+        let (name, granted_at) = row?;
+        if let Some(capability) = Capability::parse(&name) {
+            grants.push(Grant {
+                capability,
+                granted_at,
+            });
+        }
+    }
+    Ok(grants)
+}
+
+impl Permissions {
+    pub fn new(granted: impl IntoIterator<Item = Capability>) -> Self {
+        Self {
+            granted: granted.into_iter().collect(),
+        }
+    }
+
+    pub fn load(store: &Store, run_grants: &[Capability]) -> Result<Self> {
+        let persisted = list(store)?.into_iter().map(|grant| grant.capability);
+        Ok(Self::new(persisted.chain(run_grants.iter().copied())))
+    }
+
+    pub fn allows(&self, capability: Capability) -> bool {
+        self.granted.contains(&capability)
+    }
+
+    pub fn check(&self, tool: &str) -> std::result::Result<(), String> {
+        match Capability::for_tool(tool) {
+            Some(capability) if !self.allows(capability) => Err(format!(
+                "Policy: the {} tool needs the '{}' permission, which the user has not granted. \
+                 Do not retry it; continue with the other tools, and tell the user they can allow \
+                 it with --grant {} or `weavex permissions grant {}`.",
+                tool, capability, capability, capability
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persists_grants() {
+        let store = Store::open_in_memory().unwrap();
+        assert!(list(&store).unwrap().is_empty());
+
+        grant(&store, Capability::ReadFile).unwrap();
+        grant(&store, Capability::ReadFile).unwrap();
+        let grants = list(&store).unwrap();
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].capability, Capability::ReadFile);
+        assert!(Permissions::load(&store, &[])
+            .unwrap()
+            .allows(Capability::ReadFile));
+
+        assert!(revoke(&store, Capability::ReadFile).unwrap());
+        assert!(!revoke(&store, Capability::ReadFile).unwrap());
+        assert!(!Permissions::load(&store, &[])
+            .unwrap()
+            .allows(Capability::ReadFile));
+        assert!(Permissions::load(&store, &[Capability::ReadFile])
+            .unwrap()
+            .allows(Capability::ReadFile));
+    }
+
+    #[test]
+    fn test_checks_tools_against_grants() {
+        let denied = Permissions::default().check("read_file").unwrap_err();
+        assert!(denied.starts_with("Policy: the read_file tool needs the 'read_file' permission"));
+        assert!(denied.contains("weavex permissions grant read_file"));
+
+        assert!(Permissions::default().check("web_search").is_ok());
+        assert!(Permissions::new([Capability::ReadFile])
+            .check("read_file")
+            .is_ok());
+        assert_eq!(Capability::parse("read_file"), Some(Capability::ReadFile));
+        assert_eq!(Capability::parse("run_code"), None);
+    }
+}
@@ -0,0 +1,416 @@
+use crate::client::OllamaClient;
+use crate::error::{OllamaError, Result};
+use crate::glyphs::Glyph;
+use crate::ollama_local::ChatBackend;
+use crate::orchestrate::Researcher;
+use crate::provider::SearchProvider;
+use crate::summarizer::{self, Summarizer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const DEFAULT_FETCH_LIMIT: usize = 5;
+const DEFAULT_INSTRUCTIONS: &str =
+    "Summarize the following research material in a few short paragraphs, keeping concrete facts, numbers, and source URLs.";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Step {
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Search {
+        query: String,
+        #[serde(default)]
+        max_results: Option<usize>,
+    },
+    Fetch {
+        urls: String,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    Agent {
+        query: String,
+    },
+    Summarize {
+        text: String,
+        #[serde(default)]
+        instructions: Option<String>,
+    },
+    Export {
+        path: PathBuf,
+        content: String,
+    },
+}
+
+impl Action {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Action::Search { .. } => "search",
+            Action::Fetch { .. } => "fetch",
+            Action::Agent { .. } => "agent",
+            Action::Summarize { .. } => "summarize",
+            Action::Export { .. } => "export",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Output {
+    pub text: String,
+    pub urls: Vec<String>,
+}
+
+pub struct Tools<'a> {
+    pub search: &'a dyn SearchProvider,
+    pub client: &'a OllamaClient,
+    pub researcher: Option<&'a dyn Researcher>,
+    pub chat: Option<(&'a dyn ChatBackend, &'a str)>,
+    pub summarizer: Option<Summarizer>,
+}
+
+impl Pipeline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse(&contents).map_err(|e| {
+            OllamaError::ConfigError(format!("Invalid pipeline {}: {}", path.display(), e))
+        })
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let pipeline: Self =
+            serde_yaml::from_str(contents).map_err(|e| OllamaError::ConfigError(e.to_string()))?;
+        pipeline.validate()?;
+        Ok(pipeline)
+    }
+
+    pub fn step_ids(&self) -> Vec<String> {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(idx, step)| {
+                step.id
+                    .clone()
+                    .unwrap_or_else(|| format!("step{}", idx + 1))
+            })
+            .collect()
+    }
+
+    pub fn uses(&self, kind: &str) -> bool {
+        self.steps.iter().any(|step| step.action.kind() == kind)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.steps.is_empty() {
+            return Err(OllamaError::ConfigError(
+                "A pipeline needs at least one step".to_string(),
+            ));
+        }
+        let mut seen = Vec::new();
+        for id in self.step_ids() {
+            if seen.contains(&id) || self.vars.contains_key(&id) {
+                return Err(OllamaError::ConfigError(format!(
+                    "Step id '{}' is used twice or shadows a variable",
+                    id
+                )));
+            }
+            seen.push(id);
+        }
+        Ok(())
+    }
+}
+
+pub async fn run(
+    pipeline: &Pipeline,
+    vars: &[(String, String)],
+    tools: &Tools<'_>,
+) -> Result<Vec<(String, Output)>> {
+    let mut values = pipeline.vars.clone();
+    values.extend(vars.iter().cloned());
+    let mut outputs = Vec::new();
+    let total = pipeline.steps.len();
+
+    for (idx, (id, step)) in pipeline
+        .step_ids()
+        .into_iter()
+        .zip(&pipeline.steps)
+        .enumerate()
+    {
+        println!(
+            "{}[{}/{}] {} ({})",
+            Glyph::Step,
+            idx + 1,
+            total,
+            id,
+            step.action.kind()
+        );
+        let output = run_step(&id, &step.action, &values, tools).await?;
+        values.insert(id.clone(), output.text.clone());
+        values.insert(format!("{}.urls", id), output.urls.join("\n"));
+        outputs.push((id, output));
+    }
+    Ok(outputs)
+}
+
+async fn run_step(
+    id: &str,
+    action: &Action,
+    values: &BTreeMap<String, String>,
+    tools: &Tools<'_>,
+) -> Result<Output> {
+    let render = |text: &str| render(id, text, values);
+    match action {
+        Action::Search { query, max_results } => {
+            let query = render(query)?;
+            info!("Pipeline step {} searching for: {}", id, query);
+            let response = tools.search.search(&query, *max_results).await?;
+            let text = response
+                .results
+                .iter()
+                .map(|result| format!("- [{}]({})\n  {}", result.title, result.url, result.content))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let urls = response
+                .results
+                .into_iter()
+                .map(|result| result.url)
+                .collect();
+            Ok(Output { text, urls })
+        }
+        Action::Fetch { urls, limit } => {
+            let urls: Vec<String> = render(urls)?
+                .split_whitespace()
+                .take(limit.unwrap_or(DEFAULT_FETCH_LIMIT))
+                .map(str::to_string)
+                .collect();
+            let mut output = Output::default();
+            for url in urls {
+                match tools.client.fetch(&url).await {
+                    Ok(page) => {
+                        output.text.push_str(&format!(
+                            "## {}\n\nSource: {}\n\n{}\n\n",
+                            page.title, url, page.content
+                        ));
+                        output.urls.push(url);
+                    }
+                    Err(e) => warn!("Pipeline step {} could not fetch {}: {}", id, url, e),
+                }
+            }
+            if output.urls.is_empty() {
+                return Err(OllamaError::InvalidResponse(format!(
+                    "Step '{}' fetched no pages",
+                    id
+                )));
+            }
+            Ok(output)
+        }
+        Action::Agent { query } => {
+            let query = render(query)?;
+            let researcher = tools.researcher.ok_or_else(|| {
+                OllamaError::ConfigError(format!("Step '{}' needs the agent", id))
+            })?;
+            Ok(Output {
+                text: researcher.research(&query).await?,
+                urls: Vec::new(),
+            })
+        }
+        Action::Summarize { text, instructions } => {
+            let chat = match tools.summarizer {
+                Some(Summarizer::Extractive) => None,
+                _ => Some(tools.chat.ok_or_else(|| {
+                    OllamaError::ConfigError(format!("Step '{}' needs a chat model", id))
+                })?),
+            };
+            let text = render(text)?;
+            let instructions = match instructions {
+                Some(instructions) => render(instructions)?,
+                None => DEFAULT_INSTRUCTIONS.to_string(),
+            };
+            Ok(Output {
+                text: summarizer::summarize_with(
+                    chat,
+                    &text,
+                    &instructions,
+                    summarizer::EXTRACTIVE_SENTENCES,
+                )
+                .await?,
+                urls: Vec::new(),
+            })
+        }
+        Action::Export { path, content } => {
+            let content = render(content)?;
+            if let Some(parent) = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+            {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, &content)?;
+            println!("{}Wrote {}", Glyph::Save, path.display());
+            Ok(Output {
+                text: path.display().to_string(),
+                urls: Vec::new(),
+            })
+        }
+    }
+}
+
+fn render(id: &str, text: &str, values: &BTreeMap<String, String>) -> Result<String> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) if is_reference(&after[..end]) => {
+                let name = &after[..end];
+                let value = values.get(name).ok_or_else(|| {
+                    OllamaError::ConfigError(format!(
+                        "Step '{}' refers to {{{}}}, which is neither a variable nor an earlier step",
+                        id, name
+                    ))
+                })?;
+                output.push_str(value);
+                rest = &after[end + 1..];
+            }
+            _ => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn is_reference(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{SearchResponse, SearchResult};
+    use crate::config::Config;
+    use async_trait::async_trait;
+
+    const PLAN: &str = r##"
+name: runtimes
+vars:
+  topic: rust async runtimes
+steps:
+  - id: results
+    search:
+      query: "{topic} benchmarks"
+      max_results: 2
+  - export:
+      path: OUT/report.md
+      content: "# {topic}\n\n{results}\n\nSources:\n{results.urls}"
+"##;
+
+    struct Fixed;
+
+    #[async_trait]
+    impl SearchProvider for Fixed {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse> {
+            assert_eq!(query, "tokio and smol benchmarks");
+            assert_eq!(max_results, Some(2));
+            Ok(SearchResponse {
+                results: vec![SearchResult {
+                    title: "Tokio".to_string(),
+                    url: "https://tokio.rs".to_string(),
+                    content: "An async runtime".to_string(),
+                    ..Default::default()
+                }],
+            })
+        }
+    }
+
+    #[test]
+    fn test_parses_steps_and_rejects_duplicate_ids() {
+        let pipeline = Pipeline::parse(PLAN).unwrap();
+        assert_eq!(pipeline.step_ids(), ["results", "step2"]);
+        assert!(pipeline.uses("search"));
+        assert!(!pipeline.uses("agent"));
+
+        let duplicate = "steps:\n  - id: a\n    agent:\n      query: x\n  - id: a\n    agent:\n      query: y\n";
+        assert!(Pipeline::parse(duplicate).is_err());
+        assert!(Pipeline::parse("steps: []").is_err());
+        assert!(Pipeline::parse("steps:\n  - crawl:\n      url: x\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_passes_outputs_between_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let plan = PLAN.replace("OUT", &dir.path().display().to_string());
+        let pipeline = Pipeline::parse(&plan).unwrap();
+        let client = OllamaClient::new(Config::new("test".to_string())).unwrap();
+        let tools = Tools {
+            search: &Fixed,
+            client: &client,
+            researcher: None,
+            chat: None,
+            summarizer: None,
+        };
+        let vars = [("topic".to_string(), "tokio and smol".to_string())];
+
+        let outputs = run(&pipeline, &vars, &tools).await.unwrap();
+        assert_eq!(outputs[0].1.urls, ["https://tokio.rs"]);
+        let report = std::fs::read_to_string(dir.path().join("report.md")).unwrap();
+        assert_eq!(
+            report,
+            "# tokio and smol\n\n- [Tokio](https://tokio.rs)\n  An async runtime\n\nSources:\nhttps://tokio.rs"
+        );
+
+        let broken = Pipeline::parse("steps:\n  - agent:\n      query: \"{missing}\"\n").unwrap();
+        let err = run(&broken, &[], &tools).await.unwrap_err().to_string();
+        assert!(err.contains("{missing}"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_extractive_summarize_step_needs_no_model() {
+        let client = OllamaClient::new(Config::new("test".to_string())).unwrap();
+        let mut tools = Tools {
+            search: &Fixed,
+            client: &client,
+            researcher: None,
+            chat: None,
+            summarizer: None,
+        };
+        let plan = Pipeline::parse(
+            "vars:\n  notes: Tokio is a runtime. Smol is a runtime too.\nsteps:\n  - summarize:\n      text: \"{notes}\"\n",
+        )
+        .unwrap();
+        assert!(run(&plan, &[], &tools).await.is_err());
+
+        tools.summarizer = Some(Summarizer::Extractive);
+        let outputs = run(&plan, &[], &tools).await.unwrap();
+        assert_eq!(
+            outputs[0].1.text,
+            "Tokio is a runtime. Smol is a runtime too."
+        );
+    }
+}
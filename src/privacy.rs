@@ -0,0 +1,78 @@
+use std::io::{self, Write};
+use tracing_subscriber::fmt::MakeWriter;
+use url::form_urlencoded;
+
+pub const REDACTED: &str = "[private]";
+const MIN_SCRUBBED_LEN: usize = 3;
+
+#[derive(Debug, Clone, Default)]
+pub struct Scrubber {
+    secrets: Vec<String>,
+}
+
+pub struct ScrubbedStderr<'a> {
+    scrubber: &'a Scrubber,
+}
+
+impl Scrubber {
+    pub fn new(query: Option<&str>) -> Self {
+        let mut secrets = Vec::new();
+        if let Some(query) = query.map(str::trim).filter(|q| q.len() >= MIN_SCRUBBED_LEN) {
+            let form: String = form_urlencoded::byte_serialize(query.as_bytes()).collect();
+            secrets.push(form.replace('+', "%20"));
+            secrets.push(form);
+            secrets.push(query.to_string());
+        }
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        secrets.dedup();
+        Self { secrets }
+    }
+
+    pub fn scrub(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |text, secret| {
+            text.replace(secret, REDACTED)
+        })
+    }
+}
+
+impl<'a> MakeWriter<'a> for Scrubber {
+    type Writer = ScrubbedStderr<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        ScrubbedStderr { scrubber: self }
+    }
+}
+
+impl Write for ScrubbedStderr<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        io::stderr().write_all(self.scrubber.scrub(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrubs_query_and_its_url_encodings() {
+        let scrubber = Scrubber::new(Some("  divorce lawyer fees "));
+        assert_eq!(
+            scrubber.scrub(
+                "Searching for: divorce lawyer fees\n\
+                 GET https://search.example/?q=divorce+lawyer+fees&format=json\n\
+                 GET https://search.example/?q=divorce%20lawyer%20fees"
+            ),
+            "Searching for: [private]\n\
+             GET https://search.example/?q=[private]&format=json\n\
+             GET https://search.example/?q=[private]"
+        );
+        assert_eq!(Scrubber::new(Some("ab")).scrub("ab cd"), "ab cd");
+        assert_eq!(Scrubber::new(None).scrub("anything"), "anything");
+    }
+}
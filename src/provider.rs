@@ -0,0 +1,540 @@
+use crate::bookmarks::{self, BookmarkMode};
+use crate::brave::BraveClient;
+use crate::client::{OllamaClient, SearchResponse, SearchResult};
+use crate::config::Config;
+use crate::credibility;
+use crate::dedup;
+use crate::error::{OllamaError, Result};
+use crate::feedback;
+use crate::mock::{Fixtures, MockProvider};
+use crate::rerank::Reranker;
+use crate::searxng::SearxngClient;
+use async_trait::async_trait;
+use clap::ValueEnum;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, warn};
+
+pub const RRF_K: f64 = 60.0;
+pub const DEFAULT_PAGE_SIZE: usize = 10;
+const MAX_EMULATED_RESULTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProviderKind {
+    Ollama,
+    Brave,
+    Searxng,
+    Mock,
+}
+
+#[async_trait]
+pub trait SearchProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse>;
+
+    async fn search_page(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+        offset: usize,
+    ) -> Result<SearchResponse> {
+        if offset == 0 {
+            return self.search(query, max_results).await;
+        }
+        let page = max_results.unwrap_or(DEFAULT_PAGE_SIZE);
+        let wanted = (offset + page).min(MAX_EMULATED_RESULTS);
+        let mut response = self.search(query, Some(wanted)).await?;
+        response.results.drain(..offset.min(response.results.len()));
+        response.results.truncate(page);
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl SearchProvider for OllamaClient {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    async fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse> {
+        self.search_with_limit(query, max_results).await
+    }
+
+    async fn search_page(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+        offset: usize,
+    ) -> Result<SearchResponse> {
+        self.search_from(query, max_results, offset).await
+    }
+}
+
+pub struct MultiProvider {
+    providers: Vec<Box<dyn SearchProvider>>,
+    blend: bool,
+    domain_scores: HashMap<String, i64>,
+    bookmarked_domains: HashSet<String>,
+    bookmark_mode: BookmarkMode,
+    explain: bool,
+    reranker: Option<Reranker>,
+    min_score: Option<f64>,
+}
+
+impl MultiProvider {
+    pub fn new(providers: Vec<Box<dyn SearchProvider>>, blend: bool) -> Self {
+        Self {
+            providers,
+            blend,
+            domain_scores: HashMap::new(),
+            bookmarked_domains: HashSet::new(),
+            bookmark_mode: BookmarkMode::Off,
+            explain: false,
+            reranker: None,
+            min_score: None,
+        }
+    }
+
+    pub fn with_explain(mut self, explain: bool) -> Self {
+        self.explain = explain;
+        self
+    }
+
+    fn annotate(&self, provider: &str, mut results: Vec<SearchResult>) -> Vec<SearchResult> {
+        if self.explain {
+            for (rank, result) in results.iter_mut().enumerate() {
+                result
+                    .explain
+                    .push(format!("{} rank #{}", provider, rank + 1));
+            }
+        }
+        results
+    }
+
+    fn explain_personalization(&self, results: &mut [SearchResult], before: &[String]) {
+        for (position, result) in results.iter_mut().enumerate() {
+            let score = feedback::domain_score(&self.domain_scores, &result.url);
+            if score != 0 {
+                result
+                    .explain
+                    .push(format!("domain feedback score {:+}", score));
+            }
+            if self.bookmark_mode != BookmarkMode::Off
+                && self
+                    .bookmarked_domains
+                    .contains(&feedback::domain_of(&result.url))
+            {
+                result.explain.push(format!(
+                    "bookmarked domain ({})",
+                    match self.bookmark_mode {
+                        BookmarkMode::Pin => "pinned",
+                        _ => "boosted",
+                    }
+                ));
+            }
+            if let Some(original) = before.iter().position(|url| *url == result.url) {
+                if original != position {
+                    result.explain.push(format!(
+                        "moved from #{} to #{} by personalization",
+                        original + 1,
+                        position + 1
+                    ));
+                }
+            }
+        }
+    }
+
+    pub fn with_min_score(mut self, min_score: Option<f64>) -> Self {
+        self.min_score = min_score;
+        self
+    }
+
+    pub fn with_reranker(mut self, reranker: Option<Reranker>) -> Self {
+        self.reranker = reranker;
+        self
+    }
+
+    pub fn with_domain_scores(mut self, domain_scores: HashMap<String, i64>) -> Self {
+        self.domain_scores = domain_scores;
+        self
+    }
+
+    pub fn with_bookmarks(mut self, domains: HashSet<String>, mode: BookmarkMode) -> Self {
+        self.bookmarked_domains = domains;
+        self.bookmark_mode = mode;
+        self
+    }
+
+    async fn search_blended(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+        offset: usize,
+    ) -> Result<SearchResponse> {
+        let searches = self
+            .providers
+            .iter()
+            .map(|provider| provider.search_page(query, max_results, offset));
+        let outcomes = futures::future::join_all(searches).await;
+
+        let mut rankings = Vec::new();
+        let mut last_error = None;
+
+        for (provider, outcome) in self.providers.iter().zip(outcomes) {
+            match outcome {
+                Ok(response) => {
+                    debug!(
+                        "Provider {} returned {} results",
+                        provider.name(),
+                        response.results.len()
+                    );
+                    rankings.push(self.annotate(provider.name(), response.results));
+                }
+                Err(e) => {
+                    warn!("Provider {} failed: {}", provider.name(), e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        if rankings.is_empty() {
+            return Err(last_error.unwrap_or_else(|| {
+                OllamaError::ConfigError("No search providers configured".to_string())
+            }));
+        }
+
+        let mut results = reciprocal_rank_fusion(rankings);
+        if let Some(max) = max_results {
+            results.truncate(max);
+        }
+
+        Ok(SearchResponse { results })
+    }
+
+    async fn search_fallback(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+        offset: usize,
+    ) -> Result<SearchResponse> {
+        let mut empty_response = None;
+        let mut last_error = None;
+        let mut skipped = Vec::new();
+
+        for provider in &self.providers {
+            match provider.search_page(query, max_results, offset).await {
+                Ok(response) if !response.results.is_empty() => {
+                    let mut results = self.annotate(provider.name(), response.results);
+                    if self.explain && !skipped.is_empty() {
+                        let note = format!("fallback after {}", skipped.join(", "));
+                        for result in &mut results {
+                            result.explain.push(note.clone());
+                        }
+                    }
+                    return Ok(SearchResponse { results });
+                }
+                Ok(response) => {
+                    debug!("Provider {} returned no results", provider.name());
+                    skipped.push(format!("{} returned nothing", provider.name()));
+                    empty_response = Some(response);
+                }
+                Err(e) => {
+                    warn!("Provider {} failed: {}", provider.name(), e);
+                    skipped.push(format!("{} failed", provider.name()));
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match (empty_response, last_error) {
+            (Some(response), _) => Ok(response),
+            (None, Some(e)) => Err(e),
+            (None, None) => Ok(SearchResponse { results: vec![] }),
+        }
+    }
+}
+
+#[async_trait]
+impl SearchProvider for MultiProvider {
+    fn name(&self) -> &str {
+        if self.providers.len() == 1 {
+            return self.providers[0].name();
+        }
+        if self.blend {
+            "blend"
+        } else {
+            "fallback"
+        }
+    }
+
+    async fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse> {
+        self.search_page(query, max_results, 0).await
+    }
+
+    async fn search_page(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+        offset: usize,
+    ) -> Result<SearchResponse> {
+        if query.trim().is_empty() {
+            return Err(OllamaError::InvalidResponse(
+                "Search query cannot be empty".to_string(),
+            ));
+        }
+
+        let mut response = match self.providers.as_slice() {
+            [single] => {
+                let response = single.search_page(query, max_results, offset).await?;
+                SearchResponse {
+                    results: self.annotate(single.name(), response.results),
+                }
+            }
+            _ if self.blend => self.search_blended(query, max_results, offset).await?,
+            _ => self.search_fallback(query, max_results, offset).await?,
+        };
+
+        if let Some(min_score) = self.min_score {
+            let before = response.results.len();
+            response.results = filter_by_score(response.results, min_score);
+            debug!(
+                "Dropped {} results scoring below {}",
+                before - response.results.len(),
+                min_score
+            );
+        }
+        response.results = dedup::dedup(response.results, self.explain);
+        credibility::annotate(&mut response.results);
+        if let Some(reranker) = &self.reranker {
+            match reranker
+                .rerank(query, response.results.clone(), self.explain)
+                .await
+            {
+                Ok(results) => response.results = results,
+                Err(e) => warn!("Failed to rerank results, keeping provider order: {}", e),
+            }
+        }
+        let before: Vec<String> = response.results.iter().map(|r| r.url.clone()).collect();
+        feedback::apply_domain_scores(&mut response.results, &self.domain_scores);
+        bookmarks::apply_bookmarks(
+            &mut response.results,
+            &self.bookmarked_domains,
+            self.bookmark_mode,
+        );
+        if self.explain {
+            self.explain_personalization(&mut response.results, &before);
+        }
+
+        Ok(response)
+    }
+}
+
+pub fn build(
+    kinds: &[ProviderKind],
+    blend: bool,
+    config: &Config,
+    ollama: &OllamaClient,
+) -> Result<MultiProvider> {
+    let mut providers: Vec<Box<dyn SearchProvider>> = Vec::new();
+
+    for kind in kinds {
+        if providers.iter().any(|p| p.name() == kind_name(*kind)) {
+            continue;
+        }
+
+        let provider: Box<dyn SearchProvider> = match kind {
+            ProviderKind::Ollama => Box::new(ollama.clone()),
+            ProviderKind::Brave => {
+                let api_key = std::env::var("BRAVE_API_KEY").map_err(|_| {
+                    OllamaError::ConfigError(
+                        "Brave provider requires the BRAVE_API_KEY environment variable"
+                            .to_string(),
+                    )
+                })?;
+                Box::new(BraveClient::new(api_key, config.search_timeout)?)
+            }
+            ProviderKind::Searxng => {
+                let base_url = std::env::var("SEARXNG_URL").map_err(|_| {
+                    OllamaError::ConfigError(
+                        "SearXNG provider requires the SEARXNG_URL environment variable"
+                            .to_string(),
+                    )
+                })?;
+                Box::new(SearxngClient::new(base_url, config.search_timeout)?)
+            }
+            ProviderKind::Mock => Box::new(MockProvider::new(Fixtures::from_env()?)),
+        };
+        providers.push(provider);
+    }
+
+    if providers.is_empty() {
+        providers.push(Box::new(ollama.clone()));
+    }
+
+    Ok(MultiProvider::new(providers, blend))
+}
+
+fn kind_name(kind: ProviderKind) -> &'static str {
+    match kind {
+        ProviderKind::Ollama => "ollama",
+        ProviderKind::Brave => "brave",
+        ProviderKind::Searxng => "searxng",
+        ProviderKind::Mock => "mock",
+    }
+}
+
+pub fn filter_by_score(results: Vec<SearchResult>, min_score: f64) -> Vec<SearchResult> {
+    results
+        .into_iter()
+        .filter(|result| result.score.is_none_or(|score| score >= min_score))
+        .collect()
+}
+
+pub fn reciprocal_rank_fusion(rankings: Vec<Vec<SearchResult>>) -> Vec<SearchResult> {
+    let mut scores: HashMap<String, f64> = HashMap::new();
+    let mut first_seen: HashMap<String, (usize, SearchResult)> = HashMap::new();
+    let mut order = 0;
+
+    for ranking in rankings {
+        for (rank, result) in ranking.into_iter().enumerate() {
+            let key = dedup::key(&result.url);
+            *scores.entry(key.clone()).or_insert(0.0) += 1.0 / (RRF_K + rank as f64 + 1.0);
+            match first_seen.get_mut(&key) {
+                Some((_, kept)) => {
+                    for note in result.explain {
+                        if result.url == kept.url {
+                            kept.explain.push(note);
+                        } else {
+                            kept.explain
+                                .push(format!("{} (duplicate {})", note, result.url));
+                        }
+                    }
+                }
+                None => {
+                    order += 1;
+                    first_seen.insert(key, (order, result));
+                }
+            }
+        }
+    }
+
+    let mut fused: Vec<(f64, usize, SearchResult)> = first_seen
+        .into_iter()
+        .map(|(key, (seen, mut result))| {
+            if !result.explain.is_empty() {
+                result
+                    .explain
+                    .push(format!("fused RRF score {:.4}", scores[&key]));
+            }
+            (scores[&key], seen, result)
+        })
+        .collect();
+
+    fused.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    fused.into_iter().map(|(_, _, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            title: url.to_string(),
+            url: url.to_string(),
+            content: String::new(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rrf_prefers_results_ranked_by_multiple_providers() {
+        let a = vec![result("https://a.com"), result("https://shared.com")];
+        let b = vec![result("https://b.com"), result("https://shared.com/")];
+
+        let fused = reciprocal_rank_fusion(vec![a, b]);
+
+        assert_eq!(fused.len(), 3);
+        assert_eq!(fused[0].url, "https://shared.com");
+    }
+
+    #[test]
+    fn test_rrf_interleaves_by_rank() {
+        let a = vec![result("https://a1.com"), result("https://a2.com")];
+        let b = vec![result("https://b1.com"), result("https://b2.com")];
+
+        let urls: Vec<String> = reciprocal_rank_fusion(vec![a, b])
+            .into_iter()
+            .map(|r| r.url)
+            .collect();
+
+        assert_eq!(
+            urls,
+            vec![
+                "https://a1.com",
+                "https://b1.com",
+                "https://a2.com",
+                "https://b2.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explain_records_ranks_duplicates_and_fusion() {
+        let provider = MultiProvider::new(Vec::new(), true).with_explain(true);
+        let a = provider.annotate("brave", vec![result("https://shared.com")]);
+        let b = provider.annotate(
+            "searxng",
+            vec![result("https://b.com"), result("https://www.shared.com/")],
+        );
+
+        let fused = reciprocal_rank_fusion(vec![a, b]);
+        assert_eq!(
+            fused[0].explain,
+            vec![
+                "brave rank #1",
+                "searxng rank #2 (duplicate https://www.shared.com/)",
+                "fused RRF score 0.0325",
+            ]
+        );
+        assert!(
+            reciprocal_rank_fusion(vec![vec![result("https://a.com")]])[0]
+                .explain
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_search_page_skips_earlier_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let mock = crate::mock::MockProvider::new(crate::mock::Fixtures::new(dir.path().into()));
+
+        let page = mock.search_page("rust", Some(2), 2).await.unwrap();
+        let urls: Vec<&str> = page.results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            ["https://example.com/rust/3", "https://example.com/rust/4"]
+        );
+        let past_end = mock.search_page("rust", Some(2), 6).await.unwrap();
+        assert!(past_end.results.is_empty());
+    }
+
+    #[test]
+    fn test_min_score_keeps_unscored_results() {
+        let scored = |url: &str, score: f64| SearchResult {
+            score: Some(score),
+            ..result(url)
+        };
+        let results = vec![
+            scored("https://strong.com", 0.9),
+            scored("https://weak.com", 0.2),
+            result("https://unscored.com"),
+        ];
+
+        let urls: Vec<String> = filter_by_score(results, 0.5)
+            .into_iter()
+            .map(|r| r.url)
+            .collect();
+        assert_eq!(urls, ["https://strong.com", "https://unscored.com"]);
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+const WINDOW: Duration = Duration::from_secs(60);
+
+static LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    sent: Mutex<VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(limit: u32) -> Self {
+        Self::new(limit as usize, WINDOW)
+    }
+
+    fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit: limit.max(1),
+            window,
+            sent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub async fn acquire(&self) {
+        let mut sent = self.sent.lock().await;
+        let now = Instant::now();
+        while sent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= self.window)
+        {
+            sent.pop_front();
+        }
+
+        if sent.len() >= self.limit {
+            if let Some(oldest) = sent.pop_front() {
+                let wait = self.window.saturating_sub(now.duration_since(oldest));
+                debug!(
+                    "Rate limit of {} requests reached, waiting {:.1}s",
+                    self.limit,
+                    wait.as_secs_f32()
+                );
+                tokio::time::sleep(wait).await;
+            }
+        }
+        sent.push_back(Instant::now());
+    }
+}
+
+pub fn enable(per_minute: u32) {
+    if LIMITER.set(RateLimiter::per_minute(per_minute)).is_err() {
+        warn!("Rate limiting was already enabled");
+    }
+}
+
+pub async fn acquire() {
+    if let Some(limiter) = LIMITER.get() {
+        limiter.acquire().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_waits_once_the_window_is_full() {
+        let limiter = RateLimiter::new(2, Duration::from_millis(200));
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_forgets_requests_outside_the_window() {
+        let limiter = RateLimiter::new(1, Duration::from_millis(50));
+        limiter.acquire().await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::error::Result;
+use crate::ollama_local::{ChatBackend, ChatResponse, FunctionCall, Tool, ToolCall};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tracing::debug;
+
+const ACTION: &str = "Action:";
+const ACTION_INPUT: &str = "Action Input:";
+const FINAL_ANSWER: &str = "Final Answer:";
+
+pub struct ReactChat {
+    inner: Box<dyn ChatBackend>,
+    models: Vec<String>,
+}
+
+impl ReactChat {
+    pub fn new(inner: Box<dyn ChatBackend>, models: Vec<String>) -> Self {
+        Self { inner, models }
+    }
+}
+
+#[async_trait]
+impl ChatBackend for ReactChat {
+    async fn chat(
+        &self,
+        model: &str,
+        messages: Vec<Value>,
+        tools: Option<Vec<Tool>>,
+        think: bool,
+    ) -> Result<ChatResponse> {
+        let tools = match tools {
+            Some(tools) if self.models.iter().any(|m| m == model) => tools,
+            tools => return self.inner.chat(model, messages, tools, think).await,
+        };
+
+        let mut response = self
+            .inner
+            .chat(model, react_messages(&messages, &tools), None, think)
+            .await?;
+        let (content, call) = parse_action(&response.message.content, &tools);
+        if let Some(call) = &call {
+            debug!("Parsed ReAct action: {}", call.function.name);
+        }
+        response.message.content = content;
+        response.message.tool_calls = Some(call.into_iter().collect());
+        Ok(response)
+    }
+}
+
+fn instructions(tools: &[Tool]) -> String {
+    let mut prompt = String::from("You can use these tools to answer the user:\n\n");
+    for tool in tools {
+        prompt.push_str(&format!(
+            "- {}: {}\n  Arguments (JSON schema): {}\n",
+            tool.function.name, tool.function.description, tool.function.parameters
+        ));
+    }
+    prompt.push_str(&format!(
+        "\nTo use a tool, reply with your reasoning followed by exactly these two lines and \
+         nothing after them:\n{ACTION} <tool name>\n{ACTION_INPUT} <JSON object of arguments>\n\n\
+         The tool's result comes back as an Observation. Use one tool at a time. When you can \
+         answer, reply with {FINAL_ANSWER} followed by the complete answer and no {ACTION} line."
+    ));
+    prompt
+}
+
+pub fn react_messages(messages: &[Value], tools: &[Tool]) -> Vec<Value> {
+    let mut converted = vec![json!({"role": "system", "content": instructions(tools)})];
+    for message in messages {
+        let content = message["content"].as_str().unwrap_or_default();
+        match message["role"].as_str().unwrap_or_default() {
+            "assistant" => {
+                let mut text = content.trim().to_string();
+                let calls: Vec<ToolCall> = message
+                    .get("tool_calls")
+                    .cloned()
+                    .and_then(|calls| serde_json::from_value(calls).ok())
+                    .unwrap_or_default();
+                for call in calls {
+                    text.push_str(&format!(
+                        "\n{ACTION} {}\n{ACTION_INPUT} {}",
+                        call.function.name, call.function.arguments
+                    ));
+                }
+                converted.push(json!({"role": "assistant", "content": text.trim()}));
+            }
+            "tool" => converted.push(json!({
+                "role": "user",
+                "content": format!("Observation: {}", content),
+            })),
+            _ => converted.push(message.clone()),
+        }
+    }
+    converted
+}
+
+pub fn parse_action(content: &str, tools: &[Tool]) -> (String, Option<ToolCall>) {
+    let final_answer = content.rfind(FINAL_ANSWER);
+    let Some(start) = content
+        .rfind(ACTION)
+        .filter(|&idx| final_answer.is_none_or(|answer| answer < idx))
+    else {
+        let answer = match content.find(FINAL_ANSWER) {
+            Some(idx) => &content[idx + FINAL_ANSWER.len()..],
+            None => content,
+        };
+        return (answer.trim().to_string(), None);
+    };
+
+    let rest = &content[start + ACTION.len()..];
+    let (name, input) = rest.split_once(ACTION_INPUT).unwrap_or((rest, ""));
+    let name = name.trim().trim_matches('`');
+    if !tools.iter().any(|tool| tool.function.name == name) {
+        return (content.trim().to_string(), None);
+    }
+
+    let arguments = input
+        .find('{')
+        .zip(input.rfind('}'))
+        .filter(|(open, close)| open < close)
+        .and_then(|(open, close)| serde_json::from_str(&input[open..=close]).ok())
+        .unwrap_or_else(|| json!({}));
+    let thought = content[..start].trim().to_string();
+    (
+        thought,
+        Some(ToolCall {
+            function: FunctionCall {
+                name: name.to_string(),
+                arguments,
+            },
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ollama_local::{create_web_fetch_tool, create_web_search_tool};
+
+    fn tools() -> Vec<Tool> {
+        vec![create_web_search_tool(), create_web_fetch_tool(None)]
+    }
+
+    #[test]
+    fn test_parses_actions_and_final_answers() {
+        let (thought, call) = parse_action(
+            "I should look this up.\nAction: web_search\nAction Input: {\"query\": \"tokio 1.0\"}",
+            &tools(),
+        );
+        assert_eq!(thought, "I should look this up.");
+        let call = call.unwrap();
+        assert_eq!(call.function.name, "web_search");
+        assert_eq!(call.function.arguments["query"], "tokio 1.0");
+
+        let (answer, call) = parse_action(
+            "Thought: done.\nFinal Answer: Tokio 1.0 shipped in 2020.",
+            &tools(),
+        );
+        assert!(call.is_none());
+        assert_eq!(answer, "Tokio 1.0 shipped in 2020.");
+
+        let (answer, call) = parse_action("Action: launch_rockets\nAction Input: {}", &tools());
+        assert!(call.is_none());
+        assert!(answer.starts_with("Action: launch_rockets"));
+    }
+
+    #[test]
+    fn test_replays_tool_history_as_text() {
+        let messages = vec![
+            json!({"role": "user", "content": "when did tokio 1.0 ship?"}),
+            json!({
+                "role": "assistant",
+                "content": "Searching.",
+                "tool_calls": [{"function": {"name": "web_search", "arguments": {"query": "tokio"}}}],
+            }),
+            json!({"role": "tool", "content": "December 2020", "tool_name": "web_search"}),
+        ];
+        let converted = react_messages(&messages, &tools());
+        assert_eq!(converted.len(), 4);
+        assert!(converted[0]["content"]
+            .as_str()
+            .unwrap()
+            .contains("- web_fetch: "));
+        assert_eq!(
+            converted[2]["content"],
+            "Searching.\nAction: web_search\nAction Input: {\"query\":\"tokio\"}"
+        );
+        assert_eq!(converted[3]["role"], "user");
+        assert_eq!(converted[3]["content"], "Observation: December 2020");
+    }
+}
@@ -0,0 +1,192 @@
+use htmd::HtmlToMarkdown;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing::{debug, warn};
+
+const MIN_PARAGRAPH_CHARS: usize = 25;
+const MIN_CLOSING_TAGS: usize = 10;
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "header", "footer", "aside", "form", "iframe", "svg",
+    "button", "select",
+];
+
+pub fn clean(content: &str) -> String {
+    if !looks_like_html(content) {
+        return content.to_string();
+    }
+
+    let document = Html::parse_document(content);
+    let main = main_content(&document);
+    let html = match &main {
+        Some(element) => element.html(),
+        None => content.to_string(),
+    };
+
+    let converter = HtmlToMarkdown::builder()
+        .skip_tags(SKIPPED_TAGS.to_vec())
+        .build();
+    match converter.convert(&html) {
+        Ok(markdown) => {
+            debug!(
+                "Extracted {} chars of markdown from {} chars of HTML",
+                markdown.len(),
+                content.len()
+            );
+            collapse_blank_lines(&markdown)
+        }
+        Err(e) => {
+            warn!("HTML to markdown conversion failed: {}", e);
+            content.to_string()
+        }
+    }
+}
+
+pub fn looks_like_html(content: &str) -> bool {
+    static CLOSING_TAG: OnceLock<Regex> = OnceLock::new();
+    let closing_tag = CLOSING_TAG.get_or_init(|| {
+        Regex::new(r"(?i)</(div|p|span|a|li|ul|td|tr|section|article|body|html)>").unwrap()
+    });
+
+    let start = content
+        .trim_start()
+        .get(..15)
+        .unwrap_or_default()
+        .to_lowercase();
+    start.starts_with("<!doctype html")
+        || start.starts_with("<html")
+        || closing_tag
+            .find_iter(content)
+            .take(MIN_CLOSING_TAGS)
+            .count()
+            == MIN_CLOSING_TAGS
+}
+
+fn main_content(document: &Html) -> Option<ElementRef<'_>> {
+    let paragraphs = Selector::parse("p, pre, td, li, blockquote").unwrap();
+    let mut scores = HashMap::new();
+
+    for paragraph in document.select(&paragraphs) {
+        if has_unlikely_ancestor(paragraph) {
+            continue;
+        }
+        let text: String = paragraph.text().collect();
+        let length = text.trim().chars().count();
+        if length < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+
+        let score = 1.0 + text.matches(',').count() as f32 + (length as f32 / 100.0).min(3.0);
+        let mut ancestors = paragraph.ancestors().filter_map(ElementRef::wrap);
+        if let Some(parent) = ancestors.next() {
+            *scores.entry(parent.id()).or_insert(0.0) += score;
+        }
+        if let Some(grandparent) = ancestors.next() {
+            *scores.entry(grandparent.id()).or_insert(0.0) += score / 2.0;
+        }
+    }
+
+    scores
+        .into_iter()
+        .filter_map(|(id, score)| {
+            let element = ElementRef::wrap(document.tree.get(id)?)?;
+            Some((element, score * (1.0 - link_density(element))))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(element, _)| element)
+}
+
+fn has_unlikely_ancestor(element: ElementRef) -> bool {
+    static UNLIKELY: OnceLock<Regex> = OnceLock::new();
+    static LIKELY: OnceLock<Regex> = OnceLock::new();
+    let unlikely = UNLIKELY.get_or_init(|| {
+        Regex::new(r"(?i)comment|sidebar|footer|menu|nav|cookie|banner|share|social|related|advert|promo|subscribe|breadcrumb").unwrap()
+    });
+    let likely = LIKELY.get_or_init(|| Regex::new(r"(?i)article|content|main|post|body").unwrap());
+
+    element
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .chain(std::iter::once(element))
+        .any(|ancestor| {
+            let value = ancestor.value();
+            if SKIPPED_TAGS.contains(&value.name()) {
+                return true;
+            }
+            let hints = format!(
+                "{} {}",
+                value.attr("class").unwrap_or_default(),
+                value.id().unwrap_or_default()
+            );
+            unlikely.is_match(&hints) && !likely.is_match(&hints)
+        })
+}
+
+fn link_density(element: ElementRef) -> f32 {
+    let links = Selector::parse("a").unwrap();
+    let total: usize = element.text().map(|t| t.chars().count()).sum();
+    if total == 0 {
+        return 1.0;
+    }
+    let linked: usize = element
+        .select(&links)
+        .flat_map(|link| link.text())
+        .map(|t| t.chars().count())
+        .sum();
+    linked as f32 / total as f32
+}
+
+fn collapse_blank_lines(markdown: &str) -> String {
+    let mut output = String::new();
+    let mut blank = 0;
+    for line in markdown.lines() {
+        if line.trim().is_empty() {
+            blank += 1;
+            if blank > 1 {
+                continue;
+            }
+        } else {
+            blank = 0;
+        }
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html><head><title>Pin</title><script>var x = 1;</script></head>
+<body>
+  <nav><ul><li><a href="/">Home</a></li><li><a href="/blog">Blog</a></li></ul></nav>
+  <div class="sidebar"><p>Subscribe to our newsletter for more posts like this one, every week.</p></div>
+  <div class="post-content">
+    <h1>Pin and suffering</h1>
+    <p>Pinning guarantees that a value will not move in memory, which matters for self-referential futures.</p>
+    <p>The <code>Pin</code> type wraps a pointer, and <a href="https://doc.rust-lang.org/std/pin/">the docs</a> explain the rules, caveats, and history.</p>
+  </div>
+  <footer><p>Copyright 2024, all rights reserved, no part may be reproduced.</p></footer>
+</body></html>"#;
+
+    #[test]
+    fn test_extracts_main_content_as_markdown() {
+        let markdown = clean(PAGE);
+        assert!(markdown.contains("# Pin and suffering"));
+        assert!(markdown.contains("`Pin`"));
+        assert!(markdown.contains("[the docs](https://doc.rust-lang.org/std/pin/)"));
+        assert!(!markdown.contains("newsletter"));
+        assert!(!markdown.contains("Copyright"));
+        assert!(!markdown.contains("var x"));
+    }
+
+    #[test]
+    fn test_leaves_markdown_untouched() {
+        let markdown = "# Title\n\nSome <b>inline</b> text with a [link](https://a.com).";
+        assert!(!looks_like_html(markdown));
+        assert_eq!(clean(markdown), markdown);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::client::SearchResult;
+use crate::error::Result;
+use crate::kb::cosine_similarity;
+use crate::ollama_local::OllamaLocal;
+use futures::future::join_all;
+use tracing::debug;
+
+pub struct Reranker {
+    ollama: OllamaLocal,
+    embed_model: String,
+}
+
+impl Reranker {
+    pub fn new(ollama: OllamaLocal, embed_model: String) -> Self {
+        Self {
+            ollama,
+            embed_model,
+        }
+    }
+
+    pub async fn rerank(
+        &self,
+        query: &str,
+        results: Vec<SearchResult>,
+        explain: bool,
+    ) -> Result<Vec<SearchResult>> {
+        if results.len() < 2 {
+            return Ok(results);
+        }
+
+        let texts: Vec<String> = std::iter::once(query.to_string())
+            .chain(
+                results
+                    .iter()
+                    .map(|result| format!("{}\n{}", result.title, result.content)),
+            )
+            .collect();
+        let mut vectors = join_all(
+            texts
+                .iter()
+                .map(|text| self.ollama.embed(&self.embed_model, text)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+        let query_vector = vectors.remove(0);
+
+        debug!(
+            "Reranking {} results with {}",
+            results.len(),
+            self.embed_model
+        );
+        Ok(reorder(results, &query_vector, &vectors, explain))
+    }
+}
+
+pub fn reorder(
+    results: Vec<SearchResult>,
+    query: &[f32],
+    vectors: &[Vec<f32>],
+    explain: bool,
+) -> Vec<SearchResult> {
+    let mut scored: Vec<(f32, usize, SearchResult)> = results
+        .into_iter()
+        .zip(vectors)
+        .enumerate()
+        .map(|(rank, (result, vector))| (cosine_similarity(query, vector), rank, result))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    scored
+        .into_iter()
+        .enumerate()
+        .map(|(position, (score, rank, mut result))| {
+            if explain {
+                let moved = if position == rank {
+                    String::new()
+                } else {
+                    format!(", moved from #{}", rank + 1)
+                };
+                result
+                    .explain
+                    .push(format!("rerank similarity {:.3}{}", score, moved));
+            }
+            result
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            title: url.to_string(),
+            url: url.to_string(),
+            content: String::new(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_reorders_by_similarity_to_query() {
+        let results = vec![
+            result("https://rust-lang.org/game"),
+            result("https://tokio.rs"),
+            result("https://smol.rs"),
+        ];
+        let vectors = vec![vec![0.0, 1.0], vec![1.0, 0.1], vec![1.0, 0.1]];
+
+        let reranked = reorder(results, &[1.0, 0.0], &vectors, true);
+        let urls: Vec<&str> = reranked.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://tokio.rs",
+                "https://smol.rs",
+                "https://rust-lang.org/game"
+            ]
+        );
+        assert_eq!(
+            reranked[0].explain,
+            ["rerank similarity 0.995, moved from #2"]
+        );
+        assert_eq!(
+            reranked[2].explain,
+            ["rerank similarity 0.000, moved from #1"]
+        );
+    }
+}
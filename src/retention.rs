@@ -0,0 +1,317 @@
+use crate::artifacts;
+use crate::error::Result;
+use crate::settings::{Policy, RetentionSettings};
+use crate::store::{self, Store};
+use rusqlite::{params, OptionalExtension};
+use tracing::{debug, info, warn};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+const STARTUP_INTERVAL_SECS: i64 = SECONDS_PER_DAY;
+const LAST_RUN_KEY: &str = "retention_last_run";
+
+struct Namespace {
+    name: &'static str,
+    table: &'static str,
+    timestamp: &'static str,
+    size: &'static str,
+}
+
+const NAMESPACES: &[Namespace] = &[
+    Namespace {
+        name: "history",
+        table: "history",
+        timestamp: "created_at",
+        size: "length(query) + length(summary) + length(answer)",
+    },
+    Namespace {
+        name: "cache",
+        table: "seen_results",
+        timestamp: "seen_at",
+        size: "length(url) + length(title) + length(query)",
+    },
+    Namespace {
+        name: "snapshots",
+        table: "watch_snapshots",
+        timestamp: "created_at",
+        size: "length(query) + length(content)",
+    },
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Row {
+    rowid: i64,
+    timestamp: i64,
+    size: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceReport {
+    pub name: &'static str,
+    pub kept: usize,
+    pub removed: usize,
+    pub kept_bytes: u64,
+    pub freed_bytes: u64,
+}
+
+pub fn enforce(
+    store: &Store,
+    settings: &RetentionSettings,
+    dry_run: bool,
+) -> Result<Vec<NamespaceReport>> {
+    let now = store::now();
+
+    let mut plans = Vec::new();
+    for namespace in NAMESPACES {
+        let rows = load_rows(store, namespace)?;
+        let expired = select_expired(&rows, &settings.policy_for(namespace.name), now);
+        plans.push((namespace, rows, expired));
+    }
+
+    if let Some(max_total_mb) = settings.max_total_mb {
+        let mut survivors: Vec<(usize, usize, i64, u64)> = plans
+            .iter()
+            .enumerate()
+            .flat_map(|(ns, (_, rows, expired))| {
+                rows.iter()
+                    .enumerate()
+                    .filter(|(idx, _)| !expired[*idx])
+                    .map(move |(idx, row)| (ns, idx, row.timestamp, row.size))
+            })
+            .collect();
+        survivors.sort_by_key(|survivor| std::cmp::Reverse(survivor.2));
+
+        let budget = (max_total_mb * BYTES_PER_MB) as u64;
+        let mut total = 0;
+        for (ns, idx, _, size) in survivors {
+            total += size;
+            if total > budget {
+                plans[ns].2[idx] = true;
+            }
+        }
+    }
+
+    let mut reports = Vec::new();
+    let mut removed_any = false;
+
+    let transaction = if dry_run {
+        None
+    } else {
+        Some(store.conn().unchecked_transaction()?)
+    };
+    for (namespace, rows, expired) in &plans {
+        let mut report = NamespaceReport {
+            name: namespace.name,
+            ..NamespaceReport::default()
+        };
+
+        for (row, expired) in rows.iter().zip(expired) {
+            if !expired {
+                report.kept += 1;
+                report.kept_bytes += row.size;
+                continue;
+            }
+
+            report.removed += 1;
+            report.freed_bytes += row.size;
+            if !dry_run {
+                store.conn().execute(
+                    &format!("DELETE FROM {} WHERE rowid = ?1", namespace.table),
+                    params![row.rowid],
+                )?;
+            }
+        }
+
+        removed_any |= report.removed > 0;
+        reports.push(report);
+    }
+
+    if let Some(transaction) = transaction {
+        transaction.commit()?;
+        if removed_any {
+            artifacts::collect_garbage(store)?;
+            store.conn().execute_batch("VACUUM")?;
+        }
+    }
+
+    Ok(reports)
+}
+
+pub fn enforce_on_startup(settings: RetentionSettings) {
+    if !settings.on_startup {
+        return;
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let result = Store::open().and_then(|store| {
+            if !is_due(&store)? {
+                debug!("Retention cleanup ran recently; skipping");
+                return Ok(());
+            }
+
+            let reports = enforce(&store, &settings, false)?;
+            mark_run(&store)?;
+
+            let removed: usize = reports.iter().map(|r| r.removed).sum();
+            if removed > 0 {
+                info!("Retention cleanup removed {} entries", removed);
+            }
+            Ok(())
+        });
+
+        if let Err(e) = result {
+            warn!("Retention cleanup failed: {}", e);
+        }
+    });
+}
+
+fn is_due(store: &Store) -> Result<bool> {
+    let last_run: Option<String> = store
+        .conn()
+        .query_row(
+            "SELECT value FROM meta WHERE key = ?1",
+            params![LAST_RUN_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    let last_run = last_run.and_then(|v| v.parse::<i64>().ok()).unwrap_or(0);
+    Ok(store::now() - last_run >= STARTUP_INTERVAL_SECS)
+}
+
+fn mark_run(store: &Store) -> Result<()> {
+    store.conn().execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![LAST_RUN_KEY, store::now().to_string()],
+    )?;
+    Ok(())
+}
+
+fn load_rows(store: &Store, namespace: &Namespace) -> Result<Vec<Row>> {
+    let mut stmt = store.conn().prepare(&format!(
+        "SELECT rowid, {}, {} FROM {} ORDER BY {} DESC, rowid DESC",
+        namespace.timestamp, namespace.size, namespace.table, namespace.timestamp
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok(Row {
+            rowid: row.get(0)?,
+            timestamp: row.get(1)?,
+            size: row.get::<_, i64>(2)?.max(0) as u64,
+        })
+    })?;
+    Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+}
+
+fn select_expired(rows: &[Row], policy: &Policy, now: i64) -> Vec<bool> {
+    let cutoff = policy
+        .max_age_days
+        .map(|days| now - days as i64 * SECONDS_PER_DAY);
+    let budget = policy.max_mb.map(|mb| (mb * BYTES_PER_MB) as u64);
+
+    let mut kept = 0;
+    let mut total = 0;
+    let mut full = false;
+    rows.iter()
+        .map(|row| {
+            full = full || budget.is_some_and(|budget| total + row.size > budget);
+            let expired = full
+                || cutoff.is_some_and(|cutoff| row.timestamp < cutoff)
+                || policy.max_entries.is_some_and(|max| kept >= max);
+            if !expired {
+                kept += 1;
+                total += row.size;
+            }
+            expired
+        })
+        .collect()
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes as f64 >= BYTES_PER_MB {
+        format!("{:.1} MB", bytes as f64 / BYTES_PER_MB)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history;
+    use crate::settings::Policy;
+    use std::collections::HashMap;
+
+    fn rows(timestamps: &[i64]) -> Vec<Row> {
+        timestamps
+            .iter()
+            .enumerate()
+            .map(|(idx, &timestamp)| Row {
+                rowid: idx as i64,
+                timestamp,
+                size: 100,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_select_expired_by_age_and_count() {
+        let now = 100 * SECONDS_PER_DAY;
+        let rows = rows(&[now, now - SECONDS_PER_DAY, now - 10 * SECONDS_PER_DAY]);
+
+        let by_age = Policy {
+            max_age_days: Some(5),
+            ..Policy::default()
+        };
+        assert_eq!(
+            select_expired(&rows, &by_age, now),
+            vec![false, false, true]
+        );
+
+        let by_count = Policy {
+            max_entries: Some(1),
+            ..Policy::default()
+        };
+        assert_eq!(
+            select_expired(&rows, &by_count, now),
+            vec![false, true, true]
+        );
+    }
+
+    #[test]
+    fn test_select_expired_by_size() {
+        let rows = rows(&[3, 2, 1]);
+        let policy = Policy {
+            max_mb: Some(250.0 / BYTES_PER_MB),
+            ..Policy::default()
+        };
+        assert_eq!(select_expired(&rows, &policy, 3), vec![false, false, true]);
+    }
+
+    #[test]
+    fn test_enforce_dry_run_keeps_rows() {
+        let store = Store::open_in_memory().unwrap();
+        for query in ["one", "two", "three"] {
+            history::record(&store, history::Kind::Search, query, "", "").unwrap();
+        }
+        let settings = RetentionSettings {
+            namespaces: HashMap::from([(
+                "history".to_string(),
+                Policy {
+                    max_entries: Some(1),
+                    ..Policy::default()
+                },
+            )]),
+            ..RetentionSettings::default()
+        };
+
+        let report = enforce(&store, &settings, true).unwrap();
+        assert_eq!(report[0].removed, 2);
+        assert_eq!(history::list(&store, None, 10).unwrap().len(), 3);
+
+        enforce(&store, &settings, false).unwrap();
+        let remaining = history::list(&store, None, 10).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].query, "three");
+    }
+}
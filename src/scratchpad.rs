@@ -0,0 +1,194 @@
+use crate::error::{OllamaError, Result};
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::warn;
+
+pub const MAX_NOTES: usize = 200;
+const MAX_NOTE_CHARS: usize = 2000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    pub topic: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Default)]
+pub struct Scratchpad {
+    notes: Mutex<Vec<Note>>,
+    path: Option<PathBuf>,
+}
+
+impl Scratchpad {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        let notes = match fs::read_to_string(path) {
+            Ok(text) => text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .filter_map(|line| match serde_json::from_str(line) {
+                    Ok(note) => Some(note),
+                    Err(e) => {
+                        warn!("Skipping unreadable note in {}: {}", path.display(), e);
+                        None
+                    }
+                })
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            notes: Mutex::new(notes),
+            path: Some(path.to_path_buf()),
+        })
+    }
+
+    pub fn save(&self, topic: &str, content: &str, source: Option<&str>) -> Result<usize> {
+        let note = Note {
+            topic: normalize_topic(topic),
+            content: content.trim().chars().take(MAX_NOTE_CHARS).collect(),
+            source: source
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+            created_at: store::now(),
+        };
+
+        let mut notes = self.lock();
+        if notes.len() >= MAX_NOTES {
+            return Err(OllamaError::InvalidResponse(format!(
+                "The scratchpad is full ({} notes)",
+                MAX_NOTES
+            )));
+        }
+        if let Some(path) = &self.path {
+            append(path, &note)?;
+        }
+        notes.push(note);
+        Ok(notes.len())
+    }
+
+    pub fn notes(&self, topic: Option<&str>) -> Vec<Note> {
+        let topic = topic.map(normalize_topic);
+        self.lock()
+            .iter()
+            .filter(|note| topic.as_ref().is_none_or(|topic| &note.topic == topic))
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Note>> {
+        self.notes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn normalize_topic(topic: &str) -> String {
+    let topic = topic.trim().to_lowercase();
+    if topic.is_empty() {
+        "general".to_string()
+    } else {
+        topic
+    }
+}
+
+fn append(path: &Path, note: &Note) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(note)?)?;
+    Ok(())
+}
+
+pub fn format_notes(notes: &[Note]) -> String {
+    if notes.is_empty() {
+        return "No notes saved yet.".to_string();
+    }
+
+    let mut topics: Vec<&str> = Vec::new();
+    for note in notes {
+        if !topics.contains(&note.topic.as_str()) {
+            topics.push(&note.topic);
+        }
+    }
+
+    let mut output = format!(
+        "{} note{}:\n",
+        notes.len(),
+        if notes.len() == 1 { "" } else { "s" }
+    );
+    for topic in topics {
+        output.push_str(&format!("\n## {}\n", topic));
+        for note in notes.iter().filter(|note| note.topic == topic) {
+            output.push_str(&format!("- {}", note.content));
+            if let Some(source) = &note.source {
+                output.push_str(&format!(" ({})", source));
+            }
+            output.push('\n');
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saves_and_groups_notes_by_topic() {
+        let pad = Scratchpad::new();
+        assert_eq!(format_notes(&pad.notes(None)), "No notes saved yet.");
+
+        pad.save(
+            "Benchmarks",
+            "tokio: 1.2M req/s",
+            Some("https://a.dev/bench"),
+        )
+        .unwrap();
+        pad.save("", "  check io_uring support  ", None).unwrap();
+        pad.save("benchmarks ", "glommio: 1.4M req/s", Some(" "))
+            .unwrap();
+
+        assert_eq!(pad.len(), 3);
+        assert_eq!(pad.notes(Some("BENCHMARKS")).len(), 2);
+        assert_eq!(
+            format_notes(&pad.notes(None)),
+            "3 notes:\n\n## benchmarks\n- tokio: 1.2M req/s (https://a.dev/bench)\n\
+             - glommio: 1.4M req/s\n\n## general\n- check io_uring support\n"
+        );
+    }
+
+    #[test]
+    fn test_persists_notes_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes/run.jsonl");
+
+        let pad = Scratchpad::open(&path).unwrap();
+        assert!(pad.is_empty());
+        pad.save("sources", "Rust blog announces 1.80", None)
+            .unwrap();
+
+        let reopened = Scratchpad::open(&path).unwrap();
+        assert_eq!(reopened.notes(None), pad.notes(None));
+        reopened.save("sources", "second", None).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+}
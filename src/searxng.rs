@@ -0,0 +1,118 @@
+use crate::client::{SearchResponse, SearchResult};
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use crate::provider::{SearchProvider, DEFAULT_PAGE_SIZE};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+#[derive(Debug, Deserialize)]
+struct SearxngResponse {
+    #[serde(default)]
+    results: Vec<SearxngResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearxngResult {
+    #[serde(default)]
+    title: String,
+    url: String,
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    score: Option<f64>,
+    #[serde(default, rename = "publishedDate")]
+    published_date: Option<String>,
+}
+
+pub struct SearxngClient {
+    client: Client,
+    base_url: String,
+}
+
+impl SearxngClient {
+    pub fn new(base_url: String, timeout: Duration) -> Result<Self> {
+        let client = network::client_builder()
+            .timeout(timeout)
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl SearchProvider for SearxngClient {
+    fn name(&self) -> &str {
+        "searxng"
+    }
+
+    async fn search(&self, query: &str, max_results: Option<usize>) -> Result<SearchResponse> {
+        self.search_page(query, max_results, 0).await
+    }
+
+    #[instrument(skip(self))]
+    async fn search_page(
+        &self,
+        query: &str,
+        max_results: Option<usize>,
+        offset: usize,
+    ) -> Result<SearchResponse> {
+        let url = format!("{}/search", self.base_url);
+        let page = offset / max_results.unwrap_or(DEFAULT_PAGE_SIZE).max(1) + 1;
+
+        debug!("Sending search request to SearXNG at: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("q", query),
+                ("format", "json"),
+                ("pageno", page.to_string().as_str()),
+            ])
+            .send_audited()
+            .await?;
+
+        let status = response.status();
+
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(OllamaError::ApiError {
+                status: status.as_u16(),
+                message: error_text,
+            });
+        }
+
+        let searxng_response = response.json::<SearxngResponse>().await.map_err(|e| {
+            OllamaError::InvalidResponse(format!("Failed to parse SearXNG response: {}", e))
+        })?;
+
+        let mut results: Vec<SearchResult> = searxng_response
+            .results
+            .into_iter()
+            .map(|result| SearchResult {
+                title: result.title,
+                url: result.url,
+                content: result.content,
+                score: result.score,
+                published: result.published_date,
+                ..Default::default()
+            })
+            .collect();
+
+        if let Some(max) = max_results {
+            results.truncate(max);
+        }
+
+        Ok(SearchResponse { results })
+    }
+}
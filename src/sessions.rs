@@ -0,0 +1,371 @@
+use crate::glyphs::Glyph;
+use crate::history::{self, Entry};
+use crate::store::Store;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::path::PathBuf;
+
+const HELP: &str = "j/k move · / search · J/K scroll · r resume · e export · d delete · q quit";
+
+pub enum Outcome {
+    Quit,
+    Resume(Entry),
+}
+
+struct App {
+    entries: Vec<Entry>,
+    visible: Vec<usize>,
+    filter: String,
+    editing: bool,
+    pending_delete: Option<i64>,
+    list_state: ListState,
+    scroll: u16,
+    status: String,
+}
+
+impl App {
+    fn new(entries: Vec<Entry>) -> Self {
+        let mut app = Self {
+            entries,
+            visible: Vec::new(),
+            filter: String::new(),
+            editing: false,
+            pending_delete: None,
+            list_state: ListState::default(),
+            scroll: 0,
+            status: HELP.to_string(),
+        };
+        app.refilter();
+        app
+    }
+
+    fn refilter(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| match_score(&self.filter, entry).map(|s| (s, idx)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        self.visible = scored.into_iter().map(|(_, idx)| idx).collect();
+        self.list_state
+            .select((!self.visible.is_empty()).then_some(0));
+        self.scroll = 0;
+    }
+
+    fn selected(&self) -> Option<&Entry> {
+        self.list_state
+            .selected()
+            .and_then(|pos| self.visible.get(pos))
+            .map(|&idx| &self.entries[idx])
+    }
+
+    fn select_next(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let pos = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(Some((pos + 1) % self.visible.len()));
+        self.scroll = 0;
+    }
+
+    fn select_previous(&mut self) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let len = self.visible.len();
+        let pos = self.list_state.selected().unwrap_or(0);
+        self.list_state.select(Some((pos + len - 1) % len));
+        self.scroll = 0;
+    }
+
+    fn delete_selected(&mut self, store: &Store) {
+        let Some(id) = self.selected().map(|entry| entry.id) else {
+            return;
+        };
+
+        if self.pending_delete != Some(id) {
+            self.pending_delete = Some(id);
+            self.status = format!("Press d again to delete session #{}", id);
+            return;
+        }
+
+        self.pending_delete = None;
+        match history::delete(store, id) {
+            Ok(_) => {
+                self.entries.retain(|entry| entry.id != id);
+                self.refilter();
+                self.status = format!("Deleted session #{}", id);
+            }
+            Err(e) => self.status = format!("Failed to delete: {}", e),
+        }
+    }
+
+    fn export_selected(&mut self) {
+        let Some(entry) = self.selected() else {
+            return;
+        };
+
+        let path = PathBuf::from(format!("weavex-session-{}.md", entry.id));
+        self.status = match std::fs::write(&path, session_markdown(entry)) {
+            Ok(()) => format!("Exported to {}", path.display()),
+            Err(e) => format!("Failed to export: {}", e),
+        };
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(1),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(rows[1]);
+
+        let search_style = if self.editing {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default()
+        };
+        let search = Paragraph::new(self.filter.as_str()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(search_style)
+                .title(format!(
+                    " {}{}/{} sessions ",
+                    Glyph::Search,
+                    self.visible.len(),
+                    self.entries.len()
+                )),
+        );
+        frame.render_widget(search, rows[0]);
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|&idx| {
+                let entry = &self.entries[idx];
+                ListItem::new(vec![
+                    Line::from(entry.query.clone()),
+                    Line::from(Span::styled(
+                        format!("   #{} · {} · {}", entry.id, entry.kind, entry.timestamp()),
+                        Style::default().fg(Color::DarkGray),
+                    )),
+                ])
+            })
+            .collect();
+
+        let pointer = Glyph::Pointer.to_string();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" Sessions "))
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(&pointer);
+        frame.render_stateful_widget(list, columns[0], &mut self.list_state);
+
+        let (title, body) = match self.selected() {
+            Some(entry) => (format!(" {} ", entry.query), entry.answer.clone()),
+            None => (" Preview ".to_string(), "No matching sessions.".to_string()),
+        };
+        let preview = Paragraph::new(body)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(preview, columns[1]);
+
+        let status =
+            Paragraph::new(self.status.as_str()).style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(status, rows[2]);
+    }
+}
+
+pub fn run(store: &Store, entries: Vec<Entry>) -> Result<Outcome> {
+    if entries.is_empty() {
+        println!("No sessions found.");
+        return Ok(Outcome::Quit);
+    }
+
+    let mut app = App::new(entries);
+    let mut terminal = ratatui::init();
+    let outcome = event_loop(&mut terminal, &mut app, store);
+    ratatui::restore();
+    outcome
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App, store: &Store) -> Result<Outcome> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        if app.editing {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter => {
+                    app.editing = false;
+                    app.status = HELP.to_string();
+                }
+                KeyCode::Backspace => {
+                    app.filter.pop();
+                    app.refilter();
+                }
+                KeyCode::Char(c) => {
+                    app.filter.push(c);
+                    app.refilter();
+                }
+                KeyCode::Down => app.select_next(),
+                KeyCode::Up => app.select_previous(),
+                _ => {}
+            }
+            continue;
+        }
+
+        if key.code != KeyCode::Char('d') {
+            app.pending_delete = None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Outcome::Quit),
+            KeyCode::Char('/') => {
+                app.editing = true;
+                app.status = "Type to filter · enter/esc done".to_string();
+            }
+            KeyCode::Char('j') | KeyCode::Down => app.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => app.select_previous(),
+            KeyCode::Char('J') | KeyCode::PageDown => app.scroll = app.scroll.saturating_add(10),
+            KeyCode::Char('K') | KeyCode::PageUp => app.scroll = app.scroll.saturating_sub(10),
+            KeyCode::Char('r') | KeyCode::Enter => {
+                if let Some(entry) = app.selected() {
+                    return Ok(Outcome::Resume(entry.clone()));
+                }
+            }
+            KeyCode::Char('e') => app.export_selected(),
+            KeyCode::Char('d') => app.delete_selected(store),
+            _ => {}
+        }
+    }
+}
+
+pub fn session_markdown(entry: &Entry) -> String {
+    format!(
+        "# {}\n\n_{} · {}_\n\n{}\n",
+        entry.query,
+        entry.kind,
+        entry.timestamp(),
+        entry.answer
+    )
+}
+
+fn match_score(filter: &str, entry: &Entry) -> Option<i64> {
+    if filter.trim().is_empty() {
+        return Some(0);
+    }
+
+    if let Some(score) = fuzzy_score(filter, &entry.query) {
+        return Some(score + 1000);
+    }
+
+    let answer = entry.answer.to_lowercase();
+    filter
+        .split_whitespace()
+        .all(|token| answer.contains(&token.to_lowercase()))
+        .then_some(0)
+}
+
+fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous: Option<usize> = None;
+
+    for p in pattern
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+    {
+        let found = text[pos..].iter().position(|&c| c == p)? + pos;
+
+        score += 1;
+        if previous.is_some_and(|prev| prev + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        previous = Some(found);
+        pos = found + 1;
+    }
+
+    Some(score - text.len() as i64 / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i64, query: &str, answer: &str) -> Entry {
+        Entry {
+            id,
+            kind: "agent".to_string(),
+            query: query.to_string(),
+            summary: String::new(),
+            answer: answer.to_string(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_contiguous_matches() {
+        let contiguous = fuzzy_score("tok", "tokio runtime").unwrap();
+        let scattered = fuzzy_score("tok", "the ollama kit").unwrap();
+        assert!(contiguous > scattered);
+        assert!(fuzzy_score("xyz", "tokio runtime").is_none());
+    }
+
+    #[test]
+    fn test_filter_matches_queries_before_answers() {
+        let mut app = App::new(vec![
+            entry(1, "python packaging", "Use uv for rust-like speed."),
+            entry(2, "rust async", "Tokio is the default."),
+            entry(3, "go modules", "Nothing relevant."),
+        ]);
+        assert_eq!(app.visible.len(), 3);
+
+        app.filter = "rust".to_string();
+        app.refilter();
+        assert_eq!(app.visible, vec![1, 0]);
+        assert_eq!(app.selected().unwrap().id, 2);
+    }
+
+    #[test]
+    fn test_delete_requires_confirmation() {
+        let store = Store::open_in_memory().unwrap();
+        let id = history::record(&store, history::Kind::Agent, "q", "", "a").unwrap();
+        let mut app = App::new(history::list(&store, None, 10).unwrap());
+
+        app.delete_selected(&store);
+        assert_eq!(app.entries.len(), 1);
+        app.delete_selected(&store);
+        assert!(app.entries.is_empty());
+        assert!(history::get(&store, id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,526 @@
+use crate::credibility::Tier;
+use crate::error::{OllamaError, Result};
+use crate::usage::Kind;
+use crate::webhook::Format;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table};
+use tracing::debug;
+
+const CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub retention: RetentionSettings,
+    pub daemon: DaemonSettings,
+    pub jobs: Vec<Job>,
+    pub aliases: Vec<Alias>,
+    pub webhooks: Vec<Webhook>,
+    pub github: GithubSettings,
+    pub network: NetworkSettings,
+    pub timeouts: TimeoutSettings,
+    pub limits: LimitSettings,
+    pub audit: AuditSettings,
+    pub speech: SpeechSettings,
+    pub budget: BudgetSettings,
+    pub cluster: ClusterSettings,
+    pub rerank: RerankSettings,
+    pub credibility: CredibilitySettings,
+    pub preview: PreviewSettings,
+    pub openai: CloudSettings,
+    pub anthropic: CloudSettings,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    pub prefer_ipv4: bool,
+    pub dns_timeout: Option<u64>,
+    pub connect_timeout: Option<u64>,
+    pub resolve: Vec<String>,
+    pub proxy: Option<String>,
+    pub ca_bundle: Option<PathBuf>,
+    pub insecure: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TimeoutSettings {
+    pub search: Option<u64>,
+    pub fetch: Option<u64>,
+    pub agent: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LimitSettings {
+    pub tool_result: Option<usize>,
+    pub page: Option<usize>,
+    pub preview: Option<usize>,
+    pub snippet_length: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AuditSettings {
+    pub enabled: bool,
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BudgetSettings {
+    pub daily_web_calls: Option<u64>,
+    pub monthly_web_calls: Option<u64>,
+    pub daily_model_tokens: Option<u64>,
+    pub monthly_model_tokens: Option<u64>,
+    pub requests_per_minute: Option<u32>,
+    pub on_exceeded: OnExceeded,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnExceeded {
+    #[default]
+    Fail,
+    Offline,
+}
+
+impl BudgetSettings {
+    pub fn limits(&self, kind: Kind) -> (Option<u64>, Option<u64>) {
+        match kind {
+            Kind::WebCalls => (self.daily_web_calls, self.monthly_web_calls),
+            Kind::ModelTokens => (self.daily_model_tokens, self.monthly_model_tokens),
+            _ => (None, None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SpeechSettings {
+    pub command: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ClusterSettings {
+    pub model: Option<String>,
+    pub embed_model: Option<String>,
+    pub ollama_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RerankSettings {
+    pub embed_model: Option<String>,
+    pub ollama_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CredibilitySettings {
+    pub domains: HashMap<String, Tier>,
+    pub replace_defaults: bool,
+    pub prefer: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PreviewSettings {
+    pub browser: Option<String>,
+    pub print_path: bool,
+    pub max_age_days: Option<u64>,
+}
+
+impl PreviewSettings {
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age_days
+            .map(|days| Duration::from_secs(days * 24 * 60 * 60))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CloudSettings {
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+    pub model: Option<String>,
+}
+
+impl CloudSettings {
+    pub fn api_key(&self, env_var: &str) -> Option<String> {
+        std::env::var(env_var)
+            .ok()
+            .filter(|key| !key.is_empty())
+            .or_else(|| self.api_key.clone())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GithubSettings {
+    pub token: Option<String>,
+}
+
+impl GithubSettings {
+    pub fn token(&self) -> Option<String> {
+        std::env::var("GITHUB_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+            .or_else(|| self.token.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Webhook {
+    pub url: String,
+    #[serde(default)]
+    pub format: Option<Format>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DaemonSettings {
+    pub report_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Job {
+    pub name: String,
+    pub schedule: String,
+    pub query: String,
+    #[serde(default)]
+    pub agent: bool,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Alias {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub agent: bool,
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    pub on_startup: bool,
+    pub max_total_mb: Option<f64>,
+    pub default: Policy,
+    pub namespaces: HashMap<String, Policy>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    pub max_age_days: Option<u64>,
+    pub max_mb: Option<f64>,
+    pub max_entries: Option<usize>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        let cache = Policy {
+            max_age_days: Some(30),
+            ..Policy::default()
+        };
+
+        Self {
+            on_startup: true,
+            max_total_mb: None,
+            default: Policy::default(),
+            namespaces: HashMap::from([("cache".to_string(), cache)]),
+        }
+    }
+}
+
+impl RetentionSettings {
+    pub fn policy_for(&self, namespace: &str) -> Policy {
+        match self.namespaces.get(namespace) {
+            Some(policy) => Policy {
+                max_age_days: policy.max_age_days.or(self.default.max_age_days),
+                max_mb: policy.max_mb.or(self.default.max_mb),
+                max_entries: policy.max_entries.or(self.default.max_entries),
+            },
+            None => self.default,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Result<Self> {
+        let Some(path) = path() else {
+            return Ok(Self::default());
+        };
+        if !path.exists() {
+            debug!("No config file at {:?}", path);
+            return Ok(Self::default());
+        }
+
+        debug!("Loading config from {:?}", path);
+        let contents = std::fs::read_to_string(&path)?;
+        Self::parse(&contents).map_err(|e| {
+            OllamaError::ConfigError(format!("Invalid config file {}: {}", path.display(), e))
+        })
+    }
+
+    fn parse(contents: &str) -> std::result::Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+}
+
+pub fn add_job(job: &Job) -> Result<PathBuf> {
+    add_entry(|contents| with_job(contents, job))
+}
+
+pub fn remove_job(name: &str) -> Result<bool> {
+    remove_entry(|contents| without_entry(contents, "jobs", name))
+}
+
+pub fn add_alias(alias: &Alias) -> Result<PathBuf> {
+    add_entry(|contents| with_alias(contents, alias))
+}
+
+pub fn remove_alias(name: &str) -> Result<bool> {
+    remove_entry(|contents| without_entry(contents, "aliases", name))
+}
+
+fn add_entry(update: impl FnOnce(&str) -> Result<String>) -> Result<PathBuf> {
+    let path = require_path()?;
+    let contents = read_or_empty(&path)?;
+    let updated = update(&contents)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, updated)?;
+    Ok(path)
+}
+
+fn remove_entry(update: impl FnOnce(&str) -> Result<(String, bool)>) -> Result<bool> {
+    let path = require_path()?;
+    let contents = read_or_empty(&path)?;
+    let (updated, removed) = update(&contents)?;
+
+    if removed {
+        std::fs::write(&path, updated)?;
+    }
+    Ok(removed)
+}
+
+fn named_entries<'a>(
+    doc: &'a mut DocumentMut,
+    key: &str,
+    kind: &str,
+    name: &str,
+) -> Result<&'a mut ArrayOfTables> {
+    let entries = doc
+        .entry(key)
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| OllamaError::ConfigError(format!("'{}' must be an array of tables", key)))?;
+
+    if entries
+        .iter()
+        .any(|table| table.get("name").and_then(Item::as_str) == Some(name))
+    {
+        return Err(OllamaError::ConfigError(format!(
+            "A {} named '{}' already exists",
+            kind, name
+        )));
+    }
+    Ok(entries)
+}
+
+fn with_job(contents: &str, job: &Job) -> Result<String> {
+    let mut doc = parse_document(contents)?;
+    let jobs = named_entries(&mut doc, "jobs", "job", &job.name)?;
+
+    let mut table = Table::new();
+    table["name"] = toml_edit::value(job.name.as_str());
+    table["schedule"] = toml_edit::value(job.schedule.as_str());
+    table["query"] = toml_edit::value(job.query.as_str());
+    if job.agent {
+        table["agent"] = toml_edit::value(true);
+    }
+    if let Some(model) = &job.model {
+        table["model"] = toml_edit::value(model.as_str());
+    }
+    jobs.push(table);
+
+    Ok(doc.to_string())
+}
+
+fn with_alias(contents: &str, alias: &Alias) -> Result<String> {
+    let mut doc = parse_document(contents)?;
+    let aliases = named_entries(&mut doc, "aliases", "alias", &alias.name)?;
+
+    let mut table = Table::new();
+    table["name"] = toml_edit::value(alias.name.as_str());
+    table["query"] = toml_edit::value(alias.query.as_str());
+    if alias.agent {
+        table["agent"] = toml_edit::value(true);
+    }
+    if !alias.flags.is_empty() {
+        table["flags"] = toml_edit::value(alias.flags.iter().collect::<toml_edit::Array>());
+    }
+    aliases.push(table);
+
+    Ok(doc.to_string())
+}
+
+fn without_entry(contents: &str, key: &str, name: &str) -> Result<(String, bool)> {
+    let mut doc = parse_document(contents)?;
+    let Some(entries) = doc.get_mut(key).and_then(Item::as_array_of_tables_mut) else {
+        return Ok((contents.to_string(), false));
+    };
+
+    let before = entries.len();
+    entries.retain(|table| table.get("name").and_then(Item::as_str) != Some(name));
+    let removed = entries.len() < before;
+
+    Ok((doc.to_string(), removed))
+}
+
+fn parse_document(contents: &str) -> Result<DocumentMut> {
+    contents
+        .parse::<DocumentMut>()
+        .map_err(|e| OllamaError::ConfigError(format!("Invalid config file: {}", e)))
+}
+
+fn read_or_empty(path: &std::path::Path) -> Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn require_path() -> Result<PathBuf> {
+    path().ok_or_else(|| {
+        OllamaError::ConfigError(
+            "Could not determine config directory. Set WEAVEX_CONFIG.".to_string(),
+        )
+    })
+}
+
+pub fn path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("WEAVEX_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    dirs::config_dir().map(|dir| dir.join("weavex").join(CONFIG_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        let settings = Settings::parse("").unwrap();
+        assert!(settings.retention.on_startup);
+        assert_eq!(
+            settings.retention.policy_for("cache").max_age_days,
+            Some(30)
+        );
+        assert_eq!(settings.retention.policy_for("history"), Policy::default());
+    }
+
+    #[test]
+    fn test_namespace_policy_falls_back_to_default() {
+        let settings = Settings::parse(
+            r#"
+            [retention]
+            on_startup = false
+            max_total_mb = 200
+
+            [retention.default]
+            max_age_days = 180
+
+            [retention.namespaces.history]
+            max_entries = 1000
+            "#,
+        )
+        .unwrap();
+
+        let history = settings.retention.policy_for("history");
+        assert!(settings.webhooks.is_empty());
+        assert_eq!(history.max_entries, Some(1000));
+        assert_eq!(history.max_age_days, Some(180));
+        assert!(!settings.retention.on_startup);
+        assert_eq!(settings.retention.max_total_mb, Some(200.0));
+    }
+
+    #[test]
+    fn test_webhooks_with_optional_format() {
+        let settings = Settings::parse(
+            r#"
+            [[webhooks]]
+            url = "https://hooks.slack.com/services/T0/B0/xyz"
+
+            [[webhooks]]
+            url = "https://relay.example.com/hook"
+            format = "discord"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(settings.webhooks.len(), 2);
+        assert_eq!(settings.webhooks[0].format, None);
+        assert_eq!(settings.webhooks[1].format, Some(Format::Discord));
+    }
+
+    #[test]
+    fn test_github_token_from_config() {
+        let settings = Settings::parse("[github]\ntoken = \"ghp_test\"\n").unwrap();
+        assert_eq!(settings.github.token.as_deref(), Some("ghp_test"));
+        assert_eq!(Settings::parse("").unwrap().github.token, None);
+    }
+
+    #[test]
+    fn test_add_and_remove_jobs_preserves_comments() {
+        let job = Job {
+            name: "cves".to_string(),
+            schedule: "0 8 * * *".to_string(),
+            query: "openssl CVE".to_string(),
+            agent: true,
+            model: None,
+        };
+        let original = "# my settings\n[retention]\non_startup = false\n";
+
+        let updated = with_job(original, &job).unwrap();
+        assert!(updated.starts_with("# my settings"));
+        assert_eq!(Settings::parse(&updated).unwrap().jobs, vec![job.clone()]);
+        assert!(with_job(&updated, &job).is_err());
+
+        let (removed, found) = without_entry(&updated, "jobs", "cves").unwrap();
+        assert!(found);
+        assert!(Settings::parse(&removed).unwrap().jobs.is_empty());
+        assert!(!without_entry(&removed, "jobs", "cves").unwrap().1);
+    }
+
+    #[test]
+    fn test_add_alias_with_flags() {
+        let alias = Alias {
+            name: "cve-rust".to_string(),
+            query: "recent security advisories for {1}".to_string(),
+            agent: true,
+            flags: vec!["--max-iterations".to_string(), "5".to_string()],
+        };
+        let updated = with_alias("", &alias).unwrap();
+        assert_eq!(
+            Settings::parse(&updated).unwrap().aliases,
+            vec![alias.clone()]
+        );
+        assert!(with_alias(&updated, &alias).is_err());
+        assert!(without_entry(&updated, "aliases", "cve-rust").unwrap().1);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::error::{OllamaError, Result};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use regex::Regex;
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use tracing::{debug, warn};
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_COMMAND: &str = "say";
+#[cfg(not(target_os = "macos"))]
+pub const DEFAULT_COMMAND: &str = "espeak-ng";
+
+#[derive(Debug, Clone)]
+pub struct Speaker {
+    command: String,
+}
+
+impl Speaker {
+    pub fn new(command: Option<&str>) -> Self {
+        Self {
+            command: command
+                .map(str::trim)
+                .filter(|c| !c.is_empty())
+                .unwrap_or(DEFAULT_COMMAND)
+                .to_string(),
+        }
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn speak(&self, answer: &str) -> Result<()> {
+        let sentences = sentences(&speakable(answer));
+        if sentences.is_empty() {
+            return Ok(());
+        }
+
+        debug!(
+            "Speaking {} sentences with {}",
+            sentences.len(),
+            self.command
+        );
+        let mut child = shell(&self.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .map_err(|e| {
+                OllamaError::ConfigError(format!(
+                    "Failed to start speech command '{}': {}",
+                    self.command, e
+                ))
+            })?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            for sentence in &sentences {
+                match writeln!(stdin, "{}", sentence).and_then(|_| stdin.flush()) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+                        warn!("Speech command stopped reading early");
+                        break;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(OllamaError::ConfigError(format!(
+                "Speech command '{}' exited with {}",
+                self.command, status
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    shell
+}
+
+fn speakable(markdown: &str) -> String {
+    static NOISE: OnceLock<Regex> = OnceLock::new();
+    static FOOTNOTES: OnceLock<Regex> = OnceLock::new();
+    let noise = NOISE.get_or_init(|| {
+        Regex::new(r"[ \t]*(?:\[\d+(?:\s*[,-]\s*\d+)*\]|\[\^[^\]\s]+\]|https?://[^\s<>()\[\]]+)")
+            .unwrap()
+    });
+    let footnotes =
+        FOOTNOTES.get_or_init(|| Regex::new(r"(?m)^[ \t]*\[\^[^\]\s]+\]:.*(?:\n|$)").unwrap());
+    let markdown = footnotes.replace_all(markdown, "");
+
+    let mut text = String::new();
+    let mut in_code_block = false;
+    for event in Parser::new(&markdown) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(t) | Event::Code(t) if !in_code_block => text.push_str(&t),
+            Event::SoftBreak => text.push(' '),
+            Event::HardBreak
+            | Event::End(TagEnd::Paragraph)
+            | Event::End(TagEnd::Heading(_))
+            | Event::End(TagEnd::Item)
+            | Event::End(TagEnd::TableCell) => text.push('\n'),
+            _ => {}
+        }
+    }
+    noise.replace_all(&text, "").into_owned()
+}
+
+fn sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    for block in text.lines() {
+        let mut current = String::new();
+        let mut chars = block.chars().peekable();
+        while let Some(c) = chars.next() {
+            current.push(c);
+            let ends = matches!(c, '.' | '!' | '?' | '。' | '！' | '？');
+            let next_starts = match chars.peek() {
+                Some(next) if next.is_whitespace() => {
+                    let rest = chars.clone().find(|c| !c.is_whitespace());
+                    rest.is_none_or(|c| !c.is_lowercase())
+                }
+                Some(_) => matches!(c, '。' | '！' | '？'),
+                None => false,
+            };
+            if ends && next_starts {
+                push_sentence(&mut sentences, &current);
+                current.clear();
+            }
+        }
+        push_sentence(&mut sentences, &current);
+    }
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, sentence: &str) {
+    let sentence = sentence.split_whitespace().collect::<Vec<_>>().join(" ");
+    if sentence.chars().any(char::is_alphanumeric) {
+        sentences.push(sentence);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_markdown_citations_and_code() {
+        let answer = "## Summary\n\nTokio is the **default** runtime [1]. See \
+                      [the docs](https://tokio.rs) or https://docs.rs/tokio [2, 3].\n\n\
+                      ```rust\nfn main() {}\n```\n\n- Uses `epoll` on Linux\n- Works on macOS";
+        assert_eq!(
+            sentences(&speakable(answer)),
+            [
+                "Summary",
+                "Tokio is the default runtime.",
+                "See the docs or.",
+                "Uses epoll on Linux",
+                "Works on macOS",
+            ]
+        );
+
+        let footnoted = "Tokio is the default runtime[^1] and uses epoll [^2].\n\n\
+                         [^1]: Tokio docs, https://tokio.rs\n\
+                         [^2]: Linux man pages";
+        assert_eq!(
+            sentences(&speakable(footnoted)),
+            ["Tokio is the default runtime and uses epoll."]
+        );
+    }
+
+    #[test]
+    fn test_splits_sentences_without_breaking_abbreviations() {
+        assert_eq!(
+            sentences("Async runtimes, e.g. tokio, are popular! Are they fast? Yes."),
+            [
+                "Async runtimes, e.g. tokio, are popular!",
+                "Are they fast?",
+                "Yes."
+            ]
+        );
+        assert_eq!(
+            sentences("所有権は独特です。借用も重要です。"),
+            ["所有権は独特です。", "借用も重要です。"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pipes_one_sentence_per_line_to_the_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("spoken.txt");
+        let speaker = Speaker::new(Some(&format!("cat > '{}'", out.display())));
+        speaker.speak("First point. Second point [1].").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "First point.\nSecond point.\n"
+        );
+
+        assert!(Speaker::new(Some("exit 3")).speak("Hello.").is_err());
+        assert_eq!(Speaker::new(Some("  ")).command(), DEFAULT_COMMAND);
+    }
+}
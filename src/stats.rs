@@ -0,0 +1,169 @@
+use crate::error::Result;
+use crate::store::Store;
+use crate::usage::Kind;
+use chrono::NaiveDate;
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Day {
+    pub day: String,
+    pub searches: u64,
+    pub fetches: u64,
+    pub agent_runs: u64,
+    pub web_calls: u64,
+    pub model_tokens: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Stats {
+    pub days: Vec<Day>,
+    pub total: Day,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub cache_hit_rate: Option<f64>,
+    pub tracked_agent_runs: u64,
+    pub average_agent_iterations: Option<f64>,
+}
+
+pub fn collect(store: &Store, since: NaiveDate) -> Result<Stats> {
+    let since_day = since.format("%Y-%m-%d").to_string();
+    let mut days: BTreeMap<String, Day> = BTreeMap::new();
+
+    let mut stmt = store.conn().prepare(
+        "SELECT date(created_at, 'unixepoch', 'localtime') AS day, kind, COUNT(*)
+         FROM history WHERE day >= ?1 GROUP BY day, kind",
+    )?;
+    let rows = stmt.query_map(params![since_day], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (day, kind, count) = row?;
+        let entry = entry(&mut days, day);
+        let count = count as u64;
+        match kind.as_str() {
+            "search" => entry.searches += count,
+            "fetch" => entry.fetches += count,
+            "agent" => entry.agent_runs += count,
+            _ => {}
+        }
+    }
+
+    let mut counters: BTreeMap<String, u64> = BTreeMap::new();
+    let mut stmt = store
+        .conn()
+        .prepare("SELECT day, kind, amount FROM usage WHERE day >= ?1")?;
+    let rows = stmt.query_map(params![since_day], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (day, kind, amount) = row?;
+        let amount = amount as u64;
+        *counters.entry(kind.clone()).or_default() += amount;
+        if kind == Kind::WebCalls.as_str() {
+            entry(&mut days, day).web_calls += amount;
+        } else if kind == Kind::ModelTokens.as_str() {
+            entry(&mut days, day).model_tokens += amount;
+        }
+    }
+
+    let counter = |kind: Kind| counters.get(kind.as_str()).copied().unwrap_or(0);
+    let (cache_hits, cache_misses) = (counter(Kind::CacheHits), counter(Kind::CacheMisses));
+    let (runs, iterations) = (counter(Kind::AgentRuns), counter(Kind::AgentIterations));
+
+    let days: Vec<Day> = days.into_values().collect();
+    let total = days.iter().fold(
+        Day {
+            day: "total".to_string(),
+            ..Default::default()
+        },
+        |mut total, day| {
+            total.searches += day.searches;
+            total.fetches += day.fetches;
+            total.agent_runs += day.agent_runs;
+            total.web_calls += day.web_calls;
+            total.model_tokens += day.model_tokens;
+            total
+        },
+    );
+
+    Ok(Stats {
+        days,
+        total,
+        cache_hits,
+        cache_misses,
+        cache_hit_rate: ratio(cache_hits, cache_hits + cache_misses),
+        tracked_agent_runs: runs,
+        average_agent_iterations: ratio(iterations, runs),
+    })
+}
+
+fn entry(days: &mut BTreeMap<String, Day>, day: String) -> &mut Day {
+    days.entry(day.clone()).or_insert_with(|| Day {
+        day,
+        ..Default::default()
+    })
+}
+
+fn ratio(part: u64, whole: u64) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{history, usage};
+    use chrono::{Days, Local};
+
+    #[test]
+    fn test_collects_daily_activity_and_counters() {
+        let store = Store::open_in_memory().unwrap();
+        let today = Local::now().date_naive();
+        let long_ago = today - Days::new(40);
+        history::record(&store, history::Kind::Search, "tokio", "", "").unwrap();
+        history::record(&store, history::Kind::Search, "smol", "", "").unwrap();
+        history::record(&store, history::Kind::Agent, "runtimes", "", "Tokio.").unwrap();
+        usage::record(&store, Kind::WebCalls, 7, today).unwrap();
+        usage::record(&store, Kind::WebCalls, 50, long_ago).unwrap();
+        usage::record(&store, Kind::ModelTokens, 1200, today).unwrap();
+        usage::record(&store, Kind::CacheHits, 3, today).unwrap();
+        usage::record(&store, Kind::CacheMisses, 1, today).unwrap();
+        usage::record(&store, Kind::AgentRuns, 2, today).unwrap();
+        usage::record(&store, Kind::AgentIterations, 9, today).unwrap();
+
+        let stats = collect(&store, today - Days::new(6)).unwrap();
+        assert_eq!(stats.days.len(), 1);
+        assert_eq!(
+            stats.days[0],
+            Day {
+                day: today.format("%Y-%m-%d").to_string(),
+                searches: 2,
+                fetches: 0,
+                agent_runs: 1,
+                web_calls: 7,
+                model_tokens: 1200,
+            }
+        );
+        assert_eq!(stats.total.web_calls, 7);
+        assert_eq!(stats.cache_hit_rate, Some(0.75));
+        assert_eq!(stats.average_agent_iterations, Some(4.5));
+    }
+
+    #[test]
+    fn test_empty_store_has_no_rates() {
+        let store = Store::open_in_memory().unwrap();
+        let stats = collect(&store, Local::now().date_naive()).unwrap();
+        assert!(stats.days.is_empty());
+        assert_eq!(stats.cache_hit_rate, None);
+        assert_eq!(stats.average_agent_iterations, None);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::error::{OllamaError, Result};
+use chrono::{Local, TimeZone};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+const DATABASE_FILE: &str = "weavex.db";
+const ARTIFACTS_DIR: &str = "artifacts";
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("kb_documents", "url", "TEXT"),
+    ("kb_documents", "fetched_at", "INTEGER"),
+    ("kb_documents", "chunking", "TEXT"),
+];
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS seen_results (
+    id TEXT PRIMARY KEY,
+    url TEXT NOT NULL,
+    title TEXT NOT NULL,
+    query TEXT NOT NULL,
+    seen_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS feedback (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    result_id TEXT NOT NULL,
+    url TEXT NOT NULL,
+    domain TEXT NOT NULL,
+    title TEXT NOT NULL,
+    query TEXT NOT NULL,
+    rating INTEGER NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS feedback_domain ON feedback(domain);
+
+CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL,
+    query TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    answer TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+    query, summary, answer, content='history', content_rowid='id'
+);
+
+CREATE TRIGGER IF NOT EXISTS history_ai AFTER INSERT ON history BEGIN
+    INSERT INTO history_fts(rowid, query, summary, answer)
+    VALUES (new.id, new.query, new.summary, new.answer);
+END;
+
+CREATE TRIGGER IF NOT EXISTS history_ad AFTER DELETE ON history BEGIN
+    INSERT INTO history_fts(history_fts, rowid, query, summary, answer)
+    VALUES ('delete', old.id, old.query, old.summary, old.answer);
+END;
+
+CREATE TABLE IF NOT EXISTS kb_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,
+    title TEXT NOT NULL,
+    added_at INTEGER NOT NULL,
+    url TEXT,
+    fetched_at INTEGER,
+    chunking TEXT,
+    collection TEXT NOT NULL DEFAULT 'default',
+    UNIQUE (collection, source)
+);
+
+CREATE TABLE IF NOT EXISTS kb_chunks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL REFERENCES kb_documents(id),
+    chunk_index INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    embedding BLOB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS kb_chunks_document ON kb_chunks(document_id);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS kb_chunks_fts USING fts5(
+    content, content='kb_chunks', content_rowid='id'
+);
+
+CREATE TRIGGER IF NOT EXISTS kb_chunks_ai AFTER INSERT ON kb_chunks BEGIN
+    INSERT INTO kb_chunks_fts(rowid, content) VALUES (new.id, new.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS kb_chunks_ad AFTER DELETE ON kb_chunks BEGIN
+    INSERT INTO kb_chunks_fts(kb_chunks_fts, rowid, content)
+    VALUES ('delete', old.id, old.content);
+END;
+
+INSERT INTO kb_chunks_fts(kb_chunks_fts)
+SELECT 'rebuild'
+WHERE (SELECT COUNT(*) FROM kb_chunks_fts_docsize) != (SELECT COUNT(*) FROM kb_chunks);
+
+CREATE TABLE IF NOT EXISTS kb_centroids (
+    id INTEGER PRIMARY KEY,
+    embedding BLOB NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS kb_assignments (
+    chunk_id INTEGER PRIMARY KEY,
+    centroid INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS kb_assignments_centroid ON kb_assignments(centroid);
+
+CREATE TABLE IF NOT EXISTS watch_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    query TEXT NOT NULL,
+    mode TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS watch_snapshots_query ON watch_snapshots(query, mode);
+
+CREATE TABLE IF NOT EXISTS artifacts (
+    hash TEXT PRIMARY KEY,
+    media_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    stored_size INTEGER NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS artifact_refs (
+    hash TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    owner_id INTEGER NOT NULL,
+    PRIMARY KEY (hash, owner, owner_id)
+);
+
+CREATE INDEX IF NOT EXISTS artifact_refs_owner ON artifact_refs(owner, owner_id);
+
+CREATE TABLE IF NOT EXISTS bookmarks (
+    url TEXT PRIMARY KEY,
+    domain TEXT NOT NULL,
+    title TEXT NOT NULL,
+    tags TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS permissions (
+    capability TEXT PRIMARY KEY,
+    granted_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS usage (
+    day TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    PRIMARY KEY (day, kind)
+);
+";
+
+pub struct Store {
+    conn: Connection,
+    dir: Option<PathBuf>,
+}
+
+impl Store {
+    pub fn open() -> Result<Self> {
+        let dir = data_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        Self::open_at(&dir.join(DATABASE_FILE))
+    }
+
+    pub fn open_at(path: &Path) -> Result<Self> {
+        debug!("Opening local store at: {:?}", path);
+        let conn = Connection::open(path)?;
+        Self::init(conn, path.parent().map(Path::to_path_buf))
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?, None)
+    }
+
+    fn init(conn: Connection, dir: Option<PathBuf>) -> Result<Self> {
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        conn.execute_batch(SCHEMA)?;
+        add_missing_columns(&conn)?;
+        add_kb_collections(&conn)?;
+        Ok(Self { conn, dir })
+    }
+
+    pub fn conn(&self) -> &Connection {
+        &self.conn
+    }
+
+    pub fn artifacts_dir(&self) -> Result<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(ARTIFACTS_DIR))
+            .ok_or_else(|| {
+                OllamaError::ConfigError("In-memory store has no artifact directory".to_string())
+            })
+    }
+}
+
+fn add_missing_columns(conn: &Connection) -> Result<()> {
+    for (table, column, definition) in ADDED_COLUMNS {
+        let exists = conn
+            .prepare(&format!(
+                "SELECT 1 FROM pragma_table_info('{}') WHERE name = ?1",
+                table
+            ))?
+            .exists([column])?;
+        if !exists {
+            debug!("Adding column {}.{}", table, column);
+            conn.execute_batch(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, definition
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+fn add_kb_collections(conn: &Connection) -> Result<()> {
+    let migrated = conn
+        .prepare("SELECT 1 FROM pragma_table_info('kb_documents') WHERE name = 'collection'")?
+        .exists([])?;
+    if migrated {
+        return Ok(());
+    }
+
+    debug!("Migrating kb_documents to per-collection sources");
+    conn.execute_batch(
+        "PRAGMA foreign_keys = OFF;
+         BEGIN;
+         CREATE TABLE kb_documents_migrated (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             source TEXT NOT NULL,
+             title TEXT NOT NULL,
+             added_at INTEGER NOT NULL,
+             url TEXT,
+             fetched_at INTEGER,
+             chunking TEXT,
+             collection TEXT NOT NULL DEFAULT 'default',
+             UNIQUE (collection, source)
+         );
+         INSERT INTO kb_documents_migrated (id, source, title, added_at, url, fetched_at, chunking)
+             SELECT id, source, title, added_at, url, fetched_at, chunking FROM kb_documents;
+         DROP TABLE kb_documents;
+         ALTER TABLE kb_documents_migrated RENAME TO kb_documents;
+         COMMIT;
+         PRAGMA foreign_keys = ON;",
+    )?;
+    Ok(())
+}
+
+pub fn data_dir() -> Result<PathBuf> {
+    if let Some(dir) = std::env::var_os("WEAVEX_DATA_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    dirs::data_dir()
+        .map(|dir| dir.join("weavex"))
+        .ok_or_else(|| {
+            OllamaError::ConfigError(
+                "Could not determine data directory. Set WEAVEX_DATA_DIR.".to_string(),
+            )
+        })
+}
+
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+pub fn format_timestamp(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrates_existing_databases() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE kb_documents (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 source TEXT NOT NULL UNIQUE,
+                 title TEXT NOT NULL,
+                 added_at INTEGER NOT NULL
+             );
+             INSERT INTO kb_documents (source, title, added_at) VALUES ('a', 'A', 0);",
+        )
+        .unwrap();
+
+        let store = Store::init(conn, None).unwrap();
+        let url: Option<String> = store
+            .conn()
+            .query_row("SELECT url FROM kb_documents", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(url, None);
+        add_missing_columns(store.conn()).unwrap();
+
+        store
+            .conn()
+            .execute(
+                "INSERT INTO kb_documents (source, title, added_at, collection) VALUES ('a', 'A', 0, 'other')",
+                [],
+            )
+            .unwrap();
+        let collections: Vec<String> = store
+            .conn()
+            .prepare("SELECT collection FROM kb_documents ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(collections, vec!["default", "other"]);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::chunking::{self, ChunkOptions, Strategy};
+use crate::error::Result;
+use crate::ollama_local::ChatBackend;
+use clap::ValueEnum;
+use serde_json::json;
+use std::collections::HashMap;
+use tracing::debug;
+
+pub const EXTRACTIVE_SENTENCES: usize = 5;
+
+const TLDR_SENTENCES: usize = 2;
+const MAP_CHUNK_TOKENS: usize = 3000;
+const MAX_MAP_PASSES: usize = 4;
+const MAP_PROMPT: &str = "You are summarizing one part of a longer document. \
+     List its key facts, figures, and claims as concise bullet points. \
+     Do not add anything that is not in the text.";
+
+const MIN_WORD_CHARS: usize = 3;
+const STOPWORDS: &[&str] = &[
+    "and", "are", "but", "can", "for", "from", "has", "have", "into", "its", "not", "that", "the",
+    "their", "then", "there", "these", "this", "was", "were", "which", "while", "will", "with",
+    "you", "your",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Summarizer {
+    #[default]
+    Ollama,
+    Openai,
+    Extractive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Length {
+    Summary,
+    Tldr,
+}
+
+impl Length {
+    fn sentences(self) -> usize {
+        match self {
+            Length::Summary => EXTRACTIVE_SENTENCES,
+            Length::Tldr => TLDR_SENTENCES,
+        }
+    }
+
+    fn prompt(self) -> &'static str {
+        match self {
+            Length::Summary => {
+                "Summarize the following text in a few short paragraphs. \
+                 Keep the key facts, figures, and conclusions. \
+                 Do not add anything that is not in the text."
+            }
+            Length::Tldr => {
+                "Give a TL;DR of the following text in at most two sentences. \
+                 Do not add anything that is not in the text."
+            }
+        }
+    }
+}
+
+pub async fn summarize(
+    chat: Option<(&dyn ChatBackend, &str)>,
+    text: &str,
+    length: Length,
+) -> Result<String> {
+    summarize_with(chat, text, length.prompt(), length.sentences()).await
+}
+
+pub async fn summarize_with(
+    chat: Option<(&dyn ChatBackend, &str)>,
+    text: &str,
+    instructions: &str,
+    sentences: usize,
+) -> Result<String> {
+    match chat {
+        Some((chat, model)) => map_reduce(chat, model, text, instructions).await,
+        None => Ok(extractive(text, sentences)),
+    }
+}
+
+async fn map_reduce(
+    chat: &dyn ChatBackend,
+    model: &str,
+    text: &str,
+    instructions: &str,
+) -> Result<String> {
+    let options = ChunkOptions {
+        strategy: Strategy::Paragraph,
+        size: MAP_CHUNK_TOKENS,
+        overlap: 0,
+    };
+    let mut text = text.to_string();
+
+    for pass in 1..=MAX_MAP_PASSES {
+        let chunks = chunking::chunk(&text, &options);
+        if chunks.len() <= 1 {
+            break;
+        }
+        debug!("Map pass {}: summarizing {} chunks", pass, chunks.len());
+        let mut partials = Vec::with_capacity(chunks.len());
+        for chunk in &chunks {
+            partials.push(complete(chat, model, MAP_PROMPT, chunk).await?);
+        }
+        text = partials.join("\n\n");
+    }
+
+    complete(chat, model, instructions, &text).await
+}
+
+async fn complete(
+    chat: &dyn ChatBackend,
+    model: &str,
+    instructions: &str,
+    text: &str,
+) -> Result<String> {
+    let messages = vec![
+        json!({"role": "system", "content": instructions}),
+        json!({"role": "user", "content": text}),
+    ];
+    let response = chat.chat(model, messages, None, false).await?;
+    Ok(response.message.content.trim().to_string())
+}
+
+pub fn extractive(text: &str, max_sentences: usize) -> String {
+    let sentences = sentences(text);
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for sentence in &sentences {
+        for word in words(sentence) {
+            *frequencies.entry(word).or_default() += 1;
+        }
+    }
+
+    let mut scored: Vec<(usize, f64)> = sentences
+        .iter()
+        .enumerate()
+        .map(|(idx, sentence)| {
+            let words = words(sentence);
+            let total: usize = words.iter().map(|word| frequencies[word]).sum();
+            (idx, total as f64 / (words.len().max(1) as f64).sqrt())
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mut picked: Vec<usize> = scored
+        .into_iter()
+        .take(max_sentences)
+        .map(|(idx, _)| idx)
+        .collect();
+    picked.sort_unstable();
+
+    picked
+        .into_iter()
+        .map(|idx| sentences[idx])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    for line in text.lines() {
+        let mut start = 0;
+        let mut chars = line.char_indices().peekable();
+        while let Some((idx, c)) = chars.next() {
+            let ends = matches!(c, '.' | '!' | '?')
+                && chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+            if ends {
+                sentences.push(line[start..idx + c.len_utf8()].trim());
+                start = idx + c.len_utf8();
+            }
+        }
+        sentences.push(line[start..].trim());
+    }
+    sentences.retain(|sentence| !words(sentence).is_empty());
+    sentences
+}
+
+fn words(sentence: &str) -> Vec<String> {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_WORD_CHARS)
+        .map(str::to_lowercase)
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ollama_local::{ChatMessage, ChatResponse, Tool};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    struct Recorder {
+        prompts: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl ChatBackend for Recorder {
+        async fn chat(
+            &self,
+            _model: &str,
+            messages: Vec<serde_json::Value>,
+            _tools: Option<Vec<Tool>>,
+            _think: bool,
+        ) -> Result<ChatResponse> {
+            let text = messages[1]["content"].as_str().unwrap_or_default();
+            self.prompts.lock().unwrap().push(text.to_string());
+            Ok(ChatResponse {
+                message: ChatMessage {
+                    role: "assistant".to_string(),
+                    content: format!("summary of {} chars", text.len()),
+                    tool_calls: None,
+                    thinking: None,
+                },
+                done: true,
+                prompt_eval_count: 0,
+                eval_count: 0,
+            })
+        }
+    }
+
+    #[test]
+    fn test_extractive_keeps_central_sentences_in_order() {
+        let text = "Tokio is an async runtime for Rust. \
+                    The weather was nice yesterday.\n\
+                    Tokio schedules async tasks on a work-stealing runtime. \
+                    Async Rust code needs a runtime such as Tokio.";
+        let summary = extractive(text, 2);
+        assert_eq!(
+            summary,
+            "Tokio is an async runtime for Rust. \
+             Async Rust code needs a runtime such as Tokio."
+        );
+        assert_eq!(extractive("", 3), "");
+        assert_eq!(
+            extractive("Only one sentence here", 3),
+            "Only one sentence here"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_long_text_is_mapped_then_reduced() {
+        let chat = Recorder {
+            prompts: Mutex::new(Vec::new()),
+        };
+        let paragraph = "word ".repeat(2000);
+        let text = [paragraph.as_str(); 3].join("\n\n");
+
+        let summary = summarize(Some((&chat, "model")), &text, Length::Tldr)
+            .await
+            .unwrap();
+
+        let prompts = chat.prompts.lock().unwrap();
+        assert_eq!(prompts.len(), 4);
+        assert_eq!(
+            prompts[3],
+            "summary of 9999 chars\n\nsummary of 9999 chars\n\nsummary of 9999 chars"
+        );
+        assert_eq!(summary, format!("summary of {} chars", prompts[3].len()));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::error::{OllamaError, Result};
+use crate::settings;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE_DIR: &str = "templates";
+const EXTENSION: &str = "toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    Browser,
+    Terminal,
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Template {
+    #[serde(skip)]
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub query: String,
+    #[serde(default)]
+    pub agent: bool,
+    #[serde(default)]
+    pub flags: Vec<String>,
+    #[serde(default)]
+    pub output: Option<Output>,
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+}
+
+impl Template {
+    pub fn parse(name: &str, contents: &str) -> Result<Self> {
+        let mut template: Self = toml::from_str(contents)
+            .map_err(|e| OllamaError::ConfigError(format!("Invalid template '{}': {}", name, e)))?;
+        template.name = name.to_string();
+        Ok(template)
+    }
+
+    pub fn args(&self, vars: &[(String, String)], extra: &[String]) -> Result<Vec<String>> {
+        let mut values = self.vars.clone();
+        values.extend(vars.iter().cloned());
+
+        let mut args = Vec::new();
+        if self.agent {
+            args.push("agent".to_string());
+        }
+        for flag in &self.flags {
+            args.push(substitute(&self.name, flag, &values)?);
+        }
+        match self.output {
+            Some(Output::Browser) | None => {}
+            Some(Output::Terminal) => args.push("--no-preview".to_string()),
+            Some(Output::Json) => args.extend(["--json".to_string(), "--no-preview".to_string()]),
+        }
+        args.extend(extra.iter().cloned());
+        args.push("--".to_string());
+        args.push(substitute(&self.name, &self.query, &values)?);
+        Ok(args)
+    }
+
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = placeholders(&self.query);
+        names.extend(self.flags.iter().flat_map(|flag| placeholders(flag)));
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+pub fn dir() -> Option<PathBuf> {
+    settings::path().and_then(|path| path.parent().map(|dir| dir.join(TEMPLATE_DIR)))
+}
+
+pub fn load(dir: &Path, name: &str) -> Result<Template> {
+    let path = dir.join(name).with_extension(EXTENSION);
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Template::parse(name, &contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(OllamaError::ConfigError(
+            format!("No template named '{}' in {}", name, dir.display()),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn list(dir: &Path) -> Result<Vec<Template>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut templates = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some(EXTENSION) {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+            templates.push(load(dir, name)?);
+        }
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+fn substitute(name: &str, text: &str, values: &BTreeMap<String, String>) -> Result<String> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) if is_variable(&after[..end]) => {
+                let var = &after[..end];
+                let value = values.get(var).ok_or_else(|| {
+                    OllamaError::ConfigError(format!(
+                        "Template '{}' needs a value for {{{}}}; pass --var {}=...",
+                        name, var, var
+                    ))
+                })?;
+                output.push_str(value);
+                rest = &after[end + 1..];
+            }
+            _ => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn placeholders(text: &str) -> Vec<String> {
+    text.split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}'))
+        .map(|(var, _)| var)
+        .filter(|var| is_variable(var))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_variable(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CVE_REVIEW: &str = r#"
+description = "Weekly CVE review"
+query = "CVEs published this week affecting {product} {version}, with fixed versions"
+agent = true
+flags = ["--max-iterations", "20", "--kb-collection", "{product}"]
+output = "terminal"
+
+[vars]
+version = "latest"
+"#;
+
+    #[test]
+    fn test_expands_template_into_arguments() {
+        let template = Template::parse("cve-review", CVE_REVIEW).unwrap();
+        assert_eq!(template.variables(), ["product", "version"]);
+
+        let vars = [("product".to_string(), "openssl".to_string())];
+        let args = template.args(&vars, &["-v".to_string()]).unwrap();
+        assert_eq!(
+            args,
+            [
+                "agent",
+                "--max-iterations",
+                "20",
+                "--kb-collection",
+                "openssl",
+                "--no-preview",
+                "-v",
+                "--",
+                "CVEs published this week affecting openssl latest, with fixed versions",
+            ]
+        );
+
+        let err = template.args(&[], &[]).unwrap_err().to_string();
+        assert!(err.contains("--var product=..."), "{}", err);
+    }
+
+    #[test]
+    fn test_lists_templates_and_keeps_literal_braces() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("cve-review.toml"), CVE_REVIEW).unwrap();
+        std::fs::write(
+            dir.path().join("json.toml"),
+            "query = \"{\\\"a\\\": 1} {x}\"",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let templates = list(dir.path()).unwrap();
+        let names: Vec<_> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["cve-review", "json"]);
+
+        let vars = [("x".to_string(), "1".to_string())];
+        let args = templates[1].args(&vars, &[]).unwrap();
+        assert_eq!(args.last().unwrap(), "{\"a\": 1} 1");
+        assert!(load(dir.path(), "missing").is_err());
+        assert!(list(&dir.path().join("missing")).unwrap().is_empty());
+    }
+}
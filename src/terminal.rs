@@ -0,0 +1,78 @@
+use std::io::IsTerminal;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const CYAN: &str = "\x1b[36m";
+const BLUE_UNDERLINE: &str = "\x1b[4;34m";
+const RED_STRIKETHROUGH: &str = "\x1b[9;31m";
+const GREEN: &str = "\x1b[32m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+
+pub fn color_enabled(no_color: bool) -> bool {
+    if no_color {
+        return false;
+    }
+
+    if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+
+    std::io::stdout().is_terminal()
+}
+
+pub fn bold(text: &str) -> String {
+    format!("{}{}{}", BOLD, text, RESET)
+}
+
+pub fn dim(text: &str) -> String {
+    format!("{}{}{}", DIM, text, RESET)
+}
+
+pub fn cyan(text: &str) -> String {
+    format!("{}{}{}", CYAN, text, RESET)
+}
+
+pub fn deleted(text: &str) -> String {
+    format!("{}{}{}", RED_STRIKETHROUGH, text, RESET)
+}
+
+pub fn inserted(text: &str) -> String {
+    format!("{}{}{}", GREEN, text, RESET)
+}
+
+pub fn highlight(text: &str) -> String {
+    format!("{}{}{}", BOLD_YELLOW, text, RESET)
+}
+
+pub fn hyperlink(url: &str, text: &str) -> String {
+    let url: String = url.chars().filter(|c| !c.is_control()).collect();
+    format!(
+        "\x1b]8;;{}\x1b\\{}{}{}\x1b]8;;\x1b\\",
+        url, BLUE_UNDERLINE, text, RESET
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hyperlink_wraps_text_in_osc8() {
+        let link = hyperlink("https://example.com", "example");
+        assert!(link.starts_with("\x1b]8;;https://example.com\x1b\\"));
+        assert!(link.ends_with("\x1b]8;;\x1b\\"));
+        assert!(link.contains("example"));
+    }
+
+    #[test]
+    fn test_hyperlink_strips_control_characters_from_url() {
+        let link = hyperlink("https://evil.com/\x1b]8;;x\x07", "text");
+        assert!(link.starts_with("\x1b]8;;https://evil.com/]8;;x\x1b\\"));
+    }
+
+    #[test]
+    fn test_no_color_flag_disables_color() {
+        assert!(!color_enabled(true));
+    }
+}
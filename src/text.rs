@@ -0,0 +1,255 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+pub const DEFAULT_TOOL_RESULT_LIMIT: usize = 8000;
+const AGENT_SNIPPET_LENGTH: usize = 500;
+const PREVIEW_SNIPPET_LENGTH: usize = 200;
+const PREVIEW_PAGE_LENGTH: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub tool_result: usize,
+    pub page: Option<usize>,
+    pub preview: Option<usize>,
+    pub snippet: Option<usize>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Limits {
+    pub const DEFAULT: Limits = Limits {
+        tool_result: DEFAULT_TOOL_RESULT_LIMIT,
+        page: None,
+        preview: None,
+        snippet: None,
+    };
+
+    pub fn page(&self) -> usize {
+        self.page.unwrap_or(self.tool_result / 4)
+    }
+
+    pub fn preview_page(&self) -> usize {
+        self.preview.unwrap_or(PREVIEW_PAGE_LENGTH)
+    }
+
+    pub fn agent_snippet(&self) -> usize {
+        self.snippet.unwrap_or(AGENT_SNIPPET_LENGTH)
+    }
+
+    pub fn preview_snippet(&self) -> usize {
+        self.snippet.unwrap_or(PREVIEW_SNIPPET_LENGTH)
+    }
+
+    pub fn min_context_tokens(&self) -> u64 {
+        self.tool_result as u64
+    }
+}
+
+pub fn truncate_utf8(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+
+    let mut end = max_bytes;
+    while end > 0 && !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+pub fn truncate_at_sentence(s: &str, max_bytes: usize) -> &str {
+    let truncated = truncate_utf8(s, max_bytes);
+    if truncated.len() == s.len() {
+        return s;
+    }
+
+    let floor = truncated.len() / 2;
+    let sentence_end = truncated
+        .char_indices()
+        .filter(|&(idx, c)| {
+            c == '。'
+                || matches!(c, '.' | '!' | '?')
+                    && truncated[idx + c.len_utf8()..]
+                        .chars()
+                        .next()
+                        .is_none_or(char::is_whitespace)
+        })
+        .map(|(idx, c)| idx + c.len_utf8())
+        .chain(truncated.match_indices('\n').map(|(idx, _)| idx))
+        .filter(|&end| end >= floor)
+        .max();
+    if let Some(end) = sentence_end {
+        return truncated[..end].trim_end();
+    }
+
+    match truncated.rfind(char::is_whitespace) {
+        Some(end) if end >= floor => truncated[..end].trim_end(),
+        _ => truncated,
+    }
+}
+
+pub fn ellipsize(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut clipped: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    clipped.push('…');
+    clipped
+}
+
+pub fn sanitize(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('\u{40}'..='\u{7e}').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\n' | '\t' => output.push(c),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            c if c.is_control() || is_bidi_control(c) => {}
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+pub fn urls(text: &str) -> Vec<String> {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    let pattern = URL.get_or_init(|| Regex::new(r#"https?://[^\s<>()\[\]"'`]+"#).unwrap());
+
+    let mut urls: Vec<String> = Vec::new();
+    for found in pattern.find_iter(text) {
+        let url = found
+            .as_str()
+            .trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        if !urls.iter().any(|u| u == url) {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' | '\u{200e}' | '\u{200f}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_truncate_utf8_examples() {
+        assert_eq!(truncate_utf8("Hello, World!", 5), "Hello");
+        assert_eq!(truncate_utf8("Hello, World!", 100), "Hello, World!");
+        assert_eq!(truncate_utf8("🚀", 2), "");
+        assert_eq!(truncate_utf8("", 10), "");
+        assert_eq!(truncate_utf8(&"a".repeat(10000), 8000).len(), 8000);
+    }
+
+    #[test]
+    fn test_truncate_at_sentence_examples() {
+        let text = "Tokio is a runtime. It schedules tasks! Does it block? Never.";
+        assert_eq!(
+            truncate_at_sentence(text, 45),
+            "Tokio is a runtime. It schedules tasks!"
+        );
+        assert_eq!(truncate_at_sentence("v1.2.3 shipped today", 12), "v1.2.3");
+        assert_eq!(truncate_at_sentence("short", 100), "short");
+        assert_eq!(
+            truncate_at_sentence("所有权。借用规则很重要", 20),
+            "所有权。"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_strips_escapes_and_controls() {
+        assert_eq!(
+            sanitize("\u{1b}[31mred\u{1b}[0m \u{1b}]8;;https://evil\u{1b}\\link\u{1b}]8;;\u{7}"),
+            "red link"
+        );
+        assert_eq!(sanitize("a\r\nb\u{0}c\u{8}\td"), "a\nbc\td");
+        assert_eq!(sanitize("admin\u{202e}gnp.exe"), "admingnp.exe");
+        assert_eq!(ellipsize("abcdef", 4), "abc…");
+    }
+
+    #[test]
+    fn test_limits_scale_from_tool_result_limit() {
+        let limits = Limits::default();
+        assert_eq!((limits.page(), limits.preview_page()), (2000, 1000));
+        assert_eq!(
+            (limits.agent_snippet(), limits.preview_snippet()),
+            (500, 200)
+        );
+
+        let limits = Limits {
+            tool_result: 32000,
+            snippet: Some(800),
+            ..Limits::DEFAULT
+        };
+        assert_eq!((limits.page(), limits.preview_page()), (8000, 1000));
+        assert_eq!(
+            (limits.agent_snippet(), limits.preview_snippet()),
+            (800, 800)
+        );
+
+        let limits = Limits {
+            page: Some(3000),
+            preview: Some(400),
+            ..Limits::DEFAULT
+        };
+        assert_eq!((limits.page(), limits.preview_page()), (3000, 400));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_truncate_utf8_is_a_bounded_prefix(s in "\\PC*", max in 0usize..64) {
+            let truncated = truncate_utf8(&s, max);
+            prop_assert!(truncated.len() <= max);
+            prop_assert!(s.starts_with(truncated));
+            prop_assert!(s.len() <= max || max - truncated.len() < 4);
+        }
+
+        #[test]
+        fn prop_truncate_at_sentence_is_a_bounded_prefix(s in any::<String>(), max in 0usize..128) {
+            let truncated = truncate_at_sentence(&s, max);
+            prop_assert!(truncated.len() <= max);
+            prop_assert!(s.starts_with(truncated));
+        }
+
+        #[test]
+        fn prop_ellipsize_respects_char_limit(s in any::<String>(), max in 1usize..64) {
+            let clipped = ellipsize(&s, max);
+            prop_assert!(clipped.chars().count() <= max);
+        }
+
+        #[test]
+        fn prop_sanitize_removes_all_control_characters(s in any::<String>()) {
+            let clean = sanitize(&s);
+            prop_assert!(clean
+                .chars()
+                .all(|c| c == '\n' || c == '\t' || !(c.is_control() || is_bidi_control(c))));
+            prop_assert_eq!(sanitize(&clean), clean.clone());
+        }
+    }
+}
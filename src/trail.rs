@@ -0,0 +1,322 @@
+use crate::citations;
+use crate::client::{FetchResponse, OllamaClient};
+use crate::error::{OllamaError, Result};
+use crate::glyphs::Glyph;
+use crate::ollama_local::ChatBackend;
+use crate::orchestrate::Researcher;
+use crate::text::{sanitize, truncate_at_sentence};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::{HashSet, VecDeque};
+use tracing::{info, warn};
+use url::Url;
+
+pub const DEFAULT_MAX_PAGES: usize = 8;
+pub const DEFAULT_MAX_DEPTH: usize = 2;
+const LINKS_PER_PAGE: usize = 3;
+const MAX_CANDIDATE_LINKS: usize = 80;
+const PREVIEW_BYTES: usize = 1500;
+const PAGE_BYTES: usize = 6000;
+const SKIPPED_EXTENSIONS: &[&str] = &[
+    ".png", ".jpg", ".jpeg", ".gif", ".svg", ".webp", ".ico", ".css", ".js", ".zip", ".gz", ".mp4",
+    ".mp3",
+];
+
+pub struct Trail {
+    chat: Box<dyn ChatBackend>,
+    web_client: OllamaClient,
+    model: String,
+    start: String,
+    max_pages: usize,
+    max_depth: usize,
+    enable_reasoning: bool,
+}
+
+#[derive(Debug)]
+pub struct Page {
+    pub url: String,
+    pub title: String,
+    pub content: String,
+}
+
+impl Trail {
+    pub fn new(
+        chat: Box<dyn ChatBackend>,
+        web_client: OllamaClient,
+        model: String,
+        start: String,
+    ) -> Self {
+        Self {
+            chat,
+            web_client,
+            model,
+            start,
+            max_pages: DEFAULT_MAX_PAGES,
+            max_depth: DEFAULT_MAX_DEPTH,
+            enable_reasoning: true,
+        }
+    }
+
+    pub fn with_limits(mut self, max_pages: usize, max_depth: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn with_reasoning(mut self, enable_reasoning: bool) -> Self {
+        self.enable_reasoning = enable_reasoning;
+        self
+    }
+
+    pub async fn run(&self, question: &str) -> Result<String> {
+        let start = Url::parse(&self.start)
+            .map_err(|e| OllamaError::InvalidUrl(format!("Invalid URL '{}': {}", self.start, e)))?;
+        let mut queue = VecDeque::from([(without_fragment(start), 0)]);
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut pages: Vec<Page> = Vec::new();
+
+        while let Some((url, depth)) = queue.pop_front() {
+            if pages.len() >= self.max_pages {
+                break;
+            }
+            if !visited.insert(url.clone()) {
+                continue;
+            }
+
+            println!("{}{}{}", "   ".repeat(depth), Glyph::Web, url);
+            let response = match self.web_client.fetch(&url).await {
+                Ok(response) => {
+                    citations::record(&url, &response);
+                    response
+                }
+                Err(e) if pages.is_empty() => return Err(e),
+                Err(e) => {
+                    warn!("Skipping {}: {}", url, e);
+                    continue;
+                }
+            };
+
+            if depth < self.max_depth && pages.len() + 1 < self.max_pages {
+                let candidates = candidate_links(&url, &response.links, &visited);
+                if !candidates.is_empty() {
+                    let chosen = self
+                        .choose_links(question, &url, &response, &candidates)
+                        .await?;
+                    info!(
+                        "Following {} of {} links from {}",
+                        chosen.len(),
+                        candidates.len(),
+                        url
+                    );
+                    for link in chosen {
+                        queue.push_back((link, depth + 1));
+                    }
+                }
+            }
+
+            pages.push(Page {
+                url,
+                title: response.title,
+                content: response.content,
+            });
+        }
+
+        println!("\n{}Answering from {} pages\n", Glyph::Pages, pages.len());
+        let response = self
+            .chat
+            .chat(
+                &self.model,
+                vec![json!({"role": "user", "content": answer_prompt(question, &pages)})],
+                None,
+                self.enable_reasoning,
+            )
+            .await?;
+        Ok(response.message.content)
+    }
+
+    async fn choose_links(
+        &self,
+        question: &str,
+        url: &str,
+        page: &FetchResponse,
+        candidates: &[String],
+    ) -> Result<Vec<String>> {
+        let response = self
+            .chat
+            .chat(
+                &self.model,
+                vec![json!({
+                    "role": "user",
+                    "content": link_prompt(question, url, page, candidates),
+                })],
+                None,
+                false,
+            )
+            .await?;
+        Ok(parse_choices(&response.message.content, candidates.len())
+            .into_iter()
+            .map(|idx| candidates[idx].clone())
+            .collect())
+    }
+}
+
+#[async_trait(?Send)]
+impl Researcher for Trail {
+    async fn research(&self, question: &str) -> Result<String> {
+        self.run(question).await
+    }
+}
+
+fn without_fragment(mut url: Url) -> String {
+    url.set_fragment(None);
+    url.to_string()
+}
+
+pub fn candidate_links(base: &str, links: &[String], visited: &HashSet<String>) -> Vec<String> {
+    let Ok(base) = Url::parse(base) else {
+        return Vec::new();
+    };
+    let base_str = without_fragment(base.clone());
+
+    let mut candidates: Vec<String> = Vec::new();
+    for link in links {
+        let Ok(url) = base.join(link.trim()) else {
+            continue;
+        };
+        if !["http", "https"].contains(&url.scheme()) {
+            continue;
+        }
+        let path = url.path().to_lowercase();
+        if SKIPPED_EXTENSIONS.iter().any(|ext| path.ends_with(ext)) {
+            continue;
+        }
+        let url = without_fragment(url);
+        if url != base_str && !visited.contains(&url) && !candidates.contains(&url) {
+            candidates.push(url);
+        }
+        if candidates.len() >= MAX_CANDIDATE_LINKS {
+            break;
+        }
+    }
+    candidates
+}
+
+fn link_prompt(question: &str, url: &str, page: &FetchResponse, candidates: &[String]) -> String {
+    let mut prompt = format!(
+        "You are following links from a web page to answer this question: {question}\n\n\
+         Current page: {} ({url})\n{}\n\n\
+         Which of these links most likely lead to pages that help answer the question? \
+         Reply with only a JSON array of at most {LINKS_PER_PAGE} link numbers, most relevant \
+         first, or [] if none are worth following.\n\n",
+        sanitize(&page.title),
+        sanitize(truncate_at_sentence(&page.content, PREVIEW_BYTES)),
+    );
+    for (idx, link) in candidates.iter().enumerate() {
+        prompt.push_str(&format!("{}. {}\n", idx + 1, link));
+    }
+    prompt
+}
+
+pub fn parse_choices(content: &str, candidates: usize) -> Vec<usize> {
+    let items = content
+        .rfind('[')
+        .zip(content.rfind(']'))
+        .filter(|(start, end)| start < end)
+        .and_then(|(start, end)| serde_json::from_str::<Vec<Value>>(&content[start..=end]).ok())
+        .unwrap_or_default();
+
+    let mut chosen: Vec<usize> = Vec::new();
+    for item in items {
+        let number = match &item {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        };
+        if let Some(idx) = number
+            .filter(|n| (1..=candidates as u64).contains(n))
+            .map(|n| n as usize - 1)
+        {
+            if !chosen.contains(&idx) {
+                chosen.push(idx);
+            }
+        }
+    }
+    chosen.truncate(LINKS_PER_PAGE);
+    chosen
+}
+
+pub fn answer_prompt(question: &str, pages: &[Page]) -> String {
+    let mut prompt = format!(
+        "Answer the question using only the pages below, which were reached by following \
+         links from a starting page. Cite pages as [n] and end with a list of the cited URLs. \
+         If the pages do not contain the answer, say so plainly instead of guessing.\n\n\
+         Question: {question}\n"
+    );
+    for (idx, page) in pages.iter().enumerate() {
+        prompt.push_str(&format!(
+            "\n[{}] {} ({})\n{}\n",
+            idx + 1,
+            sanitize(&page.title),
+            page.url,
+            sanitize(truncate_at_sentence(&page.content, PAGE_BYTES))
+        ));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_links_resolve_and_skip_visited() {
+        let links = [
+            "/book/ch01.html".to_string(),
+            "ch02.html#intro".to_string(),
+            "https://docs.rs/tokio".to_string(),
+            "mailto:team@example.com".to_string(),
+            "/static/logo.png".to_string(),
+            "https://example.com/book/index.html#top".to_string(),
+            "/book/ch01.html".to_string(),
+            "/book/seen.html".to_string(),
+        ];
+        let visited = HashSet::from(["https://example.com/book/seen.html".to_string()]);
+        assert_eq!(
+            candidate_links("https://example.com/book/index.html", &links, &visited),
+            [
+                "https://example.com/book/ch01.html",
+                "https://example.com/book/ch02.html",
+                "https://docs.rs/tokio",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parses_link_choices() {
+        assert_eq!(
+            parse_choices("Follow these: [3, \"1\", 3, 9, 2, 4]", 5),
+            [2, 0, 1]
+        );
+        assert!(parse_choices("[]", 5).is_empty());
+        assert!(parse_choices("none look useful", 5).is_empty());
+    }
+
+    #[test]
+    fn test_answer_prompt_numbers_pages() {
+        let pages = [
+            Page {
+                url: "https://example.com/".to_string(),
+                title: "Home".to_string(),
+                content: "Welcome.".to_string(),
+            },
+            Page {
+                url: "https://example.com/pricing".to_string(),
+                title: "Pricing".to_string(),
+                content: "Pro costs $10.".to_string(),
+            },
+        ];
+        let prompt = answer_prompt("How much is Pro?", &pages);
+        assert!(prompt.contains("Question: How much is Pro?\n"));
+        assert!(prompt.contains("\n[2] Pricing (https://example.com/pricing)\nPro costs $10.\n"));
+    }
+}
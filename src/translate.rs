@@ -0,0 +1,175 @@
+use crate::error::Result;
+use crate::ollama_local::ChatBackend;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+const SAMPLE_WORDS: usize = 200;
+const MIN_ENGLISH_RATIO: f64 = 0.12;
+const ENGLISH_STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "have", "in", "is",
+    "it", "not", "of", "on", "or", "that", "the", "this", "to", "was", "were", "which", "with",
+];
+const NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("hu", "Hungarian"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ro", "Romanian"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnswerLanguage {
+    code: String,
+}
+
+impl AnswerLanguage {
+    pub fn name(&self) -> &str {
+        let primary = self.code.split('-').next().unwrap_or_default();
+        NAMES
+            .iter()
+            .find(|(code, _)| *code == primary)
+            .map(|(_, name)| *name)
+            .unwrap_or(&self.code)
+    }
+
+    pub fn is_english(&self) -> bool {
+        self.name() == "English"
+    }
+
+    pub fn instruction(&self) -> String {
+        format!(
+            "Write your final answer in {name} ({code}), even when the question or the sources \
+             are in another language. Keep URLs, code, product names, and quotations in their \
+             original form, and search in whatever language finds the best sources.",
+            name = self.name(),
+            code = self.code
+        )
+    }
+}
+
+impl FromStr for AnswerLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let code = s.trim();
+        let mut parts = code.split('-');
+        let primary = parts.next().unwrap_or_default();
+        let valid = (2..=3).contains(&primary.len())
+            && primary.chars().all(|c| c.is_ascii_alphabetic())
+            && parts.all(|part| {
+                (2..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+            });
+        if !valid {
+            return Err(format!(
+                "'{}' is not a language code (use e.g. de, pt-BR, or zh-Hant)",
+                s
+            ));
+        }
+        Ok(Self {
+            code: code.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for AnswerLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.code)
+    }
+}
+
+pub fn looks_english(text: &str) -> bool {
+    let stopwords: HashSet<&str> = ENGLISH_STOPWORDS.iter().copied().collect();
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .filter(|word| !word.is_empty())
+        .take(SAMPLE_WORDS)
+        .map(str::to_lowercase)
+        .collect();
+    if words.is_empty() {
+        return false;
+    }
+    let hits = words
+        .iter()
+        .filter(|word| stopwords.contains(word.as_str()))
+        .count();
+    hits as f64 / words.len() as f64 >= MIN_ENGLISH_RATIO
+}
+
+pub async fn translate(
+    chat: &dyn ChatBackend,
+    model: &str,
+    language: &AnswerLanguage,
+    text: &str,
+) -> Result<String> {
+    let prompt = format!(
+        "Translate the following text from English into {}. Keep markdown formatting, URLs, \
+         code, numbers, and names unchanged. Reply with the translation only.\n\n{}",
+        language.name(),
+        text
+    );
+    let response = chat
+        .chat(
+            model,
+            vec![json!({"role": "user", "content": prompt})],
+            None,
+            false,
+        )
+        .await?;
+    Ok(response.message.content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_codes_and_names_languages() {
+        let german: AnswerLanguage = "de".parse().unwrap();
+        assert_eq!(german.name(), "German");
+        assert!(german.instruction().contains("German (de)"));
+        assert!(!german.is_english());
+
+        let brazilian: AnswerLanguage = "pt-BR".parse().unwrap();
+        assert_eq!(brazilian.name(), "Portuguese");
+        assert_eq!("tlh".parse::<AnswerLanguage>().unwrap().name(), "tlh");
+        assert!("en".parse::<AnswerLanguage>().unwrap().is_english());
+        assert!("German".parse::<AnswerLanguage>().is_err());
+        assert!("de_DE".parse::<AnswerLanguage>().is_err());
+    }
+
+    #[test]
+    fn test_detects_english_text() {
+        assert!(looks_english(
+            "Tokio is an asynchronous runtime for the Rust programming language. It provides the building blocks."
+        ));
+        assert!(!looks_english(
+            "Tokio ist eine asynchrone Laufzeitumgebung für die Programmiersprache Rust."
+        ));
+        assert!(!looks_english(""));
+    }
+}
@@ -0,0 +1,246 @@
+use crate::error::{OllamaError, Result};
+use crate::settings::{BudgetSettings, OnExceeded};
+use crate::store::Store;
+use chrono::{Local, NaiveDate};
+use rusqlite::params;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use tracing::{debug, warn};
+
+static GUARD: OnceLock<UsageGuard> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    WebCalls,
+    ModelTokens,
+    CacheHits,
+    CacheMisses,
+    AgentRuns,
+    AgentIterations,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Totals {
+    pub today: u64,
+    pub month: u64,
+}
+
+pub struct UsageGuard {
+    store: Mutex<Store>,
+    budget: BudgetSettings,
+}
+
+impl Kind {
+    pub const BUDGETED: [Kind; 2] = [Kind::WebCalls, Kind::ModelTokens];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Kind::WebCalls => "web_calls",
+            Kind::ModelTokens => "model_tokens",
+            Kind::CacheHits => "cache_hits",
+            Kind::CacheMisses => "cache_misses",
+            Kind::AgentRuns => "agent_runs",
+            Kind::AgentIterations => "agent_iterations",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Kind::WebCalls => "web API calls",
+            Kind::ModelTokens => "model tokens",
+            Kind::CacheHits => "cache hits",
+            Kind::CacheMisses => "cache misses",
+            Kind::AgentRuns => "agent runs",
+            Kind::AgentIterations => "agent iterations",
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+pub fn enable(store: Store, budget: BudgetSettings) {
+    let guard = UsageGuard {
+        store: Mutex::new(store),
+        budget,
+    };
+    if GUARD.set(guard).is_err() {
+        warn!("Usage tracking was already enabled");
+    }
+}
+
+pub fn check(kind: Kind) -> Result<()> {
+    match GUARD.get() {
+        Some(guard) => guard.check(kind),
+        None => Ok(()),
+    }
+}
+
+pub fn add(kind: Kind, amount: u64) {
+    if let Some(guard) = GUARD.get() {
+        guard.add(kind, amount);
+    }
+}
+
+pub fn cache_lookup(hit: bool) {
+    add(
+        if hit {
+            Kind::CacheHits
+        } else {
+            Kind::CacheMisses
+        },
+        1,
+    );
+}
+
+pub fn agent_run(iterations: usize) {
+    add(Kind::AgentRuns, 1);
+    add(Kind::AgentIterations, iterations as u64);
+}
+
+pub fn degrades() -> bool {
+    GUARD
+        .get()
+        .is_some_and(|guard| guard.budget.on_exceeded == OnExceeded::Offline)
+}
+
+impl UsageGuard {
+    fn check(&self, kind: Kind) -> Result<()> {
+        let store = self.lock();
+        check_limits(&store, &self.budget, kind, today())
+    }
+
+    fn add(&self, kind: Kind, amount: u64) {
+        let store = self.lock();
+        if let Err(e) = record(&store, kind, amount, today()) {
+            warn!("Failed to record {} usage: {}", kind, e);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Store> {
+        self.store.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+pub fn record(store: &Store, kind: Kind, amount: u64, day: NaiveDate) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    store.conn().execute(
+        "INSERT INTO usage (day, kind, amount) VALUES (?1, ?2, ?3)
+         ON CONFLICT(day, kind) DO UPDATE SET amount = amount + excluded.amount",
+        params![day.format("%Y-%m-%d").to_string(), kind.as_str(), amount],
+    )?;
+    Ok(())
+}
+
+pub fn totals(store: &Store, kind: Kind, day: NaiveDate) -> Result<Totals> {
+    let (today, month): (Option<i64>, Option<i64>) = store.conn().query_row(
+        "SELECT SUM(CASE WHEN day = ?1 THEN amount END), SUM(amount)
+         FROM usage WHERE kind = ?2 AND substr(day, 1, 7) = ?3",
+        params![
+            day.format("%Y-%m-%d").to_string(),
+            kind.as_str(),
+            day.format("%Y-%m").to_string()
+        ],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok(Totals {
+        today: today.unwrap_or(0) as u64,
+        month: month.unwrap_or(0) as u64,
+    })
+}
+
+pub fn check_limits(
+    store: &Store,
+    budget: &BudgetSettings,
+    kind: Kind,
+    day: NaiveDate,
+) -> Result<()> {
+    let (daily, monthly) = budget.limits(kind);
+    if daily.is_none() && monthly.is_none() {
+        return Ok(());
+    }
+
+    let totals = totals(store, kind, day)?;
+    for (period, used, limit) in [
+        ("today", totals.today, daily),
+        ("this month", totals.month, monthly),
+    ] {
+        if let Some(limit) = limit.filter(|limit| used >= *limit) {
+            debug!("Budget for {} exhausted: {} of {}", kind, used, limit);
+            return Err(OllamaError::BudgetExceeded {
+                kind: kind.to_string(),
+                period: period.to_string(),
+                used,
+                limit,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_totals_by_day_and_month() {
+        let store = Store::open_in_memory().unwrap();
+        record(&store, Kind::WebCalls, 3, day("2026-09-30")).unwrap();
+        record(&store, Kind::WebCalls, 2, day("2026-10-01")).unwrap();
+        record(&store, Kind::WebCalls, 1, day("2026-10-02")).unwrap();
+        record(&store, Kind::WebCalls, 1, day("2026-10-02")).unwrap();
+        record(&store, Kind::ModelTokens, 900, day("2026-10-02")).unwrap();
+
+        assert_eq!(
+            totals(&store, Kind::WebCalls, day("2026-10-02")).unwrap(),
+            Totals { today: 2, month: 4 }
+        );
+        assert_eq!(
+            totals(&store, Kind::ModelTokens, day("2026-10-03")).unwrap(),
+            Totals {
+                today: 0,
+                month: 900
+            }
+        );
+    }
+
+    #[test]
+    fn test_check_limits_reports_the_exhausted_period() {
+        let store = Store::open_in_memory().unwrap();
+        let budget = BudgetSettings {
+            daily_web_calls: Some(5),
+            monthly_web_calls: Some(8),
+            ..Default::default()
+        };
+        let today = day("2026-10-15");
+        assert!(check_limits(&store, &budget, Kind::WebCalls, today).is_ok());
+
+        record(&store, Kind::WebCalls, 5, today).unwrap();
+        let err = check_limits(&store, &budget, Kind::WebCalls, today).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Usage budget exceeded: 5 of 5 web API calls used today"
+        );
+
+        record(&store, Kind::WebCalls, 3, day("2026-10-14")).unwrap();
+        let tomorrow = day("2026-10-16");
+        let err = check_limits(&store, &budget, Kind::WebCalls, tomorrow).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("8 of 8 web API calls used this month"));
+        assert!(check_limits(&store, &budget, Kind::ModelTokens, tomorrow).is_ok());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::error::{OllamaError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing::{debug, warn};
+use url::Url;
+
+static CASSETTE: OnceLock<Cassette> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Record,
+    Replay,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    path: String,
+    request: Value,
+    status: u16,
+    body: String,
+}
+
+pub struct Cassette {
+    mode: Mode,
+    path: PathBuf,
+    recorded: Mutex<HashMap<String, VecDeque<Interaction>>>,
+}
+
+impl Mode {
+    pub fn parse(value: &str) -> Result<Option<Self>> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "off" => Ok(None),
+            "record" => Ok(Some(Mode::Record)),
+            "replay" => Ok(Some(Mode::Replay)),
+            other => Err(OllamaError::ConfigError(format!(
+                "Unknown WEAVEX_VCR mode '{}'; expected record, replay, or off",
+                other
+            ))),
+        }
+    }
+}
+
+pub fn enable(mode: Mode, path: &Path) -> Result<()> {
+    let cassette = Cassette::open(mode, path)?;
+    if CASSETTE.set(cassette).is_err() {
+        warn!("HTTP cassette was already enabled");
+    }
+    Ok(())
+}
+
+pub fn replaying() -> bool {
+    CASSETTE
+        .get()
+        .is_some_and(|cassette| cassette.mode == Mode::Replay)
+}
+
+pub async fn exchange<T, F, Fut>(url: &str, request: &T, send: F) -> Result<String>
+where
+    T: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    match CASSETTE.get() {
+        Some(cassette) => cassette.exchange(url, request, send).await,
+        None => send().await,
+    }
+}
+
+impl Cassette {
+    pub fn open(mode: Mode, path: &Path) -> Result<Self> {
+        let mut recorded: HashMap<String, VecDeque<Interaction>> = HashMap::new();
+        match mode {
+            Mode::Replay => {
+                let contents = fs::read_to_string(path).map_err(|e| {
+                    OllamaError::ConfigError(format!(
+                        "Failed to read cassette {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+                    let interaction: Interaction = serde_json::from_str(line)?;
+                    recorded
+                        .entry(key(&interaction.path, &interaction.request))
+                        .or_default()
+                        .push_back(interaction);
+                }
+            }
+            Mode::Record => {
+                if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, "")?;
+            }
+        }
+
+        Ok(Self {
+            mode,
+            path: path.to_path_buf(),
+            recorded: Mutex::new(recorded),
+        })
+    }
+
+    pub async fn exchange<T, F, Fut>(&self, url: &str, request: &T, send: F) -> Result<String>
+    where
+        T: Serialize,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let path = request_path(url);
+        let request = serde_json::to_value(request)?;
+        match self.mode {
+            Mode::Replay => self.replay(&path, &request),
+            Mode::Record => {
+                let result = send().await;
+                let (status, body) = match &result {
+                    Ok(body) => (200, body.clone()),
+                    Err(OllamaError::ApiError { status, message }) => (*status, message.clone()),
+                    Err(_) => return result,
+                };
+                self.record(Interaction {
+                    path,
+                    request,
+                    status,
+                    body,
+                })?;
+                result
+            }
+        }
+    }
+
+    fn replay(&self, path: &str, request: &Value) -> Result<String> {
+        let mut recorded = self.recorded.lock().unwrap_or_else(|e| e.into_inner());
+        let queue = recorded
+            .get_mut(&key(path, request))
+            .filter(|queue| !queue.is_empty())
+            .ok_or_else(|| {
+                OllamaError::ConfigError(format!(
+                    "No recorded response for POST {} in cassette {}",
+                    path,
+                    self.path.display()
+                ))
+            })?;
+        let interaction = if queue.len() > 1 {
+            queue.pop_front().unwrap_or_else(|| unreachable!())
+        } else {
+            queue[0].clone()
+        };
+
+        debug!("Replaying {} ({})", path, interaction.status);
+        if (200..300).contains(&interaction.status) {
+            Ok(interaction.body)
+        } else {
+            Err(OllamaError::ApiError {
+                status: interaction.status,
+                message: interaction.body,
+            })
+        }
+    }
+
+    fn record(&self, interaction: Interaction) -> Result<()> {
+        let _guard = self.recorded.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&interaction)?)?;
+        Ok(())
+    }
+}
+
+fn request_path(url: &str) -> String {
+    Url::parse(url)
+        .map(|url| url.path().to_string())
+        .unwrap_or_else(|_| url.to_string())
+}
+
+fn key(path: &str, request: &Value) -> String {
+    format!("{} {}", path, request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tokio::runtime::Runtime;
+
+    #[test]
+    fn test_replays_recorded_exchanges_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes/agent.jsonl");
+        let rt = Runtime::new().unwrap();
+
+        let recorder = Cassette::open(Mode::Record, &path).unwrap();
+        rt.block_on(async {
+            for body in ["first", "second"] {
+                recorder
+                    .exchange(
+                        "http://localhost:11434/api/chat",
+                        &json!({"q": 1}),
+                        || async { Ok(body.to_string()) },
+                    )
+                    .await
+                    .unwrap();
+            }
+            let missing = recorder
+                .exchange(
+                    "https://ollama.com/api/web_fetch",
+                    &json!({"url": "x"}),
+                    || async {
+                        Err(OllamaError::ApiError {
+                            status: 404,
+                            message: "not found".to_string(),
+                        })
+                    },
+                )
+                .await;
+            assert!(missing.is_err());
+        });
+
+        let player = Cassette::open(Mode::Replay, &path).unwrap();
+        let replay = |url: &'static str, request: Value| {
+            rt.block_on(player.exchange(url, &request, || async {
+                panic!("replay must not send requests")
+            }))
+        };
+        let chat = "http://127.0.0.1:9999/api/chat";
+        assert_eq!(replay(chat, json!({"q": 1})).unwrap(), "first");
+        assert_eq!(replay(chat, json!({"q": 1})).unwrap(), "second");
+        assert_eq!(replay(chat, json!({"q": 1})).unwrap(), "second");
+
+        let err = replay("https://ollama.com/api/web_fetch", json!({"url": "x"})).unwrap_err();
+        assert_eq!(err.to_string(), "API returned error: 404 - not found");
+        let err = replay(chat, json!({"q": 2})).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("No recorded response for POST /api/chat"));
+    }
+
+    #[test]
+    fn test_parses_modes() {
+        assert_eq!(Mode::parse("Replay").unwrap(), Some(Mode::Replay));
+        assert_eq!(Mode::parse("record").unwrap(), Some(Mode::Record));
+        assert_eq!(Mode::parse("").unwrap(), None);
+        assert!(Mode::parse("rewind").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{debug, instrument};
+use url::Url;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+pub const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+const DEFAULT_PROMPT: &str = "Describe this image for a researcher. Transcribe any visible text, \
+    and for charts or tables report the axes, labels, units, and the key values and trends.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub format: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+pub struct VisionClient {
+    client: Client,
+}
+
+impl VisionClient {
+    pub fn new() -> Result<Self> {
+        let client = network::client_builder()
+            .timeout(TIMEOUT)
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self { client })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn download(&self, url: &str) -> Result<Image> {
+        let parsed = Url::parse(url)
+            .map_err(|e| OllamaError::InvalidUrl(format!("Invalid URL '{}': {}", url, e)))?;
+        if !["http", "https"].contains(&parsed.scheme()) {
+            return Err(OllamaError::InvalidUrl(format!(
+                "Only HTTP and HTTPS image URLs are supported: {}",
+                url
+            )));
+        }
+
+        debug!("Downloading image {}", url);
+        let mut response = self
+            .client
+            .get(parsed)
+            .send_audited()
+            .await?
+            .error_for_status()?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if response
+            .content_length()
+            .is_some_and(|len| len as usize > MAX_IMAGE_BYTES)
+        {
+            return Err(too_large());
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            bytes.extend_from_slice(&chunk);
+            if bytes.len() > MAX_IMAGE_BYTES {
+                return Err(too_large());
+            }
+        }
+
+        match sniff_format(&bytes) {
+            Some(format) => Ok(Image { format, bytes }),
+            None if content_type.contains("svg") => Err(OllamaError::InvalidResponse(
+                "SVG images are not supported; find a PNG or JPEG version".to_string(),
+            )),
+            None => Err(OllamaError::InvalidResponse(format!(
+                "Not a supported image (content type '{}'); expected PNG, JPEG, GIF, or WebP",
+                content_type
+            ))),
+        }
+    }
+}
+
+fn too_large() -> OllamaError {
+    OllamaError::InvalidResponse(format!(
+        "Image is larger than {} MB",
+        MAX_IMAGE_BYTES / (1024 * 1024)
+    ))
+}
+
+pub fn sniff_format(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => Some("png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("jpeg"),
+        [b'G', b'I', b'F', b'8', ..] => Some("gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("webp"),
+        _ => None,
+    }
+}
+
+pub fn message(image: &Image, question: Option<&str>) -> Value {
+    let prompt = question
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+        .unwrap_or(DEFAULT_PROMPT);
+    json!({
+        "role": "user",
+        "content": prompt,
+        "images": [STANDARD.encode(&image.bytes)],
+    })
+}
+
+pub fn format_description(url: &str, model: &str, image: &Image, description: &str) -> String {
+    format!(
+        "Image: {}\nFormat: {} ({} KB)\nDescribed by: {}\n\n{}",
+        url,
+        image.format,
+        image.bytes.len().div_ceil(1024),
+        model,
+        description.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniffs_supported_formats() {
+        assert_eq!(sniff_format(b"\x89PNG\r\n\x1a\n...."), Some("png"));
+        assert_eq!(sniff_format(&[0xFF, 0xD8, 0xFF, 0xE0]), Some("jpeg"));
+        assert_eq!(sniff_format(b"GIF89a"), Some("gif"));
+        assert_eq!(sniff_format(b"RIFF\x10\0\0\0WEBPVP8 "), Some("webp"));
+        assert_eq!(sniff_format(b"<svg xmlns="), None);
+        assert_eq!(sniff_format(b""), None);
+    }
+
+    #[test]
+    fn test_builds_multimodal_message() {
+        let image = Image {
+            format: "png",
+            bytes: b"\x89PNG".to_vec(),
+        };
+        let with_question = message(&image, Some("What is the peak value?"));
+        assert_eq!(with_question["content"], "What is the peak value?");
+        assert_eq!(with_question["images"][0], "iVBORw==");
+
+        assert!(message(&image, Some("  "))
+            .to_string()
+            .contains("axes, labels"));
+        assert_eq!(
+            format_description("https://a.dev/c.png", "llava", &image, " A bar chart.\n"),
+            "Image: https://a.dev/c.png\nFormat: png (1 KB)\nDescribed by: llava\n\nA bar chart."
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use crate::agent::Agent;
+use crate::client::{SearchResponse, SearchResult};
+use crate::dedup;
+use crate::diff;
+use crate::error::Result;
+use crate::formatter::{Markdown, Renderer};
+use crate::glyphs::Glyph;
+use crate::provider::SearchProvider;
+use crate::store::{self, Store};
+use crate::terminal;
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub enum Runner {
+    Search(Box<dyn SearchProvider>),
+    Agent(Box<Agent>),
+}
+
+impl Runner {
+    fn mode(&self) -> &'static str {
+        match self {
+            Runner::Search(_) => "search",
+            Runner::Agent(_) => "agent",
+        }
+    }
+
+    async fn run(&self, query: &str) -> Result<String> {
+        match self {
+            Runner::Search(search) => {
+                let response = search.search(query, None).await?;
+                Ok(serde_json::to_string(&response.results)?)
+            }
+            Runner::Agent(agent) => agent.run(query).await,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub content: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone)]
+pub enum Change {
+    New(SearchResult),
+    Updated {
+        result: SearchResult,
+        previous: SearchResult,
+    },
+}
+
+pub struct Report {
+    pub mode: &'static str,
+    pub previous: Option<Snapshot>,
+    pub current: String,
+}
+
+impl Report {
+    pub fn result_changes(&self) -> Result<Vec<Change>> {
+        let Some(previous) = &self.previous else {
+            return Ok(Vec::new());
+        };
+        let previous: Vec<SearchResult> = serde_json::from_str(&previous.content)?;
+        let current: Vec<SearchResult> = serde_json::from_str(&self.current)?;
+        Ok(diff_results(&previous, &current))
+    }
+
+    pub fn has_changes(&self) -> Result<bool> {
+        match (self.mode, &self.previous) {
+            (_, None) => Ok(false),
+            ("search", Some(_)) => Ok(!self.result_changes()?.is_empty()),
+            (_, Some(previous)) => Ok(previous.content.trim() != self.current.trim()),
+        }
+    }
+
+    pub fn render(&self, color: bool) -> Result<String> {
+        let Some(previous) = &self.previous else {
+            return Ok(match self.mode {
+                "search" => {
+                    let results: Vec<SearchResult> = serde_json::from_str(&self.current)?;
+                    format!(
+                        "Recorded a baseline of {} result(s); future runs report only new or changed results.",
+                        results.len()
+                    )
+                }
+                _ => format!(
+                    "Recorded a baseline answer; future runs report only changes.\n\n{}",
+                    self.current
+                ),
+            });
+        };
+
+        if !self.has_changes()? {
+            return Ok(format!(
+                "No changes since {}.",
+                store::format_timestamp(previous.created_at)
+            ));
+        }
+
+        if self.mode == "search" {
+            return Ok(format_changes(&self.result_changes()?, color));
+        }
+
+        let hunks = diff::changed_hunks(&previous.content, &self.current, color);
+        Ok(format!(
+            "{}The answer changed since {}:\n\n{}",
+            Glyph::Changed,
+            store::format_timestamp(previous.created_at),
+            hunks.join("\n\n⋯\n\n")
+        ))
+    }
+
+    pub fn html(&self, query: &str) -> Result<Option<String>> {
+        let Some(previous) = &self.previous else {
+            return Ok(None);
+        };
+
+        let (old, new) = match self.mode {
+            "search" => (
+                results_text(&previous.content)?,
+                results_text(&self.current)?,
+            ),
+            _ => (previous.content.clone(), self.current.clone()),
+        };
+
+        Ok(Some(diff::side_by_side_html(
+            &format!("Changes for: {}", query),
+            &store::format_timestamp(previous.created_at),
+            &store::format_timestamp(store::now()),
+            &old,
+            &new,
+        )))
+    }
+}
+
+pub async fn check(store: &Store, runner: &Runner, query: &str) -> Result<Report> {
+    let previous = latest(store, query, runner.mode())?;
+    let current = runner.run(query).await?;
+    save(store, query, runner.mode(), &current)?;
+
+    Ok(Report {
+        mode: runner.mode(),
+        previous,
+        current,
+    })
+}
+
+fn latest(store: &Store, query: &str, mode: &str) -> Result<Option<Snapshot>> {
+    let snapshot = store
+        .conn()
+        .query_row(
+            "SELECT content, created_at FROM watch_snapshots
+             WHERE query = ?1 AND mode = ?2 ORDER BY id DESC LIMIT 1",
+            params![query, mode],
+            |row| {
+                Ok(Snapshot {
+                    content: row.get(0)?,
+                    created_at: row.get(1)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(snapshot)
+}
+
+fn save(store: &Store, query: &str, mode: &str, content: &str) -> Result<()> {
+    store.conn().execute(
+        "INSERT INTO watch_snapshots (query, mode, content, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![query, mode, content, store::now()],
+    )?;
+    Ok(())
+}
+
+pub fn diff_results(previous: &[SearchResult], current: &[SearchResult]) -> Vec<Change> {
+    let previous: HashMap<String, &SearchResult> = previous
+        .iter()
+        .map(|result| (dedup::key(&result.url), result))
+        .collect();
+
+    current
+        .iter()
+        .filter_map(|result| match previous.get(&dedup::key(&result.url)) {
+            None => Some(Change::New(result.clone())),
+            Some(old) if old.title != result.title || old.content != result.content => {
+                Some(Change::Updated {
+                    result: result.clone(),
+                    previous: (*old).clone(),
+                })
+            }
+            Some(_) => None,
+        })
+        .collect()
+}
+
+fn format_changes(changes: &[Change], color: bool) -> String {
+    let link = |result: &SearchResult| {
+        if color {
+            terminal::hyperlink(&result.url, &result.url)
+        } else {
+            result.url.clone()
+        }
+    };
+
+    let mut output = String::new();
+    for change in changes {
+        match change {
+            Change::New(result) => output.push_str(&format!(
+                "🆕 {}\n   {}\n   {}\n\n",
+                result.title,
+                link(result),
+                result.content.trim()
+            )),
+            Change::Updated { result, previous } => output.push_str(&format!(
+                "{}{}\n   {}\n   {}\n\n",
+                Glyph::Changed,
+                diff::word_diff(&previous.title, &result.title, color),
+                link(result),
+                diff::word_diff(previous.content.trim(), result.content.trim(), color)
+            )),
+        }
+    }
+    output.trim_end().to_string()
+}
+
+fn results_text(content: &str) -> Result<String> {
+    let results: Vec<SearchResult> = serde_json::from_str(content)?;
+    Ok(Markdown.search_results(&SearchResponse { results }))
+}
+
+pub fn parse_interval(value: &str) -> std::result::Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("invalid interval '{}': expected e.g. 30m, 6h, 1d", value))?;
+    let seconds = match unit {
+        "s" => amount,
+        "m" | "" => amount * 60,
+        "h" => amount * 60 * 60,
+        "d" => amount * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid interval unit '{}': use s, m, h, or d",
+                unit
+            ))
+        }
+    };
+
+    if seconds == 0 {
+        return Err("interval must be greater than zero".to_string());
+    }
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(url: &str, content: &str) -> SearchResult {
+        SearchResult {
+            title: "Result".to_string(),
+            url: url.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("45s"), Ok(Duration::from_secs(45)));
+        assert_eq!(parse_interval("30m"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse_interval("6h"), Ok(Duration::from_secs(21600)));
+        assert_eq!(parse_interval("1d"), Ok(Duration::from_secs(86400)));
+        assert!(parse_interval("0h").is_err());
+        assert!(parse_interval("6w").is_err());
+        assert!(parse_interval("h").is_err());
+    }
+
+    #[test]
+    fn test_diff_results_reports_new_and_updated_only() {
+        let previous = vec![
+            result("https://a.com/", "same"),
+            result("https://b.com/", "old snippet"),
+            result("https://gone.com/", "dropped"),
+        ];
+        let current = vec![
+            result("https://www.a.com", "same"),
+            result("https://b.com/", "new snippet"),
+            result("https://c.com/", "fresh"),
+        ];
+
+        let changes = diff_results(&previous, &current);
+        assert_eq!(changes.len(), 2);
+        assert!(
+            matches!(&changes[0], Change::Updated { previous, .. } if previous.content == "old snippet")
+        );
+        assert!(matches!(&changes[1], Change::New(r) if r.url == "https://c.com/"));
+    }
+
+    #[test]
+    fn test_agent_report_without_changes() {
+        let report = Report {
+            mode: "agent",
+            previous: Some(Snapshot {
+                content: "Answer.\n".to_string(),
+                created_at: 0,
+            }),
+            current: "Answer.".to_string(),
+        };
+        assert!(!report.has_changes().unwrap());
+        assert!(report.render(false).unwrap().starts_with("No changes"));
+    }
+}
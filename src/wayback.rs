@@ -0,0 +1,96 @@
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+const AVAILABILITY_API: &str = "https://archive.org/wayback/available";
+
+#[derive(Debug, Deserialize)]
+struct Availability {
+    #[serde(default)]
+    archived_snapshots: Snapshots,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Snapshots {
+    closest: Option<Closest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Closest {
+    #[serde(default)]
+    available: bool,
+    url: String,
+    timestamp: String,
+    #[serde(default)]
+    status: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub url: String,
+    pub timestamp: String,
+}
+
+impl Snapshot {
+    pub fn raw_url(&self) -> String {
+        let marker = format!("/web/{}/", self.timestamp);
+        self.url
+            .replacen(&marker, &format!("/web/{}id_/", self.timestamp), 1)
+            .replacen("http://", "https://", 1)
+    }
+
+    pub fn date(&self) -> String {
+        NaiveDateTime::parse_from_str(&self.timestamp, "%Y%m%d%H%M%S")
+            .map(|at| at.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|_| self.timestamp.clone())
+    }
+}
+
+pub fn availability_url(url: &str) -> String {
+    let mut api = url::Url::parse(AVAILABILITY_API).unwrap();
+    api.query_pairs_mut().append_pair("url", url);
+    api.to_string()
+}
+
+pub fn parse_snapshot(body: &str) -> Option<Snapshot> {
+    let availability: Availability = serde_json::from_str(body).ok()?;
+    let closest = availability.archived_snapshots.closest?;
+    if !closest.available || !(closest.status.is_empty() || closest.status.starts_with('2')) {
+        return None;
+    }
+    Some(Snapshot {
+        url: closest.url,
+        timestamp: closest.timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_snapshot() {
+        let body = r#"{"url": "example.com/gone", "archived_snapshots": {"closest": {
+            "status": "200", "available": true,
+            "url": "http://web.archive.org/web/20240315120000/https://example.com/gone",
+            "timestamp": "20240315120000"}}}"#;
+        let snapshot = parse_snapshot(body).unwrap();
+        assert_eq!(snapshot.date(), "2024-03-15");
+        assert_eq!(
+            snapshot.raw_url(),
+            "https://web.archive.org/web/20240315120000id_/https://example.com/gone"
+        );
+
+        assert_eq!(
+            parse_snapshot(r#"{"url": "x", "archived_snapshots": {}}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_availability_url_encodes_target() {
+        assert_eq!(
+            availability_url("https://example.com/a?b=c&d"),
+            "https://archive.org/wayback/available?url=https%3A%2F%2Fexample.com%2Fa%3Fb%3Dc%26d"
+        );
+    }
+}
@@ -0,0 +1,364 @@
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+const USER_AGENT: &str = concat!(
+    "weavex/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/guitaripod/weavex)"
+);
+const NOMINATIM_URL: &str = "https://nominatim.openstreetmap.org/search";
+const OPEN_METEO_URL: &str = "https://api.open-meteo.com/v1/forecast";
+const CURRENT_FIELDS: &str = "temperature_2m,apparent_temperature,relative_humidity_2m,\
+                              precipitation,weather_code,wind_speed_10m,wind_direction_10m";
+const DAILY_FIELDS: &str = "weather_code,temperature_2m_max,temperature_2m_min,\
+                            precipitation_sum,precipitation_probability_max";
+pub const MAX_FORECAST_DAYS: u8 = 7;
+pub const MAX_PLACES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Place {
+    #[serde(rename = "display_name")]
+    pub name: String,
+    #[serde(deserialize_with = "coordinate")]
+    pub lat: f64,
+    #[serde(deserialize_with = "coordinate")]
+    pub lon: f64,
+    #[serde(default, rename = "addresstype")]
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Forecast {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub timezone: String,
+    #[serde(default)]
+    current_units: HashMap<String, String>,
+    pub current: Option<Current>,
+    #[serde(default)]
+    daily_units: HashMap<String, String>,
+    pub daily: Option<Daily>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Current {
+    pub time: String,
+    pub temperature_2m: f64,
+    pub apparent_temperature: Option<f64>,
+    pub relative_humidity_2m: Option<f64>,
+    pub precipitation: Option<f64>,
+    pub weather_code: Option<u8>,
+    pub wind_speed_10m: Option<f64>,
+    pub wind_direction_10m: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Daily {
+    pub time: Vec<String>,
+    pub weather_code: Vec<Option<u8>>,
+    pub temperature_2m_max: Vec<Option<f64>>,
+    pub temperature_2m_min: Vec<Option<f64>>,
+    pub precipitation_sum: Vec<Option<f64>>,
+    #[serde(default)]
+    pub precipitation_probability_max: Vec<Option<f64>>,
+}
+
+fn coordinate<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<f64, D::Error> {
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+impl Units {
+    pub fn parse(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "imperial" | "us" | "fahrenheit" => Units::Imperial,
+            _ => Units::Metric,
+        }
+    }
+
+    fn query(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Units::Metric => &[],
+            Units::Imperial => &[
+                ("temperature_unit", "fahrenheit"),
+                ("wind_speed_unit", "mph"),
+                ("precipitation_unit", "inch"),
+            ],
+        }
+    }
+}
+
+pub struct WeatherClient {
+    client: Client,
+}
+
+impl WeatherClient {
+    pub fn new() -> Result<Self> {
+        let client = network::client_builder()
+            .timeout(TIMEOUT)
+            .user_agent(network::user_agent(USER_AGENT))
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self { client })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn geocode(&self, query: &str, limit: usize) -> Result<Vec<Place>> {
+        if query.trim().is_empty() {
+            return Err(OllamaError::InvalidResponse(
+                "Place name cannot be empty".to_string(),
+            ));
+        }
+
+        debug!("Geocoding with Nominatim: {}", query);
+        let limit = limit.clamp(1, MAX_PLACES).to_string();
+        let response = self
+            .client
+            .get(NOMINATIM_URL)
+            .query(&[
+                ("q", query),
+                ("format", "jsonv2"),
+                ("limit", limit.as_str()),
+            ])
+            .send_audited()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json::<Vec<Place>>().await?)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn forecast(&self, lat: f64, lon: f64, days: u8, units: Units) -> Result<Forecast> {
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            return Err(OllamaError::InvalidResponse(format!(
+                "Coordinates out of range: {}, {}",
+                lat, lon
+            )));
+        }
+
+        debug!("Fetching Open-Meteo forecast for {}, {}", lat, lon);
+        let days = days.clamp(1, MAX_FORECAST_DAYS).to_string();
+        let (lat, lon) = (lat.to_string(), lon.to_string());
+        let response = self
+            .client
+            .get(OPEN_METEO_URL)
+            .query(&[
+                ("latitude", lat.as_str()),
+                ("longitude", lon.as_str()),
+                ("current", CURRENT_FIELDS),
+                ("daily", DAILY_FIELDS),
+                ("timezone", "auto"),
+                ("forecast_days", days.as_str()),
+            ])
+            .query(units.query())
+            .send_audited()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json::<Forecast>().await?)
+    }
+}
+
+pub fn describe_weather_code(code: u8) -> &'static str {
+    match code {
+        0 => "clear sky",
+        1 => "mainly clear",
+        2 => "partly cloudy",
+        3 => "overcast",
+        45 | 48 => "fog",
+        51 | 53 | 55 => "drizzle",
+        56 | 57 => "freezing drizzle",
+        61 => "light rain",
+        63 => "rain",
+        65 => "heavy rain",
+        66 | 67 => "freezing rain",
+        71 => "light snow",
+        73 => "snow",
+        75 => "heavy snow",
+        77 => "snow grains",
+        80..=82 => "rain showers",
+        85 | 86 => "snow showers",
+        95 => "thunderstorm",
+        96 | 99 => "thunderstorm with hail",
+        _ => "unknown conditions",
+    }
+}
+
+fn compass(degrees: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((degrees.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+pub fn format_places(query: &str, places: &[Place]) -> String {
+    if places.is_empty() {
+        return format!("No places found for '{}'.", query);
+    }
+
+    let mut output = String::new();
+    for (idx, place) in places.iter().enumerate() {
+        output.push_str(&format!(
+            "{}. {}\n   Coordinates: {:.4}, {:.4}",
+            idx + 1,
+            place.name,
+            place.lat,
+            place.lon
+        ));
+        if let Some(kind) = &place.kind {
+            output.push_str(&format!(" ({})", kind));
+        }
+        output.push('\n');
+    }
+    output.push_str("\nSource: OpenStreetMap Nominatim (https://nominatim.openstreetmap.org)\n");
+    output
+}
+
+pub fn format_forecast(location: &str, forecast: &Forecast) -> String {
+    let unit = |units: &HashMap<String, String>, field: &str| {
+        units.get(field).cloned().unwrap_or_default()
+    };
+
+    let mut output = format!(
+        "Location: {} ({:.4}, {:.4})\n",
+        location, forecast.latitude, forecast.longitude
+    );
+    if !forecast.timezone.is_empty() {
+        output.push_str(&format!("Timezone: {}\n", forecast.timezone));
+    }
+
+    if let Some(current) = &forecast.current {
+        let temp = unit(&forecast.current_units, "temperature_2m");
+        output.push_str(&format!(
+            "\nCurrent ({}): {}{}",
+            current.time, current.temperature_2m, temp
+        ));
+        if let Some(feels) = current.apparent_temperature {
+            output.push_str(&format!(", feels like {}{}", feels, temp));
+        }
+        if let Some(code) = current.weather_code {
+            output.push_str(&format!(", {}", describe_weather_code(code)));
+        }
+        if let Some(humidity) = current.relative_humidity_2m {
+            output.push_str(&format!(", humidity {}%", humidity));
+        }
+        if let Some(speed) = current.wind_speed_10m {
+            output.push_str(&format!(
+                ", wind {} {}",
+                speed,
+                unit(&forecast.current_units, "wind_speed_10m")
+            ));
+            if let Some(direction) = current.wind_direction_10m {
+                output.push_str(&format!(" from {}", compass(direction)));
+            }
+        }
+        if let Some(precipitation) = current.precipitation {
+            output.push_str(&format!(
+                ", precipitation {} {}",
+                precipitation,
+                unit(&forecast.current_units, "precipitation")
+            ));
+        }
+        output.push('\n');
+    }
+
+    if let Some(daily) = &forecast.daily {
+        let temp = unit(&forecast.daily_units, "temperature_2m_max");
+        let rain = unit(&forecast.daily_units, "precipitation_sum");
+        output.push_str("\nDaily forecast:\n");
+        for (idx, day) in daily.time.iter().enumerate() {
+            let value = |values: &[Option<f64>]| values.get(idx).copied().flatten();
+            let mut parts = Vec::new();
+            if let Some(code) = daily.weather_code.get(idx).copied().flatten() {
+                parts.push(describe_weather_code(code).to_string());
+            }
+            if let (Some(low), Some(high)) = (
+                value(&daily.temperature_2m_min),
+                value(&daily.temperature_2m_max),
+            ) {
+                parts.push(format!("{}{} to {}{}", low, temp, high, temp));
+            }
+            if let Some(sum) = value(&daily.precipitation_sum) {
+                let mut rain = format!("precipitation {} {}", sum, rain);
+                if let Some(chance) = value(&daily.precipitation_probability_max) {
+                    rain.push_str(&format!(" ({}% chance)", chance));
+                }
+                parts.push(rain);
+            }
+            output.push_str(&format!("- {}: {}", day, parts.join(", ")));
+            output.push('\n');
+        }
+    }
+
+    output.push_str("\nSource: Open-Meteo (https://open-meteo.com)\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORECAST: &str = r#"{
+        "latitude": 52.52, "longitude": 13.419998, "timezone": "Europe/Berlin",
+        "current_units": {"temperature_2m": "°C", "wind_speed_10m": "km/h", "precipitation": "mm"},
+        "current": {"time": "2026-10-15T14:00", "temperature_2m": 12.3, "apparent_temperature": 10.1,
+                    "relative_humidity_2m": 65, "precipitation": 0.0, "weather_code": 2,
+                    "wind_speed_10m": 14.0, "wind_direction_10m": 225},
+        "daily_units": {"temperature_2m_max": "°C", "precipitation_sum": "mm"},
+        "daily": {"time": ["2026-10-15", "2026-10-16"], "weather_code": [2, 63],
+                  "temperature_2m_max": [13.5, 11.0], "temperature_2m_min": [8.1, null],
+                  "precipitation_sum": [0.2, 6.4], "precipitation_probability_max": [40, 90]}
+    }"#;
+
+    #[test]
+    fn test_formats_current_conditions_and_daily_forecast() {
+        let forecast: Forecast = serde_json::from_str(FORECAST).unwrap();
+        assert_eq!(
+            format_forecast("Berlin, Germany", &forecast),
+            "Location: Berlin, Germany (52.5200, 13.4200)\nTimezone: Europe/Berlin\n\n\
+             Current (2026-10-15T14:00): 12.3°C, feels like 10.1°C, partly cloudy, humidity 65%, \
+             wind 14 km/h from SW, precipitation 0 mm\n\n\
+             Daily forecast:\n\
+             - 2026-10-15: partly cloudy, 8.1°C to 13.5°C, precipitation 0.2 mm (40% chance)\n\
+             - 2026-10-16: rain, precipitation 6.4 mm (90% chance)\n\n\
+             Source: Open-Meteo (https://open-meteo.com)\n"
+        );
+    }
+
+    #[test]
+    fn test_parses_nominatim_places() {
+        let places: Vec<Place> = serde_json::from_str(
+            r#"[{"place_id": 1, "lat": "48.8588897", "lon": "2.3200410",
+                 "display_name": "Paris, Île-de-France, France", "addresstype": "city"}]"#,
+        )
+        .unwrap();
+        assert_eq!(places[0].lat, 48.8588897);
+        assert_eq!(
+            format_places("Paris", &places),
+            "1. Paris, Île-de-France, France\n   Coordinates: 48.8589, 2.3200 (city)\n\n\
+             Source: OpenStreetMap Nominatim (https://nominatim.openstreetmap.org)\n"
+        );
+        assert_eq!(
+            format_places("Atlantis", &[]),
+            "No places found for 'Atlantis'."
+        );
+        assert_eq!(compass(350.0), "N");
+        assert_eq!(Units::parse("Imperial"), Units::Imperial);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use crate::settings::Webhook;
+use crate::text::{ellipsize, urls};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{info, warn};
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_SOURCES: usize = 10;
+const SLACK_HEADER_LIMIT: usize = 150;
+const SLACK_TEXT_LIMIT: usize = 3000;
+const DISCORD_TITLE_LIMIT: usize = 256;
+const DISCORD_DESCRIPTION_LIMIT: usize = 4096;
+const DISCORD_FIELD_LIMIT: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Json,
+    Slack,
+    Discord,
+}
+
+impl Format {
+    pub fn detect(url: &str) -> Self {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return Format::Json;
+        };
+        let host = parsed.host_str().unwrap_or_default();
+
+        if host == "hooks.slack.com" {
+            Format::Slack
+        } else if (host == "discord.com" || host == "discordapp.com")
+            && parsed.path().starts_with("/api/webhooks/")
+        {
+            Format::Discord
+        } else {
+            Format::Json
+        }
+    }
+}
+
+pub struct Report<'a> {
+    pub query: &'a str,
+    pub answer: &'a str,
+    pub job: Option<&'a str>,
+}
+
+pub fn targets(urls: &[String], configured: &[Webhook]) -> Vec<Webhook> {
+    urls.iter()
+        .map(|url| Webhook {
+            url: url.clone(),
+            format: None,
+        })
+        .chain(configured.iter().cloned())
+        .collect()
+}
+
+pub async fn deliver(webhooks: &[Webhook], report: &Report<'_>) {
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let client = match network::client_builder().timeout(TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Failed to create webhook client: {}", e);
+            return;
+        }
+    };
+
+    for webhook in webhooks {
+        let format = webhook
+            .format
+            .unwrap_or_else(|| Format::detect(&webhook.url));
+        match post(&client, &webhook.url, &payload(format, report)).await {
+            Ok(()) => info!("Delivered report to webhook at {}", host(&webhook.url)),
+            Err(e) => warn!(
+                "Failed to deliver report to webhook at {}: {}",
+                host(&webhook.url),
+                e
+            ),
+        }
+    }
+}
+
+async fn post(client: &Client, url: &str, payload: &Value) -> Result<()> {
+    let response = client.post(url).json(payload).send_audited().await?;
+    let status = response.status();
+
+    if !status.is_success() {
+        let error_text = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(OllamaError::ApiError {
+            status: status.as_u16(),
+            message: error_text,
+        });
+    }
+    Ok(())
+}
+
+pub fn payload(format: Format, report: &Report<'_>) -> Value {
+    let sources = sources(report.answer);
+    let completed_at = chrono::Local::now().to_rfc3339();
+
+    match format {
+        Format::Json => json!({
+            "query": report.query,
+            "job": report.job,
+            "answer": report.answer,
+            "sources": sources,
+            "completed_at": completed_at,
+        }),
+        Format::Slack => {
+            let mut blocks = vec![
+                json!({
+                    "type": "header",
+                    "text": {"type": "plain_text", "text": ellipsize(report.query, SLACK_HEADER_LIMIT)}
+                }),
+                json!({
+                    "type": "section",
+                    "text": {"type": "mrkdwn", "text": ellipsize(report.answer, SLACK_TEXT_LIMIT)}
+                }),
+            ];
+            if !sources.is_empty() {
+                blocks.push(json!({
+                    "type": "context",
+                    "elements": [{"type": "mrkdwn", "text": ellipsize(&source_list(&sources), SLACK_TEXT_LIMIT)}]
+                }));
+            }
+            json!({
+                "text": format!("Weavex finished researching: {}", report.query),
+                "blocks": blocks,
+            })
+        }
+        Format::Discord => {
+            let mut embed = json!({
+                "title": ellipsize(report.query, DISCORD_TITLE_LIMIT),
+                "description": ellipsize(report.answer, DISCORD_DESCRIPTION_LIMIT),
+                "timestamp": completed_at,
+            });
+            if !sources.is_empty() {
+                embed["fields"] = json!([{
+                    "name": "Sources",
+                    "value": ellipsize(&source_list(&sources), DISCORD_FIELD_LIMIT),
+                }]);
+            }
+            json!({
+                "username": "Weavex",
+                "embeds": [embed],
+            })
+        }
+    }
+}
+
+pub fn sources(answer: &str) -> Vec<String> {
+    let mut sources = urls(answer);
+    sources.truncate(MAX_SOURCES);
+    sources
+}
+
+fn source_list(sources: &[String]) -> String {
+    sources
+        .iter()
+        .map(|url| format!("• {}", url))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn host(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| "<invalid url>".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_format_from_url() {
+        assert_eq!(
+            Format::detect("https://hooks.slack.com/services/T0/B0/xyz"),
+            Format::Slack
+        );
+        assert_eq!(
+            Format::detect("https://discord.com/api/webhooks/1/abc"),
+            Format::Discord
+        );
+        assert_eq!(
+            Format::detect("https://example.com/api/webhooks/1"),
+            Format::Json
+        );
+    }
+
+    #[test]
+    fn test_sources_are_deduplicated_and_trimmed() {
+        let answer = "See [Tokio](https://tokio.rs/tokio/tutorial). Also https://docs.rs/tokio, \
+                      and https://tokio.rs/tokio/tutorial again.";
+        assert_eq!(
+            sources(answer),
+            vec!["https://tokio.rs/tokio/tutorial", "https://docs.rs/tokio"]
+        );
+    }
+
+    #[test]
+    fn test_payload_shapes() {
+        let answer = "x".repeat(5000) + " https://a.com";
+        let report = Report {
+            query: "rust async",
+            answer: &answer,
+            job: Some("daily"),
+        };
+
+        let json = payload(Format::Json, &report);
+        assert_eq!(json["job"], "daily");
+        assert_eq!(json["sources"][0], "https://a.com");
+
+        let slack = payload(Format::Slack, &report);
+        let section = slack["blocks"][1]["text"]["text"].as_str().unwrap();
+        assert_eq!(section.chars().count(), SLACK_TEXT_LIMIT);
+        assert_eq!(slack["blocks"].as_array().unwrap().len(), 3);
+
+        let discord = payload(Format::Discord, &report);
+        assert_eq!(discord["embeds"][0]["title"], "rust async");
+        assert_eq!(
+            discord["embeds"][0]["fields"][0]["value"],
+            "• https://a.com"
+        );
+    }
+}
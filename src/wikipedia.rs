@@ -0,0 +1,332 @@
+use crate::error::{OllamaError, Result};
+use crate::network::{self, AuditedSend};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+const USER_AGENT: &str = concat!(
+    "weavex/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/guitaripod/weavex)"
+);
+const SECTION_BUDGET: usize = 4000;
+const SKIPPED_SECTIONS: &[&str] = &[
+    "see also",
+    "references",
+    "notes",
+    "external links",
+    "further reading",
+    "bibliography",
+    "sources",
+    "citations",
+];
+
+#[derive(Debug, Deserialize)]
+struct Summary {
+    title: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    extract: String,
+    #[serde(default)]
+    content_urls: Option<ContentUrls>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentUrls {
+    desktop: PageUrl,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageUrl {
+    page: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractResponse {
+    query: Option<ExtractQuery>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractQuery {
+    #[serde(default)]
+    pages: Vec<ExtractPage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExtractPage {
+    #[serde(default)]
+    extract: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub heading: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Article {
+    pub title: String,
+    pub description: Option<String>,
+    pub summary: String,
+    pub url: String,
+    pub disambiguation: bool,
+    pub sections: Vec<Section>,
+}
+
+pub struct WikipediaClient {
+    client: Client,
+}
+
+impl WikipediaClient {
+    pub fn new() -> Result<Self> {
+        let client = network::client_builder()
+            .timeout(TIMEOUT)
+            .user_agent(network::user_agent(USER_AGENT))
+            .build()
+            .map_err(OllamaError::RequestFailed)?;
+
+        Ok(Self { client })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn lookup(&self, title: &str, language: &str) -> Result<Option<Article>> {
+        let base = format!("https://{}.wikipedia.org", language);
+
+        let summary = match self.summary(&base, title).await? {
+            Some(summary) => summary,
+            None => match self.resolve_title(&base, title).await? {
+                Some(resolved) => match self.summary(&base, &resolved).await? {
+                    Some(summary) => summary,
+                    None => return Ok(None),
+                },
+                None => return Ok(None),
+            },
+        };
+
+        let sections = self.sections(&base, &summary.title).await?;
+        Ok(Some(Article {
+            url: summary
+                .content_urls
+                .map(|urls| urls.desktop.page)
+                .unwrap_or_else(|| format!("{}/wiki/{}", base, summary.title.replace(' ', "_"))),
+            disambiguation: summary.kind == "disambiguation",
+            title: summary.title,
+            description: summary.description,
+            summary: summary.extract,
+            sections,
+        }))
+    }
+
+    async fn summary(&self, base: &str, title: &str) -> Result<Option<Summary>> {
+        let mut url = url::Url::parse(&format!("{}/api/rest_v1/page/summary/", base))
+            .map_err(|e| OllamaError::InvalidUrl(e.to_string()))?;
+        url.path_segments_mut()
+            .map_err(|_| OllamaError::InvalidUrl(base.to_string()))?
+            .pop_if_empty()
+            .push(&title.trim().replace(' ', "_"));
+
+        debug!("Fetching Wikipedia summary: {}", url);
+        let response = self.client.get(url).send_audited().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        Ok(Some(response.json::<Summary>().await?))
+    }
+
+    async fn resolve_title(&self, base: &str, query: &str) -> Result<Option<String>> {
+        let response = self
+            .client
+            .get(format!("{}/w/api.php", base))
+            .query(&[
+                ("action", "opensearch"),
+                ("search", query),
+                ("limit", "1"),
+                ("namespace", "0"),
+                ("format", "json"),
+            ])
+            .send_audited()
+            .await?
+            .error_for_status()?;
+
+        let body: serde_json::Value = response.json().await?;
+        Ok(body[1][0].as_str().map(str::to_string))
+    }
+
+    async fn sections(&self, base: &str, title: &str) -> Result<Vec<Section>> {
+        let response = self
+            .client
+            .get(format!("{}/w/api.php", base))
+            .query(&[
+                ("action", "query"),
+                ("prop", "extracts"),
+                ("explaintext", "1"),
+                ("redirects", "1"),
+                ("format", "json"),
+                ("formatversion", "2"),
+                ("titles", title),
+            ])
+            .send_audited()
+            .await?
+            .error_for_status()?;
+
+        let body: ExtractResponse = response.json().await?;
+        let extract = body
+            .query
+            .and_then(|query| query.pages.into_iter().next())
+            .map(|page| page.extract)
+            .unwrap_or_default();
+        Ok(parse_sections(&extract))
+    }
+}
+
+pub fn parse_sections(extract: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+
+    for line in extract.lines() {
+        let trimmed = line.trim();
+        let is_heading =
+            trimmed.len() > 4 && trimmed.starts_with("== ") && trimmed.ends_with(" ==");
+        if is_heading && !trimmed.starts_with("=== ") {
+            sections.push(Section {
+                heading: trimmed.trim_matches('=').trim().to_string(),
+                text: String::new(),
+            });
+            continue;
+        }
+
+        if let Some(section) = sections.last_mut() {
+            let line = if trimmed.starts_with("===") && trimmed.ends_with("===") {
+                format!("{}:", trimmed.trim_matches('=').trim())
+            } else {
+                trimmed.to_string()
+            };
+            if line.is_empty() && section.text.ends_with("\n\n") {
+                continue;
+            }
+            section.text.push_str(&line);
+            section.text.push('\n');
+        }
+    }
+
+    sections
+        .into_iter()
+        .map(|section| Section {
+            text: section.text.trim().to_string(),
+            ..section
+        })
+        .filter(|section| {
+            !section.text.is_empty()
+                && !SKIPPED_SECTIONS.contains(&section.heading.to_lowercase().as_str())
+        })
+        .collect()
+}
+
+pub fn format_article(article: &Article, section: Option<&str>) -> String {
+    let mut output = format!("Title: {}\nURL: {}\n", article.title, article.url);
+    if let Some(description) = &article.description {
+        output.push_str(&format!("Description: {}\n", description));
+    }
+    if article.disambiguation {
+        output.push_str("Note: this is a disambiguation page; look up a more specific title.\n");
+    }
+    output.push_str(&format!("\nSummary:\n{}\n", article.summary));
+
+    if let Some(wanted) = section {
+        let wanted = wanted.to_lowercase();
+        return match article
+            .sections
+            .iter()
+            .find(|s| s.heading.to_lowercase().contains(&wanted))
+        {
+            Some(found) => format!("{}\n## {}\n{}\n", output, found.heading, found.text),
+            None => format!(
+                "{}\nNo section matching '{}'. Available sections: {}\n",
+                output,
+                wanted,
+                section_names(article)
+            ),
+        };
+    }
+
+    let mut budget = SECTION_BUDGET;
+    for section in &article.sections {
+        if section.text.len() > budget {
+            break;
+        }
+        budget -= section.text.len();
+        output.push_str(&format!("\n## {}\n{}\n", section.heading, section.text));
+    }
+    output.push_str(&format!("\nAll sections: {}\n", section_names(article)));
+    output
+}
+
+fn section_names(article: &Article) -> String {
+    article
+        .sections
+        .iter()
+        .map(|s| s.heading.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXTRACT: &str = "Rust is a general-purpose programming language.\n\n\n\
+        == History ==\nGraydon Hoare started Rust in 2006.\n\n\
+        === Mozilla ===\nMozilla sponsored the project.\n\n\n\n\
+        == Syntax ==\nRust syntax is similar to C++.\n\n\
+        == See also ==\nGo\n\n\
+        == References ==\n";
+
+    fn article() -> Article {
+        Article {
+            title: "Rust (programming language)".to_string(),
+            description: Some("General-purpose programming language".to_string()),
+            summary: "Rust is a general-purpose programming language.".to_string(),
+            url: "https://en.wikipedia.org/wiki/Rust_(programming_language)".to_string(),
+            disambiguation: false,
+            sections: parse_sections(EXTRACT),
+        }
+    }
+
+    #[test]
+    fn test_parse_sections_keeps_content_sections() {
+        assert_eq!(
+            parse_sections(EXTRACT),
+            vec![
+                Section {
+                    heading: "History".to_string(),
+                    text: "Graydon Hoare started Rust in 2006.\n\nMozilla:\nMozilla sponsored the project."
+                        .to_string(),
+                },
+                Section {
+                    heading: "Syntax".to_string(),
+                    text: "Rust syntax is similar to C++.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_article_selects_section() {
+        let full = format_article(&article(), None);
+        assert!(full.contains("## History"));
+        assert!(full.ends_with("All sections: History, Syntax\n"));
+
+        let syntax = format_article(&article(), Some("syntax"));
+        assert!(syntax.contains("## Syntax\nRust syntax"));
+        assert!(!syntax.contains("## History"));
+
+        let missing = format_article(&article(), Some("Governance"));
+        assert!(missing.contains("Available sections: History, Syntax"));
+    }
+}